@@ -1,11 +1,41 @@
-use anyhow::Result;
+// `project_json` lists every record field in one `json!`, which needs more than the default
+#![recursion_limit = "256"]
+
+use anyhow::{Context, Result};
 use clap::{Parser, Subcommand, ValueEnum};
-use indexer::{scan_roots, ConfigStore, Db, ScanOptions, SortKey};
+use indexer::actions;
+use indexer::autostart::{self, AutostartMode, AutostartStatus};
+use indexer::backup;
+use indexer::cleanup;
+use indexer::columns::{column_json, column_text, Template};
+use indexer::daemon;
+use indexer::diagnose;
+use indexer::encryption;
+use indexer::explain;
+use indexer::fetch::{self, FetchMode, FetchOutcome};
+use indexer::fields::{parse_field_filter, FieldValue};
+use indexer::jsonrpc::RpcServer;
+use indexer::query::Query;
+use indexer::reconcile;
+use indexer::redact::{redact_config, Redactor};
+use indexer::roots::suggest_home_roots;
+use indexer::rpc::{Backend, ScanOutcome};
+use indexer::sbom::{self, SbomFormat};
+use indexer::system;
+use indexer::vulns::{audit_project, Severity};
+use indexer::{
+    AnalyzerRegistry, ConfigStore, Db, ProjectFilter, ScanOptions, ScanPriority, SortKey,
+};
+use std::io::Write;
+use std::path::PathBuf;
 use tracing_subscriber::EnvFilter;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about = "Project Browser CLI", long_about = None)]
 struct Cli {
+    /// Keep config and data in `project-browser-data/` next to this executable
+    #[arg(long, global = true)]
+    portable: bool,
     #[command(subcommand)]
     command: Commands,
 }
@@ -20,15 +50,47 @@ enum Commands {
         /// Print the default DB path
         #[arg(long)]
         db_path: bool,
+        /// Print the config and data directories in use
+        #[arg(long)]
+        dirs: bool,
+        /// With --print: hash paths, remote URLs and emails (for attaching to a bug report)
+        #[arg(long, requires = "print")]
+        redact: bool,
     },
     /// Scan roots and populate the database
     Scan {
         /// Optional roots (defaults to config roots). Repeatable.
         #[arg(long)]
         root: Vec<String>,
-        /// Dry run without writing to the DB
+        /// Scan the given --root(s) at this priority ahead of the rest of the configured
+        /// roots, instead of scanning only them
+        #[arg(long, value_enum, requires = "root")]
+        priority: Option<PriorityArg>,
+        /// Write nothing; show what the scan would add, update and no longer find
         #[arg(long)]
         dry_run: bool,
+        /// Print the --dry-run changes as JSON
+        #[arg(long, requires = "dry_run")]
+        json: bool,
+        /// Only find projects and store their name, path and type; sizes, LOC and git info
+        /// keep their previous values until a full scan
+        #[arg(long)]
+        discover_only: bool,
+        /// Print a per-phase timing breakdown after the scan
+        #[arg(long)]
+        timings: bool,
+        /// Low-impact scan: pace directory walking to `nice.ops_per_sec` and run at background
+        /// CPU/IO priority
+        #[arg(long)]
+        nice: bool,
+        /// Scan in this process even when a `watch` daemon is running, instead of queueing
+        /// the roots with it
+        #[arg(long)]
+        no_daemon: bool,
+        /// Continue a scan that was interrupted: roots it finished are skipped and the one it
+        /// was walking continues from the last project found
+        #[arg(long, conflicts_with = "dry_run")]
+        resume: bool,
         /// Override database path
         #[arg(long)]
         db: Option<String>,
@@ -38,142 +100,2519 @@ enum Commands {
         /// Sort key
         #[arg(long, value_enum, default_value_t = ListSort::Recent)]
         sort: ListSort,
-        /// Max rows
+        /// Max rows (0 for no limit)
         #[arg(long, default_value_t = 100)]
         limit: usize,
         /// Output JSON instead of table
         #[arg(long)]
         json: bool,
+        /// Output CSV instead of table
+        #[arg(long, conflicts_with = "json")]
+        csv: bool,
         /// Override database path
         #[arg(long)]
         db: Option<String>,
         /// Show LOC column in text output
         #[arg(long)]
         show_loc: bool,
+        /// Only projects whose last dependency audit found vulnerabilities
+        #[arg(long)]
+        vulnerable: bool,
+        /// Only projects whose highest audited severity is at least this
+        #[arg(long, value_enum)]
+        min_severity: Option<SeverityArg>,
+        /// Filter on whether tests were detected (true/false)
+        #[arg(long)]
+        has_tests: Option<bool>,
+        /// Filter on whether CI config was detected (true/false)
+        #[arg(long)]
+        has_ci: Option<bool>,
+        /// Only projects on this git branch
+        #[arg(long)]
+        branch: Option<String>,
+        /// Filter on whether the repo has a remote; `false` lists git repos without one
+        #[arg(long)]
+        has_remote: Option<bool>,
+        /// Filter on uncommitted/untracked changes (true/false)
+        #[arg(long)]
+        dirty: Option<bool>,
+        /// Match a custom field, e.g. `field:owner=platform-team`. Repeatable; all must match.
+        #[arg(long = "where", value_name = "FIELD")]
+        where_: Vec<String>,
+        /// Filter expression, e.g. "type:rust AND loc>5000 AND edited<90d"
+        #[arg(long)]
+        query: Option<String>,
+        /// Hash names, paths, remote URLs, owners and branches; JSON leaves out custom fields
+        #[arg(long)]
+        redact: bool,
+        /// Only these columns, e.g. `name,type,loc,last_commit,tags`: any JSON key or custom
+        /// field. Text output becomes tab-separated.
+        #[arg(long, value_delimiter = ',', conflicts_with = "template")]
+        columns: Vec<String>,
+        /// One line per project from a format string, e.g. "{name}\t{path}"; placeholders are
+        /// column names, `{{` and `}}` are literal braces
+        #[arg(long, conflicts_with_all = ["json", "csv"])]
+        template: Option<String>,
+    },
+    /// Print the end of the log file
+    Logs {
+        /// How many lines
+        #[arg(long, default_value_t = 200)]
+        lines: usize,
+        /// Hash paths, remote URLs, emails, and project, action and author names (for
+        /// attaching to a bug report)
+        #[arg(long)]
+        redact: bool,
+    },
+    /// Build, schema, database and last-scan details for a bug report, redacted; printed as
+    /// JSON, or zipped with recent logs by --bundle
+    Diagnose {
+        /// Write a zip with the report and the last 1000 log lines to this path
+        #[arg(long)]
+        bundle: Option<String>,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Full-text search over names, paths and READMEs, showing what matched
+    Search {
+        /// Words to look for; each matches as a prefix
+        query: String,
+        /// Max results
+        #[arg(long, default_value_t = 20)]
+        limit: u32,
+        /// Output JSON (with match offsets) instead of text
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Commit messages across every repo (kept by the `commits` analyzer at scan time)
+    Commits {
+        #[command(subcommand)]
+        action: CommitsAction,
+    },
+    /// Why a directory is or is not indexed: markers checked, type assigned, ignores and any
+    /// project root above it that the scan stops at
+    Explain {
+        /// Directory to explain
+        path: String,
+        /// Output JSON instead of a table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Get, set or remove user-defined fields on a project
+    Field {
+        #[command(subcommand)]
+        action: FieldAction,
+    },
+    /// Run a configured quick action in a project and inspect past runs
+    Action {
+        #[command(subcommand)]
+        action: ActionCmd,
+    },
+    /// Fetch or pull many repositories at once
+    Git {
+        #[command(subcommand)]
+        action: GitAction,
+    },
+    /// Fold a duplicate record into another (fields, notes, open history) and delete it
+    Merge {
+        /// Project to keep (path or name)
+        keep: String,
+        /// Duplicate to fold in and delete; give its path when both share a name
+        remove: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Remove a project from the index (files on disk are untouched); undo with `undo`
+    Forget {
+        /// Project path or name
+        project: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Restore the rows changed by the last `merge` or `forget`
+    Undo {
+        /// List recent journal entries instead of undoing
+        #[arg(long)]
+        list: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Disk usage across the index: by type, by dominant language, largest projects, and
+    /// source vs build/dependency output
+    Stats {
+        /// How many of the largest projects to list
+        #[arg(long, default_value_t = 20)]
+        top: usize,
+        /// Estimated hours spent instead, from commit times (the `hours` analyzer)
+        #[arg(long)]
+        hours: bool,
+        /// With --hours: how far back to look
+        #[arg(long, default_value_t = 365)]
+        days: i64,
+        /// Output JSON instead of charts
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Back up or restore the index database
+    Db {
+        #[command(subcommand)]
+        action: DbAction,
+    },
+    /// Suggestions for what to do next
+    Recommend {
+        #[command(subcommand)]
+        what: RecommendAction,
+    },
+    /// Index every user's home into a shared database (run as an administrator)
+    System {
+        #[command(subcommand)]
+        action: SystemAction,
+    },
+    /// Stay running: rescan the configured roots every `watch.interval_minutes` and answer
+    /// `daemon` commands and the app over a local control socket
+    Watch {
+        /// Minutes between rescans (defaults to `watch.interval_minutes`)
+        #[arg(long)]
+        interval: Option<u32>,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Run `watch` or a daily scan at login without the app (LaunchAgent, systemd user unit or
+    /// Windows startup task)
+    Autostart {
+        #[command(subcommand)]
+        action: AutostartAction,
+    },
+    /// Control a running `watch` daemon
+    Daemon {
+        #[command(subcommand)]
+        action: DaemonAction,
+    },
+    /// Answer JSON-RPC requests on stdin/stdout for editor plugins: `search`, `detail`,
+    /// `record_open` and `refresh`, one JSON message per line
+    Rpc {
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Find and manage scan roots
+    Roots {
+        #[command(subcommand)]
+        action: RootsAction,
+    },
+    /// Export or import the desktop app's saved UI state (sort, filters, column widths, search)
+    UiState {
+        #[command(subcommand)]
+        action: UiStateAction,
+    },
+    /// Export a software bill of materials from the indexed dependencies
+    Sbom {
+        /// Project name or path
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        project: Option<String>,
+        /// Export every indexed project (one document each)
+        #[arg(long)]
+        all: bool,
+        /// Output format
+        #[arg(long, value_enum, default_value_t = SbomFormatArg::Cyclonedx)]
+        format: SbomFormatArg,
+        /// Write one file per project into this directory instead of stdout
+        #[arg(long)]
+        out_dir: Option<String>,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Match a list of remote URLs against local clones: which are cloned (and where), which
+    /// are missing, and which local repos are not in the list
+    Reconcile {
+        /// File with one remote URL per line (`#` comments allowed); `-` reads stdin
+        file: String,
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Audit reports over the indexed projects
+    Audit {
+        #[command(subcommand)]
+        report: AuditReport,
+    },
+    /// Benchmark helpers
+    Bench {
+        #[command(subcommand)]
+        action: BenchAction,
+    },
+    /// Manage this CLI installation
+    #[command(name = "self")]
+    SelfCmd {
+        #[command(subcommand)]
+        action: SelfAction,
     },
 }
 
-#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
-enum ListSort {
-    Recent,
-    Size,
-    Name,
-    Type,
-    Loc,
+#[derive(Subcommand, Debug)]
+enum AutostartAction {
+    /// Register this binary to start at login, replacing any earlier registration
+    Enable {
+        /// Keep `watch` running, or run one low-impact scan a day
+        #[arg(long, value_enum, default_value_t = AutostartModeArg::Watch)]
+        mode: AutostartModeArg,
+    },
+    /// Remove the registration
+    Disable,
+    /// Whether autostart is set up, in which mode, and whether the OS has it active
+    Status {
+        /// Output JSON
+        #[arg(long)]
+        json: bool,
+    },
 }
 
-fn main() -> Result<()> {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
-
-    let cli = Cli::parse();
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum AutostartModeArg {
+    Watch,
+    Daily,
+}
 
-    match cli.command {
-        Commands::Config { print, db_path } => {
-            let cfg = ConfigStore::load()?;
-            if print {
-                println!("{}", serde_json::to_string_pretty(&cfg)?);
-            } else if db_path {
-                let db = Db::open_default()?;
-                println!("{}", db.path.display());
-            } else {
-                println!("Use --print or --db-path");
-            }
-        }
-        Commands::Scan { root, dry_run, db } => {
-            let mut cfg = ConfigStore::load()?;
-            if !root.is_empty() {
-                cfg.roots = root
-                    .into_iter()
-                    .map(|s| shellexpand::tilde(&s).to_string().into())
-                    .collect();
-            }
-            let db = if let Some(path) = db {
-                let p = shellexpand::tilde(&path).to_string();
-                Db::open(std::path::Path::new(&p))?
-            } else {
-                Db::open_default()?
-            };
-            let count = scan_roots(&db, &cfg, &ScanOptions { dry_run })?;
-            eprintln!("Scanned {count} project(s)");
-        }
-        Commands::List {
-            sort,
-            limit,
-            json,
-            db,
-            show_loc,
-        } => {
-            let db = if let Some(path) = db {
-                let p = shellexpand::tilde(&path).to_string();
-                Db::open(std::path::Path::new(&p))?
-            } else {
-                Db::open_default()?
-            };
-            let sort_key = match sort {
-                ListSort::Recent => SortKey::Recent,
-                ListSort::Size => SortKey::Size,
-                ListSort::Name => SortKey::Name,
-                ListSort::Type => SortKey::Type,
-                ListSort::Loc => SortKey::Loc,
-            };
-            let rows = db.list_projects(sort_key, limit)?;
-            if json {
-                println!("{}", serde_json::to_string_pretty(&rows_as_json(&rows))?);
-            } else if show_loc {
-                for r in rows {
-                    println!(
-                        "{:<24}  {:<6}  {:>10}  {:>8}  {}",
-                        truncate(&r.name, 24),
-                        r.project_type.clone().unwrap_or_else(|| "-".into()),
-                        r.size_bytes.unwrap_or_default(),
-                        r.loc.unwrap_or_default(),
-                        r.path
-                    );
-                }
-            } else {
-                for r in rows {
-                    println!(
-                        "{:<24}  {:<6}  {:>10}  {}",
-                        truncate(&r.name, 24),
-                        r.project_type.clone().unwrap_or_else(|| "-".into()),
-                        r.size_bytes.unwrap_or_default(),
-                        r.path
-                    );
-                }
-            }
+impl From<AutostartModeArg> for AutostartMode {
+    fn from(m: AutostartModeArg) -> Self {
+        match m {
+            AutostartModeArg::Watch => AutostartMode::Watch,
+            AutostartModeArg::Daily => AutostartMode::Daily,
         }
     }
+}
 
-    Ok(())
+#[derive(Subcommand, Debug)]
+enum SelfAction {
+    /// Download the latest release for this platform, verify its checksum and replace this binary
+    Update {
+        /// Only report whether a newer version is available
+        #[arg(long)]
+        check: bool,
+        /// Output JSON (with --check)
+        #[arg(long, requires = "check")]
+        json: bool,
+    },
 }
 
-fn truncate(s: &str, width: usize) -> String {
-    if s.len() <= width {
-        s.to_string()
-    } else {
-        let mut t = s.chars().take(width.saturating_sub(1)).collect::<String>();
-        t.push('…');
-        t
-    }
+#[derive(Subcommand, Debug)]
+enum BenchAction {
+    /// Generate a synthetic project tree and/or a seeded database for reproducing perf issues
+    Seed {
+        /// Write this many synthetic projects under --tree
+        #[arg(long, default_value_t = 1000)]
+        projects: usize,
+        /// Directory to create the project tree in
+        #[arg(long)]
+        tree: Option<String>,
+        /// Fraction of generated projects that are git repos
+        #[arg(long, default_value_t = 0.5)]
+        git_ratio: f64,
+        /// Give git projects real commits via the `git` CLI (slower)
+        #[arg(long)]
+        git_commits: bool,
+        /// Insert this many synthetic rows into --db
+        #[arg(long, default_value_t = 100_000)]
+        rows: usize,
+        /// Database file to seed (never the default DB)
+        #[arg(long)]
+        db: Option<String>,
+        /// RNG seed; the same seed produces the same data
+        #[arg(long, default_value_t = 0x5eed)]
+        seed: u64,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AuditReport {
+    /// Projects with likely secrets on disk (requires `secrets.enabled` during scan)
+    Secrets {
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Projects no backup covers (see `offsite` config) with work missing from any git
+    /// remote: uncommitted changes, unpushed commits or no remote at all
+    UnbackedUp {
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Projects per pinned toolchain version (Node, Python, Go, Rust), flagging versions past
+    /// their end of life
+    Toolchains {
+        /// Only list end-of-life versions
+        #[arg(long)]
+        eol: bool,
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Projects violating a retention policy (see `policies` config), checked again now
+    Policies {
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Run cargo-audit/npm-audit/pip-audit and store a vulnerability summary
+    Deps {
+        /// Project name or path to audit
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        project: Option<String>,
+        /// Audit every indexed project
+        #[arg(long)]
+        all: bool,
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum FieldAction {
+    /// Show a project's fields (hook-provided and user-defined)
+    List {
+        /// Project name or path
+        project: String,
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Set a field; `true`/`false` and numbers are stored typed unless --string is given
+    Set {
+        /// Project name or path
+        project: String,
+        key: String,
+        value: String,
+        /// Store the value as a string even if it looks like a number or bool
+        #[arg(long)]
+        string: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Remove a user-defined field
+    Rm {
+        /// Project name or path
+        project: String,
+        key: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
 }
 
-fn rows_as_json(rows: &[indexer::ProjectRecord]) -> serde_json::Value {
-    serde_json::json!(rows
-        .iter()
-        .map(|r| {
-            serde_json::json!({
-                "id": r.id,
-                "name": r.name,
-                "path": r.path,
-                "type": r.project_type,
-                "is_git_repo": r.is_git_repo,
-                "size_bytes": r.size_bytes,
-                "files_count": r.files_count,
-                "last_edited_at": r.last_edited_at,
-                "loc": r.loc,
-            })
-        })
-        .collect::<Vec<_>>())
+#[derive(Subcommand, Debug)]
+enum ActionCmd {
+    /// Show the actions configured for a project's type
+    List {
+        /// Project name or path
+        project: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Run an action, printing its output as it arrives; exits non-zero if the action fails
+    Run {
+        /// Project name or path
+        project: String,
+        /// Action name from the `actions` config
+        name: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// List a project's recent runs, newest first
+    Runs {
+        /// Project name or path
+        project: String,
+        /// Output JSON (with captured output) instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Print a run's captured output
+    Show {
+        /// Run id, from `action runs`
+        run: i64,
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CommitsAction {
+    /// Which repos have a commit whose subject matches, with sha and date
+    Search {
+        /// Words to look for; each matches as a prefix
+        query: String,
+        /// Max results
+        #[arg(long, default_value_t = 50)]
+        limit: u32,
+        /// Output JSON (with match offsets) instead of text
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum GitAction {
+    /// `git fetch` every matching repo, reporting which got new upstream commits
+    Fetch {
+        /// Filter expression choosing the repos, e.g. "tag:work"; all git repos when omitted
+        #[arg(long)]
+        filter: Option<String>,
+        /// Repos fetched at once (default: `concurrency`)
+        #[arg(long)]
+        jobs: Option<usize>,
+        /// Output JSON instead of progress and a summary
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// `git pull --ff-only` every matching repo, reporting new commits and conflicts
+    Pull {
+        /// Filter expression choosing the repos, e.g. "tag:work"; all git repos when omitted
+        #[arg(long)]
+        filter: Option<String>,
+        /// Repos pulled at once (default: `concurrency`)
+        #[arg(long)]
+        jobs: Option<usize>,
+        /// Output JSON instead of progress and a summary
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Current branch of every matching repo, flagging repos off their default branch or
+    /// with many stale local branches (as of the last scan)
+    Branches {
+        /// Filter expression choosing the repos; all git repos when omitted
+        #[arg(long)]
+        filter: Option<String>,
+        /// Days without a commit before a local branch counts as stale
+        #[arg(long, default_value_t = 90)]
+        stale_days: i64,
+        /// Stale branches that get a repo flagged
+        #[arg(long, default_value_t = 5)]
+        many: usize,
+        /// Only list flagged repos
+        #[arg(long)]
+        flagged: bool,
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DaemonAction {
+    /// Whether it is scanning or paused, what is queued and when it last scanned
+    Status {
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stop starting scans until `resume`
+    Pause,
+    Resume,
+    /// Queue a root to be scanned next
+    Rescan {
+        root: String,
+        /// Defaults to ahead of everything the daemon queued itself
+        #[arg(long, value_enum)]
+        priority: Option<PriorityArg>,
+    },
+    /// Projects matching a filter expression, answered by the daemon
+    Query {
+        /// Filter expression, e.g. `type:rust edited<30d`; empty matches everything
+        #[arg(default_value = "")]
+        query: String,
+        #[arg(long, default_value_t = 50)]
+        limit: u32,
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RootsAction {
+    /// Look in ~/Code, ~/Projects, ~/src, ~/Developer, ~/work, ... and report candidate roots
+    /// with an estimated project count
+    Suggest {
+        /// Add every suggestion that is not configured yet to the config's roots
+        #[arg(long)]
+        add: bool,
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum DbAction {
+    /// Snapshot the database into the backups directory now
+    Backup {
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// List backups, newest first
+    Backups {
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Find and fix orphaned rows, duplicate paths (trailing slash, letter case) and blank
+    /// names, reporting every change
+    Repair {
+        /// Only report what would change
+        #[arg(long)]
+        dry_run: bool,
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Replace the database with a backup (the current one is backed up first)
+    Restore {
+        /// Backup file path, or a file name from `db backups`
+        backup: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Encrypt the database with a key kept in the OS keychain (needs an `encryption` build);
+    /// stop the app and `watch` first
+    Encrypt {
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Turn an encrypted database back into plaintext; stop the app and `watch` first
+    Decrypt {
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum RecommendAction {
+    /// What to delete to free space: artifacts of idle projects, stale duplicate checkouts
+    /// and long-untouched fully pushed projects, biggest wins first
+    Cleanup {
+        /// Show at most this many
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Output JSON instead of text
+        #[arg(long)]
+        json: bool,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum SystemAction {
+    /// Scan user homes, attributing each project to its home's user
+    Scan {
+        /// Homes to scan (repeatable); defaults to `system.homes`, else every home
+        #[arg(long)]
+        home: Vec<String>,
+        /// Do not write to the database
+        #[arg(long)]
+        dry_run: bool,
+        /// Override database path (default: the system-wide database)
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Per-owner totals: projects, size, build/dependency output and stale projects
+    Report {
+        /// Days without edits after which a project is stale (default: `system.stale_after_days`)
+        #[arg(long)]
+        stale_days: Option<u32>,
+        /// Output JSON instead of table
+        #[arg(long)]
+        json: bool,
+        /// Override database path (default: the system-wide database)
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum UiStateAction {
+    /// Print every saved UI state value as one JSON object
+    Export {
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+    /// Restore values from a JSON object written by `export` (`-` reads stdin)
+    Import {
+        file: String,
+        /// Override database path
+        #[arg(long)]
+        db: Option<String>,
+    },
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum ListSort {
+    Recent,
+    Size,
+    Name,
+    Type,
+    Loc,
+    Vulns,
+    LastCommit,
+    /// Source files (by extension)
+    SourceFiles,
+    /// Average file size
+    AvgFileSize,
+    /// Deepest directory nesting
+    Depth,
+    /// Top-level modules under src/, lib/ or the root
+    Modules,
+    /// Bytes in images, video, audio, archives and model weights
+    Assets,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum SbomFormatArg {
+    Cyclonedx,
+    SpdxJson,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum SeverityArg {
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+enum PriorityArg {
+    Low,
+    Normal,
+    High,
+}
+
+impl From<PriorityArg> for ScanPriority {
+    fn from(p: PriorityArg) -> Self {
+        match p {
+            PriorityArg::Low => ScanPriority::Low,
+            PriorityArg::Normal => ScanPriority::Normal,
+            PriorityArg::High => ScanPriority::High,
+        }
+    }
+}
+
+impl From<SeverityArg> for Severity {
+    fn from(s: SeverityArg) -> Self {
+        match s {
+            SeverityArg::Low => Severity::Low,
+            SeverityArg::Moderate => Severity::Moderate,
+            SeverityArg::High => Severity::High,
+            SeverityArg::Critical => Severity::Critical,
+        }
+    }
+}
+
+fn main() -> Result<()> {
+    // Logs go to stderr so they never interleave with `rpc` responses or piped output
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    let cli = Cli::parse();
+    if cli.portable {
+        ConfigStore::set_portable(true);
+    }
+
+    match cli.command {
+        Commands::Config {
+            print,
+            db_path,
+            dirs,
+            redact,
+        } => {
+            let cfg = ConfigStore::load()?;
+            if print {
+                let mut value = serde_json::to_value(&cfg)?;
+                if redact {
+                    redact_config(&Redactor::new(), &mut value);
+                }
+                println!("{}", serde_json::to_string_pretty(&value)?);
+            } else if db_path {
+                let db = Db::open_default()?;
+                println!("{}", db.path.display());
+            } else if dirs {
+                println!("config: {}", ConfigStore::config_dir()?.display());
+                println!("data:   {}", ConfigStore::data_dir()?.display());
+            } else {
+                println!("Use --print, --db-path or --dirs");
+            }
+        }
+        Commands::Scan {
+            root,
+            priority,
+            dry_run,
+            json,
+            discover_only,
+            timings,
+            nice,
+            no_daemon,
+            resume,
+            db,
+        } => {
+            let mut cfg = ConfigStore::load()?;
+            let roots: Vec<PathBuf> = root
+                .into_iter()
+                .map(|s| shellexpand::tilde(&s).to_string().into())
+                .collect();
+            let mut queue: Vec<(PathBuf, ScanPriority)> = Vec::new();
+            match priority {
+                // Listed roots jump the queue; the configured ones follow
+                Some(priority) => queue.extend(roots.into_iter().map(|r| (r, priority.into()))),
+                None => {
+                    if !roots.is_empty() {
+                        cfg.roots = roots;
+                    }
+                }
+            }
+            queue.extend(cfg.roots.iter().map(|r| (r.clone(), cfg.priority_of(r))));
+            // Partial, resumed and dry-run scans are not something the daemon does for others
+            let backend = if no_daemon || dry_run || discover_only || resume {
+                Backend::Local(open_db(db)?)
+            } else {
+                backend(db)?
+            };
+            if let (true, Backend::Local(db)) = (resume, &backend) {
+                let checkpoints = db.interrupted_scan()?;
+                if checkpoints.is_empty() && db.pending_enrichment()?.is_empty() {
+                    eprintln!("No interrupted scan to resume; scanning from the start");
+                }
+                for c in checkpoints.iter().filter(|c| !c.done) {
+                    if let Some(last) = &c.last_path {
+                        eprintln!("Resuming {} from {last}", c.root);
+                    }
+                }
+            }
+            let report = match backend.scan(
+                &cfg,
+                &queue,
+                &ScanOptions {
+                    dry_run,
+                    discover_only,
+                    nice,
+                    resume,
+                    ..Default::default()
+                },
+                &AnalyzerRegistry::with_builtins(),
+            )? {
+                ScanOutcome::Scanned(report) => report,
+                ScanOutcome::Queued(pending) => {
+                    eprintln!(
+                        "Queued with the running `watch` daemon ({} root(s) waiting); follow it with `daemon status`",
+                        pending.len()
+                    );
+                    return Ok(());
+                }
+            };
+            if let (true, false, Backend::Local(db)) = (dry_run, discover_only, &backend) {
+                let roots: Vec<PathBuf> = queue.iter().map(|(r, _)| r.clone()).collect();
+                let diff = db.scan_diff(&roots, &report.previews)?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&diff)?);
+                } else {
+                    print_scan_diff(&diff);
+                }
+            }
+            eprintln!("Scanned {} project(s)", report.projects);
+            for m in &report.moves {
+                eprintln!(
+                    "Moved {} -> {} (matched by {})",
+                    m.from,
+                    m.to,
+                    m.matched_by.as_str()
+                );
+            }
+            if timings {
+                let t = &report.timings;
+                eprintln!(
+                    "{:<24}  {:>10}  {:>8}  {:>6}",
+                    "phase", "ms", "calls", "share"
+                );
+                for p in t.by_cost() {
+                    eprintln!(
+                        "{:<24}  {:>10.1}  {:>8}  {:>5.1}%",
+                        p.phase,
+                        p.total_ms,
+                        p.calls,
+                        100.0 * p.total_ms / t.total_ms.max(f64::EPSILON)
+                    );
+                }
+                eprintln!("{:<24}  {:>10.1}", "total", t.total_ms);
+            }
+        }
+        Commands::List {
+            sort,
+            limit,
+            json,
+            db,
+            show_loc,
+            vulnerable,
+            min_severity,
+            has_tests,
+            has_ci,
+            branch,
+            has_remote,
+            dirty,
+            where_,
+            query,
+            csv,
+            redact,
+            columns,
+            template,
+        } => {
+            let template = template.as_deref().map(Template::parse).transpose()?;
+            let db = open_db(db)?;
+            warn_if_stale(&db)?;
+            let redactor = redact.then(Redactor::new);
+            let redactor = redactor.as_ref();
+            let scrub = |r: indexer::ProjectRecord| match redactor {
+                Some(redactor) => redactor.project(&r),
+                None => r,
+            };
+            let sort_key = match sort {
+                ListSort::Recent => SortKey::Recent,
+                ListSort::Size => SortKey::Size,
+                ListSort::Name => SortKey::Name,
+                ListSort::Type => SortKey::Type,
+                ListSort::Loc => SortKey::Loc,
+                ListSort::Vulns => SortKey::Vulns,
+                ListSort::LastCommit => SortKey::LastCommit,
+                ListSort::SourceFiles => SortKey::SourceFiles,
+                ListSort::AvgFileSize => SortKey::AvgFileSize,
+                ListSort::Depth => SortKey::Depth,
+                ListSort::Modules => SortKey::Modules,
+                ListSort::Assets => SortKey::Assets,
+            };
+            let filter = ProjectFilter {
+                vulnerable,
+                min_severity: min_severity.map(Severity::from),
+                has_tests,
+                has_ci,
+                branch,
+                has_remote,
+                dirty,
+                fields: where_
+                    .iter()
+                    .map(|w| parse_field_filter(w))
+                    .collect::<Result<Vec<_>>>()?,
+                query: query.as_deref().map(Query::parse).transpose()?,
+                ..Default::default()
+            };
+            let ascending = matches!(sort_key, SortKey::Name | SortKey::Type);
+            let limit = (limit > 0).then_some(limit as u32);
+            let stdout = std::io::stdout();
+            let mut out = std::io::BufWriter::new(stdout.lock());
+            if json {
+                // Emit the array incrementally; matches `to_string_pretty` of the whole list.
+                let mut first = true;
+                db.stream_projects(&filter, sort_key, ascending, limit, |r| {
+                    let mut item = list_item(&db, &r, redactor)?;
+                    if !columns.is_empty() {
+                        item = columns
+                            .iter()
+                            .map(|c| (c.clone(), column_json(&item, c).clone()))
+                            .collect::<serde_json::Map<_, _>>()
+                            .into();
+                    }
+                    let item = serde_json::to_string_pretty(&item)?;
+                    write!(out, "{}", if first { "[\n" } else { ",\n" })?;
+                    first = false;
+                    write!(out, "  {}", item.replace('\n', "\n  "))?;
+                    Ok(())
+                })?;
+                writeln!(out, "{}", if first { "[]" } else { "\n]" })?;
+            } else if csv {
+                let mut w = csv::Writer::from_writer(out);
+                if columns.is_empty() {
+                    w.write_record(CSV_COLUMNS)?;
+                } else {
+                    w.write_record(&columns)?;
+                }
+                db.stream_projects(&filter, sort_key, ascending, limit, |r| {
+                    if columns.is_empty() {
+                        w.write_record(csv_record(&scrub(r)))?;
+                    } else {
+                        let item = list_item(&db, &r, redactor)?;
+                        w.write_record(columns.iter().map(|c| column_text(&item, c)))?;
+                    }
+                    Ok(())
+                })?;
+                w.flush()?;
+                return Ok(());
+            } else if let Some(template) = &template {
+                db.stream_projects(&filter, sort_key, ascending, limit, |r| {
+                    let item = list_item(&db, &r, redactor)?;
+                    writeln!(out, "{}", template.render(&item))?;
+                    Ok(())
+                })?;
+            } else if !columns.is_empty() {
+                db.stream_projects(&filter, sort_key, ascending, limit, |r| {
+                    let item = list_item(&db, &r, redactor)?;
+                    let values: Vec<String> =
+                        columns.iter().map(|c| column_text(&item, c)).collect();
+                    writeln!(out, "{}", values.join("\t"))?;
+                    Ok(())
+                })?;
+            } else {
+                db.stream_projects(&filter, sort_key, ascending, limit, |r| {
+                    let r = scrub(r);
+                    if show_loc {
+                        writeln!(
+                            out,
+                            "{:<24}  {:<6}  {:>10}  {:>8}  {}",
+                            truncate(&r.name, 24),
+                            r.project_type.clone().unwrap_or_else(|| "-".into()),
+                            r.size_bytes.unwrap_or_default(),
+                            r.loc.unwrap_or_default(),
+                            r.path
+                        )?;
+                    } else {
+                        writeln!(
+                            out,
+                            "{:<24}  {:<6}  {:>10}  {}",
+                            truncate(&r.name, 24),
+                            r.project_type.clone().unwrap_or_else(|| "-".into()),
+                            r.size_bytes.unwrap_or_default(),
+                            r.path
+                        )?;
+                    }
+                    Ok(())
+                })?;
+            }
+            out.flush()?;
+        }
+        Commands::Logs { lines, redact } => {
+            let cfg = ConfigStore::load()?;
+            let path = indexer::logging::log_path(&cfg.logging)?;
+            let redactor = if redact {
+                Some(diagnose::log_redactor(&Db::open_default()?, &cfg)?)
+            } else {
+                None
+            };
+            for line in indexer::logging::tail_lines(&path, lines)? {
+                match &redactor {
+                    Some(redactor) => println!("{}", redactor.text(&line)),
+                    None => println!("{line}"),
+                }
+            }
+        }
+        Commands::Diagnose { bundle, db } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            match bundle {
+                Some(out) => {
+                    let out = PathBuf::from(shellexpand::tilde(&out).as_ref());
+                    diagnose::write_bundle(&db, &cfg, &out)?;
+                    eprintln!("Wrote {}", out.display());
+                }
+                None => {
+                    let report = diagnose::collect(&db, &cfg, &Redactor::new())?;
+                    println!("{}", serde_json::to_string_pretty(&report)?);
+                }
+            }
+        }
+        Commands::Field {
+            action: FieldAction::List { project, json, db },
+        } => {
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            let fields = db.all_fields(p.id)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&fields)?);
+            } else {
+                for (k, v) in fields {
+                    println!("{k:<24}  {v}");
+                }
+            }
+        }
+        Commands::Field {
+            action:
+                FieldAction::Set {
+                    project,
+                    key,
+                    value,
+                    string,
+                    db,
+                },
+        } => {
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            let value = if string {
+                FieldValue::Text(value)
+            } else {
+                FieldValue::parse(&value)
+            };
+            db.set_project_field(p.id, &key, &value)?;
+        }
+        Commands::Field {
+            action: FieldAction::Rm { project, key, db },
+        } => {
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            if !db.remove_project_field(p.id, &key)? {
+                anyhow::bail!("no field {key:?} on {}", p.name);
+            }
+        }
+        Commands::Action {
+            action: ActionCmd::List { project, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            for a in actions::actions_for(&cfg, &p) {
+                println!("{:<16}  {}", a.name, a.command.join(" "));
+            }
+        }
+        Commands::Action {
+            action: ActionCmd::Run { project, name, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            let run = actions::run_action(&db, &cfg, &p, &name, |_, stream, line| match stream {
+                actions::OutputStream::Stdout => println!("{line}"),
+                actions::OutputStream::Stderr => eprintln!("{line}"),
+            })?;
+            match run.status {
+                actions::RunStatus::Succeeded => {}
+                actions::RunStatus::TimedOut => anyhow::bail!(
+                    "{name} timed out (run {}); raise actions.{name}.timeout_secs",
+                    run.id
+                ),
+                _ => anyhow::bail!(
+                    "{name} failed with exit code {} (run {})",
+                    run.exit_code.map_or("-".into(), |c| c.to_string()),
+                    run.id
+                ),
+            }
+        }
+        Commands::Action {
+            action: ActionCmd::Runs { project, json, db },
+        } => {
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            let runs = db.action_runs(p.id)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&runs)?);
+            } else {
+                for r in &runs {
+                    let code = r.exit_code.map_or("-".into(), |c| c.to_string());
+                    println!(
+                        "{:>5}  {:<16} {:<10} exit {code:<4} {}s ago",
+                        r.id,
+                        r.action,
+                        r.status.as_str(),
+                        now() - r.started_at
+                    );
+                }
+            }
+        }
+        Commands::Action {
+            action: ActionCmd::Show { run, json, db },
+        } => {
+            let db = open_db(db)?;
+            let r = db
+                .action_run(run)?
+                .ok_or_else(|| anyhow::anyhow!("no action run {run}"))?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&r)?);
+            } else {
+                println!("$ {}", r.command.join(" "));
+                print!("{}", r.stdout);
+                if !r.stderr.is_empty() {
+                    println!("--- stderr");
+                    print!("{}", r.stderr);
+                }
+            }
+        }
+        Commands::Search {
+            query,
+            limit,
+            json,
+            db,
+        } => {
+            let hits = backend(db)?.search(&query, limit)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&hits)?);
+            } else {
+                let color = std::io::IsTerminal::is_terminal(&std::io::stdout());
+                for h in &hits {
+                    println!("{}  {}", mark(&h.name, color), mark(&h.path, color));
+                    if let Some(readme) = &h.readme {
+                        println!("    README: {}", mark(readme, color).replace('\n', " "));
+                    }
+                }
+            }
+        }
+        Commands::Commits {
+            action:
+                CommitsAction::Search {
+                    query,
+                    limit,
+                    json,
+                    db,
+                },
+        } => {
+            let hits = open_db(db)?.search_commits(&query, limit)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&hits)?);
+            } else {
+                let color = std::io::IsTerminal::is_terminal(&std::io::stdout());
+                for h in &hits {
+                    println!(
+                        "{:<20} {:.10}  {}  {}",
+                        truncate(&h.project, 20),
+                        h.sha,
+                        h.date,
+                        mark(&h.subject, color)
+                    );
+                }
+            }
+        }
+        Commands::Git {
+            action:
+                GitAction::Branches {
+                    filter,
+                    stale_days,
+                    many,
+                    flagged,
+                    json,
+                    db,
+                },
+        } => {
+            let db = open_db(db)?;
+            let filter = ProjectFilter {
+                query: filter.as_deref().map(Query::parse).transpose()?,
+                ..Default::default()
+            };
+            let mut repos = db.branch_overview(&filter, stale_days, many, now())?;
+            if flagged {
+                repos.retain(|r| r.off_default || r.many_stale);
+            }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&repos)?);
+            } else {
+                for r in &repos {
+                    let mut notes = Vec::new();
+                    if r.off_default {
+                        notes.push(format!(
+                            "not on {}",
+                            r.default_branch.as_deref().unwrap_or("-")
+                        ));
+                    }
+                    if r.many_stale {
+                        notes.push(format!("{} stale branches", r.stale_branches.len()));
+                    }
+                    if r.dirty == Some(true) {
+                        notes.push("uncommitted changes".into());
+                    }
+                    if let Some(ahead) = r.ahead.filter(|n| *n > 0) {
+                        notes.push(format!("{ahead} unpushed"));
+                    }
+                    println!(
+                        "{:<24} {:<24} {:>3} branch(es)  {}",
+                        truncate(&r.name, 24),
+                        truncate(r.branch.as_deref().unwrap_or("-"), 24),
+                        r.local_branches,
+                        notes.join(", ")
+                    );
+                }
+                let off = repos.iter().filter(|r| r.off_default).count();
+                let stale = repos.iter().filter(|r| r.many_stale).count();
+                eprintln!(
+                    "{} repo(s): {off} off their default branch, {stale} with {many}+ stale branches",
+                    repos.len()
+                );
+            }
+        }
+        Commands::Git {
+            action:
+                GitAction::Fetch {
+                    filter,
+                    jobs,
+                    json,
+                    db,
+                },
+        } => git_batch(FetchMode::Fetch, filter, jobs, json, db)?,
+        Commands::Git {
+            action:
+                GitAction::Pull {
+                    filter,
+                    jobs,
+                    json,
+                    db,
+                },
+        } => git_batch(FetchMode::Pull, filter, jobs, json, db)?,
+        Commands::Explain { path, json, db } => {
+            let db = open_db(db)?;
+            let cfg = ConfigStore::load()?;
+            let path = PathBuf::from(shellexpand::tilde(&path).to_string());
+            let ex = explain::explain(&db, &cfg, &path)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&ex)?);
+            } else {
+                println!("{}", ex.path);
+                println!("  root:      {}", ex.root.as_deref().unwrap_or("-"));
+                println!("  markers:");
+                for m in &ex.markers {
+                    let hit = if m.matched { "matched" } else { "-" };
+                    println!("    {:<10}  {:<18}  {hit}", m.project_type, m.marker);
+                }
+                let types = match ex.matched_types.split_first() {
+                    None => "none (not a project)".to_string(),
+                    Some((first, [])) => first.clone(),
+                    Some((first, rest)) => format!("{first} (also matches {})", rest.join(", ")),
+                };
+                println!("  type:      {types}");
+                println!("  ignored:   {}", ex.ignored.as_deref().unwrap_or("no"));
+                let shadowed = match (&ex.shadowed_by, ex.workspace_member) {
+                    (None, _) => "no".to_string(),
+                    (Some(p), false) => format!("by project {p}; the scan does not look inside it"),
+                    (Some(p), true) => {
+                        format!("by project {p}, which declares it a workspace member")
+                    }
+                };
+                println!("  shadowed:  {shadowed}");
+                match ex.indexed_id {
+                    Some(id) => println!("  indexed:   yes (id {id})"),
+                    None => println!("  indexed:   no"),
+                }
+            }
+        }
+        Commands::Merge { keep, remove, db } => {
+            let db = open_db(db)?;
+            let keep = resolve_project(&db, &keep)?;
+            let remove = resolve_project(&db, &remove)?;
+            let report = db.merge_projects(keep.id, remove.id)?;
+            eprintln!(
+                "Merged {} into {}: {} field(s), {} custom field(s), {} open(s) carried over",
+                remove.path, keep.path, report.fields, report.custom_fields, report.opens
+            );
+        }
+        Commands::Forget { project, db } => {
+            let db = open_db(db)?;
+            let p = resolve_project(&db, &project)?;
+            db.delete_project(p.id)?;
+            eprintln!("Forgot {} (run `undo` to restore it)", p.path);
+        }
+        Commands::Undo { list, db } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            if list {
+                db.prune_journal(cfg.journal.retention_days)?;
+                for e in db.journal(20)? {
+                    let state = if e.undone_at.is_some() {
+                        " (undone)"
+                    } else {
+                        ""
+                    };
+                    println!("{:>5}  {:<6} {}{state}", e.id, e.kind, e.summary);
+                }
+            } else {
+                match db.undo_last(cfg.journal.retention_days)? {
+                    Some(e) => eprintln!("Undid {}: {}", e.kind, e.summary),
+                    None => eprintln!("Nothing to undo"),
+                }
+            }
+        }
+        Commands::Db {
+            action: DbAction::Backup { db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let path = open_db(db)?.backup(cfg.backup.keep)?;
+            eprintln!("Backed up to {}", path.display());
+        }
+        Commands::Db {
+            action: DbAction::Backups { db },
+        } => {
+            let db = open_db(db)?;
+            for b in backup::list_backups(&backup::backup_dir(&db.path))? {
+                // File names carry the UTC timestamp
+                println!("{:>10}  {}", b.size_bytes, b.path.display());
+            }
+        }
+        Commands::Db {
+            action: DbAction::Repair { dry_run, json, db },
+        } => {
+            let db = open_db(db)?;
+            let report = db.repair(dry_run)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else if report.is_clean() {
+                eprintln!("No problems found");
+            } else {
+                let pre = if dry_run { "(dry run) " } else { "" };
+                for (table, n) in &report.orphans {
+                    println!("{pre}Removed {n} orphaned row(s) from {table}");
+                }
+                for m in &report.merged {
+                    println!("{pre}Merged duplicate {} into {}", m.removed, m.kept);
+                }
+                for p in &report.paths_normalized {
+                    println!("{pre}Dropped the trailing slash from {p}");
+                }
+                for p in &report.names_fixed {
+                    println!("{pre}Named {p} after its directory");
+                }
+            }
+        }
+        Commands::Db {
+            action: DbAction::Restore { backup: name, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db_path = open_db(db)?.path;
+            let given = PathBuf::from(shellexpand::tilde(&name).to_string());
+            let file = if given.exists() {
+                given
+            } else {
+                backup::backup_dir(&db_path).join(&name)
+            };
+            backup::restore(&db_path, &file, &cfg.backup)?;
+            eprintln!("Restored {} from {}", db_path.display(), file.display());
+        }
+        Commands::Db {
+            action: DbAction::Encrypt { db },
+        } => {
+            let db_path = open_db(db)?.path;
+            let plaintext = encryption::encrypt(&db_path)?;
+            eprintln!("Encrypted {}", db_path.display());
+            if !plaintext.is_empty() {
+                eprintln!(
+                    "These earlier backups are still plaintext; delete them unless you need them:"
+                );
+                for b in plaintext {
+                    eprintln!("  {}", b.display());
+                }
+            }
+        }
+        Commands::Db {
+            action: DbAction::Decrypt { db },
+        } => {
+            let db_path = open_db(db)?.path;
+            encryption::decrypt(&db_path)?;
+            eprintln!("Decrypted {}", db_path.display());
+        }
+        Commands::Stats {
+            top,
+            hours: true,
+            days,
+            json,
+            db,
+        } => {
+            let db = open_db(db)?;
+            let stats = db.hours_stats(now() - days * 86_400, top)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print_hours(&stats, days);
+            }
+        }
+        Commands::Stats { top, json, db, .. } => {
+            let db = open_db(db)?;
+            let stats = db.disk_usage_stats(top)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                print_disk_usage(&stats);
+            }
+        }
+        Commands::Recommend {
+            what: RecommendAction::Cleanup { limit, json, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            let mut recs = db.recommend_cleanup(&cfg.cleanup)?;
+            recs.truncate(limit);
+            if json {
+                println!("{}", serde_json::to_string_pretty(&recs)?);
+            } else if recs.is_empty() {
+                println!("Nothing worth cleaning up");
+            } else {
+                for r in &recs {
+                    println!("{}", r.summary);
+                }
+                let total: i64 = recs.iter().map(|r| r.reclaim_bytes).sum();
+                println!("\n{} in total", cleanup::human_bytes(total));
+            }
+        }
+        Commands::System {
+            action: SystemAction::Scan { home, dry_run, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let homes: Vec<system::UserHome> = if !home.is_empty() {
+                home.iter()
+                    .map(|h| PathBuf::from(shellexpand::tilde(h).to_string()))
+                    .filter_map(|p| system::home_of(&p))
+                    .collect()
+            } else if !cfg.system.homes.is_empty() {
+                cfg.system
+                    .homes
+                    .iter()
+                    .filter_map(|p| system::home_of(p))
+                    .collect()
+            } else {
+                system::user_homes(&system::home_base())
+            };
+            if homes.is_empty() {
+                anyhow::bail!(
+                    "no home directories found under {}",
+                    system::home_base().display()
+                );
+            }
+            let db = open_system_db(db)?;
+            let opts = ScanOptions {
+                dry_run,
+                ..Default::default()
+            };
+            let counts = system::scan_homes(&db, &cfg, &opts, &homes)?;
+            for (h, n) in homes.iter().zip(counts) {
+                println!("{:<16}  {:>6} projects  {}", h.user, n, h.path.display());
+            }
+        }
+        Commands::System {
+            action:
+                SystemAction::Report {
+                    stale_days,
+                    json,
+                    db,
+                },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_system_db(db)?;
+            let days = stale_days.unwrap_or(cfg.system.stale_after_days);
+            let owners = db.owner_summary(days)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&owners)?);
+            } else {
+                println!(
+                    "{:<16}  {:>8}  {:>14}  {:>14}  {:>6}  {:>14}",
+                    "owner", "projects", "size", "artifacts", "stale", "stale size"
+                );
+                for o in &owners {
+                    println!(
+                        "{:<16}  {:>8}  {:>14}  {:>14}  {:>6}  {:>14}",
+                        truncate(&o.owner, 16),
+                        o.projects,
+                        o.size_bytes,
+                        o.artifact_bytes,
+                        o.stale_projects,
+                        o.stale_bytes
+                    );
+                }
+            }
+        }
+        Commands::Watch { interval, db } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            let minutes = interval.unwrap_or(cfg.watch.interval_minutes).max(1);
+            let daemon = std::sync::Arc::new(daemon::Daemon::default());
+            daemon::listen(daemon.clone(), &daemon::default_socket()?, &db.path)?;
+            indexer::serve::start_from_config(&cfg.serve, &db.path);
+            eprintln!(
+                "Watching {} root(s), rescanning every {minutes} min; control with `daemon`",
+                cfg.roots.len()
+            );
+            daemon.run(
+                &db,
+                &ScanOptions::default(),
+                &AnalyzerRegistry::with_builtins(),
+                std::time::Duration::from_secs(u64::from(minutes) * 60),
+            )?;
+        }
+        Commands::Autostart { action } => match action {
+            AutostartAction::Enable { mode } => {
+                let exe = std::env::current_exe()?.canonicalize()?;
+                let status = autostart::enable(&exe, mode.into())?;
+                print_autostart(&status);
+            }
+            AutostartAction::Disable => {
+                if autostart::disable()? {
+                    eprintln!("Autostart disabled");
+                } else {
+                    eprintln!("Autostart was not enabled");
+                }
+            }
+            AutostartAction::Status { json } => {
+                let status = autostart::status()?;
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&status)?);
+                } else {
+                    print_autostart(&status);
+                }
+            }
+        },
+        Commands::Daemon { action } => {
+            let mut client = daemon::DaemonClient::connect()?
+                .context("no daemon is running; start one with `project-browser watch`")?;
+            let req = match &action {
+                DaemonAction::Status { .. } => daemon::Request::Status,
+                DaemonAction::Pause => daemon::Request::Pause,
+                DaemonAction::Resume => daemon::Request::Resume,
+                DaemonAction::Rescan { root, priority } => daemon::Request::Rescan {
+                    root: shellexpand::tilde(root).to_string().into(),
+                    priority: priority.map(Into::into),
+                },
+                DaemonAction::Query { query, limit, .. } => daemon::Request::Query {
+                    query: query.clone(),
+                    limit: *limit,
+                },
+            };
+            let result = client.call(&req)?;
+            match action {
+                DaemonAction::Query { json: false, .. } => {
+                    let rows: Vec<indexer::ProjectRecord> = serde_json::from_value(result)?;
+                    for r in &rows {
+                        println!("{:<32}  {}", truncate(&r.name, 32), r.path);
+                    }
+                }
+                DaemonAction::Status { json: false } => {
+                    let st: daemon::DaemonStatus = serde_json::from_value(result)?;
+                    let state = match (st.paused, st.scanning) {
+                        (_, true) => "scanning",
+                        (true, false) => "paused",
+                        (false, false) => "idle",
+                    };
+                    println!("pid {}: {state}", st.pid);
+                    for q in &st.queued {
+                        println!("  queued: {} ({:?})", q.root.display(), q.priority);
+                    }
+                    if let Some(at) = st.last_scan_at {
+                        let projects = st.last_scan_projects.unwrap_or(0);
+                        println!("  last scan: {projects} project(s), {}s ago", now() - at);
+                    }
+                    if let Some(err) = &st.last_error {
+                        println!("  last error: {err}");
+                    }
+                    if let Some(reason) = &st.deferred {
+                        println!("  periodic rescan deferred: {reason}");
+                    }
+                    if let Some(at) = st.next_scan_at {
+                        println!("  next rescan in {}s", (at - now()).max(0));
+                    }
+                }
+                DaemonAction::Status { json: true } | DaemonAction::Query { json: true, .. } => {
+                    println!("{}", serde_json::to_string_pretty(&result)?);
+                }
+                DaemonAction::Pause | DaemonAction::Resume | DaemonAction::Rescan { .. } => {
+                    let st: daemon::DaemonStatus = serde_json::from_value(result)?;
+                    eprintln!(
+                        "{}, {} root(s) queued",
+                        if st.paused { "Paused" } else { "Running" },
+                        st.queued.len()
+                    );
+                }
+            }
+        }
+        Commands::Rpc { db } => {
+            let server = RpcServer::new(open_db(db)?, ConfigStore::load()?);
+            server.serve(std::io::stdin().lock(), std::io::stdout().lock())?;
+        }
+        Commands::Roots {
+            action: RootsAction::Suggest { add, json },
+        } => {
+            let mut cfg = ConfigStore::load()?;
+            let suggestions = suggest_home_roots(&cfg)?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&suggestions)?);
+            } else if suggestions.is_empty() {
+                eprintln!("No projects found in the usual places; add roots to the config by hand");
+            } else {
+                for s in &suggestions {
+                    let count = format!(
+                        "{}{}",
+                        s.estimated_projects,
+                        if s.truncated { "+" } else { "" }
+                    );
+                    let note = if s.configured { "  (configured)" } else { "" };
+                    println!("{:>6} projects  {}{note}", count, s.path.display());
+                }
+            }
+            if add {
+                let added = suggestions
+                    .iter()
+                    .filter(|s| cfg.add_root(s.path.clone()))
+                    .count();
+                if added > 0 {
+                    ConfigStore::save(&cfg)?;
+                }
+                eprintln!("Added {added} root(s)");
+            }
+        }
+        Commands::UiState {
+            action: UiStateAction::Export { db },
+        } => {
+            let db = open_db(db)?;
+            println!("{}", serde_json::to_string_pretty(&db.ui_state(&[])?)?);
+        }
+        Commands::UiState {
+            action: UiStateAction::Import { file, db },
+        } => {
+            let db = open_db(db)?;
+            let text = if file == "-" {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                std::fs::read_to_string(&file)?
+            };
+            let state: serde_json::Map<String, serde_json::Value> = serde_json::from_str(&text)?;
+            for (key, value) in &state {
+                db.set_ui_state(key, value)?;
+            }
+            eprintln!("Imported {} UI state value(s)", state.len());
+        }
+        Commands::Sbom {
+            project,
+            all,
+            format,
+            out_dir,
+            db,
+        } => {
+            let db = open_db(db)?;
+            let format = match format {
+                SbomFormatArg::Cyclonedx => SbomFormat::CycloneDx,
+                SbomFormatArg::SpdxJson => SbomFormat::SpdxJson,
+            };
+            let targets = resolve_targets(&db, project, all)?;
+            let mut docs = Vec::new();
+            for p in &targets {
+                let deps = db.dependencies(p.id)?;
+                let doc = sbom::render(format, p, &deps);
+                if let Some(dir) = &out_dir {
+                    let dir = std::path::PathBuf::from(shellexpand::tilde(dir).to_string());
+                    std::fs::create_dir_all(&dir)?;
+                    let file = dir.join(format!("{}-{}.{}", p.name, p.id, format.extension()));
+                    std::fs::write(&file, serde_json::to_string_pretty(&doc)?)?;
+                    eprintln!("Wrote {} ({} components)", file.display(), deps.len());
+                } else {
+                    docs.push(doc);
+                }
+            }
+            if out_dir.is_none() {
+                if all {
+                    println!("{}", serde_json::to_string_pretty(&docs)?);
+                } else if let Some(doc) = docs.first() {
+                    println!("{}", serde_json::to_string_pretty(doc)?);
+                }
+            }
+        }
+        Commands::Bench {
+            action:
+                BenchAction::Seed {
+                    projects,
+                    tree,
+                    git_ratio,
+                    git_commits,
+                    rows,
+                    db,
+                    seed,
+                },
+        } => {
+            if tree.is_none() && db.is_none() {
+                anyhow::bail!("nothing to do: pass --tree and/or --db");
+            }
+            if let Some(tree) = tree {
+                let dir = std::path::PathBuf::from(shellexpand::tilde(&tree).to_string());
+                std::fs::create_dir_all(&dir)?;
+                let spec = indexer::fixtures::FixtureSpec {
+                    projects,
+                    seed,
+                    git_ratio,
+                    git_commits,
+                    ..Default::default()
+                };
+                let generated = indexer::fixtures::generate(&dir, &spec)?;
+                let files: usize = generated.iter().map(|p| p.files).sum();
+                eprintln!(
+                    "Wrote {} project(s), {files} file(s) under {}",
+                    generated.len(),
+                    dir.display()
+                );
+            }
+            if let Some(path) = db {
+                let db = open_db(Some(path))?;
+                indexer::bench::seed_db(&db, rows, seed)?;
+                eprintln!("Seeded {rows} row(s) into {}", db.path.display());
+            }
+        }
+        Commands::SelfCmd {
+            action: SelfAction::Update { check, json },
+        } => {
+            use indexer::update;
+            let release = update::latest_release()?;
+            let status = update::check(env!("CARGO_PKG_VERSION"), &release)?;
+            if check {
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&status)?);
+                } else if status.update_available {
+                    println!(
+                        "Update available: {} -> {} ({})",
+                        status.current, status.latest, status.url
+                    );
+                } else {
+                    println!("Up to date ({})", status.current);
+                }
+                return Ok(());
+            }
+            if !status.update_available {
+                println!("Already on the latest version ({})", status.current);
+                return Ok(());
+            }
+            let asset = update::platform_asset("project-browser");
+            eprintln!("Downloading {asset} {}...", status.latest);
+            let bytes = update::download_verified(&release, &asset)?;
+            let path = update::replace_current_exe(&bytes)?;
+            println!(
+                "Updated {} from {} to {}",
+                path.display(),
+                status.current,
+                status.latest
+            );
+        }
+        Commands::Reconcile { file, json, db } => {
+            let text = if file == "-" {
+                std::io::read_to_string(std::io::stdin())?
+            } else {
+                let path = shellexpand::tilde(&file).to_string();
+                std::fs::read_to_string(&path).with_context(|| format!("reading {path}"))?
+            };
+            let db = open_db(db)?;
+            let report = db.reconcile_remotes(&reconcile::parse_remote_list(&text))?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("Cloned ({}):", report.cloned.len());
+                for c in &report.cloned {
+                    println!("  {}  {}", c.url, c.paths.join(", "));
+                }
+                println!("Missing ({}):", report.missing.len());
+                for url in &report.missing {
+                    println!("  {url}");
+                }
+                println!("Not in the list ({}):", report.unlisted.len());
+                for r in &report.unlisted {
+                    println!(
+                        "  {}  {}",
+                        r.path,
+                        r.remote_url.as_deref().unwrap_or("(no remote)")
+                    );
+                }
+            }
+        }
+        Commands::Audit {
+            report: AuditReport::Secrets { json, db },
+        } => {
+            let db = open_db(db)?;
+            let rows = db.secrets_audit()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else if rows.is_empty() {
+                println!("No likely secrets found (is `secrets.enabled` set in config?)");
+            } else {
+                for r in rows {
+                    println!(
+                        "{:<24}  {:>3}  {}",
+                        truncate(&r.name, 24),
+                        r.findings.len(),
+                        r.path
+                    );
+                    for f in r.findings {
+                        println!("    {:<16}  {}", f.kind.as_str(), f.path);
+                    }
+                }
+            }
+        }
+        Commands::Audit {
+            report: AuditReport::UnbackedUp { json, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            db.check_backups(&cfg.offsite)?;
+            let rows = db.unbacked_up()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else if rows.is_empty() {
+                println!("Every project is backed up or pushed");
+            } else {
+                for r in rows {
+                    println!(
+                        "{:<24}  {:<40}  {}",
+                        truncate(&r.project.name, 24),
+                        r.reasons.join(", "),
+                        r.project.path
+                    );
+                }
+            }
+        }
+        Commands::Audit {
+            report: AuditReport::Toolchains { eol, json, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            let mut rows = db.toolchain_audit(&cfg.toolchain_eol)?;
+            if eol {
+                rows.retain(|r| r.end_of_life);
+            }
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else if rows.is_empty() {
+                println!(
+                    "{}",
+                    if eol {
+                        "No project pins an end-of-life toolchain"
+                    } else {
+                        "No toolchain pins indexed yet (they are read during scans)"
+                    }
+                );
+            } else {
+                for r in rows {
+                    let status = match (&r.eol_date, r.end_of_life) {
+                        (Some(date), true) => format!("EOL since {date}"),
+                        (None, true) => "EOL".to_string(),
+                        (Some(date), false) => format!("supported until {date}"),
+                        (None, false) => String::new(),
+                    };
+                    println!(
+                        "{:<13}  {:<10}  {:>4} projects  {}",
+                        r.language,
+                        r.version.as_deref().unwrap_or("(channel)"),
+                        r.projects.len(),
+                        status
+                    );
+                    for p in r.projects {
+                        println!("    {:<24}  {}", truncate(&p.name, 24), p.pins.join(", "));
+                    }
+                }
+            }
+        }
+        Commands::Audit {
+            report: AuditReport::Policies { json, db },
+        } => {
+            let cfg = ConfigStore::load()?;
+            let db = open_db(db)?;
+            db.evaluate_policies(&cfg.policies)?;
+            let rows = db.policy_violations()?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&rows)?);
+            } else if cfg.policies.is_empty() {
+                println!("No retention policies configured (see `policies` in the config)");
+            } else if rows.is_empty() {
+                println!("No project violates a retention policy");
+            } else {
+                let now = now();
+                let mut policy = "";
+                for r in &rows {
+                    if r.policy != policy {
+                        policy = &r.policy;
+                        let query = cfg.policies.get(policy).map_or("", |p| p.query.as_str());
+                        println!("{policy}  ({query})");
+                    }
+                    println!(
+                        "    {:<24}  {:>8}  {:>4}d  {}",
+                        truncate(&r.project.name, 24),
+                        cleanup::human_bytes(r.project.size_bytes.unwrap_or(0)),
+                        (now - r.first_seen_at) / 86_400,
+                        r.project.path
+                    );
+                }
+            }
+        }
+        Commands::Audit {
+            report:
+                AuditReport::Deps {
+                    project,
+                    all,
+                    json,
+                    db,
+                },
+        } => {
+            let db = open_db(db)?;
+            let targets = resolve_targets(&db, project, all)?;
+            let mut results = Vec::new();
+            for p in targets {
+                let summary = match audit_project(&p.dir(), p.project_type.as_deref()) {
+                    Ok(s) => s,
+                    Err(err) => {
+                        tracing::warn!(%err, path = %p.path, "dependency audit failed");
+                        None
+                    }
+                };
+                if let Some(summary) = &summary {
+                    db.upsert_dep_audit(p.id, summary)?;
+                }
+                results.push((p, summary));
+            }
+            if json {
+                let out = results
+                    .iter()
+                    .map(|(p, s)| {
+                        serde_json::json!({
+                            "id": p.id,
+                            "name": p.name,
+                            "path": p.path,
+                            "tool": s.as_ref().map(|s| s.tool),
+                            "vuln_count": s.as_ref().map(|s| s.vuln_count),
+                            "severity_max": s.as_ref().and_then(|s| s.severity_max),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                println!("{}", serde_json::to_string_pretty(&out)?);
+            } else {
+                for (p, s) in results {
+                    match s {
+                        Some(s) => println!(
+                            "{:<24}  {:<11}  {:>4}  {:<8}  {}",
+                            truncate(&p.name, 24),
+                            s.tool,
+                            s.vuln_count,
+                            s.severity_max.map(|v| v.as_str()).unwrap_or("-"),
+                            p.path
+                        ),
+                        None => println!(
+                            "{:<24}  {:<11}  {:>4}  {:<8}  {}",
+                            truncate(&p.name, 24),
+                            "skipped",
+                            "-",
+                            "-",
+                            p.path
+                        ),
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// `git fetch` / `git pull` over the repos matching `filter`, with progress on stderr.
+fn git_batch(
+    mode: FetchMode,
+    filter: Option<String>,
+    jobs: Option<usize>,
+    json: bool,
+    db: Option<String>,
+) -> Result<()> {
+    let cfg = ConfigStore::load()?;
+    let db = open_db(db)?;
+    let filter = ProjectFilter {
+        query: filter.as_deref().map(Query::parse).transpose()?,
+        ..Default::default()
+    };
+    let mut repos = Vec::new();
+    db.stream_projects(&filter, SortKey::Name, true, None, |r| {
+        if r.is_git_repo {
+            repos.push(r);
+        }
+        Ok(())
+    })?;
+    let total = repos.len();
+    let mut done = 0;
+    let results = fetch::fetch_projects(&db, &repos, mode, jobs.unwrap_or(cfg.concurrency), |r| {
+        done += 1;
+        if !json {
+            let reason = r.message.as_deref().unwrap_or("-");
+            let what = match (r.outcome, r.new_commits) {
+                (FetchOutcome::Updated, Some(n)) => format!("{n} new commit(s)"),
+                (FetchOutcome::Updated, None) => "updated".into(),
+                (FetchOutcome::UpToDate, _) => "up to date".into(),
+                (FetchOutcome::Skipped, _) => "skipped (no remote)".into(),
+                (FetchOutcome::Conflict, _) => format!("conflict: {reason}"),
+                (FetchOutcome::Failed, _) => format!("failed: {reason}"),
+            };
+            eprintln!("[{done}/{total}] {}: {what}", r.name);
+        }
+    })?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&results)?);
+    } else {
+        let count = |o| results.iter().filter(|r| r.outcome == o).count();
+        println!(
+            "{} updated, {} up to date, {} conflict(s), {} failed, {} skipped",
+            count(FetchOutcome::Updated),
+            count(FetchOutcome::UpToDate),
+            count(FetchOutcome::Conflict),
+            count(FetchOutcome::Failed),
+            count(FetchOutcome::Skipped)
+        );
+        for r in results
+            .iter()
+            .filter(|r| matches!(r.outcome, FetchOutcome::Updated | FetchOutcome::Conflict))
+        {
+            let label = match r.outcome {
+                FetchOutcome::Updated => "new commits",
+                _ => "conflict",
+            };
+            println!("  {label:<11}  {}", r.path);
+        }
+    }
+    Ok(())
+}
+
+/// Matched ranges in bold on a terminal, in [brackets] otherwise.
+fn mark(h: &indexer::search::Highlighted, color: bool) -> String {
+    let (open, close) = if color {
+        ("\x1b[1m", "\x1b[0m")
+    } else {
+        ("[", "]")
+    };
+    let mut out = String::new();
+    let mut spans = h.spans.iter().peekable();
+    for (i, c) in h.text.chars().enumerate() {
+        let i = i as u32;
+        if spans.peek().is_some_and(|s| s.0 == i) {
+            out.push_str(open);
+        }
+        out.push(c);
+        if spans.peek().is_some_and(|s| s.1 == i + 1) {
+            out.push_str(close);
+            spans.next();
+        }
+    }
+    out
+}
+
+/// `--db`, else the system-wide database (created on first use).
+fn open_system_db(db: Option<String>) -> Result<Db> {
+    if db.is_some() {
+        return open_db(db);
+    }
+    let path = system::system_db_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("creating {} (run as an administrator?)", dir.display()))?;
+    }
+    Db::open(&path)
+}
+
+/// The running `watch` daemon, unless `db` overrides the database it serves.
+fn backend(db: Option<String>) -> Result<Backend> {
+    match db {
+        Some(_) => Ok(Backend::Local(open_db(db)?)),
+        None => Backend::connect(),
+    }
+}
+
+fn open_db(db: Option<String>) -> Result<Db> {
+    if let Some(path) = db {
+        let p = shellexpand::tilde(&path).to_string();
+        Db::open(std::path::Path::new(&p))
+    } else {
+        Db::open_default()
+    }
+}
+
+/// Either every indexed project (`--all`) or the single project named by path or name.
+fn resolve_targets(
+    db: &Db,
+    project: Option<String>,
+    all: bool,
+) -> Result<Vec<indexer::ProjectRecord>> {
+    if all {
+        return db.query_projects(&ProjectFilter::default(), SortKey::Name, true, 0, u32::MAX);
+    }
+    Ok(vec![resolve_project(db, &project.unwrap_or_default())?])
+}
+
+fn resolve_project(db: &Db, key: &str) -> Result<indexer::ProjectRecord> {
+    let expanded = shellexpand::tilde(key).to_string();
+    match db.find_project(&expanded)? {
+        Some(p) => Ok(p),
+        None => anyhow::bail!("project not found in index: {key}"),
+    }
+}
+
+/// `value` as a bar of `#` scaled so that `max` fills `BAR_WIDTH`.
+fn bar(value: i64, max: i64) -> String {
+    const BAR_WIDTH: usize = 30;
+    let n = if max > 0 {
+        ((value.max(0) as f64 / max as f64) * BAR_WIDTH as f64).round() as usize
+    } else {
+        0
+    };
+    format!("{:<BAR_WIDTH$}", "#".repeat(n.min(BAR_WIDTH)))
+}
+
+fn print_scan_diff(diff: &indexer::preview::ScanDiff) {
+    let show = |v: &serde_json::Value| match v {
+        serde_json::Value::Null => "-".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    println!("Would add {} project(s)", diff.added.len());
+    for p in &diff.added {
+        println!("  + {:<10}  {}", p.project_type, p.path);
+    }
+    println!("Would update {} project(s)", diff.updated.len());
+    for u in &diff.updated {
+        println!("  ~ {}", u.path);
+        for c in &u.changes {
+            println!(
+                "      {:<16}  {} -> {}",
+                c.field,
+                show(&c.old),
+                show(&c.new)
+            );
+        }
+    }
+    println!(
+        "No longer found: {} project(s) (kept in the index; `forget` removes them)",
+        diff.missing.len()
+    );
+    for p in &diff.missing {
+        println!("  - {}", p.path);
+    }
+    println!("{} unchanged", diff.unchanged);
+}
+
+fn print_hours(stats: &indexer::hours::HoursStats, days: i64) {
+    if stats.sessions == 0 {
+        println!("No commits in the last {days} days (is the `hours` analyzer enabled?)");
+        return;
+    }
+    println!(
+        "~{:.0} hours over {} sessions in the last {days} days\n",
+        stats.hours, stats.sessions
+    );
+    // Bars in tenths of an hour
+    let tenths = |h: f64| (h * 10.0).round() as i64;
+    for (title, rows) in [("By month", &stats.by_month), ("By type", &stats.by_type)] {
+        println!("{title}");
+        let max = rows.iter().map(|r| tenths(r.hours)).max().unwrap_or(0);
+        for r in rows {
+            println!(
+                "{:<16}  {}  {:>6.1} h  {:>5} project(s)",
+                truncate(r.name.as_deref().unwrap_or("unknown"), 16),
+                bar(tenths(r.hours), max),
+                r.hours,
+                r.projects
+            );
+        }
+        println!();
+    }
+    println!("Top projects");
+    let max = stats.projects.first().map_or(0, |p| tenths(p.hours));
+    for p in &stats.projects {
+        println!(
+            "{:<16}  {}  {:>6.1} h  {:>4} session(s)  {}",
+            truncate(&p.name, 16),
+            bar(tenths(p.hours), max),
+            p.hours,
+            p.sessions,
+            p.path
+        );
+    }
+}
+
+fn print_disk_usage(stats: &indexer::stats::DiskUsageStats) {
+    use cleanup::human_bytes;
+    let total = stats.source_bytes + stats.artifact_bytes;
+    println!(
+        "{} projects, {} ({} source, {} build/dependency output)\n",
+        stats.projects,
+        human_bytes(total),
+        human_bytes(stats.source_bytes),
+        human_bytes(stats.artifact_bytes)
+    );
+    for (label, bytes) in [
+        ("source", stats.source_bytes),
+        ("artifacts", stats.artifact_bytes),
+    ] {
+        println!(
+            "{label:<16}  {}  {:>8}",
+            bar(bytes, total),
+            human_bytes(bytes)
+        );
+    }
+    for (title, rows) in [
+        ("By type", &stats.by_type),
+        ("By dominant language", &stats.by_language),
+    ] {
+        println!("\n{title}");
+        let max = rows.iter().map(|r| r.total_bytes()).max().unwrap_or(0);
+        for r in rows {
+            println!(
+                "{:<16}  {}  {:>8}  {:>5} project(s)",
+                truncate(r.name.as_deref().unwrap_or("unknown"), 16),
+                bar(r.total_bytes(), max),
+                human_bytes(r.total_bytes()),
+                r.projects
+            );
+        }
+    }
+    if !stats.by_asset.is_empty() {
+        println!("\nAssets");
+        let max = stats.by_asset.first().map_or(0, |a| a.bytes);
+        for a in &stats.by_asset {
+            println!(
+                "{:<16}  {}  {:>8}  {:>5} file(s)",
+                a.category,
+                bar(a.bytes, max),
+                human_bytes(a.bytes),
+                a.files
+            );
+        }
+    }
+    if stats.ml_bytes > 0 {
+        println!(
+            "\nML artifacts: {} (checkpoints, datasets, runs, environments)",
+            human_bytes(stats.ml_bytes)
+        );
+    }
+    println!("\nLargest projects");
+    let max = stats
+        .largest
+        .first()
+        .and_then(|p| p.size_bytes)
+        .unwrap_or(0);
+    for p in &stats.largest {
+        let size = p.size_bytes.unwrap_or(0);
+        println!(
+            "{:<16}  {}  {:>8}  {}",
+            truncate(&p.name, 16),
+            bar(size, max),
+            human_bytes(size),
+            p.path
+        );
+    }
+}
+
+fn now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
+
+fn truncate(s: &str, width: usize) -> String {
+    if s.len() <= width {
+        s.to_string()
+    } else {
+        let mut t = s.chars().take(width.saturating_sub(1)).collect::<String>();
+        t.push('…');
+        t
+    }
+}
+
+const CSV_COLUMNS: [&str; 22] = [
+    "id",
+    "name",
+    "path",
+    "type",
+    "is_git_repo",
+    "size_bytes",
+    "files_count",
+    "last_edited_at",
+    "loc",
+    "loc_truncated",
+    "secrets_count",
+    "vuln_count",
+    "vuln_severity",
+    "has_tests",
+    "has_ci",
+    "ci_providers",
+    "last_commit_at",
+    "branch",
+    "remote_url",
+    "size_estimated",
+    "artifact_bytes",
+    "owner",
+];
+
+fn csv_record(r: &indexer::ProjectRecord) -> [String; 22] {
+    fn opt<T: ToString>(v: &Option<T>) -> String {
+        v.as_ref().map(|v| v.to_string()).unwrap_or_default()
+    }
+    [
+        r.id.to_string(),
+        r.name.clone(),
+        r.path.clone(),
+        opt(&r.project_type),
+        r.is_git_repo.to_string(),
+        opt(&r.size_bytes),
+        opt(&r.files_count),
+        opt(&r.last_edited_at),
+        opt(&r.loc),
+        r.loc_truncated.to_string(),
+        opt(&r.secrets_count),
+        opt(&r.vuln_count),
+        opt(&r.vuln_severity),
+        opt(&r.has_tests),
+        opt(&r.has_ci),
+        opt(&r.ci_providers),
+        opt(&r.last_commit_at),
+        opt(&r.branch),
+        opt(&r.remote_url),
+        r.size_estimated.to_string(),
+        opt(&r.artifact_bytes),
+        opt(&r.owner),
+    ]
+}
+
+fn project_json(db: &Db, r: &indexer::ProjectRecord) -> Result<serde_json::Value> {
+    Ok(serde_json::json!({
+        "id": r.id,
+        "name": r.name,
+        "path": r.path,
+        "type": r.project_type,
+        "is_git_repo": r.is_git_repo,
+        "size_bytes": r.size_bytes,
+        "size_estimated": r.size_estimated,
+        "files_count": r.files_count,
+        "last_edited_at": r.last_edited_at,
+        "loc": r.loc,
+        "loc_truncated": r.loc_truncated,
+        "has_secrets": r.has_secrets,
+        "secrets_count": r.secrets_count,
+        "vuln_count": r.vuln_count,
+        "vuln_severity": r.vuln_severity,
+        "has_tests": r.has_tests,
+        "test_files_count": r.test_files_count,
+        "notebooks_count": r.notebooks_count,
+        "has_ci": r.has_ci,
+        "ci_providers": r.ci_providers,
+        "has_devcontainer": r.has_devcontainer,
+        "has_dockerfile": r.has_dockerfile,
+        "has_compose": r.has_compose,
+        "has_nix_shell": r.has_nix_shell,
+        "has_tool_versions": r.has_tool_versions,
+        "source_files_count": r.source_files_count,
+        "avg_file_bytes": r.avg_file_bytes,
+        "max_depth": r.max_depth,
+        "top_level_modules": r.top_level_modules,
+        "asset_bytes": r.asset_bytes,
+        "ml_bytes": r.ml_bytes,
+        "last_commit_at": r.last_commit_at,
+        "branch": r.branch,
+        "remote_url": r.remote_url,
+        "git_dirty": r.git_dirty,
+        "parent_id": r.parent_id,
+        "child_count": r.child_count,
+        "artifact_bytes": r.artifact_bytes,
+        "owner": r.owner,
+        "machine": r.machine,
+        "volume_id": r.volume_id,
+        "analysis_skipped": r.analysis_skipped,
+        "fields": db.all_fields(r.id)?,
+    }))
+}
+
+/// A project's `list --json` row. Under `--redact` custom fields are left out, as free-form
+/// values (author names, client codes) cannot be told apart.
+fn list_item(
+    db: &Db,
+    r: &indexer::ProjectRecord,
+    redactor: Option<&Redactor>,
+) -> Result<serde_json::Value> {
+    let Some(redactor) = redactor else {
+        return project_json(db, r);
+    };
+    let mut item = project_json(db, &redactor.project(r))?;
+    if let Some(o) = item.as_object_mut() {
+        o.remove("fields");
+    }
+    Ok(item)
+}
+
+fn print_autostart(status: &AutostartStatus) {
+    let Some(mode) = status.mode else {
+        println!("Autostart: off");
+        return;
+    };
+    let mode = match mode {
+        AutostartMode::Watch => "watch",
+        AutostartMode::Daily => "daily scan",
+    };
+    let active = match status.active {
+        Some(true) => "active",
+        Some(false) => "not active",
+        None => "state unknown",
+    };
+    println!("Autostart: {mode} ({active})");
+    for file in &status.files {
+        println!("  {}", file.display());
+    }
+}
+
+/// A note on stderr when the index is older than `freshness.stale_after_days`.
+fn warn_if_stale(db: &Db) -> Result<()> {
+    let cfg = ConfigStore::load()?;
+    let info = db.last_scan_info(&cfg.freshness)?;
+    if let (true, Some(at)) = (info.stale, info.finished_at) {
+        let days = (now() - at) / 86_400;
+        eprintln!("warning: the index was last updated {days} days ago; run `scan` to refresh it");
+    }
+    if let Some(err) = &info.last_error {
+        eprintln!("warning: the last scan failed: {err}");
+    }
+    Ok(())
 }
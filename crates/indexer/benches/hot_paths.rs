@@ -0,0 +1,100 @@
+//! Hot-path benchmarks. Run with `cargo bench -p indexer -F bench`.
+//! `PB_BENCH_ROWS` overrides the seeded DB size (default 100k).
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use indexer::bench::seed_db;
+use indexer::fixtures::{generate, FixtureSpec};
+use indexer::query::Query;
+use indexer::scan::compute_metrics;
+use indexer::{
+    scan_roots_with, AnalyzerRegistry, AppConfig, Db, ProjectFilter, ScanOptions, SortKey,
+};
+
+const SEED: u64 = 0x5eed;
+
+fn scan(c: &mut Criterion) {
+    let dir = tempfile::tempdir().unwrap();
+    let projects = generate(
+        dir.path(),
+        &FixtureSpec {
+            projects: 500,
+            seed: SEED,
+            git_ratio: 0.0,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    let cfg = AppConfig {
+        roots: vec![dir.path().to_path_buf()],
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group("scan");
+    group.sample_size(10);
+    for (label, registry) in [
+        ("detect_metrics", AnalyzerRegistry::empty()),
+        ("with_analyzers", AnalyzerRegistry::with_builtins()),
+    ] {
+        group.bench_function(BenchmarkId::new("scan_one_root_500", label), |b| {
+            b.iter(|| {
+                let db_dir = tempfile::tempdir().unwrap();
+                let db = Db::open(&db_dir.path().join("bench.sqlite")).unwrap();
+                scan_roots_with(&db, &cfg, &ScanOptions::default(), &registry).unwrap()
+            })
+        });
+    }
+    group.finish();
+
+    let one = &projects[0].path;
+    c.bench_function("compute_metrics", |b| {
+        b.iter(|| compute_metrics(one, &cfg, false).unwrap())
+    });
+}
+
+fn query(c: &mut Criterion) {
+    let rows = std::env::var("PB_BENCH_ROWS")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(100_000);
+    let dir = tempfile::tempdir().unwrap();
+    let db = Db::open(&dir.path().join("bench.sqlite")).unwrap();
+    seed_db(&db, rows, SEED).unwrap();
+
+    let mut group = c.benchmark_group(format!("query_{rows}"));
+    for (label, sort) in [
+        ("recent", SortKey::Recent),
+        ("size", SortKey::Size),
+        ("name", SortKey::Name),
+    ] {
+        group.bench_function(BenchmarkId::new("query_projects", label), |b| {
+            b.iter(|| {
+                db.query_projects(&ProjectFilter::default(), sort, false, 0, 100)
+                    .unwrap()
+            })
+        });
+    }
+    group.bench_function("count_projects", |b| {
+        b.iter(|| db.count_projects(&ProjectFilter::default()).unwrap())
+    });
+    // Name/path substring search backs the UI search box
+    let search = ProjectFilter::search(Some("proj-0042"));
+    group.bench_function("search", |b| {
+        b.iter(|| {
+            db.query_projects(&search, SortKey::Recent, false, 0, 100)
+                .unwrap()
+        })
+    });
+    let advanced = ProjectFilter {
+        query: Some(Query::parse("type:rust loc>5000 edited<365d").unwrap()),
+        ..Default::default()
+    };
+    group.bench_function("query_language", |b| {
+        b.iter(|| {
+            db.query_projects(&advanced, SortKey::Recent, false, 0, 100)
+                .unwrap()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, scan, query);
+criterion_main!(benches);
@@ -0,0 +1,359 @@
+//! Quick actions (`actions` config): named commands such as `git pull` or `cargo check` run
+//! in a project's directory from the app or `action run`. Output is passed on line by line
+//! as it arrives and kept, capped, in `action_runs` for later inspection.
+//!
+//! Only configured actions run, each as one program with fixed arguments and no shell.
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::config::{ActionConfig, AppConfig};
+use crate::db::{Db, ProjectRecord};
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS action_runs (
+      id INTEGER PRIMARY KEY AUTOINCREMENT,
+      project_id INTEGER NOT NULL,
+      action TEXT NOT NULL,
+      -- JSON array: program and arguments
+      command TEXT NOT NULL,
+      started_at INTEGER NOT NULL,
+      finished_at INTEGER,
+      status TEXT NOT NULL,
+      exit_code INTEGER,
+      stdout TEXT NOT NULL DEFAULT '',
+      stderr TEXT NOT NULL DEFAULT '',
+      -- When the timeout kills it; a run still `running` after that was abandoned
+      deadline INTEGER,
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_action_runs_project ON action_runs(project_id, started_at);
+"#;
+
+/// Runs kept per project; older ones are deleted as new ones start
+const KEEP_RUNS: i64 = 20;
+
+/// Output kept per stream; the rest is still passed on but not stored
+const MAX_STORED_BYTES: usize = 256 * 1024;
+
+/// How long output is still collected after the command exits, for lines in flight
+const DRAIN: Duration = Duration::from_millis(300);
+
+/// Runs recorded before deadlines were (still `running` a day later are assumed dead)
+const LEGACY_DEADLINE_SECS: i64 = 86_400;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStream {
+    Stdout,
+    Stderr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Running,
+    /// Exited with status 0
+    Succeeded,
+    Failed,
+    /// Killed after `timeout_secs`
+    TimedOut,
+}
+
+impl RunStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RunStatus::Running => "running",
+            RunStatus::Succeeded => "succeeded",
+            RunStatus::Failed => "failed",
+            RunStatus::TimedOut => "timed_out",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "running" => RunStatus::Running,
+            "succeeded" => RunStatus::Succeeded,
+            "timed_out" => RunStatus::TimedOut,
+            _ => RunStatus::Failed,
+        }
+    }
+}
+
+/// One configured action offered for a project.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ActionInfo {
+    pub name: String,
+    pub command: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ActionRun {
+    pub id: i64,
+    pub project_id: i64,
+    pub action: String,
+    pub command: Vec<String>,
+    pub started_at: i64,
+    pub finished_at: Option<i64>,
+    pub status: RunStatus,
+    pub exit_code: Option<i32>,
+    /// Captured output, cut off after 256 KiB per stream
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// The actions `cfg` offers for `project`, by name.
+pub fn actions_for(cfg: &AppConfig, project: &ProjectRecord) -> Vec<ActionInfo> {
+    cfg.actions
+        .iter()
+        .filter(|(_, a)| !a.command.is_empty())
+        .filter(|(_, a)| a.applies_to(project.project_type.as_deref(), project.is_git_repo))
+        .map(|(name, a)| ActionInfo {
+            name: name.clone(),
+            command: a.command.clone(),
+        })
+        .collect()
+}
+
+/// Run action `name` for `project`, calling `on_output` with each line as it is printed, and
+/// record the run. Fails without running anything when the action is unknown or not offered
+/// for the project; a command that fails or times out is a finished run, not an error.
+pub fn run_action(
+    db: &Db,
+    cfg: &AppConfig,
+    project: &ProjectRecord,
+    name: &str,
+    mut on_output: impl FnMut(i64, OutputStream, &str),
+) -> Result<ActionRun> {
+    let Some(action) = cfg.actions.get(name) else {
+        bail!("no action named {name:?} in config");
+    };
+    if action.command.is_empty()
+        || !action.applies_to(project.project_type.as_deref(), project.is_git_repo)
+    {
+        bail!("action {name:?} does not apply to {}", project.name);
+    }
+    let run_id = db.start_action_run(project.id, name, action)?;
+    let (status, exit_code, stdout, stderr) =
+        match execute(action, &project.dir(), |s, l| on_output(run_id, s, l)) {
+            Ok(done) => done,
+            Err(err) => {
+                // e.g. the program is not installed: keep the reason with the run
+                let message = format!("{err:#}");
+                on_output(run_id, OutputStream::Stderr, &message);
+                (RunStatus::Failed, None, String::new(), message)
+            }
+        };
+    db.finish_action_run(run_id, status, exit_code, &stdout, &stderr)?;
+    db.action_run(run_id)?
+        .context("action run disappeared while it ran")
+}
+
+fn execute(
+    action: &ActionConfig,
+    dir: &Path,
+    mut on_output: impl FnMut(OutputStream, &str),
+) -> Result<(RunStatus, Option<i32>, String, String)> {
+    let (program, args) = action.command.split_first().expect("checked non-empty");
+    let mut child = Command::new(program)
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("could not start {program}"))?;
+
+    let (tx, rx) = mpsc::channel();
+    let forward = |stream: OutputStream, pipe: Box<dyn Read + Send>| {
+        let tx = tx.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+                if tx.send((stream, line)).is_err() {
+                    break;
+                }
+            }
+        })
+    };
+    let readers = [
+        forward(
+            OutputStream::Stdout,
+            Box::new(child.stdout.take().expect("piped")),
+        ),
+        forward(
+            OutputStream::Stderr,
+            Box::new(child.stderr.take().expect("piped")),
+        ),
+    ];
+    drop(tx);
+
+    let deadline = Instant::now() + Duration::from_secs(action.timeout_secs.max(1));
+    let (mut stdout, mut stderr) = (String::new(), String::new());
+    let mut timed_out = false;
+    let mut exited = None;
+    let mut drain_until = None;
+    let mut readers_done = false;
+    loop {
+        if readers_done {
+            // Both pipes closed but the process may still run: keep it to the deadline
+            std::thread::sleep(Duration::from_millis(100));
+        } else {
+            match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok((stream, line)) => {
+                    on_output(stream, &line);
+                    let buf = match stream {
+                        OutputStream::Stdout => &mut stdout,
+                        OutputStream::Stderr => &mut stderr,
+                    };
+                    if buf.len() + line.len() < MAX_STORED_BYTES {
+                        buf.push_str(&line);
+                        buf.push('\n');
+                    }
+                }
+                Err(mpsc::RecvTimeoutError::Timeout) => {}
+                Err(mpsc::RecvTimeoutError::Disconnected) => readers_done = true,
+            }
+        }
+        if exited.is_none() {
+            exited = child.try_wait()?;
+            // A grandchild that inherited the pipes (a daemon the command started) can hold
+            // them open long after; take what is in flight, then stop reading
+            if exited.is_some() {
+                drain_until = Some(Instant::now() + DRAIN);
+            }
+        }
+        if (readers_done && exited.is_some()) || drain_until.is_some_and(|t| Instant::now() >= t) {
+            break;
+        }
+        if exited.is_none() && Instant::now() >= deadline {
+            timed_out = true;
+            let _ = child.kill();
+            break;
+        }
+    }
+    let exit = match exited {
+        Some(exit) => exit,
+        None => child.wait()?,
+    };
+    // Readers still blocked on a grandchild's pipe end once it closes or sends again
+    if readers_done {
+        for reader in readers {
+            let _ = reader.join();
+        }
+    }
+    let status = if timed_out {
+        RunStatus::TimedOut
+    } else if exit.success() {
+        RunStatus::Succeeded
+    } else {
+        RunStatus::Failed
+    };
+    Ok((status, exit.code(), stdout, stderr))
+}
+
+fn now() -> i64 {
+    time::OffsetDateTime::now_utc().unix_timestamp()
+}
+
+impl Db {
+    fn start_action_run(&self, project_id: i64, name: &str, action: &ActionConfig) -> Result<i64> {
+        let started_at = now();
+        self.conn.execute(
+            "INSERT INTO action_runs (project_id, action, command, started_at, status, deadline) \
+             VALUES (?1, ?2, ?3, ?4, 'running', ?5)",
+            params![
+                project_id,
+                name,
+                serde_json::to_string(&action.command)?,
+                started_at,
+                started_at + action.timeout_secs.max(1) as i64,
+            ],
+        )?;
+        let id = self.conn.last_insert_rowid();
+        self.conn.execute(
+            "DELETE FROM action_runs WHERE project_id = ?1 AND id NOT IN \
+             (SELECT id FROM action_runs WHERE project_id = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![project_id, KEEP_RUNS],
+        )?;
+        Ok(id)
+    }
+
+    fn finish_action_run(
+        &self,
+        run_id: i64,
+        status: RunStatus,
+        exit_code: Option<i32>,
+        stdout: &str,
+        stderr: &str,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE action_runs SET finished_at = ?2, status = ?3, exit_code = ?4, \
+             stdout = ?5, stderr = ?6 WHERE id = ?1",
+            params![run_id, now(), status.as_str(), exit_code, stdout, stderr],
+        )?;
+        Ok(())
+    }
+
+    /// Mark runs still `running` past their timeout as failed: the process that ran them
+    /// died (crash, quit, reboot) before it could record how they ended.
+    pub(crate) fn fail_abandoned_action_runs(&self) -> Result<usize> {
+        Ok(self.conn.execute(
+            "UPDATE action_runs SET status = 'failed', finished_at = ?1, \
+             stderr = stderr || 'interrupted: the run was never finished\n' \
+             WHERE status = 'running' AND COALESCE(deadline, started_at + ?2) < ?1",
+            params![now(), LEGACY_DEADLINE_SECS],
+        )?)
+    }
+
+    pub fn action_run(&self, run_id: i64) -> Result<Option<ActionRun>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!("SELECT {RUN_COLUMNS} FROM action_runs WHERE id = ?1"),
+                params![run_id],
+                run_from_row,
+            )
+            .optional()?)
+    }
+
+    /// `project_id`'s recorded runs, newest first.
+    pub fn action_runs(&self, project_id: i64) -> Result<Vec<ActionRun>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {RUN_COLUMNS} FROM action_runs WHERE project_id = ?1 ORDER BY id DESC"
+        ))?;
+        let rows = stmt
+            .query_map(params![project_id], run_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+const RUN_COLUMNS: &str = "id, project_id, action, command, started_at, finished_at, status, \
+     exit_code, stdout, stderr";
+
+fn run_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ActionRun> {
+    let command: String = row.get(3)?;
+    let status: String = row.get(6)?;
+    Ok(ActionRun {
+        id: row.get(0)?,
+        project_id: row.get(1)?,
+        action: row.get(2)?,
+        command: serde_json::from_str(&command).unwrap_or_default(),
+        started_at: row.get(4)?,
+        finished_at: row.get(5)?,
+        status: RunStatus::parse(&status),
+        exit_code: row.get(7)?,
+        stdout: row.get(8)?,
+        stderr: row.get(9)?,
+    })
+}
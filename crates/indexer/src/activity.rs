@@ -0,0 +1,126 @@
+//! Per-day activity for contribution heatmaps: commits from each repo's history and edits
+//! seen by successive scans (a day counts an edit when a scan finds the project's last
+//! edit time moved onto it). Days are UTC and kept for a year.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::db::Db;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS activity (
+      project_id INTEGER NOT NULL,
+      day TEXT NOT NULL,
+      commits INTEGER NOT NULL DEFAULT 0,
+      edits INTEGER NOT NULL DEFAULT 0,
+      PRIMARY KEY(project_id, day),
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+"#;
+
+/// Days of history in a calendar, ending today
+pub const CALENDAR_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ActivityDay {
+    /// `YYYY-MM-DD`, UTC
+    pub date: String,
+    pub commits: i64,
+    pub edits: i64,
+}
+
+/// Start of the first calendar day, as Unix seconds
+pub fn calendar_start() -> i64 {
+    let today = OffsetDateTime::now_utc().unix_timestamp() / 86400;
+    (today - (CALENDAR_DAYS - 1)) * 86400
+}
+
+impl Db {
+    /// Replace `project_id`'s commit counts for the calendar window with `commit_times`
+    /// (Unix seconds, e.g. from `vcs::commit_times_since(dir, calendar_start())`).
+    pub fn set_commit_activity(&self, project_id: i64, commit_times: &[i64]) -> Result<()> {
+        let start = calendar_start();
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE activity SET commits = 0 WHERE project_id = ?1 AND day >= date(?2, 'unixepoch')",
+            params![project_id, start],
+        )?;
+        for &t in commit_times.iter().filter(|&&t| t >= start) {
+            tx.execute(
+                "INSERT INTO activity (project_id, day, commits) VALUES (?1, date(?2, 'unixepoch'), 1) \
+                 ON CONFLICT(project_id, day) DO UPDATE SET commits = commits + 1",
+                params![project_id, t],
+            )?;
+        }
+        prune(&tx, project_id, start)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Count an edit on the day of `last_edited_at` when it differs from the last edit time
+    /// stored for `project_id`. Call before `upsert_metrics` stores the new value.
+    pub fn record_edit(&self, project_id: i64, last_edited_at: Option<i64>) -> Result<()> {
+        let Some(edited) = last_edited_at else {
+            return Ok(());
+        };
+        let previous: Option<i64> = self
+            .conn
+            .query_row(
+                "SELECT last_edited_at FROM metrics WHERE project_id = ?1",
+                params![project_id],
+                |r| r.get(0),
+            )
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })?;
+        let start = calendar_start();
+        if previous == Some(edited) || edited < start {
+            return Ok(());
+        }
+        self.conn.execute(
+            "INSERT INTO activity (project_id, day, edits) VALUES (?1, date(?2, 'unixepoch'), 1) \
+             ON CONFLICT(project_id, day) DO UPDATE SET edits = edits + 1",
+            params![project_id, edited],
+        )?;
+        prune(&self.conn, project_id, start)?;
+        Ok(())
+    }
+
+    /// One entry per day for the last `CALENDAR_DAYS` days, oldest first, including empty
+    /// days. Sums every project when `project_id` is None.
+    pub fn activity_calendar(&self, project_id: Option<i64>) -> Result<Vec<ActivityDay>> {
+        let mut stmt = self.conn.prepare(
+            "WITH RECURSIVE days(d) AS ( \
+               SELECT date(?1, 'unixepoch') \
+               UNION ALL SELECT date(d, '+1 day') FROM days WHERE d < date('now') \
+             ) \
+             SELECT d, COALESCE(SUM(a.commits), 0), COALESCE(SUM(a.edits), 0) \
+             FROM days LEFT JOIN activity a ON a.day = d AND (?2 IS NULL OR a.project_id = ?2) \
+             GROUP BY d ORDER BY d",
+        )?;
+        let rows = stmt
+            .query_map(params![calendar_start(), project_id], |r| {
+                Ok(ActivityDay {
+                    date: r.get(0)?,
+                    commits: r.get(1)?,
+                    edits: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+/// Drop `project_id`'s days before `start` and days with nothing left on them.
+fn prune(conn: &rusqlite::Connection, project_id: i64, start: i64) -> Result<()> {
+    conn.execute(
+        "DELETE FROM activity WHERE project_id = ?1 \
+         AND (day < date(?2, 'unixepoch') OR (commits = 0 AND edits = 0))",
+        params![project_id, start],
+    )?;
+    Ok(())
+}
@@ -1,26 +1,422 @@
+use anyhow::Result;
 use std::path::Path;
+use std::time::{Duration, Instant};
 #[cfg(feature = "analyzers")]
 use tokei::{Config, Languages};
 
-/// Compute total LOC and per-language breakdown.
-pub fn compute_loc_breakdown(root: &Path) -> Option<(i64, Vec<(String, i64)>)> {
-    #[cfg(feature = "analyzers")]
-    {
-        let mut languages = Languages::new();
-        let config = Config::default();
+use crate::authors::AuthorsAnalyzer;
+use crate::commits::{CommitEntry, CommitsAnalyzer};
+use crate::config::AppConfig;
+use crate::db::Db;
+use crate::deps::{extract_dependencies, Dependency};
+use crate::devenv::{detect_dev_env, DevEnvInfo};
+use crate::hooks::HooksAnalyzer;
+use crate::hours::{HoursAnalyzer, WorkSession};
+use crate::ml::{MlAnalyzer, MlArtifact};
+use crate::quality::{detect_tests_ci, TestCiInfo};
+use crate::secrets::{scan_secrets, SecretFinding};
+use crate::toolchains::{detect_toolchains, ToolchainPin};
+
+/// What an analyzer knows about the project it is asked to analyze.
+pub struct AnalyzerContext<'a> {
+    pub cfg: &'a AppConfig,
+    pub project_type: &'a str,
+    pub is_git_repo: bool,
+    /// Why the project is over `analyzer_limits`; analyzers listed in its `skip` do not run
+    pub oversized: Option<&'a str>,
+}
+
+/// Result of one analyzer run. Built-in variants map onto dedicated tables; `Json` lets
+/// downstream analyzers persist arbitrary results without schema changes.
+#[derive(Debug, Clone)]
+pub enum AnalyzerOutput {
+    Loc {
+        total: i64,
+        breakdown: Vec<(String, i64)>,
+        /// A `loc_limits` cap was hit and the counts are extrapolated
+        truncated: bool,
+    },
+    Dependencies(Vec<Dependency>),
+    Secrets(Vec<SecretFinding>),
+    TestCi(TestCiInfo),
+    DevEnv(DevEnvInfo),
+    /// Jupyter notebooks in the project
+    Notebooks(i64),
+    /// Toolchain versions the project pins or requires
+    Toolchains(Vec<ToolchainPin>),
+    /// Checkpoints, datasets, experiment runs and environments
+    MlArtifacts(Vec<MlArtifact>),
+    /// Recent commits, for searching their messages
+    Commits(Vec<CommitEntry>),
+    /// Work sessions clustered from commit times
+    WorkSessions(Vec<WorkSession>),
+    /// Stored in `analyzer_results` under the analyzer's name
+    Json(serde_json::Value),
+    /// Key/value metadata merged into `custom_fields`, replacing this analyzer's previous keys
+    Fields(serde_json::Map<String, serde_json::Value>),
+    /// Nothing to record (e.g. the analyzer does not apply to this project type)
+    Skipped,
+}
+
+impl AnalyzerOutput {
+    pub fn store(self, db: &Db, project_id: i64, analyzer: &str) -> Result<()> {
+        match self {
+            AnalyzerOutput::Loc {
+                total,
+                breakdown,
+                truncated,
+            } => {
+                db.update_loc(project_id, total)?;
+                db.set_loc_truncated(project_id, truncated)?;
+                db.replace_loc_breakdown(project_id, &breakdown)
+            }
+            AnalyzerOutput::Dependencies(deps) => db.replace_dependencies(project_id, &deps),
+            AnalyzerOutput::Secrets(findings) => db.replace_secret_findings(project_id, &findings),
+            AnalyzerOutput::TestCi(info) => db.update_test_ci(project_id, &info),
+            AnalyzerOutput::DevEnv(info) => db.update_dev_env(project_id, &info),
+            AnalyzerOutput::Notebooks(count) => db.set_notebooks_count(project_id, count),
+            AnalyzerOutput::Toolchains(pins) => db.replace_toolchains(project_id, &pins),
+            AnalyzerOutput::MlArtifacts(found) => db.replace_ml_artifacts(project_id, &found),
+            AnalyzerOutput::Commits(commits) => db.replace_commits(project_id, &commits),
+            AnalyzerOutput::WorkSessions(sessions) => {
+                db.replace_work_sessions(project_id, &sessions)
+            }
+            AnalyzerOutput::Json(value) => db.upsert_analyzer_result(project_id, analyzer, &value),
+            AnalyzerOutput::Fields(fields) => {
+                db.replace_custom_fields(project_id, analyzer, &fields)
+            }
+            AnalyzerOutput::Skipped => Ok(()),
+        }
+    }
+}
+
+/// A per-project analysis step run after detection and metrics.
+pub trait Analyzer: Send + Sync {
+    /// Stable identifier, also the key for `analyzers.<name>` in config
+    fn name(&self) -> &'static str;
+
+    /// Whether the analyzer runs when config has no explicit `analyzers.<name>` entry.
+    fn enabled_by_default(&self, _cfg: &AppConfig) -> bool {
+        true
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput>;
+}
+
+/// Ordered set of analyzers the scanner runs for every detected project.
+pub struct AnalyzerRegistry {
+    analyzers: Vec<Box<dyn Analyzer>>,
+}
+
+impl AnalyzerRegistry {
+    /// An empty registry; scans only detect projects and compute metrics.
+    pub fn empty() -> Self {
+        Self {
+            analyzers: Vec::new(),
+        }
+    }
+
+    /// The analyzers shipped with the indexer (LOC only with the `analyzers` feature).
+    pub fn with_builtins() -> Self {
+        let mut reg = Self::empty();
+        #[cfg(feature = "analyzers")]
+        reg.register(LocAnalyzer);
+        reg.register(DependencyAnalyzer);
+        reg.register(TestCiAnalyzer);
+        reg.register(DevEnvAnalyzer);
+        reg.register(NotebooksAnalyzer);
+        reg.register(ToolchainAnalyzer);
+        reg.register(SecretsAnalyzer);
+        reg.register(MlAnalyzer);
+        reg.register(AuthorsAnalyzer);
+        reg.register(HoursAnalyzer);
+        reg.register(CommitsAnalyzer);
+        reg.register(HooksAnalyzer);
+        reg
+    }
+
+    pub fn register<A: Analyzer + 'static>(&mut self, analyzer: A) -> &mut Self {
+        self.analyzers.push(Box::new(analyzer));
+        self
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.analyzers.iter().map(|a| a.name()).collect()
+    }
+
+    pub fn is_enabled(&self, analyzer: &dyn Analyzer, cfg: &AppConfig) -> bool {
+        cfg.analyzers
+            .get(analyzer.name())
+            .copied()
+            .unwrap_or_else(|| analyzer.enabled_by_default(cfg))
+    }
+
+    /// Run every enabled analyzer and persist its output. A failing analyzer is logged
+    /// and skipped so one bad manifest does not abort the whole scan. Returns how long
+    /// each analyzer that ran took, including storing its output.
+    pub fn run(
+        &self,
+        db: &Db,
+        project_id: i64,
+        root: &Path,
+        ctx: &AnalyzerContext<'_>,
+    ) -> Result<Vec<(&'static str, Duration)>> {
+        let mut timings = Vec::new();
+        for analyzer in &self.analyzers {
+            if !self.is_enabled(analyzer.as_ref(), ctx.cfg) {
+                continue;
+            }
+            if ctx.oversized.is_some() && ctx.cfg.analyzer_limits.skips(analyzer.name()) {
+                continue;
+            }
+            let span = tracing::debug_span!(
+                "analyzer",
+                name = analyzer.name(),
+                elapsed_ms = tracing::field::Empty
+            );
+            let _enter = span.enter();
+            let started = Instant::now();
+            match analyzer.analyze(root, ctx) {
+                Ok(output) => output.store(db, project_id, analyzer.name())?,
+                Err(err) => {
+                    tracing::warn!(%err, analyzer = analyzer.name(), ?root, "analyzer failed")
+                }
+            }
+            let elapsed = started.elapsed();
+            span.record("elapsed_ms", elapsed.as_secs_f64() * 1000.0);
+            timings.push((analyzer.name(), elapsed));
+        }
+        Ok(timings)
+    }
+}
+
+impl Default for AnalyzerRegistry {
+    fn default() -> Self {
+        Self::with_builtins()
+    }
+}
+
+/// Lines of code via tokei.
+#[cfg(feature = "analyzers")]
+pub struct LocAnalyzer;
+
+#[cfg(feature = "analyzers")]
+impl Analyzer for LocAnalyzer {
+    fn name(&self) -> &'static str {
+        "loc"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(match compute_loc_breakdown(root, ctx.cfg) {
+            Some(loc) => AnalyzerOutput::Loc {
+                total: loc.total,
+                breakdown: loc.breakdown,
+                truncated: loc.truncated,
+            },
+            None => AnalyzerOutput::Skipped,
+        })
+    }
+}
+
+/// Third-party dependencies from manifests/lockfiles.
+pub struct DependencyAnalyzer;
+
+impl Analyzer for DependencyAnalyzer {
+    fn name(&self) -> &'static str {
+        "deps"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(AnalyzerOutput::Dependencies(extract_dependencies(
+            root,
+            ctx.project_type,
+        )))
+    }
+}
+
+/// Presence of tests and CI configuration.
+pub struct TestCiAnalyzer;
+
+impl Analyzer for TestCiAnalyzer {
+    fn name(&self) -> &'static str {
+        "tests_ci"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(AnalyzerOutput::TestCi(detect_tests_ci(root, ctx.cfg)))
+    }
+}
+
+/// Devcontainer, Dockerfile, Compose, Nix shell and `.tool-versions` badges.
+pub struct DevEnvAnalyzer;
+
+impl Analyzer for DevEnvAnalyzer {
+    fn name(&self) -> &'static str {
+        "devenv"
+    }
+
+    fn analyze(&self, root: &Path, _ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(AnalyzerOutput::DevEnv(detect_dev_env(root)))
+    }
+}
+
+/// Jupyter notebooks, counted for the project types they are found in (`data-science`,
+/// `python`, `julia`) rather than walking every project again.
+pub struct NotebooksAnalyzer;
+
+impl Analyzer for NotebooksAnalyzer {
+    fn name(&self) -> &'static str {
+        "notebooks"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(match ctx.project_type {
+            "data-science" | "python" | "julia" => {
+                AnalyzerOutput::Notebooks(count_notebooks(root, ctx.cfg))
+            }
+            _ => AnalyzerOutput::Skipped,
+        })
+    }
+}
+
+/// Pinned or required toolchain versions, read from the project root's manifests and
+/// version files whatever the project type (a Rust tool may ship an `.nvmrc` for its docs).
+pub struct ToolchainAnalyzer;
+
+impl Analyzer for ToolchainAnalyzer {
+    fn name(&self) -> &'static str {
+        "toolchains"
+    }
+
+    fn analyze(&self, root: &Path, _ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(AnalyzerOutput::Toolchains(detect_toolchains(root)))
+    }
+}
+
+/// `.ipynb` files under `root`, skipping hidden, ignored and `global_ignores` directories
+/// (checkpoints live in `.ipynb_checkpoints`).
+pub fn count_notebooks(root: &Path, cfg: &AppConfig) -> i64 {
+    let ignores = cfg.global_ignores.clone();
+    ignore::WalkBuilder::new(root)
+        .filter_entry(move |e| {
+            !(e.file_type().is_some_and(|ft| ft.is_dir())
+                && ignores.iter().any(|ign| e.file_name() == ign.as_str()))
+        })
+        .build()
+        .inspect(|_| crate::nice::pace())
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .filter(|e| e.path().extension().is_some_and(|x| x == "ipynb"))
+        .count() as i64
+}
+
+/// Likely secrets on disk; opt-in via `secrets.enabled` or `analyzers.secrets`.
+pub struct SecretsAnalyzer;
+
+impl Analyzer for SecretsAnalyzer {
+    fn name(&self) -> &'static str {
+        "secrets"
+    }
+
+    fn enabled_by_default(&self, cfg: &AppConfig) -> bool {
+        cfg.secrets.enabled
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        Ok(AnalyzerOutput::Secrets(scan_secrets(root, ctx.cfg)))
+    }
+}
+
+/// Lines of code in one project.
+#[derive(Debug, Clone)]
+pub struct LocCount {
+    pub total: i64,
+    /// Code lines per language
+    pub breakdown: Vec<(String, i64)>,
+    /// Only part of the project was read (`loc_limits`); the counts are scaled up by the
+    /// bytes of source left unread
+    pub truncated: bool,
+}
+
+/// Compute total LOC and per-language breakdown, skipping files `content_exclude` leaves out
+/// and reading no more than `loc_limits` allows.
+#[cfg(feature = "analyzers")]
+pub fn compute_loc_breakdown(root: &Path, cfg: &AppConfig) -> Option<LocCount> {
+    let mut languages = Languages::new();
+    let config = Config::default();
+    let (max_files, max_bytes) = cfg.loc_limits.limits_for(&crate::paths::canonical(root));
+    let mut scale = 1.0;
+    if cfg.content_exclude.is_active() || max_files > 0 || max_bytes > 0 {
+        // tokei's own exclude globs cannot express "larger than" or "the first N files", so
+        // hand it the files to read
+        let candidates = loc_candidates(root, cfg, &config);
+        let total_bytes: u64 = candidates.iter().map(|(_, len)| len).sum();
+        let mut read_bytes = 0;
+        let mut files = Vec::new();
+        for (path, len) in candidates {
+            let full = (max_files > 0 && files.len() as u64 >= max_files)
+                || (max_bytes > 0 && read_bytes + len > max_bytes && !files.is_empty());
+            if full {
+                break;
+            }
+            read_bytes += len;
+            files.push(path);
+        }
+        if read_bytes < total_bytes {
+            scale = total_bytes as f64 / read_bytes.max(1) as f64;
+            tracing::debug!(
+                ?root,
+                read = files.len(),
+                read_bytes,
+                total_bytes,
+                "LOC limit reached; extrapolating"
+            );
+        }
+        if !files.is_empty() {
+            languages.get_statistics(&files, &[], &config);
+        }
+    } else {
         languages.get_statistics(&[root], &[], &config);
-        let total = languages.total().code as i64;
-        let breakdown = languages
-            .iter()
-            .map(|(lang, stats)| (lang.to_string(), stats.code as i64))
-            .collect::<Vec<_>>();
-        return Some((total, breakdown));
-    }
-    #[allow(unreachable_code)]
+    }
+    let scaled = |code: usize| (code as f64 * scale).round() as i64;
+    let total = scaled(languages.total().code);
+    let breakdown = languages
+        .iter()
+        .map(|(lang, stats)| (lang.to_string(), scaled(stats.code)))
+        .collect::<Vec<_>>();
+    Some(LocCount {
+        total,
+        breakdown,
+        truncated: scale > 1.0,
+    })
+}
+
+/// Source files under `root` that tokei would read, with their sizes, in a stable order
+/// (hidden and gitignored files are skipped, as tokei does) minus those `content_exclude`
+/// leaves out.
+#[cfg(feature = "analyzers")]
+fn loc_candidates(root: &Path, cfg: &AppConfig, config: &Config) -> Vec<(std::path::PathBuf, u64)> {
+    ignore::WalkBuilder::new(root)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .build()
+        .inspect(|_| crate::nice::pace())
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_some_and(|t| t.is_file()))
+        .filter(|e| !cfg.content_exclude.excludes_by_name(e.path()))
+        .filter(|e| tokei::LanguageType::from_path(e.path(), config).is_some())
+        .filter_map(|e| {
+            let len = e.metadata().ok()?.len();
+            (!cfg.content_exclude.excludes(e.path(), len)).then(|| (e.into_path(), len))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "analyzers"))]
+pub fn compute_loc_breakdown(_root: &Path, _cfg: &AppConfig) -> Option<LocCount> {
     None
 }
 
 /// Convenience to only return total LOC.
-pub fn compute_loc(root: &Path) -> Option<i64> {
-    compute_loc_breakdown(root).map(|(total, _)| total)
+pub fn compute_loc(root: &Path, cfg: &AppConfig) -> Option<i64> {
+    compute_loc_breakdown(root, cfg).map(|loc| loc.total)
 }
@@ -0,0 +1,183 @@
+//! Binary and media assets per project, by category (images, video, audio, archives, model
+//! weights), counted during the metrics walk so projects that are heavy because of assets
+//! rather than code stand out. Files are classified by extension; like the rest of the
+//! metrics, gitignored files are not counted.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::db::Db;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS project_assets (
+      project_id INTEGER NOT NULL,
+      category TEXT NOT NULL,
+      files INTEGER NOT NULL,
+      bytes INTEGER NOT NULL,
+      PRIMARY KEY(project_id, category),
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+"#;
+
+/// `(category, extensions)`
+const CATEGORIES: &[(&str, &[&str])] = &[
+    (
+        "image",
+        &[
+            "png", "jpg", "jpeg", "gif", "webp", "bmp", "tif", "tiff", "psd", "heic", "avif",
+            "ico", "exr", "hdr", "raw", "cr2", "nef", "dng",
+        ],
+    ),
+    (
+        "video",
+        &[
+            "mp4", "mov", "avi", "mkv", "webm", "m4v", "wmv", "flv", "mpg", "mpeg",
+        ],
+    ),
+    (
+        "audio",
+        &[
+            "mp3", "wav", "flac", "ogg", "aac", "m4a", "aif", "aiff", "opus",
+        ],
+    ),
+    (
+        "archive",
+        &[
+            "zip", "tar", "gz", "tgz", "bz2", "xz", "7z", "rar", "zst", "dmg", "iso",
+        ],
+    ),
+    (
+        "model",
+        &[
+            "safetensors",
+            "ckpt",
+            "pt",
+            "pth",
+            "onnx",
+            "h5",
+            "keras",
+            "pb",
+            "tflite",
+            "gguf",
+            "ggml",
+            "mlmodel",
+            "joblib",
+        ],
+    ),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct AssetTotal {
+    pub category: String,
+    pub files: i64,
+    pub bytes: i64,
+}
+
+/// The asset category of `path` by its extension, if any.
+pub fn asset_category(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_str()?;
+    CATEGORIES
+        .iter()
+        .find(|(_, exts)| exts.iter().any(|e| e.eq_ignore_ascii_case(ext)))
+        .map(|(category, _)| *category)
+}
+
+/// Accumulates asset totals from the files of one walk.
+#[derive(Default)]
+pub(crate) struct AssetCounter {
+    totals: BTreeMap<&'static str, (i64, i64)>,
+}
+
+impl AssetCounter {
+    pub(crate) fn add(&mut self, category: &'static str, bytes: u64) {
+        let total = self.totals.entry(category).or_default();
+        total.0 += 1;
+        total.1 += bytes as i64;
+    }
+
+    /// Largest first.
+    pub(crate) fn finish(self) -> Vec<AssetTotal> {
+        let mut out: Vec<AssetTotal> = self
+            .totals
+            .into_iter()
+            .map(|(category, (files, bytes))| AssetTotal {
+                category: category.to_string(),
+                files,
+                bytes,
+            })
+            .collect();
+        out.sort_by(|a, b| {
+            b.bytes
+                .cmp(&a.bytes)
+                .then_with(|| a.category.cmp(&b.category))
+        });
+        out
+    }
+}
+
+impl Db {
+    /// Replace `project_id`'s assets; their total goes to `metrics.asset_bytes`.
+    pub fn replace_assets(&self, project_id: i64, assets: &[AssetTotal]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM project_assets WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for a in assets {
+            tx.execute(
+                "INSERT INTO project_assets (project_id, category, files, bytes) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, a.category, a.files, a.bytes],
+            )?;
+        }
+        tx.execute(
+            "UPDATE metrics SET asset_bytes = ?2 WHERE project_id = ?1",
+            params![project_id, assets.iter().map(|a| a.bytes).sum::<i64>()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// `project_id`'s assets, largest first.
+    pub fn assets(&self, project_id: i64) -> Result<Vec<AssetTotal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT category, files, bytes FROM project_assets WHERE project_id = ?1 \
+             ORDER BY bytes DESC, category",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |r| {
+                Ok(AssetTotal {
+                    category: r.get(0)?,
+                    files: r.get(1)?,
+                    bytes: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Asset totals by category across the index, largest first. Only top-level projects are
+    /// summed, as a parent's walk already counted its nested children's files.
+    pub fn asset_totals(&self) -> Result<Vec<AssetTotal>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT a.category, SUM(a.files), SUM(a.bytes) FROM project_assets a \
+             JOIN projects p ON p.id = a.project_id \
+             WHERE p.parent_id IS NULL \
+             GROUP BY a.category ORDER BY SUM(a.bytes) DESC, a.category",
+        )?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok(AssetTotal {
+                    category: r.get(0)?,
+                    files: r.get(1)?,
+                    bytes: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
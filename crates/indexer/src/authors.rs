@@ -0,0 +1,174 @@
+//! Who wrote a repo, from its commit authors (the `authors` analyzer). Identities are merged
+//! by the repo's own `.mailmap`, then `authors.aliases`, then by shared email or name, so
+//! work and personal addresses of one person count together.
+//!
+//! Results are stored as fields: `primary_author`, `primary_author_email`,
+//! `primary_author_share` (percent of commits), `top_authors` and, with `authors.me` set,
+//! `mine`, so `mine:true` lists the projects you actually wrote.
+
+use anyhow::Result;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerOutput};
+use crate::config::AuthorsConfig;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthorCount {
+    pub name: String,
+    pub email: String,
+    pub commits: usize,
+}
+
+pub struct AuthorsAnalyzer;
+
+impl Analyzer for AuthorsAnalyzer {
+    fn name(&self) -> &'static str {
+        "authors"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        if !ctx.is_git_repo {
+            return Ok(AnalyzerOutput::Skipped);
+        }
+        let cfg = &ctx.cfg.authors;
+        let Some(log) = commit_authors(root, cfg.max_commits) else {
+            return Ok(AnalyzerOutput::Skipped);
+        };
+        let authors = aggregate_authors(log.lines().filter_map(|l| l.split_once('\t')), cfg);
+        Ok(AnalyzerOutput::Fields(author_fields(&authors, cfg)))
+    }
+}
+
+/// `name\temail` per non-merge commit, newest first, with `.mailmap` applied.
+fn commit_authors(root: &Path, max_commits: usize) -> Option<String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "--no-merges", "--format=%aN%x09%aE"])
+        .arg(format!("--max-count={max_commits}"))
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).into_owned())
+}
+
+/// Commits per person, most first. Each `(name, email)` is first mapped through
+/// `cfg.aliases`; commits sharing an email or a name with an earlier one count for the same
+/// person, shown under the name and email they used most.
+pub fn aggregate_authors<'a>(
+    commits: impl IntoIterator<Item = (&'a str, &'a str)>,
+    cfg: &AuthorsConfig,
+) -> Vec<AuthorCount> {
+    let aliases: HashMap<String, (String, String)> = cfg
+        .aliases
+        .iter()
+        .filter_map(|(from, to)| Some((from.trim().to_lowercase(), parse_identity(to)?)))
+        .collect();
+
+    // Person index by lowercase email and by lowercase name
+    let mut by_key: HashMap<String, usize> = HashMap::new();
+    let mut people: Vec<BTreeMap<(String, String), usize>> = Vec::new();
+    for (name, email) in commits {
+        let (name, email) = (name.trim(), email.trim());
+        let (name, email) = aliases
+            .get(&email.to_lowercase())
+            .or_else(|| aliases.get(&name.to_lowercase()))
+            // A bare email or name in an alias keeps the commit's other half
+            .map_or((name, email), |(n, e)| {
+                (
+                    if n.is_empty() { name } else { n },
+                    if e.is_empty() { email } else { e },
+                )
+            });
+        let keys = [email.to_lowercase(), name.to_lowercase()];
+        let person = keys
+            .iter()
+            .filter(|k| !k.is_empty())
+            .find_map(|k| by_key.get(k).copied())
+            .unwrap_or_else(|| {
+                people.push(BTreeMap::new());
+                people.len() - 1
+            });
+        for key in keys.into_iter().filter(|k| !k.is_empty()) {
+            by_key.entry(key).or_insert(person);
+        }
+        *people[person]
+            .entry((name.to_string(), email.to_string()))
+            .or_default() += 1;
+    }
+
+    let mut out: Vec<AuthorCount> = people
+        .into_iter()
+        .map(|identities| {
+            let most_used = |pick: fn(&(String, String)) -> &String| {
+                let mut counts: BTreeMap<&String, usize> = BTreeMap::new();
+                for (id, n) in &identities {
+                    *counts.entry(pick(id)).or_default() += n;
+                }
+                // Ties go to the alphabetically first, for stable output
+                counts
+                    .into_iter()
+                    .max_by(|a, b| a.1.cmp(&b.1).then(b.0.cmp(a.0)))
+                    .map(|(s, _)| s.clone())
+                    .unwrap_or_default()
+            };
+            AuthorCount {
+                name: most_used(|(n, _)| n),
+                email: most_used(|(_, e)| e),
+                commits: identities.values().sum(),
+            }
+        })
+        .collect();
+    out.sort_by(|a, b| b.commits.cmp(&a.commits).then_with(|| a.name.cmp(&b.name)));
+    out
+}
+
+/// `Name <email>` into its parts; a bare email or name leaves the other part empty.
+fn parse_identity(s: &str) -> Option<(String, String)> {
+    let s = s.trim();
+    if let Some((name, rest)) = s.split_once('<') {
+        let email = rest.trim_end().strip_suffix('>')?;
+        return Some((name.trim().to_string(), email.trim().to_string()));
+    }
+    if s.is_empty() {
+        None
+    } else if s.contains('@') {
+        Some((String::new(), s.to_string()))
+    } else {
+        Some((s.to_string(), String::new()))
+    }
+}
+
+fn author_fields(authors: &[AuthorCount], cfg: &AuthorsConfig) -> Map<String, Value> {
+    let mut fields = Map::new();
+    let Some(primary) = authors.first() else {
+        return fields;
+    };
+    let total: usize = authors.iter().map(|a| a.commits).sum();
+    fields.insert("primary_author".into(), primary.name.clone().into());
+    fields.insert("primary_author_email".into(), primary.email.clone().into());
+    fields.insert(
+        "primary_author_share".into(),
+        (primary.commits * 100 / total.max(1)).into(),
+    );
+    let top = authors
+        .iter()
+        .take(cfg.top.max(1))
+        .map(|a| format!("{} ({})", a.name, a.commits))
+        .collect::<Vec<_>>()
+        .join(", ");
+    fields.insert("top_authors".into(), top.into());
+    if !cfg.me.is_empty() {
+        let mine = cfg.me.iter().any(|me| {
+            me.eq_ignore_ascii_case(&primary.name) || me.eq_ignore_ascii_case(&primary.email)
+        });
+        fields.insert("mine".into(), mine.into());
+    }
+    fields
+}
@@ -0,0 +1,446 @@
+//! Keeping the index warm without the app: `autostart enable` registers the CLI with the OS
+//! to run at login, either as the `watch` daemon or as a daily low-impact scan.
+//!
+//! - macOS: a LaunchAgent in `~/Library/LaunchAgents`, loaded with `launchctl`
+//! - Linux: a systemd user service (plus a timer for the daily scan) in
+//!   `~/.config/systemd/user`, enabled with `systemctl --user`
+//! - Windows: a script in the Startup folder for `watch`, a Task Scheduler task for the
+//!   daily scan; neither needs an elevated prompt
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Name of the LaunchAgent and the scheduled task
+pub const LABEL: &str = "com.project-browser.autostart";
+
+/// Comment written into every registration file, followed by the mode, so `status` reads
+/// the mode back instead of guessing it from the command line
+const MODE_MARKER: &str = "project-browser autostart mode:";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutostartMode {
+    /// Run `watch` from login on, restarting it if it exits
+    Watch,
+    /// Run `scan --nice` once a day, and at login when a day was missed
+    Daily,
+}
+
+impl AutostartMode {
+    fn args(self) -> &'static [&'static str] {
+        match self {
+            AutostartMode::Watch => &["watch"],
+            AutostartMode::Daily => &["scan", "--nice"],
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AutostartMode::Watch => "watch",
+            AutostartMode::Daily => "daily",
+        }
+    }
+
+    /// The marker text for this mode, to put in a comment.
+    fn marker(self) -> String {
+        format!("{MODE_MARKER} {}", self.as_str())
+    }
+}
+
+/// The mode recorded in a registration file written by `enable`; `None` when the file has
+/// no marker (or an unknown mode).
+pub fn parse_mode(contents: &str) -> Option<AutostartMode> {
+    let (_, rest) = contents.split_once(MODE_MARKER)?;
+    match rest.split_whitespace().next()? {
+        "watch" => Some(AutostartMode::Watch),
+        "daily" => Some(AutostartMode::Daily),
+        _ => None,
+    }
+}
+
+/// A file `enable` writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnitFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AutostartStatus {
+    /// `None` when autostart is not set up
+    pub mode: Option<AutostartMode>,
+    /// Where it is registered
+    pub files: Vec<PathBuf>,
+    /// Whether the OS reports it loaded/active; `None` when it cannot tell
+    pub active: Option<bool>,
+}
+
+/// Register `exe` to run in `mode`, replacing any earlier registration.
+pub fn enable(exe: &Path, mode: AutostartMode) -> Result<AutostartStatus> {
+    disable()?;
+    let files = unit_files(exe, mode)?;
+    for file in &files {
+        if let Some(dir) = file.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        fs::write(&file.path, &file.contents)
+            .with_context(|| format!("writing {}", file.path.display()))?;
+    }
+    if let Err(err) = platform::activate(exe, mode) {
+        // Leave nothing half-registered behind
+        for file in &files {
+            let _ = fs::remove_file(&file.path);
+        }
+        return Err(err);
+    }
+    status()
+}
+
+/// Remove the registration. Returns whether there was one.
+pub fn disable() -> Result<bool> {
+    let current = status()?;
+    let Some(mode) = current.mode else {
+        return Ok(false);
+    };
+    platform::deactivate(mode)?;
+    for file in current.files {
+        match fs::remove_file(&file) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e).with_context(|| format!("removing {}", file.display())),
+        }
+    }
+    Ok(true)
+}
+
+pub fn status() -> Result<AutostartStatus> {
+    platform::status()
+}
+
+/// The files that register `exe` in `mode` on this platform.
+pub fn unit_files(exe: &Path, mode: AutostartMode) -> Result<Vec<UnitFile>> {
+    platform::unit_files(exe, mode)
+}
+
+fn run(cmd: &mut Command) -> Result<()> {
+    let out = cmd.output().with_context(|| format!("running {cmd:?}"))?;
+    if !out.status.success() {
+        bail!(
+            "{cmd:?} failed: {}",
+            String::from_utf8_lossy(&out.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    fn plist_path() -> Result<PathBuf> {
+        let home = dirs_next::home_dir().context("no home directory")?;
+        Ok(home
+            .join("Library/LaunchAgents")
+            .join(format!("{LABEL}.plist")))
+    }
+
+    fn xml_escape(s: &str) -> String {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    pub(super) fn unit_files(exe: &Path, mode: AutostartMode) -> Result<Vec<UnitFile>> {
+        let log = crate::config::ConfigStore::data_dir()?
+            .join("logs")
+            .join("autostart.log");
+        let args: String = std::iter::once(exe.to_string_lossy().into_owned())
+            .chain(mode.args().iter().map(|a| a.to_string()))
+            .map(|a| format!("    <string>{}</string>\n", xml_escape(&a)))
+            .collect();
+        let schedule = match mode {
+            AutostartMode::Watch => "  <key>KeepAlive</key>\n  <true/>\n".to_string(),
+            AutostartMode::Daily => "  <key>StartCalendarInterval</key>\n  <dict>\n    \
+                 <key>Hour</key>\n    <integer>12</integer>\n    \
+                 <key>Minute</key>\n    <integer>0</integer>\n  </dict>\n"
+                .to_string(),
+        };
+        let log = xml_escape(&log.to_string_lossy());
+        let marker = mode.marker();
+        let contents = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n\
+             <!-- {marker} -->\n\
+             <plist version=\"1.0\">\n<dict>\n  \
+             <key>Label</key>\n  <string>{LABEL}</string>\n  \
+             <key>ProgramArguments</key>\n  <array>\n{args}  </array>\n  \
+             <key>RunAtLoad</key>\n  <true/>\n{schedule}  \
+             <key>ProcessType</key>\n  <string>Background</string>\n  \
+             <key>StandardOutPath</key>\n  <string>{log}</string>\n  \
+             <key>StandardErrorPath</key>\n  <string>{log}</string>\n\
+             </dict>\n</plist>\n"
+        );
+        Ok(vec![UnitFile {
+            path: plist_path()?,
+            contents,
+        }])
+    }
+
+    pub(super) fn activate(_exe: &Path, _mode: AutostartMode) -> Result<()> {
+        run(Command::new("launchctl")
+            .arg("load")
+            .arg("-w")
+            .arg(plist_path()?))
+    }
+
+    pub(super) fn deactivate(_mode: AutostartMode) -> Result<()> {
+        // Not loaded is fine; the plist is removed either way
+        let _ = run(Command::new("launchctl")
+            .arg("unload")
+            .arg("-w")
+            .arg(plist_path()?));
+        Ok(())
+    }
+
+    pub(super) fn status() -> Result<AutostartStatus> {
+        let path = plist_path()?;
+        let Ok(text) = fs::read_to_string(&path) else {
+            return Ok(AutostartStatus {
+                mode: None,
+                files: Vec::new(),
+                active: None,
+            });
+        };
+        // Plists written before the marker: only `watch` is kept alive
+        let mode = parse_mode(&text).unwrap_or(if text.contains("<key>KeepAlive</key>") {
+            AutostartMode::Watch
+        } else {
+            AutostartMode::Daily
+        });
+        let active = Command::new("launchctl")
+            .args(["list", LABEL])
+            .output()
+            .ok()
+            .map(|o| o.status.success());
+        Ok(AutostartStatus {
+            mode: Some(mode),
+            files: vec![path],
+            active,
+        })
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    /// systemd units are named like files rather than reverse-DNS
+    const UNIT: &str = "project-browser-autostart";
+
+    fn unit_dir() -> Result<PathBuf> {
+        Ok(dirs_next::config_dir()
+            .context("no config directory")?
+            .join("systemd/user"))
+    }
+
+    fn service() -> String {
+        format!("{UNIT}.service")
+    }
+
+    fn timer() -> String {
+        format!("{UNIT}.timer")
+    }
+
+    /// `s` as one word of a systemd command line.
+    fn quote(s: &str) -> String {
+        format!(
+            "\"{}\"",
+            s.replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('%', "%%")
+        )
+    }
+
+    pub(super) fn unit_files(exe: &Path, mode: AutostartMode) -> Result<Vec<UnitFile>> {
+        let dir = unit_dir()?;
+        let exec = std::iter::once(quote(&exe.to_string_lossy()))
+            .chain(mode.args().iter().map(|a| a.to_string()))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let marker = mode.marker();
+        Ok(match mode {
+            AutostartMode::Watch => vec![UnitFile {
+                path: dir.join(service()),
+                contents: format!(
+                    "# {marker}\n[Unit]\nDescription=Project Browser index watcher\n\n\
+                     [Service]\nExecStart={exec}\nRestart=on-failure\nRestartSec=60\n\n\
+                     [Install]\nWantedBy=default.target\n"
+                ),
+            }],
+            AutostartMode::Daily => vec![
+                UnitFile {
+                    path: dir.join(service()),
+                    contents: format!(
+                        "# {marker}\n[Unit]\nDescription=Project Browser daily scan\n\n\
+                         [Service]\nType=oneshot\nExecStart={exec}\n"
+                    ),
+                },
+                UnitFile {
+                    path: dir.join(timer()),
+                    contents: "[Unit]\nDescription=Project Browser daily scan\n\n\
+                               [Timer]\nOnCalendar=daily\nPersistent=true\n\n\
+                               [Install]\nWantedBy=timers.target\n"
+                        .to_string(),
+                },
+            ],
+        })
+    }
+
+    fn systemctl(args: &[&str]) -> Command {
+        let mut cmd = Command::new("systemctl");
+        cmd.arg("--user").args(args);
+        cmd
+    }
+
+    fn unit_for(mode: AutostartMode) -> String {
+        match mode {
+            AutostartMode::Watch => service(),
+            AutostartMode::Daily => timer(),
+        }
+    }
+
+    pub(super) fn activate(_exe: &Path, mode: AutostartMode) -> Result<()> {
+        run(&mut systemctl(&["daemon-reload"]))?;
+        run(&mut systemctl(&["enable", "--now", &unit_for(mode)]))
+    }
+
+    pub(super) fn deactivate(mode: AutostartMode) -> Result<()> {
+        // Already stopped or unknown to systemd is fine; the files are removed either way
+        let _ = run(&mut systemctl(&["disable", "--now", &unit_for(mode)]));
+        Ok(())
+    }
+
+    pub(super) fn status() -> Result<AutostartStatus> {
+        let dir = unit_dir()?;
+        let (service, timer) = (dir.join(service()), dir.join(timer()));
+        let (mode, files) = if timer.exists() {
+            (AutostartMode::Daily, vec![service, timer])
+        } else if service.exists() {
+            (AutostartMode::Watch, vec![service])
+        } else {
+            return Ok(AutostartStatus {
+                mode: None,
+                files: Vec::new(),
+                active: None,
+            });
+        };
+        let active = systemctl(&["is-active", &unit_for(mode)])
+            .output()
+            .ok()
+            .map(|o| String::from_utf8_lossy(&o.stdout).trim() == "active");
+        Ok(AutostartStatus {
+            mode: Some(mode),
+            files,
+            active,
+        })
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    fn startup_script() -> Result<PathBuf> {
+        Ok(dirs_next::data_dir()
+            .context("no AppData directory")?
+            .join("Microsoft/Windows/Start Menu/Programs/Startup")
+            .join("project-browser-watch.cmd"))
+    }
+
+    fn command_line(exe: &Path, mode: AutostartMode) -> String {
+        std::iter::once(format!("\"{}\"", exe.display()))
+            .chain(mode.args().iter().map(|a| a.to_string()))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    pub(super) fn unit_files(exe: &Path, mode: AutostartMode) -> Result<Vec<UnitFile>> {
+        Ok(match mode {
+            AutostartMode::Watch => vec![UnitFile {
+                path: startup_script()?,
+                contents: format!(
+                    "@echo off\r\nrem {}\r\nstart \"\" /min {}\r\n",
+                    mode.marker(),
+                    command_line(exe, mode)
+                ),
+            }],
+            // Registered with Task Scheduler instead
+            AutostartMode::Daily => Vec::new(),
+        })
+    }
+
+    pub(super) fn activate(exe: &Path, mode: AutostartMode) -> Result<()> {
+        match mode {
+            // Runs at the next login; start it now as well
+            AutostartMode::Watch => {
+                Command::new(exe)
+                    .args(mode.args())
+                    .spawn()
+                    .context("starting watch")?;
+                Ok(())
+            }
+            AutostartMode::Daily => run(Command::new("schtasks").args([
+                "/Create",
+                "/F",
+                "/TN",
+                LABEL,
+                "/SC",
+                "DAILY",
+                "/ST",
+                "12:00",
+                "/TR",
+                command_line(exe, mode).as_str(),
+            ])),
+        }
+    }
+
+    pub(super) fn deactivate(mode: AutostartMode) -> Result<()> {
+        match mode {
+            // Nothing supervises the started `watch`; stop it through the pid it reports
+            AutostartMode::Watch => {
+                if let Some(mut client) = crate::daemon::DaemonClient::connect()? {
+                    let pid = client.status()?.pid.to_string();
+                    run(Command::new("taskkill").args(["/PID", &pid, "/F"]))?;
+                }
+                Ok(())
+            }
+            AutostartMode::Daily => {
+                run(Command::new("schtasks").args(["/Delete", "/F", "/TN", LABEL]))
+            }
+        }
+    }
+
+    pub(super) fn status() -> Result<AutostartStatus> {
+        let script = startup_script()?;
+        if script.exists() {
+            return Ok(AutostartStatus {
+                mode: Some(AutostartMode::Watch),
+                files: vec![script],
+                // A running `watch` answers on its control socket
+                active: Some(crate::daemon::DaemonClient::connect()?.is_some()),
+            });
+        }
+        let task = Command::new("schtasks")
+            .args(["/Query", "/TN", LABEL])
+            .output()
+            .is_ok_and(|o| o.status.success());
+        Ok(AutostartStatus {
+            mode: task.then_some(AutostartMode::Daily),
+            files: Vec::new(),
+            active: task.then_some(true),
+        })
+    }
+}
@@ -0,0 +1,158 @@
+//! Rotating snapshots of the index database. A backup is taken before a scan when the newest
+//! one is older than `backup.interval_hours`, into a `backups` directory next to the
+//! database; only the newest `backup.keep` are kept.
+
+use anyhow::{Context, Result};
+use rusqlite::{params, OpenFlags};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use time::OffsetDateTime;
+
+use crate::config::BackupConfig;
+use crate::db::Db;
+
+const PREFIX: &str = "projects-";
+const EXT: &str = "sqlite";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BackupFile {
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    /// Unix seconds
+    pub created_at: i64,
+}
+
+/// `backups` next to the database file.
+pub fn backup_dir(db_path: &Path) -> PathBuf {
+    db_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join("backups")
+}
+
+/// Backups in `dir`, newest first.
+pub fn list_backups(dir: &Path) -> Result<Vec<BackupFile>> {
+    let rd = match fs::read_dir(dir) {
+        Ok(rd) => rd,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    let mut out = Vec::new();
+    for entry in rd.flatten() {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if !name.starts_with(PREFIX) || path.extension().and_then(|e| e.to_str()) != Some(EXT) {
+            continue;
+        }
+        let md = entry.metadata()?;
+        let created_at = md
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(SystemTime::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs() as i64)
+            .unwrap_or_default();
+        out.push(BackupFile {
+            path,
+            size_bytes: md.len(),
+            created_at,
+        });
+    }
+    // Names carry the timestamp (and a counter for same-second backups), so they order by
+    // creation even where mtimes are coarse
+    out.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(out)
+}
+
+/// Check that `path` is a readable SQLite database that passes `PRAGMA integrity_check`.
+pub fn verify(path: &Path) -> Result<()> {
+    // Not read-only: checking the FTS5 search index needs a writable connection
+    let conn = crate::encryption::open_connection(path, OpenFlags::SQLITE_OPEN_READ_WRITE)?;
+    let result: String = conn
+        .query_row("PRAGMA integrity_check", [], |r| r.get(0))
+        .with_context(|| format!("{} is not a valid database", path.display()))?;
+    if result != "ok" {
+        anyhow::bail!("{} failed the integrity check: {result}", path.display());
+    }
+    Ok(())
+}
+
+/// Replace the database at `db_path` with `backup`. The current file is backed up first
+/// (when it can still be read) so a restore can itself be reverted. No `Db` may be open on
+/// `db_path` while this runs.
+pub fn restore(db_path: &Path, backup: &Path, cfg: &BackupConfig) -> Result<()> {
+    verify(backup)?;
+    if db_path.exists() {
+        match Db::open(db_path).and_then(|db| db.backup(cfg.keep)) {
+            Ok(saved) => {
+                tracing::info!(path = %saved.display(), "saved current database before restore")
+            }
+            Err(err) => {
+                tracing::warn!(%err, "could not back up the current database; restoring anyway")
+            }
+        }
+    }
+    // Stale WAL/shared-memory files would be replayed over the restored copy
+    for suffix in ["-wal", "-shm"] {
+        let mut side = db_path.as_os_str().to_owned();
+        side.push(suffix);
+        let _ = fs::remove_file(PathBuf::from(side));
+    }
+    fs::copy(backup, db_path)
+        .with_context(|| format!("copying {} to {}", backup.display(), db_path.display()))?;
+    Ok(())
+}
+
+impl Db {
+    /// Snapshot the database into its backup directory and drop all but the newest `keep`
+    /// backups. Returns the new file.
+    pub fn backup(&self, keep: usize) -> Result<PathBuf> {
+        let dir = backup_dir(&self.path);
+        fs::create_dir_all(&dir)?;
+        let now = OffsetDateTime::now_utc();
+        let stamp = format!(
+            "{:04}{:02}{:02}-{:02}{:02}{:02}",
+            now.year(),
+            now.month() as u8,
+            now.day(),
+            now.hour(),
+            now.minute(),
+            now.second()
+        );
+        let mut path = dir.join(format!("{PREFIX}{stamp}.{EXT}"));
+        let mut n = 1;
+        while path.exists() {
+            // `_` sorts after `.`, keeping same-second backups in creation order by name
+            path = dir.join(format!("{PREFIX}{stamp}_{n:03}.{EXT}"));
+            n += 1;
+        }
+        // VACUUM INTO writes a consistent, compacted copy even while other connections write
+        self.conn
+            .execute("VACUUM INTO ?1", params![path.to_string_lossy()])?;
+        for old in list_backups(&dir)?.into_iter().skip(keep.max(1)) {
+            fs::remove_file(&old.path)?;
+        }
+        Ok(path)
+    }
+
+    /// Back up when backups are enabled and the newest one is older than the configured
+    /// interval. Returns the new file, if one was written.
+    pub fn backup_if_due(&self, cfg: &BackupConfig) -> Result<Option<PathBuf>> {
+        if !cfg.enabled {
+            return Ok(None);
+        }
+        let interval = Duration::from_secs(cfg.interval_hours * 3600);
+        let newest = list_backups(&backup_dir(&self.path))?.into_iter().next();
+        if let Some(b) = newest {
+            let age = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .unwrap_or_default()
+                .saturating_sub(Duration::from_secs(b.created_at.max(0) as u64));
+            if age < interval {
+                return Ok(None);
+            }
+        }
+        self.backup(cfg.keep).map(Some)
+    }
+}
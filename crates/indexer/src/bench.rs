@@ -0,0 +1,38 @@
+//! Seeded databases for benchmarks and `cli bench seed`; project trees come from
+//! `fixtures`. Everything is derived from a seed so runs are comparable across commits.
+
+use anyhow::Result;
+
+use crate::db::Db;
+
+const TYPES: &[&str] = &["rust", "node", "python", "go", "java", "terraform"];
+
+/// Insert `rows` synthetic projects with metrics and git info in one transaction.
+pub fn seed_db(db: &Db, rows: usize, seed: u64) -> Result<()> {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let now = 1_700_000_000i64;
+    let tx = db.conn.unchecked_transaction()?;
+    for i in 0..rows {
+        let ty = TYPES[rng.usize(..TYPES.len())];
+        let name = format!("proj-{i:06}");
+        let id = db.upsert_project(&name, &format!("/bench/{name}"), Some(ty), rng.bool())?;
+        db.upsert_metrics(
+            id,
+            Some(rng.i64(1_000..5_000_000_000)),
+            Some(rng.i64(1..50_000)),
+            Some(now - rng.i64(0..3 * 365 * 86_400)),
+        )?;
+        db.update_loc(id, rng.i64(10..500_000))?;
+        if rng.bool() {
+            db.upsert_git_info(
+                id,
+                Some(now - rng.i64(0..3 * 365 * 86_400)),
+                Some(if rng.bool() { "main" } else { "dev" }),
+                rng.bool().then_some("git@example.com:o/r.git"),
+                Some(rng.bool()),
+            )?;
+        }
+    }
+    tx.commit()?;
+    Ok(())
+}
@@ -0,0 +1,133 @@
+//! Local branches per repository, recorded at scan time, for the cross-repo overview
+//! (`git branches`): the branch each repo is on, whether that is its default branch, and how
+//! many local branches have gone stale.
+
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+
+use crate::db::{Db, ProjectFilter};
+use crate::vcs::BranchTip;
+use crate::SortKey;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS git_branches (
+      project_id INTEGER NOT NULL,
+      name TEXT NOT NULL,
+      last_commit_at INTEGER NOT NULL,
+      PRIMARY KEY(project_id, name),
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+"#;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BranchStatus {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    /// Checked-out branch; `HEAD` when detached
+    pub branch: Option<String>,
+    pub default_branch: Option<String>,
+    /// On a branch other than the default one
+    pub off_default: bool,
+    pub dirty: Option<bool>,
+    pub ahead: Option<i64>,
+    pub local_branches: usize,
+    /// Local branches besides the current and default ones with no commit in `stale_days`,
+    /// oldest first
+    pub stale_branches: Vec<String>,
+    /// At least `many_stale` stale branches
+    pub many_stale: bool,
+}
+
+impl Db {
+    pub fn replace_git_branches(
+        &self,
+        project_id: i64,
+        default_branch: Option<&str>,
+        branches: &[BranchTip],
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "UPDATE git_info SET default_branch = ?2 WHERE project_id = ?1",
+            params![project_id, default_branch],
+        )?;
+        tx.execute(
+            "DELETE FROM git_branches WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for b in branches {
+            tx.execute(
+                "INSERT INTO git_branches (project_id, name, last_commit_at) VALUES (?1, ?2, ?3)",
+                params![project_id, b.name, b.last_commit_at],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Branch status of every git repository matching `filter`, by name, with branches idle
+    /// for `stale_days` as of `now` counted stale.
+    pub fn branch_overview(
+        &self,
+        filter: &ProjectFilter,
+        stale_days: i64,
+        many_stale: usize,
+        now: i64,
+    ) -> Result<Vec<BranchStatus>> {
+        let mut repos = Vec::new();
+        self.stream_projects(filter, SortKey::Name, true, None, |p| {
+            if p.is_git_repo {
+                repos.push(p);
+            }
+            Ok(())
+        })?;
+        let cutoff = now - stale_days * 86_400;
+        let mut branches = self.conn.prepare(
+            "SELECT name, last_commit_at FROM git_branches WHERE project_id = ?1 \
+             ORDER BY last_commit_at, name",
+        )?;
+        let mut git = self
+            .conn
+            .prepare("SELECT default_branch, ahead FROM git_info WHERE project_id = ?1")?;
+        let mut out = Vec::with_capacity(repos.len());
+        for p in repos {
+            let tips = branches
+                .query_map(params![p.id], |row| {
+                    Ok(BranchTip {
+                        name: row.get(0)?,
+                        last_commit_at: row.get(1)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            let (default_branch, ahead): (Option<String>, Option<i64>) = git
+                .query_row(params![p.id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .optional()?
+                .unwrap_or_default();
+            let stale_branches: Vec<String> = tips
+                .iter()
+                .filter(|b| b.last_commit_at < cutoff)
+                .filter(|b| Some(&b.name) != p.branch.as_ref())
+                .filter(|b| Some(&b.name) != default_branch.as_ref())
+                .map(|b| b.name.clone())
+                .collect();
+            out.push(BranchStatus {
+                off_default: match (&p.branch, &default_branch) {
+                    (Some(current), Some(default)) => current != default,
+                    _ => false,
+                },
+                many_stale: many_stale > 0 && stale_branches.len() >= many_stale,
+                local_branches: tips.len(),
+                stale_branches,
+                default_branch,
+                id: p.id,
+                name: p.name,
+                path: p.path,
+                branch: p.branch,
+                dirty: p.git_dirty,
+                ahead,
+            });
+        }
+        Ok(out)
+    }
+}
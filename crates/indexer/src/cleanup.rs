@@ -0,0 +1,278 @@
+//! Cleanup recommendations: what could be deleted to free space, ranked by how much it
+//! frees and how long the project has sat untouched. Whole projects are only suggested
+//! when every commit is on a git remote, so they can be cloned again; otherwise artifact
+//! directories and ML leftovers (checkpoints, experiment runs, environments) are.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use crate::config::CleanupConfig;
+use crate::db::{Db, ProjectRecord};
+use crate::ml::MlArtifactKind;
+use crate::offsite::{RemoteState, REMOTE_STATE_COLUMNS};
+
+/// Staleness stops adding to the score past this many multiples of `stale_after_days`
+const MAX_STALENESS: f64 = 4.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum CleanupAction {
+    /// Delete build/dependency output; it is regenerated by the next build
+    DeleteArtifacts,
+    /// Delete a stale copy of a repo that is also checked out elsewhere
+    DeleteDuplicate,
+    /// Delete a long-untouched project; it can be cloned again from its remote
+    DeleteClone,
+    /// Delete ML checkpoints, experiment runs and environments (see `ml`)
+    DeleteMlArtifacts,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Recommendation {
+    pub action: CleanupAction,
+    pub project: ProjectRecord,
+    /// What to delete
+    pub paths: Vec<String>,
+    pub reclaim_bytes: i64,
+    /// Days since the project was last edited or opened
+    pub idle_days: i64,
+    /// Clean working tree and every commit on the upstream
+    pub fully_pushed: bool,
+    /// The more recently used checkout, for `delete_duplicate`
+    pub duplicate_of: Option<String>,
+    /// Ranking score: bytes freed (GB) weighted by staleness
+    pub score: f64,
+    /// One line for people, e.g. "delete node_modules in web: 2.1 GB, last touched 14
+    /// months ago, fully pushed"
+    pub summary: String,
+}
+
+/// `1.5 GB`, `320 MB`, `12 KB`
+pub fn human_bytes(bytes: i64) -> String {
+    const UNITS: [(&str, f64); 3] = [
+        ("GB", 1024.0 * 1024.0 * 1024.0),
+        ("MB", 1024.0 * 1024.0),
+        ("KB", 1024.0),
+    ];
+    let b = bytes as f64;
+    for (unit, size) in UNITS {
+        if b >= size {
+            return if b / size >= 10.0 {
+                format!("{:.0} {unit}", b / size)
+            } else {
+                format!("{:.1} {unit}", b / size)
+            };
+        }
+    }
+    format!("{bytes} B")
+}
+
+fn idle_phrase(days: i64) -> String {
+    match days {
+        0..=59 => format!("{days} days ago"),
+        60..=729 => format!("{} months ago", days / 30),
+        _ => format!("{} years ago", days / 365),
+    }
+}
+
+struct Candidate {
+    id: i64,
+    name: String,
+    path: String,
+    size: i64,
+    artifacts: i64,
+    ml: i64,
+    last_touched: i64,
+    remote: RemoteState,
+}
+
+impl Db {
+    /// Ranked cleanup recommendations: deleting the whole project when it is a stale
+    /// duplicate or long untouched and fully pushed, otherwise deleting its artifact
+    /// directories and, separately, its ML artifacts when it has been idle for
+    /// `stale_after_days`. Datasets are only included with `reclaim_datasets`.
+    pub fn recommend_cleanup(&self, cfg: &CleanupConfig) -> Result<Vec<Recommendation>> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let candidates: Vec<Candidate> = {
+            let mut stmt = self.conn.prepare(&format!(
+                "SELECT p.id, p.name, p.path, COALESCE(m.size_bytes, 0), \
+                        COALESCE(m.artifact_bytes, 0), COALESCE(m.ml_bytes, 0), \
+                        MAX(COALESCE(m.last_edited_at, 0), COALESCE((SELECT MAX(opened_at) \
+                            FROM project_opens o WHERE o.project_id = p.id), 0)), \
+                        {REMOTE_STATE_COLUMNS} \
+                 FROM projects p \
+                 LEFT JOIN metrics m ON m.project_id = p.id \
+                 LEFT JOIN git_info g ON g.project_id = p.id"
+            ))?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok(Candidate {
+                        id: r.get(0)?,
+                        name: r.get(1)?,
+                        path: r.get(2)?,
+                        size: r.get(3)?,
+                        artifacts: r.get(4)?,
+                        ml: r.get(5)?,
+                        last_touched: r.get(6)?,
+                        remote: RemoteState::from_row(r, 7)?,
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        // Most recently used checkout per remote, to tell which copy is the stale one
+        let mut newest_by_remote: HashMap<&str, &Candidate> = HashMap::new();
+        for c in &candidates {
+            if let Some(url) = c.remote.remote_url.as_deref() {
+                let newest = newest_by_remote.entry(url).or_insert(c);
+                if c.last_touched > newest.last_touched {
+                    *newest = c;
+                }
+            }
+        }
+
+        let stale_secs = i64::from(cfg.stale_after_days.max(1)) * 86400;
+        let mut out = Vec::new();
+        for c in &candidates {
+            // Unknown recency is never treated as stale
+            if c.last_touched == 0 {
+                continue;
+            }
+            let idle = (now - c.last_touched).max(0);
+            if idle < stale_secs {
+                continue;
+            }
+            let idle_days = idle / 86400;
+            let gaps = c.remote.gaps();
+            let fully_pushed = gaps.is_empty();
+            let status = if fully_pushed {
+                "fully pushed".to_string()
+            } else {
+                gaps.join(", ")
+            };
+            let duplicate_of = c
+                .remote
+                .remote_url
+                .as_deref()
+                .and_then(|url| newest_by_remote.get(url))
+                .filter(|newest| newest.id != c.id)
+                .map(|newest| newest.path.clone());
+
+            let whole = c.size + c.artifacts;
+            let mut picks = Vec::new();
+            if fully_pushed && duplicate_of.is_some() {
+                let summary = format!(
+                    "delete {} (also checked out at {}): {}, last touched {}, {status}",
+                    c.name,
+                    duplicate_of.as_deref().unwrap_or_default(),
+                    human_bytes(whole),
+                    idle_phrase(idle_days),
+                );
+                picks.push((
+                    CleanupAction::DeleteDuplicate,
+                    vec![c.path.clone()],
+                    whole,
+                    summary,
+                ));
+            } else if fully_pushed && idle_days >= i64::from(cfg.clone_after_days) {
+                let summary = format!(
+                    "delete {} (re-clone when needed): {}, last touched {}, {status}",
+                    c.name,
+                    human_bytes(whole),
+                    idle_phrase(idle_days),
+                );
+                picks.push((
+                    CleanupAction::DeleteClone,
+                    vec![c.path.clone()],
+                    whole,
+                    summary,
+                ));
+            } else {
+                let artifact_dirs = self.artifacts_of(c.id)?;
+                if c.artifacts > 0 {
+                    let dirs = &artifact_dirs;
+                    let names: Vec<&str> = dirs.iter().map(|(d, _)| d.as_str()).collect();
+                    let summary = format!(
+                        "delete {} in {}: {}, last touched {}, {status}",
+                        names.join(", "),
+                        c.name,
+                        human_bytes(c.artifacts),
+                        idle_phrase(idle_days),
+                    );
+                    let paths = dirs
+                        .iter()
+                        .map(|(d, _)| Path::new(&c.path).join(d).to_string_lossy().into_owned())
+                        .collect();
+                    picks.push((CleanupAction::DeleteArtifacts, paths, c.artifacts, summary));
+                }
+                if c.ml > 0 {
+                    let found: Vec<_> = self
+                        .ml_artifacts(c.id)?
+                        .into_iter()
+                        .filter(|a| cfg.reclaim_datasets || a.kind != MlArtifactKind::Dataset)
+                        // `.venv` is suggested with the build artifacts already
+                        .filter(|a| !artifact_dirs.iter().any(|(d, _)| *d == a.path))
+                        .collect();
+                    let bytes: i64 = found.iter().map(|a| a.bytes).sum();
+                    if bytes > 0 {
+                        let mut kinds: Vec<&str> = found.iter().map(|a| a.kind.as_str()).collect();
+                        kinds.sort_unstable();
+                        kinds.dedup();
+                        let summary = format!(
+                            "delete ML artifacts ({}) in {}: {}, last touched {}, {status}",
+                            kinds.join(", "),
+                            c.name,
+                            human_bytes(bytes),
+                            idle_phrase(idle_days),
+                        );
+                        let paths = found
+                            .iter()
+                            .map(|a| {
+                                Path::new(&c.path)
+                                    .join(&a.path)
+                                    .to_string_lossy()
+                                    .into_owned()
+                            })
+                            .collect();
+                        picks.push((CleanupAction::DeleteMlArtifacts, paths, bytes, summary));
+                    }
+                }
+            }
+            for (action, paths, reclaim, summary) in picks {
+                if reclaim < cfg.min_bytes {
+                    continue;
+                }
+                let staleness = (idle as f64 / stale_secs as f64).min(MAX_STALENESS);
+                let score = reclaim as f64 / (1024.0 * 1024.0 * 1024.0) * staleness;
+                let Some(project) = self.project_by_id(c.id)? else {
+                    continue;
+                };
+                out.push(Recommendation {
+                    action,
+                    project,
+                    paths,
+                    reclaim_bytes: reclaim,
+                    idle_days,
+                    fully_pushed,
+                    duplicate_of: duplicate_of
+                        .clone()
+                        .filter(|_| action == CleanupAction::DeleteDuplicate),
+                    score,
+                    summary,
+                });
+            }
+        }
+        out.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.project.path.cmp(&b.project.path))
+        });
+        Ok(out)
+    }
+}
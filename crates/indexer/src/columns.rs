@@ -0,0 +1,129 @@
+//! `list --columns` and `list --template`: picking single values out of a project's JSON row
+//! (the object `list --json` prints) by column name, and filling format strings with them.
+
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// Column names that are not JSON keys as they stand.
+fn column_key(name: &str) -> &str {
+    match name {
+        "last_commit" => "last_commit_at",
+        "edited" | "last_edited" => "last_edited_at",
+        "size" => "size_bytes",
+        "files" => "files_count",
+        "remote" => "remote_url",
+        "dirty" => "git_dirty",
+        other => other,
+    }
+}
+
+/// A column of a project row: a built-in key, else a custom field, as `fields.<key>` or just
+/// `<key>` (`tags`). Unknown columns and missing values are `null`.
+pub fn column_json<'a>(item: &'a Value, name: &str) -> &'a Value {
+    let key = column_key(name);
+    let field = name.strip_prefix("fields.").unwrap_or(name);
+    match item.get(key) {
+        Some(value) if key != "fields" && field == name => value,
+        _ => item
+            .get("fields")
+            .and_then(|fields| fields.get(field))
+            .unwrap_or(&Value::Null),
+    }
+}
+
+/// [`column_json`] as text: `null` is empty and lists are joined with commas.
+pub fn column_text(item: &Value, name: &str) -> String {
+    fn scalar(value: &Value) -> String {
+        match value {
+            Value::Null => String::new(),
+            Value::String(s) => s.clone(),
+            other => other.to_string(),
+        }
+    }
+    match column_json(item, name) {
+        Value::Array(items) => items.iter().map(scalar).collect::<Vec<_>>().join(","),
+        other => scalar(other),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TemplatePiece {
+    Text(String),
+    Column(String),
+}
+
+/// A parsed `list --template` string.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Template(pub Vec<TemplatePiece>);
+
+impl Template {
+    /// Split `template` into text and `{column}` placeholders; `{{` and `}}` are literal
+    /// braces. `\t`, `\n` and `\\` are unescaped too, as shells pass them through literally.
+    pub fn parse(template: &str) -> Result<Self> {
+        let mut pieces = Vec::new();
+        let mut text = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => name.push(c),
+                            None => bail!("unclosed `{{` in template: {template}"),
+                        }
+                    }
+                    let name = name.trim();
+                    if name.is_empty() {
+                        bail!("empty `{{}}` in template: {template}");
+                    }
+                    if !text.is_empty() {
+                        pieces.push(TemplatePiece::Text(std::mem::take(&mut text)));
+                    }
+                    pieces.push(TemplatePiece::Column(name.to_string()));
+                }
+                '}' => bail!("unmatched `}}` in template (write `}}}}` for a brace): {template}"),
+                '\\' => match chars.peek() {
+                    Some('t') => {
+                        chars.next();
+                        text.push('\t');
+                    }
+                    Some('n') => {
+                        chars.next();
+                        text.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        text.push('\\');
+                    }
+                    _ => text.push('\\'),
+                },
+                c => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            pieces.push(TemplatePiece::Text(text));
+        }
+        Ok(Self(pieces))
+    }
+
+    /// Fill in the placeholders from a project row with [`column_text`].
+    pub fn render(&self, item: &Value) -> String {
+        self.0
+            .iter()
+            .map(|piece| match piece {
+                TemplatePiece::Text(text) => text.clone(),
+                TemplatePiece::Column(name) => column_text(item, name),
+            })
+            .collect()
+    }
+}
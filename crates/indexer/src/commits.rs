@@ -0,0 +1,162 @@
+//! Searching commit messages across every repo (`commits search`): the `commits` analyzer
+//! keeps the subjects of each repo's last `commit_search.max_commits` commits in an FTS5 index,
+//! so "which repo had that timezone fix?" is one query instead of a `git log` per clone.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerOutput};
+use crate::db::Db;
+use crate::search::{fts_query, Highlighted, CLOSE, OPEN};
+
+/// `commit_search` indexes `commits.subject` (external content), kept in step by triggers.
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS commits (
+      id INTEGER PRIMARY KEY AUTOINCREMENT,
+      project_id INTEGER NOT NULL,
+      sha TEXT NOT NULL,
+      committed_at INTEGER NOT NULL,
+      author TEXT NOT NULL,
+      subject TEXT NOT NULL,
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_commits_project ON commits(project_id);
+    CREATE VIRTUAL TABLE IF NOT EXISTS commit_search USING fts5(
+      subject,
+      content = 'commits',
+      content_rowid = 'id',
+      tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER IF NOT EXISTS commit_search_insert AFTER INSERT ON commits BEGIN
+      INSERT INTO commit_search (rowid, subject) VALUES (new.id, new.subject);
+    END;
+    CREATE TRIGGER IF NOT EXISTS commit_search_delete AFTER DELETE ON commits BEGIN
+      INSERT INTO commit_search (commit_search, rowid, subject)
+        VALUES ('delete', old.id, old.subject);
+    END;
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommitEntry {
+    pub sha: String,
+    pub committed_at: i64,
+    pub author: String,
+    pub subject: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CommitHit {
+    pub project_id: i64,
+    pub project: String,
+    pub path: String,
+    pub sha: String,
+    pub committed_at: i64,
+    /// `YYYY-MM-DD` (UTC)
+    pub date: String,
+    pub author: String,
+    pub subject: Highlighted,
+}
+
+pub struct CommitsAnalyzer;
+
+impl Analyzer for CommitsAnalyzer {
+    fn name(&self) -> &'static str {
+        "commits"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        if !ctx.is_git_repo {
+            return Ok(AnalyzerOutput::Skipped);
+        }
+        Ok(recent_commits(root, ctx.cfg.commit_search.max_commits)
+            .map_or(AnalyzerOutput::Skipped, AnalyzerOutput::Commits))
+    }
+}
+
+/// The last `max_commits` commits on HEAD, newest first.
+fn recent_commits(root: &Path, max_commits: usize) -> Option<Vec<CommitEntry>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "--format=%H%x09%ct%x09%aN%x09%s"])
+        .arg(format!("--max-count={max_commits}"))
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let commits = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(4, '\t');
+            Some(CommitEntry {
+                sha: parts.next()?.to_string(),
+                committed_at: parts.next()?.parse().ok()?,
+                author: parts.next()?.to_string(),
+                subject: parts.next().unwrap_or("").to_string(),
+            })
+        })
+        .collect();
+    Some(commits)
+}
+
+impl Db {
+    pub fn replace_commits(&self, project_id: i64, commits: &[CommitEntry]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM commits WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for c in commits {
+            tx.execute(
+                "INSERT INTO commits (project_id, sha, committed_at, author, subject) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![project_id, c.sha, c.committed_at, c.author, c.subject],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Commits whose subject has every word of `q` (each as a prefix), best match first and
+    /// newest first among equals.
+    pub fn search_commits(&self, q: &str, limit: u32) -> Result<Vec<CommitHit>> {
+        let Some(query) = fts_query(q) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(
+            "SELECT c.project_id, p.name, p.path, c.sha, c.committed_at, \
+               strftime('%Y-%m-%d', c.committed_at, 'unixepoch'), c.author, \
+               highlight(commit_search, 0, ?2, ?3) \
+             FROM commit_search \
+             JOIN commits c ON c.id = commit_search.rowid \
+             JOIN projects p ON p.id = c.project_id \
+             WHERE commit_search MATCH ?1 \
+             ORDER BY bm25(commit_search), c.committed_at DESC \
+             LIMIT ?4",
+        )?;
+        let rows = stmt
+            .query_map(
+                params![query, OPEN.to_string(), CLOSE.to_string(), limit],
+                |r| {
+                    Ok(CommitHit {
+                        project_id: r.get(0)?,
+                        project: r.get(1)?,
+                        path: r.get(2)?,
+                        sha: r.get(3)?,
+                        committed_at: r.get(4)?,
+                        date: r.get(5)?,
+                        author: r.get(6)?,
+                        subject: Highlighted::parse(&r.get::<_, String>(7)?),
+                    })
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
@@ -1,34 +1,1056 @@
 use anyhow::Result;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 
+/// Format version written to `config.json`; older files are upgraded by [`upgrade`] on load.
+pub const CONFIG_VERSION: u32 = 1;
+
+/// Every field falls back to its default when missing, so a config written by an older
+/// release still loads; keys this release does not know are kept in `extra` and written
+/// back on save, so a newer release's settings survive a round trip through an older one.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct AppConfig {
+    /// Format version of the file this was loaded from; 0 for files predating versioning
+    pub version: u32,
     pub roots: Vec<PathBuf>,
+    /// Scan order for roots; roots without an entry are `normal`
+    #[serde(default)]
+    pub root_priority: BTreeMap<PathBuf, ScanPriority>,
     pub global_ignores: Vec<String>,
     pub size_mode: SizeMode,
+    /// Measure allocated blocks and count hard-linked files once per project, like `du`,
+    /// instead of summing apparent file lengths
+    #[serde(default)]
+    pub size_on_disk: bool,
+    /// Files left out of file counts and LOC
+    #[serde(default)]
+    pub content_exclude: ContentExcludeConfig,
+    /// How much of one project LOC analysis reads before settling for an estimate
+    #[serde(default)]
+    pub loc_limits: LocLimitsConfig,
+    /// Where `last_edited_at` (and so "Recent" sorting) comes from
+    #[serde(default)]
+    pub recency: RecencyMode,
+    /// Type names in the order that decides a project's one `type` when it matches several;
+    /// unlisted types follow in the built-in order (`detect::DEFAULT_TYPE_PRIORITY`)
+    #[serde(default)]
+    pub type_priority: Vec<String>,
+    /// What the scan does with projects inside another project
+    #[serde(default)]
+    pub nested: NestedPolicy,
+    /// How discovery treats links, junctions and mounted volumes
+    #[serde(default)]
+    pub walk: WalkConfig,
     pub concurrency: usize,
     pub git: GitConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    /// Per-analyzer enable flags keyed by analyzer name (loc, deps, tests_ci, secrets, ...).
+    /// Analyzers without an entry use their own default.
+    #[serde(default)]
+    pub analyzers: BTreeMap<String, bool>,
+    /// Projects too large for the expensive analyzers
+    #[serde(default)]
+    pub analyzer_limits: AnalyzerLimitsConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub metrics: MetricsConfig,
+    #[serde(default)]
+    pub logging: LoggingConfig,
+    #[serde(default)]
+    pub launcher: LauncherConfig,
+    /// Commands runnable per project from the app and `action run`, by name
+    #[serde(default = "default_actions")]
+    pub actions: BTreeMap<String, ActionConfig>,
+    #[serde(default)]
+    pub notifications: NotificationsConfig,
+    #[serde(default)]
+    pub journal: JournalConfig,
+    #[serde(default)]
+    pub backup: BackupConfig,
+    #[serde(default)]
+    pub ranking: RankingConfig,
+    #[serde(default)]
+    pub system: SystemConfig,
+    /// Where projects are backed up, for `audit unbacked-up`
+    #[serde(default)]
+    pub offsite: OffsiteConfig,
+    /// End-of-life dates (`YYYY-MM-DD`) by language and release line for `audit toolchains`,
+    /// e.g. `{"go": {"1.24": "2026-02-11"}}`; added to and overriding the built-in dates
+    #[serde(default)]
+    pub toolchain_eol: BTreeMap<String, BTreeMap<String, String>>,
+    /// Who wrote each repo, from its commit authors
+    #[serde(default)]
+    pub authors: AuthorsConfig,
+    /// Time spent per project, estimated from commit times
+    #[serde(default)]
+    pub hours: HoursConfig,
+    /// Commit messages kept for `commits search`
+    #[serde(default)]
+    pub commit_search: CommitSearchConfig,
+    /// What the `ml` analyzer counts as ML artifacts
+    #[serde(default)]
+    pub ml: MlConfig,
+    #[serde(default)]
+    pub cleanup: CleanupConfig,
+    /// Retention policies checked after every scan, by name
+    #[serde(default)]
+    pub policies: BTreeMap<String, PolicyConfig>,
+    #[serde(default)]
+    pub watch: WatchConfig,
+    /// When the index counts as out of date
+    #[serde(default)]
+    pub freshness: FreshnessConfig,
+    /// Local HTTP endpoints for launchers
+    #[serde(default)]
+    pub serve: ServeConfig,
+    #[serde(default)]
+    pub nice: NiceConfig,
+    /// When scheduled scans wait for mains power
+    #[serde(default)]
+    pub power: PowerConfig,
+    /// Unknown keys, preserved as-is
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A quick action: one program run in the project directory without a shell, so project
+/// names and paths never reach a command line as code.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionConfig {
+    /// Program and arguments, e.g. `["cargo", "check"]`
+    pub command: Vec<String>,
+    /// Project types it is offered for; empty offers it for every type
+    pub types: Vec<String>,
+    /// Only offered for git repositories
+    pub git: bool,
+    /// Killed after this long
+    pub timeout_secs: u64,
+}
+
+impl Default for ActionConfig {
+    fn default() -> Self {
+        Self {
+            command: Vec::new(),
+            types: Vec::new(),
+            git: false,
+            timeout_secs: 600,
+        }
+    }
+}
+
+impl ActionConfig {
+    /// Whether the action is offered for a project of `project_type`.
+    pub fn applies_to(&self, project_type: Option<&str>, is_git_repo: bool) -> bool {
+        (!self.git || is_git_repo)
+            && (self.types.is_empty()
+                || project_type.is_some_and(|t| self.types.iter().any(|want| want == t)))
+    }
+}
+
+fn default_actions() -> BTreeMap<String, ActionConfig> {
+    let action = |command: &[&str], types: &[&str], git: bool| ActionConfig {
+        command: command.iter().map(|s| s.to_string()).collect(),
+        types: types.iter().map(|s| s.to_string()).collect(),
+        git,
+        ..ActionConfig::default()
+    };
+    BTreeMap::from([
+        (
+            "git-pull".to_string(),
+            action(&["git", "pull", "--ff-only"], &[], true),
+        ),
+        (
+            "cargo-check".to_string(),
+            action(&["cargo", "check"], &["rust"], false),
+        ),
+        (
+            "npm-install".to_string(),
+            action(&["npm", "install"], &["node", "deno", "bun"], false),
+        ),
+    ])
+}
+
+/// The `watch` daemon.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WatchConfig {
+    /// Minutes between rescans of every configured root
+    pub interval_minutes: u32,
+}
+
+impl Default for WatchConfig {
+    fn default() -> Self {
+        Self {
+            interval_minutes: 30,
+        }
+    }
+}
+
+/// When `list` warns and the app flags the index as stale.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FreshnessConfig {
+    /// Days since the last completed scan after which the index is stale; 0 never warns
+    pub stale_after_days: u32,
+}
+
+impl Default for FreshnessConfig {
+    fn default() -> Self {
+        Self {
+            stale_after_days: 7,
+        }
+    }
+}
+
+/// HTTP endpoints served by `watch` and the app, e.g. `POST /opened`; needs the `serve`
+/// build feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ServeConfig {
+    pub enabled: bool,
+    pub listen: String,
+}
+
+impl Default for ServeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: "127.0.0.1:9465".into(),
+        }
+    }
+}
+
+/// Low-impact scans (`scan --nice`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NiceConfig {
+    /// Run the `watch` daemon's and the app's scans in nice mode too
+    pub background: bool,
+    /// Directory entries walked (and so files stat'ed) per second
+    pub ops_per_sec: u32,
+}
+
+impl Default for NiceConfig {
+    fn default() -> Self {
+        Self {
+            background: false,
+            ops_per_sec: 2000,
+        }
+    }
+}
+
+/// Scheduled (`watch`) scans wait while one of these holds; scans someone asks for still run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PowerConfig {
+    /// Defer while on battery below `min_battery_percent`
+    pub defer_on_battery: bool,
+    /// 100 defers on any battery power
+    pub min_battery_percent: u8,
+    pub defer_in_power_save: bool,
+}
+
+impl Default for PowerConfig {
+    fn default() -> Self {
+        Self {
+            defer_on_battery: true,
+            min_battery_percent: 50,
+            defer_in_power_save: true,
+        }
+    }
+}
+
+/// A retention policy: projects matching `query` violate it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PolicyConfig {
+    /// Filter expression (see `query`), e.g. `edited>18mo AND size>5gb`
+    pub query: String,
+}
+
+/// Thresholds for `recommend cleanup`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CleanupConfig {
+    /// Days without edits or opens before a project's artifacts (or a duplicate checkout)
+    /// are worth deleting
+    pub stale_after_days: u32,
+    /// Days without edits or opens before a fully pushed project is worth deleting outright
+    pub clone_after_days: u32,
+    /// Recommendations freeing less than this are left out
+    pub min_bytes: i64,
+    /// Include `datasets/` directories in ML artifact recommendations; off because data is
+    /// often not downloadable again
+    pub reclaim_datasets: bool,
+}
+
+impl Default for CleanupConfig {
+    fn default() -> Self {
+        Self {
+            stale_after_days: 90,
+            clone_after_days: 365,
+            min_bytes: 50 * 1024 * 1024,
+            reclaim_datasets: false,
+        }
+    }
+}
+
+/// Backup locations that count as a copy of a project beyond this disk.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OffsiteConfig {
+    /// Ask Time Machine (macOS) which projects it backs up
+    pub time_machine: bool,
+    /// Local directories synced to rclone remotes
+    pub rclone: Vec<RcloneSync>,
+    /// Folders a sync client mirrors (Dropbox, iCloud Drive, OneDrive, ...)
+    pub synced_dirs: Vec<PathBuf>,
+}
+
+impl Default for OffsiteConfig {
+    fn default() -> Self {
+        Self {
+            time_machine: true,
+            rclone: Vec::new(),
+            synced_dirs: Vec::new(),
+        }
+    }
+}
+
+/// `local` is kept in sync with `remote` (e.g. by a scheduled `rclone sync`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RcloneSync {
+    pub local: PathBuf,
+    /// rclone remote path, e.g. `b2:backups/code`
+    pub remote: String,
+}
+
+/// Commit authorship per repo (the `authors` analyzer).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthorsConfig {
+    /// Identities to merge, like a `.mailmap` across every repo: an email or name maps to the
+    /// canonical `Name <email>`, e.g. `{"jd@old-job.com": "Jane Doe <jane@example.com>"}`
+    pub aliases: BTreeMap<String, String>,
+    /// Your own names or emails (after `aliases`); repos you are the primary author of get
+    /// the `mine` field
+    pub me: Vec<String>,
+    /// Most recent commits counted per repo
+    pub max_commits: usize,
+    /// Authors listed in the `top_authors` field
+    pub top: usize,
+}
+
+impl Default for AuthorsConfig {
+    fn default() -> Self {
+        Self {
+            aliases: BTreeMap::new(),
+            me: Vec::new(),
+            max_commits: 5000,
+            top: 3,
+        }
+    }
+}
+
+/// Work sessions from commit times (the `hours` analyzer).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HoursConfig {
+    /// Commits further apart than this start a new session
+    pub session_gap_minutes: u32,
+    /// Added to each session for the work before its first commit
+    pub first_commit_minutes: u32,
+    /// Only count commits by `authors.me`, when it is set
+    pub only_mine: bool,
+    /// Most recent commits looked at per repo
+    pub max_commits: usize,
+}
+
+impl Default for HoursConfig {
+    fn default() -> Self {
+        Self {
+            session_gap_minutes: 120,
+            first_commit_minutes: 30,
+            only_mine: true,
+            max_commits: 10000,
+        }
+    }
+}
+
+/// Commit messages indexed per repo (the `commits` analyzer).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CommitSearchConfig {
+    /// Most recent commits on the checked-out branch kept per repo
+    pub max_commits: usize,
+}
+
+impl Default for CommitSearchConfig {
+    fn default() -> Self {
+        Self { max_commits: 500 }
+    }
+}
+
+/// Thresholds for the `ml` analyzer.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MlConfig {
+    /// Checkpoint and weights files smaller than this are ignored
+    pub min_file_mb: u64,
+    /// Virtualenvs and conda environments smaller than this are ignored
+    pub min_env_mb: u64,
+    /// Project types the analyzer runs for
+    pub types: Vec<String>,
+    /// Directory levels below the project root that are searched
+    pub max_depth: usize,
+}
+
+impl Default for MlConfig {
+    fn default() -> Self {
+        Self {
+            min_file_mb: 10,
+            min_env_mb: 500,
+            types: vec!["python".into(), "data-science".into(), "julia".into()],
+            max_depth: 6,
+        }
+    }
+}
+
+/// System-wide scans of every user's home (`system scan`, run as an administrator).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SystemConfig {
+    /// Homes to scan; empty scans every home under `/home` (`/Users` on macOS)
+    pub homes: Vec<PathBuf>,
+    /// Projects not edited for this long count as stale in `system report`
+    pub stale_after_days: u32,
+}
+
+impl Default for SystemConfig {
+    fn default() -> Self {
+        Self {
+            homes: Vec::new(),
+            stale_after_days: default_stale_after_days(),
+        }
+    }
+}
+
+/// How the quick-open palette orders matches. Every signal is scaled to 0..1 before its
+/// weight is applied; set a weight to 0 to ignore that signal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RankingConfig {
+    /// Text match quality (BM25 over name, path and README)
+    #[serde(default = "default_match_weight")]
+    pub match_weight: f64,
+    /// How often and how recently the project was opened
+    #[serde(default = "default_one")]
+    pub frecency_weight: f64,
+    /// Bonus for projects marked `favorite`
+    #[serde(default = "default_favorite_weight")]
+    pub favorite_weight: f64,
+    /// Penalty for projects neither edited nor opened lately
+    #[serde(default = "default_staleness_weight")]
+    pub staleness_weight: f64,
+    /// Days without edits or opens after which the full staleness penalty applies
+    #[serde(default = "default_stale_after_days")]
+    pub stale_after_days: u32,
+}
+
+fn default_one() -> f64 {
+    1.0
+}
+
+fn default_match_weight() -> f64 {
+    2.0
+}
+
+fn default_favorite_weight() -> f64 {
+    0.5
+}
+
+fn default_staleness_weight() -> f64 {
+    0.25
+}
+
+fn default_stale_after_days() -> u32 {
+    180
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        Self {
+            match_weight: default_match_weight(),
+            frecency_weight: default_one(),
+            favorite_weight: default_favorite_weight(),
+            staleness_weight: default_staleness_weight(),
+            stale_after_days: default_stale_after_days(),
+        }
+    }
+}
+
+/// Automatic database snapshots taken before scans.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupConfig {
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Minimum age of the newest backup before a scan takes another; 0 backs up before
+    /// every scan
+    #[serde(default = "default_backup_interval_hours")]
+    pub interval_hours: u64,
+    /// Backups to keep; older ones are deleted
+    #[serde(default = "default_backup_keep")]
+    pub keep: usize,
+}
+
+fn default_backup_interval_hours() -> u64 {
+    24
+}
+
+fn default_backup_keep() -> usize {
+    7
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            interval_hours: default_backup_interval_hours(),
+            keep: default_backup_keep(),
+        }
+    }
+}
+
+/// Undo journal for deletes and merges.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalConfig {
+    /// Operations older than this can no longer be undone and are dropped
+    #[serde(default = "default_journal_retention_days")]
+    pub retention_days: u32,
+}
+
+fn default_journal_retention_days() -> u32 {
+    30
+}
+
+impl Default for JournalConfig {
+    fn default() -> Self {
+        Self {
+            retention_days: default_journal_retention_days(),
+        }
+    }
+}
+
+/// Desktop notifications after app-initiated scans; each event kind can be turned off.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NotificationsConfig {
+    /// "Scan finished: N projects"
+    #[serde(default = "default_true")]
+    pub scan_complete: bool,
+    /// Projects that were not indexed before the scan
+    #[serde(default = "default_true")]
+    pub new_projects: bool,
+    /// Projects whose size grew by at least `size_growth_bytes` since the previous scan
+    #[serde(default = "default_true")]
+    pub size_growth: bool,
+    #[serde(default = "default_size_growth_bytes")]
+    pub size_growth_bytes: i64,
+    /// Repos that were clean before the scan and now have uncommitted changes
+    #[serde(default = "default_true")]
+    pub became_dirty: bool,
+    /// Projects that started violating a retention policy (see `policies`)
+    #[serde(default = "default_true")]
+    pub policy_violations: bool,
+}
+
+fn default_size_growth_bytes() -> i64 {
+    1024 * 1024 * 1024
+}
+
+impl Default for NotificationsConfig {
+    fn default() -> Self {
+        Self {
+            scan_complete: true,
+            new_projects: true,
+            size_growth: true,
+            size_growth_bytes: default_size_growth_bytes(),
+            became_dirty: true,
+            policy_violations: true,
+        }
+    }
+}
+
+/// Where a root goes in the scan queue: higher priorities are scanned first, equal
+/// priorities in the order they were queued.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum ScanPriority {
+    Low,
+    #[default]
+    Normal,
+    High,
+}
+
+/// Quick-launch surfaces of the app (tray menu, hotkey palette) and what they do.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LauncherConfig {
+    /// Editor command used when no editor is picked explicitly, e.g. `code` or `cursor`
+    #[serde(default = "default_editor")]
+    pub editor: String,
+    /// Global shortcut that toggles the quick-open palette; empty disables it
+    #[serde(default = "default_hotkey")]
+    pub hotkey: String,
+    /// What Enter does in the palette and a click does in the tray menu
+    #[serde(default)]
+    pub default_action: LaunchAction,
+    #[serde(default = "default_true")]
+    pub tray: bool,
+    /// Recent projects listed in the tray menu
+    #[serde(default = "default_tray_items")]
+    pub tray_items: u32,
+    /// Terminal that runs projects' launch commands, as the arguments before the command,
+    /// e.g. `["kitty", "--"]`; empty uses the platform's default terminal
+    #[serde(default)]
+    pub terminal: Vec<String>,
+}
+
+fn default_editor() -> String {
+    "code".into()
+}
+
+fn default_hotkey() -> String {
+    "CmdOrCtrl+Shift+Space".into()
+}
+
+fn default_tray_items() -> u32 {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum LaunchAction {
+    /// Open in `launcher.editor`
+    #[default]
+    Editor,
+    /// Show in the system file manager
+    Reveal,
+    /// Bring up the main window filtered to the project
+    Show,
+}
+
+impl Default for LauncherConfig {
+    fn default() -> Self {
+        Self {
+            editor: default_editor(),
+            hotkey: default_hotkey(),
+            default_action: LaunchAction::default(),
+            tray: true,
+            tray_items: default_tray_items(),
+            terminal: Vec::new(),
+        }
+    }
+}
+
+/// File logging for the app; stderr output is unaffected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingConfig {
+    #[serde(default = "default_true")]
+    pub file_enabled: bool,
+    /// Defaults to `logs/project-browser.log` in the data dir
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    /// EnvFilter directive used when `RUST_LOG` is unset
+    #[serde(default = "default_log_level")]
+    pub level: String,
+    /// Rotate once the file would grow past this many bytes
+    #[serde(default = "default_log_max_bytes")]
+    pub max_bytes: u64,
+    /// Rotated files kept alongside the active one
+    #[serde(default = "default_log_max_files")]
+    pub max_files: usize,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_log_level() -> String {
+    "info".into()
+}
+
+fn default_log_max_bytes() -> u64 {
+    5 * 1024 * 1024
+}
+
+fn default_log_max_files() -> usize {
+    3
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            file_enabled: true,
+            path: None,
+            level: default_log_level(),
+            max_bytes: default_log_max_bytes(),
+            max_files: default_log_max_files(),
+        }
+    }
+}
+
+/// Prometheus exporter for long-running processes; needs the `metrics` build feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricsConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Address for the `/metrics` endpoint
+    #[serde(default = "default_metrics_listen")]
+    pub listen: String,
+}
+
+fn default_metrics_listen() -> String {
+    "127.0.0.1:9464".into()
 }
 
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            listen: default_metrics_listen(),
+        }
+    }
+}
+
+/// External commands run per discovered project; their JSON stdout becomes custom fields.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    /// Command templates with `{path}`, `{type}` and `{name}` placeholders
+    #[serde(default)]
+    pub post_detect: Vec<String>,
+    /// Kill a hook that runs longer than this
+    #[serde(default = "default_hook_timeout")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout() -> u64 {
+    10
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            post_detect: Vec::new(),
+            timeout_secs: default_hook_timeout(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct GitConfig {
     pub use_cli_fallback: bool,
 }
 
+/// Opt-in heuristic scan for credentials left on disk (.env keys, private keys, AWS keys).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SecretsConfig {
+    pub enabled: bool,
+    /// Files larger than this are never read for content checks
+    pub max_file_bytes: u64,
+}
+
+impl Default for SecretsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_file_bytes: 256 * 1024,
+        }
+    }
+}
+
+/// How a project's `last_edited_at` is derived. Projects outside git always use mtimes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecencyMode {
+    /// Newest mtime of any non-ignored file, or the last commit if that is newer
+    #[default]
+    Mtime,
+    /// Newest mtime among the files git tracks, so rebuilt artifacts do not count
+    TrackedFiles,
+    /// Time of the last commit on HEAD
+    LastCommit,
+}
+
+/// Projects found inside another project's directory (say a `frontend/` Node app in a Python
+/// repo). Declared workspace members are indexed under every policy.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NestedPolicy {
+    /// Stop at the outermost project and skip everything beneath it
+    #[default]
+    Skip,
+    /// Keep looking inside projects and index nested ones as top-level projects
+    Index,
+    /// As `index`, with each nested project linked to the project around it as a child
+    IndexWithParentLink,
+}
+
+/// Links and mounted volumes met while walking a root for projects. A directory reached
+/// twice (a macOS firmlink, a bind mount, a link into a tree already walked) is only walked
+/// the first time, so scans neither loop nor index a project twice.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct WalkConfig {
+    /// Descend through symlinks to directories and, on Windows, junctions and volume mount
+    /// points (which Windows reports as links, so Dev Drives mounted in a folder are otherwise
+    /// skipped). Default: on for Windows only.
+    pub follow_links: bool,
+    /// Descend into other mounted volumes (case-sensitive APFS volumes, Dev Drives, network
+    /// shares); off keeps each root on its own file system
+    pub cross_mounts: bool,
+}
+
+impl Default for WalkConfig {
+    fn default() -> Self {
+        Self {
+            follow_links: cfg!(windows),
+            cross_mounts: true,
+        }
+    }
+}
+
+/// Files that still count towards a project's size but are left out of `files_count` and
+/// never read for LOC, so repos full of model weights and media assets scan quickly.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentExcludeConfig {
+    /// Skip files larger than this many MB; 0 disables the limit
+    #[serde(default)]
+    pub max_file_mb: u64,
+    /// Skip files whose extension is in `binary_extensions`
+    #[serde(default)]
+    pub skip_binary: bool,
+    #[serde(default = "default_binary_extensions")]
+    pub binary_extensions: Vec<String>,
+}
+
+fn default_binary_extensions() -> Vec<String> {
+    [
+        // model weights and data
+        "safetensors",
+        "ckpt",
+        "pt",
+        "pth",
+        "onnx",
+        "h5",
+        "pb",
+        "tflite",
+        "gguf",
+        "npy",
+        "npz",
+        "parquet",
+        "bin",
+        // media
+        "png",
+        "jpg",
+        "jpeg",
+        "gif",
+        "webp",
+        "psd",
+        "mp4",
+        "mov",
+        "mkv",
+        "avi",
+        "mp3",
+        "wav",
+        "flac",
+        // archives and build outputs
+        "zip",
+        "tar",
+        "gz",
+        "7z",
+        "rar",
+        "iso",
+        "dmg",
+        "exe",
+        "dll",
+        "so",
+        "dylib",
+        "a",
+        "o",
+        "class",
+        "jar",
+        "wasm",
+        "pdf",
+        "ttf",
+        "woff",
+        "woff2",
+    ]
+    .into_iter()
+    .map(String::from)
+    .collect()
+}
+
+impl Default for ContentExcludeConfig {
+    fn default() -> Self {
+        Self {
+            max_file_mb: 0,
+            skip_binary: false,
+            binary_extensions: default_binary_extensions(),
+        }
+    }
+}
+
+impl ContentExcludeConfig {
+    pub fn is_active(&self) -> bool {
+        self.max_file_mb > 0 || self.skip_binary
+    }
+
+    /// Whether `path` has one of the binary extensions (and `skip_binary` is on).
+    pub fn excludes_by_name(&self, path: &Path) -> bool {
+        self.skip_binary
+            && path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+                self.binary_extensions
+                    .iter()
+                    .any(|b| b.eq_ignore_ascii_case(e))
+            })
+    }
+
+    /// Whether a file of `len` bytes at `path` is left out of counts and LOC.
+    pub fn excludes(&self, path: &Path, len: u64) -> bool {
+        (self.max_file_mb > 0 && len > self.max_file_mb * 1024 * 1024)
+            || self.excludes_by_name(path)
+    }
+}
+
+/// Caps on the files LOC analysis reads per project, so a giant monorepo does not hold every
+/// file's counts in memory at once. Past a cap the rest is extrapolated from the bytes left
+/// and the LOC is flagged `loc_truncated`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LocLimitsConfig {
+    /// Source files read per project; 0 disables the limit
+    pub max_files: u64,
+    /// MB of source read per project; 0 disables the limit
+    pub max_mb: u64,
+    /// Overrides for projects under a directory (usually a root); the deepest match wins
+    pub roots: BTreeMap<PathBuf, RootLocLimits>,
+}
+
+/// Per-root `loc_limits`; a missing field keeps the global value.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RootLocLimits {
+    pub max_files: Option<u64>,
+    pub max_mb: Option<u64>,
+}
+
+impl Default for LocLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_files: 100_000,
+            max_mb: 1024,
+            roots: BTreeMap::new(),
+        }
+    }
+}
+
+impl LocLimitsConfig {
+    /// `(max_files, max_bytes)` for the project at `project`, 0 meaning unlimited.
+    pub fn limits_for(&self, project: &Path) -> (u64, u64) {
+        let mut over: Option<(usize, &RootLocLimits)> = None;
+        for (dir, limits) in &self.roots {
+            let dir =
+                crate::paths::canonical(Path::new(&*shellexpand::tilde(&dir.to_string_lossy())));
+            let depth = dir.components().count();
+            if project.starts_with(&dir) && over.is_none_or(|(d, _)| depth > d) {
+                over = Some((depth, limits));
+            }
+        }
+        let over = over.map(|(_, l)| l);
+        let max_files = over.and_then(|l| l.max_files).unwrap_or(self.max_files);
+        let max_mb = over.and_then(|l| l.max_mb).unwrap_or(self.max_mb);
+        (max_files, max_mb * 1024 * 1024)
+    }
+}
+
+/// Projects past either threshold skip the analyzers in `skip`, so one gigantic vendored repo
+/// does not dominate every scan. Thresholds are checked against the scan's own size and file
+/// count, so with `size_mode: none` only `max_files` applies.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AnalyzerLimitsConfig {
+    /// 0 disables the limit
+    pub max_size_mb: u64,
+    /// 0 disables the limit
+    pub max_files: u64,
+    /// Analyzer names to skip; `readme` is the README text indexed for search
+    pub skip: Vec<String>,
+}
+
+impl Default for AnalyzerLimitsConfig {
+    fn default() -> Self {
+        Self {
+            max_size_mb: 20 * 1024,
+            max_files: 250_000,
+            skip: vec!["loc".into(), "deps".into(), "readme".into()],
+        }
+    }
+}
+
+impl AnalyzerLimitsConfig {
+    /// Why a project this large skips the analyzers in `skip`, or None when it is small
+    /// enough (or its size is unknown).
+    pub fn exceeded(&self, size_bytes: Option<i64>, files_count: Option<i64>) -> Option<String> {
+        let size_mb = size_bytes.map(|b| b.max(0) as u64 / (1024 * 1024));
+        if let Some(mb) = size_mb.filter(|mb| self.max_size_mb > 0 && *mb > self.max_size_mb) {
+            return Some(format!(
+                "{mb} MB is over analyzer_limits.max_size_mb ({})",
+                self.max_size_mb
+            ));
+        }
+        let files = files_count.map(|n| n.max(0) as u64);
+        files
+            .filter(|n| self.max_files > 0 && *n > self.max_files)
+            .map(|n| {
+                format!(
+                    "{n} files is over analyzer_limits.max_files ({})",
+                    self.max_files
+                )
+            })
+    }
+
+    pub fn skips(&self, analyzer: &str) -> bool {
+        self.skip.iter().any(|name| name == analyzer)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SizeMode {
     ExactCached,
+    /// Stat a sample of each directory's files and extrapolate; much faster on large trees,
+    /// and records are flagged `size_estimated`
+    Estimate,
     None,
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            version: CONFIG_VERSION,
             roots: vec![shellexpand::tilde("~/Code").to_string().into()],
+            root_priority: BTreeMap::new(),
             global_ignores: vec![
                 ".git".into(),
                 "node_modules".into(),
@@ -41,24 +1063,172 @@ impl Default for AppConfig {
                 ".cache".into(),
             ],
             size_mode: SizeMode::ExactCached,
+            size_on_disk: false,
+            content_exclude: ContentExcludeConfig::default(),
+            loc_limits: LocLimitsConfig::default(),
+            recency: RecencyMode::default(),
+            type_priority: Vec::new(),
+            nested: NestedPolicy::default(),
+            walk: WalkConfig::default(),
             concurrency: 8,
-            git: GitConfig {
-                use_cli_fallback: false,
-            },
+            git: GitConfig::default(),
+            secrets: SecretsConfig::default(),
+            analyzers: BTreeMap::new(),
+            analyzer_limits: AnalyzerLimitsConfig::default(),
+            hooks: HooksConfig::default(),
+            metrics: MetricsConfig::default(),
+            logging: LoggingConfig::default(),
+            launcher: LauncherConfig::default(),
+            actions: default_actions(),
+            notifications: NotificationsConfig::default(),
+            journal: JournalConfig::default(),
+            backup: BackupConfig::default(),
+            ranking: RankingConfig::default(),
+            system: SystemConfig::default(),
+            offsite: OffsiteConfig::default(),
+            toolchain_eol: BTreeMap::new(),
+            authors: AuthorsConfig::default(),
+            hours: HoursConfig::default(),
+            commit_search: CommitSearchConfig::default(),
+            ml: MlConfig::default(),
+            cleanup: CleanupConfig::default(),
+            policies: BTreeMap::new(),
+            watch: WatchConfig::default(),
+            freshness: FreshnessConfig::default(),
+            serve: ServeConfig::default(),
+            nice: NiceConfig::default(),
+            power: PowerConfig::default(),
+            extra: serde_json::Map::new(),
         }
     }
 }
 
+/// Last size and position of an app window, in logical pixels.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct WindowState {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+    #[serde(default)]
+    pub maximized: bool,
+}
+
+impl AppConfig {
+    /// Configured queue priority for `root`.
+    pub fn priority_of(&self, root: &Path) -> ScanPriority {
+        self.root_priority.get(root).copied().unwrap_or_default()
+    }
+
+    /// Add `root` unless it (or a root containing it) is already configured.
+    /// Returns whether the roots changed.
+    pub fn add_root(&mut self, root: PathBuf) -> bool {
+        if self.roots.iter().any(|r| root.starts_with(r)) {
+            return false;
+        }
+        self.roots.push(root);
+        true
+    }
+}
+
+/// Upgrade steps, indexed by the version they upgrade from.
+const UPGRADES: &[fn(&mut serde_json::Map<String, serde_json::Value>)] = &[upgrade_v0];
+
+/// v0 files (written before versioning) use today's key names; fields added since then
+/// are filled in by their serde defaults, so there is nothing to rewrite.
+fn upgrade_v0(_cfg: &mut serde_json::Map<String, serde_json::Value>) {}
+
+/// Bring raw config JSON up to [`CONFIG_VERSION`]. Files from a newer release are left
+/// alone: their unknown keys are kept and known ones still load.
+pub fn upgrade(mut cfg: serde_json::Value) -> serde_json::Value {
+    let Some(map) = cfg.as_object_mut() else {
+        return cfg;
+    };
+    let from = map
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0)
+        .min(u64::from(u32::MAX)) as u32;
+    if from >= CONFIG_VERSION {
+        return cfg;
+    }
+    for step in &UPGRADES[from as usize..] {
+        step(map);
+    }
+    map.insert("version".into(), CONFIG_VERSION.into());
+    cfg
+}
+
+impl AppConfig {
+    /// Parse `config.json` text, upgrading older formats.
+    pub fn from_json(text: &str) -> Result<AppConfig> {
+        let raw: serde_json::Value = serde_json::from_str(text)?;
+        Ok(serde_json::from_value(upgrade(raw))?)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        let mut cfg = self.clone();
+        // Never mark a newer release's file as older than it is
+        cfg.version = cfg.version.max(CONFIG_VERSION);
+        Ok(serde_json::to_string_pretty(&cfg)?)
+    }
+}
+
+/// Puts config and data in this one directory instead of the per-user locations.
+pub const HOME_ENV: &str = "PROJECT_BROWSER_HOME";
+/// A file with this name next to the executable turns portable mode on without `--portable`.
+pub const PORTABLE_MARKER: &str = "portable";
+/// Directory next to the executable that holds everything in portable mode
+pub const PORTABLE_DIR: &str = "project-browser-data";
+
+static PORTABLE: AtomicBool = AtomicBool::new(false);
+
 pub struct ConfigStore;
 
 impl ConfigStore {
+    /// Keep config and data next to the executable (`--portable`), taking precedence over
+    /// `PROJECT_BROWSER_HOME`. Call before anything reads a path.
+    pub fn set_portable(on: bool) {
+        PORTABLE.store(on, Ordering::Relaxed);
+    }
+
+    /// The single directory holding config and data when relocated by `--portable`,
+    /// `PROJECT_BROWSER_HOME` or a `portable` file next to the executable, in that order.
+    /// `None` means the usual per-user directories.
+    pub fn home_override() -> Result<Option<PathBuf>> {
+        let portable_dir = || -> Result<PathBuf> {
+            let exe = std::env::current_exe()?;
+            let dir = exe
+                .parent()
+                .ok_or_else(|| anyhow::anyhow!("executable has no parent directory"))?;
+            Ok(dir.to_path_buf())
+        };
+        if PORTABLE.load(Ordering::Relaxed) {
+            return Ok(Some(portable_dir()?.join(PORTABLE_DIR)));
+        }
+        if let Some(home) = std::env::var_os(HOME_ENV).filter(|h| !h.is_empty()) {
+            let home = shellexpand::tilde(&home.to_string_lossy()).to_string();
+            return Ok(Some(PathBuf::from(home)));
+        }
+        match portable_dir() {
+            Ok(dir) if dir.join(PORTABLE_MARKER).is_file() => Ok(Some(dir.join(PORTABLE_DIR))),
+            _ => Ok(None),
+        }
+    }
+
     pub fn config_dir() -> Result<PathBuf> {
+        if let Some(home) = Self::home_override()? {
+            return Ok(home);
+        }
         let dirs = ProjectDirs::from("com.projectbrowser", "Local", "ProjectBrowser")
             .ok_or_else(|| anyhow::anyhow!("could not resolve project dirs"))?;
         Ok(dirs.config_dir().to_path_buf())
     }
 
     pub fn data_dir() -> Result<PathBuf> {
+        if let Some(home) = Self::home_override()? {
+            return Ok(home);
+        }
         let dirs = ProjectDirs::from("com.projectbrowser", "Local", "ProjectBrowser")
             .ok_or_else(|| anyhow::anyhow!("could not resolve project dirs"))?;
         Ok(dirs.data_dir().to_path_buf())
@@ -68,23 +1238,33 @@ impl ConfigStore {
         Ok(Self::config_dir()?.join("config.json"))
     }
 
+    /// Window geometry keyed by window label; kept out of config.json since it changes
+    /// every time a window moves.
+    pub fn window_state_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("window-state.json"))
+    }
+
     /// Primary app-level ignore file next to config.json
     pub fn app_ignore_path() -> Result<PathBuf> {
         Ok(Self::config_dir()?.join("ignore"))
     }
 
-    /// Legacy/convenience ignore file: ~/.config/project-browser/ignore
-    pub fn user_ignore_path_legacy() -> PathBuf {
+    /// Legacy/convenience ignore file: ~/.config/project-browser/ignore. Not read when
+    /// config and data are relocated, which must not depend on the user's home.
+    pub fn user_ignore_path_legacy() -> Option<PathBuf> {
+        if Self::home_override().ok().flatten().is_some() {
+            return None;
+        }
         let home = dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("~"));
-        home.join(".config").join("project-browser").join("ignore")
+        Some(home.join(".config").join("project-browser").join("ignore"))
     }
 
     pub fn load() -> Result<AppConfig> {
         let path = Self::config_path()?;
         if path.exists() {
             let s = fs::read_to_string(&path)?;
-            let cfg: AppConfig = serde_json::from_str(&s)?;
-            Ok(cfg)
+            AppConfig::from_json(&s)
+                .map_err(|e| anyhow::anyhow!("invalid config {}: {e}", path.display()))
         } else {
             Ok(AppConfig::default())
         }
@@ -94,8 +1274,31 @@ impl ConfigStore {
         let dir = Self::config_dir()?;
         fs::create_dir_all(&dir)?;
         let path = dir.join("config.json");
-        let s = serde_json::to_string_pretty(cfg)?;
-        fs::write(path, s)?;
+        fs::write(path, cfg.to_json()?)?;
+        Ok(())
+    }
+
+    pub fn load_window_states() -> Result<BTreeMap<String, WindowState>> {
+        let path = Self::window_state_path()?;
+        if !path.exists() {
+            return Ok(BTreeMap::new());
+        }
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn window_state(label: &str) -> Result<Option<WindowState>> {
+        Ok(Self::load_window_states()?.remove(label))
+    }
+
+    pub fn save_window_state(label: &str, state: WindowState) -> Result<()> {
+        // A corrupt state file only costs remembered geometry
+        let mut states = Self::load_window_states().unwrap_or_default();
+        states.insert(label.to_string(), state);
+        fs::create_dir_all(Self::config_dir()?)?;
+        fs::write(
+            Self::window_state_path()?,
+            serde_json::to_string_pretty(&states)?,
+        )?;
         Ok(())
     }
 }
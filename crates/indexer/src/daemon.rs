@@ -0,0 +1,411 @@
+//! The `watch` daemon: one long-running process that rescans the configured roots on an
+//! interval and answers a local control socket (a Unix socket in the data directory, a
+//! named pipe on Windows), so the CLI and the app can ask it for status, pause it, queue a
+//! root or run a query instead of each opening the database and scanning on their own.
+//!
+//! The protocol is one JSON request per line, e.g. `{"cmd":"rescan","root":"/code/web"}`,
+//! answered by one JSON line: `{"ok":<result>}` or `{"error":"<message>"}`.
+
+use anyhow::{anyhow, Context, Result};
+use interprocess::local_socket::{prelude::*, ListenerOptions, Name, Stream};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+use time::OffsetDateTime;
+
+use crate::analyzers::AnalyzerRegistry;
+use crate::config::{AppConfig, ConfigStore};
+use crate::db::{Db, ProjectFilter, SortKey};
+use crate::power::{defer_reason, power_status};
+use crate::query::Query;
+use crate::queue::{QueuedRoot, ScanPriority, ScanQueue};
+use crate::rpc::ProjectsRequest;
+use crate::scan::{ScanOptions, ScanReport};
+
+/// How soon a periodic rescan put off for power is tried again
+const DEFER_RETRY: Duration = Duration::from_secs(5 * 60);
+
+/// Socket file in the data directory (Unix)
+pub const SOCKET_FILE: &str = "daemon.sock";
+
+fn default_limit() -> u32 {
+    50
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case")]
+pub enum Request {
+    /// `DaemonStatus`
+    Status,
+    /// Stop starting scans until `resume`; a scan already running finishes
+    Pause,
+    Resume,
+    /// Queue `root`, by default ahead of the periodic rescans
+    Rescan {
+        root: PathBuf,
+        #[serde(default)]
+        priority: Option<ScanPriority>,
+    },
+    /// Projects matching a filter expression (see `query`), most recently edited first
+    Query {
+        #[serde(default)]
+        query: String,
+        #[serde(default = "default_limit")]
+        limit: u32,
+    },
+    /// A `ProjectsPage`, as the app's project list asks for it
+    Projects(ProjectsRequest),
+    /// Full-text matches (see `search`)
+    Search {
+        q: String,
+        #[serde(default = "default_limit")]
+        limit: u32,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Response {
+    Ok(Value),
+    Error(String),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DaemonStatus {
+    pub pid: u32,
+    pub started_at: i64,
+    pub paused: bool,
+    pub scanning: bool,
+    /// Roots waiting to be scanned, next first
+    pub queued: Vec<QueuedRoot>,
+    pub last_scan_at: Option<i64>,
+    /// Projects seen by the last scan
+    pub last_scan_projects: Option<usize>,
+    pub last_error: Option<String>,
+    /// When every configured root is queued again
+    pub next_scan_at: Option<i64>,
+    /// Why the last periodic rescan was put off (see `power`)
+    #[serde(default)]
+    pub deferred: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct LoopState {
+    scanning: bool,
+    last_scan_at: Option<i64>,
+    last_scan_projects: Option<usize>,
+    last_error: Option<String>,
+    next_scan_at: Option<i64>,
+    deferred: Option<String>,
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+/// Shared between the scan loop and the socket's connection threads.
+#[derive(Debug)]
+pub struct Daemon {
+    queue: ScanQueue,
+    paused: AtomicBool,
+    started_at: i64,
+    state: Mutex<LoopState>,
+    /// Signalled when a request may let the scan loop make progress
+    wake: Condvar,
+}
+
+impl Default for Daemon {
+    fn default() -> Self {
+        Self {
+            queue: ScanQueue::default(),
+            paused: AtomicBool::new(false),
+            started_at: now(),
+            state: Mutex::default(),
+            wake: Condvar::new(),
+        }
+    }
+}
+
+impl Daemon {
+    pub fn status(&self) -> DaemonStatus {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        DaemonStatus {
+            pid: std::process::id(),
+            started_at: self.started_at,
+            paused: self.paused.load(Ordering::SeqCst),
+            scanning: state.scanning,
+            queued: self.queue.pending(),
+            last_scan_at: state.last_scan_at,
+            last_scan_projects: state.last_scan_projects,
+            last_error: state.last_error.clone(),
+            next_scan_at: state.next_scan_at,
+            deferred: state.deferred.clone(),
+        }
+    }
+
+    fn notify(&self) {
+        let _state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.wake.notify_all();
+    }
+
+    /// Answer one request, reading from `db` for queries.
+    pub fn handle(&self, db: &Db, req: Request) -> Result<Value> {
+        match req {
+            Request::Status => {}
+            Request::Pause => self.paused.store(true, Ordering::SeqCst),
+            Request::Resume => {
+                self.paused.store(false, Ordering::SeqCst);
+                self.notify();
+            }
+            Request::Rescan { root, priority } => {
+                if !root.is_dir() {
+                    return Err(anyhow!("cannot rescan {}: not a directory", root.display()));
+                }
+                self.queue.enqueue(
+                    crate::paths::canonical(&root),
+                    priority.unwrap_or(ScanPriority::High),
+                );
+                self.notify();
+            }
+            Request::Query { query, limit } => {
+                let filter = ProjectFilter {
+                    query: (!query.trim().is_empty())
+                        .then(|| Query::parse(&query))
+                        .transpose()?,
+                    ..Default::default()
+                };
+                let rows = db.query_projects(&filter, SortKey::Recent, false, 0, limit)?;
+                return Ok(serde_json::to_value(rows)?);
+            }
+            Request::Projects(req) => return Ok(serde_json::to_value(db.projects_page(&req)?)?),
+            Request::Search { q, limit } => {
+                return Ok(serde_json::to_value(db.search(&q, limit)?)?)
+            }
+        }
+        Ok(serde_json::to_value(self.status())?)
+    }
+
+    /// Scan whatever is queued unless paused. Returns None when nothing ran.
+    pub fn run_pending(
+        &self,
+        db: &Db,
+        cfg: &AppConfig,
+        opts: &ScanOptions,
+        analyzers: &AnalyzerRegistry,
+    ) -> Option<Result<ScanReport>> {
+        if self.paused.load(Ordering::SeqCst) || self.queue.is_empty() {
+            return None;
+        }
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .scanning = true;
+        let opts = ScanOptions {
+            nice: opts.nice || cfg.nice.background,
+            ..opts.clone()
+        };
+        let result = self.queue.run(db, cfg, &opts, analyzers);
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.scanning = false;
+        state.last_scan_at = Some(now());
+        match &result {
+            Ok(report) => {
+                state.last_scan_projects = Some(report.projects);
+                state.last_error = None;
+            }
+            Err(err) => state.last_error = Some(format!("{err:#}")),
+        }
+        Some(result)
+    }
+
+    /// Scan loop: queue every configured root every `interval` (re-reading the config each
+    /// time so root changes apply) and scan whatever is queued. Never returns except on a
+    /// config error.
+    pub fn run(
+        &self,
+        db: &Db,
+        opts: &ScanOptions,
+        analyzers: &AnalyzerRegistry,
+        interval: Duration,
+    ) -> Result<()> {
+        let mut cfg = ConfigStore::load()?;
+        let mut next_round = Instant::now();
+        loop {
+            if Instant::now() >= next_round {
+                cfg = ConfigStore::load()?;
+                let deferred = defer_reason(&cfg.power, &power_status());
+                // A deferred round is retried soon rather than a whole interval later
+                let wait = match &deferred {
+                    Some(reason) => {
+                        tracing::info!(%reason, "deferring periodic rescan");
+                        interval.min(DEFER_RETRY)
+                    }
+                    None => {
+                        self.queue.enqueue_configured(&cfg);
+                        interval
+                    }
+                };
+                next_round = Instant::now() + wait;
+                let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                state.next_scan_at = Some(now() + wait.as_secs() as i64);
+                state.deferred = deferred;
+            }
+            if let Some(result) = self.run_pending(db, &cfg, opts, analyzers) {
+                match result {
+                    Ok(report) => tracing::info!(projects = report.projects, "watch scan done"),
+                    Err(err) => tracing::warn!(error = %format!("{err:#}"), "watch scan failed"),
+                }
+                continue;
+            }
+            // Check again under the lock `notify` takes, so a request cannot slip in between
+            let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            if !self.paused.load(Ordering::SeqCst) && !self.queue.is_empty() {
+                continue;
+            }
+            let wait = next_round.saturating_duration_since(Instant::now());
+            let _ = self.wake.wait_timeout(state, wait);
+        }
+    }
+}
+
+/// The daemon's socket for the current data directory.
+pub fn default_socket() -> Result<PathBuf> {
+    Ok(ConfigStore::data_dir()?.join(SOCKET_FILE))
+}
+
+/// A socket file path on Unix; on Windows a pipe named after it, so each data directory
+/// (see `PROJECT_BROWSER_HOME`) gets its own daemon.
+fn socket_name(path: &Path) -> Result<Name<'static>> {
+    #[cfg(unix)]
+    {
+        use interprocess::local_socket::GenericFilePath;
+        Ok(path.to_path_buf().to_fs_name::<GenericFilePath>()?)
+    }
+    #[cfg(not(unix))]
+    {
+        use interprocess::local_socket::GenericNamespaced;
+        // FNV-1a, stable across runs unlike `DefaultHasher`
+        let hash = path
+            .to_string_lossy()
+            .bytes()
+            .fold(0xcbf29ce484222325u64, |h, b| {
+                (h ^ u64::from(b)).wrapping_mul(0x100000001b3)
+            });
+        Ok(format!("project-browser-{hash:016x}").to_ns_name::<GenericNamespaced>()?)
+    }
+}
+
+/// Serve the control socket at `path` from a background thread, one thread per client,
+/// each with its own connection to `db_path`. Fails when another daemon already answers
+/// there; a socket file left by one that died is replaced.
+pub fn listen(
+    daemon: Arc<Daemon>,
+    path: &Path,
+    db_path: &Path,
+) -> Result<std::thread::JoinHandle<()>> {
+    if DaemonClient::connect_to(path)?.is_some() {
+        return Err(anyhow!(
+            "a daemon is already listening on {}",
+            path.display()
+        ));
+    }
+    #[cfg(unix)]
+    let _ = std::fs::remove_file(path);
+    let listener = ListenerOptions::new()
+        .name(socket_name(path)?)
+        .create_sync()
+        .with_context(|| format!("cannot listen on {}", path.display()))?;
+    tracing::info!(socket = %path.display(), "daemon listening");
+    let db_path = db_path.to_path_buf();
+    Ok(std::thread::spawn(move || {
+        for conn in listener.incoming() {
+            let conn = match conn {
+                Ok(c) => c,
+                Err(err) => {
+                    tracing::debug!(%err, "daemon accept failed");
+                    continue;
+                }
+            };
+            let daemon = daemon.clone();
+            let db_path = db_path.clone();
+            std::thread::spawn(move || {
+                if let Err(err) = serve_client(&daemon, conn, &db_path) {
+                    tracing::debug!(error = %format!("{err:#}"), "daemon client dropped");
+                }
+            });
+        }
+    }))
+}
+
+fn serve_client(daemon: &Daemon, conn: Stream, db_path: &Path) -> Result<()> {
+    let db = Db::open(db_path)?;
+    let mut reader = BufReader::new(&conn);
+    let mut writer = &conn;
+    let mut line = String::new();
+    while reader.read_line(&mut line)? > 0 {
+        let resp = serde_json::from_str::<Request>(line.trim())
+            .map_err(anyhow::Error::from)
+            .and_then(|req| daemon.handle(&db, req));
+        let resp = match resp {
+            Ok(v) => Response::Ok(v),
+            Err(err) => Response::Error(format!("{err:#}")),
+        };
+        serde_json::to_writer(&mut writer, &resp)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+        line.clear();
+    }
+    Ok(())
+}
+
+/// A connection to a running daemon.
+pub struct DaemonClient {
+    reader: BufReader<Stream>,
+}
+
+impl DaemonClient {
+    /// Connect to the daemon for the current data directory; None when none is running.
+    pub fn connect() -> Result<Option<Self>> {
+        Self::connect_to(&default_socket()?)
+    }
+
+    pub fn connect_to(path: &Path) -> Result<Option<Self>> {
+        #[cfg(unix)]
+        if !path.exists() {
+            return Ok(None);
+        }
+        match Stream::connect(socket_name(path)?) {
+            Ok(stream) => Ok(Some(Self {
+                reader: BufReader::new(stream),
+            })),
+            Err(err) => {
+                tracing::debug!(%err, socket = %path.display(), "no daemon");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Send `req` and wait for its result; a daemon-side failure comes back as `Err`.
+    pub fn call(&mut self, req: &Request) -> Result<Value> {
+        let mut line = serde_json::to_string(req)?;
+        line.push('\n');
+        self.reader.get_mut().write_all(line.as_bytes())?;
+        self.reader.get_mut().flush()?;
+        let mut reply = String::new();
+        if self.reader.read_line(&mut reply)? == 0 {
+            return Err(anyhow!("daemon closed the connection"));
+        }
+        match serde_json::from_str(&reply)? {
+            Response::Ok(v) => Ok(v),
+            Response::Error(e) => Err(anyhow!(e)),
+        }
+    }
+
+    pub fn status(&mut self) -> Result<DaemonStatus> {
+        Ok(serde_json::from_value(self.call(&Request::Status)?)?)
+    }
+}
@@ -1,16 +1,27 @@
 use anyhow::Result;
-use rusqlite::{params, Connection};
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection, OpenFlags, OptionalExtension};
 use std::fs;
 use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
 
 use crate::config::ConfigStore;
+use crate::deps::Dependency;
+use crate::devenv::DevEnvInfo;
+use crate::fields::{FieldValue, FAVORITE_FIELD};
+use crate::quality::TestCiInfo;
+use crate::query::{field_eq_clause, Query};
+use crate::scan::ArtifactSize;
+use crate::secrets::{SecretFinding, SecretKind};
+use crate::vulns::{Severity, VulnSummary};
 
 pub struct Db {
     pub conn: Connection,
     pub path: PathBuf,
 }
 
-#[derive(Debug, Clone, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
 pub struct ProjectRecord {
     pub id: i64,
     pub name: String,
@@ -18,18 +29,361 @@ pub struct ProjectRecord {
     pub project_type: Option<String>,
     pub is_git_repo: bool,
     pub size_bytes: Option<i64>,
+    /// `size_bytes` was extrapolated from a sample (`size_mode: estimate`), not summed
+    pub size_estimated: bool,
     pub files_count: Option<i64>,
     pub last_edited_at: Option<i64>,
     pub loc: Option<i64>,
+    /// `loc` was extrapolated after reading only part of the project (`loc_limits`)
+    pub loc_truncated: bool,
+    /// True when the secrets heuristic flagged at least one file
+    pub has_secrets: bool,
+    /// Number of flagged files; None when the secrets scan has not run for this project
+    pub secrets_count: Option<i64>,
+    /// Vulnerabilities found by the last `audit deps` run; None when never audited
+    pub vuln_count: Option<i64>,
+    /// Highest severity from the last dependency audit (see `vulns::Severity`)
+    pub vuln_severity: Option<String>,
+    pub has_tests: Option<bool>,
+    pub test_files_count: Option<i64>,
+    /// Jupyter notebooks; only counted for data-science, Python and Julia projects
+    pub notebooks_count: Option<i64>,
+    pub has_ci: Option<bool>,
+    /// Comma-separated CI systems, e.g. "github-actions,gitlab-ci"
+    pub ci_providers: Option<String>,
+    /// Dev-environment configs at the root (see `devenv::DevEnvInfo`); None until analyzed
+    pub has_devcontainer: Option<bool>,
+    pub has_dockerfile: Option<bool>,
+    pub has_compose: Option<bool>,
+    pub has_nix_shell: Option<bool>,
+    pub has_tool_versions: Option<bool>,
+    /// Shape of the tree (see `shape::ShapeMetrics`); None until scanned
+    pub source_files_count: Option<i64>,
+    /// `size_bytes / files_count`
+    pub avg_file_bytes: Option<i64>,
+    pub max_depth: Option<i64>,
+    pub top_level_modules: Option<i64>,
+    /// Bytes in images, video, audio, archives and model weights (see `assets`)
+    pub asset_bytes: Option<i64>,
+    /// Bytes in checkpoints, datasets, experiment runs and environments (see `ml`)
+    pub ml_bytes: Option<i64>,
+    /// Git enrichment; populated only when scanning with the `git` feature
+    pub last_commit_at: Option<i64>,
+    pub branch: Option<String>,
+    pub remote_url: Option<String>,
+    pub git_dirty: Option<bool>,
+    /// Workspace/monorepo root this project is a member of
+    pub parent_id: Option<i64>,
+    /// Workspace members indexed under this project
+    pub child_count: i64,
+    /// Bytes in build/dependency output directories (`node_modules`, `target`, ...); None
+    /// when there are none or sizes are off
+    pub artifact_bytes: Option<i64>,
+    /// User the project was attributed to by a system-wide scan
+    pub owner: Option<String>,
+    /// Host name of the machine that last scanned the project (see `location`)
+    pub machine: Option<String>,
+    /// Filesystem UUID, mount point or drive of the volume it is on
+    pub volume_id: Option<String>,
+    /// Why the last scan skipped the expensive analyzers (`analyzer_limits`); LOC and
+    /// dependencies are then from an earlier scan, if any
+    pub analysis_skipped: Option<String>,
+    /// The exact path when it is not valid UTF-8 and `path` is lossy; use [`Self::dir`]
+    #[serde(skip)]
+    #[cfg_attr(feature = "specta", specta(skip))]
+    pub path_bytes: Option<Vec<u8>>,
+}
+
+impl ProjectRecord {
+    /// The project directory on disk, byte for byte even when `path` had to be made lossy.
+    pub fn dir(&self) -> PathBuf {
+        match &self.path_bytes {
+            Some(bytes) => crate::paths::from_raw_bytes(bytes),
+            None => PathBuf::from(&self.path),
+        }
+    }
+}
+
+/// What `merge_projects` carried over from the removed record.
+#[derive(Debug, Clone, Default, serde::Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct MergeReport {
+    /// User fields (tags, notes, ...) the kept project did not already have
+    pub fields: u32,
+    /// Hook-provided custom fields the kept project did not already have
+    pub custom_fields: u32,
+    /// Open-history entries
+    pub opens: u32,
+}
+
+/// What a project can be recognised by after it moves (see `moves`).
+#[derive(Debug, Clone)]
+pub struct ProjectIdentity {
+    pub id: i64,
+    pub path: String,
+    pub remote_url: Option<String>,
+    /// Hash of the top-level file names and sizes
+    pub fingerprint: Option<String>,
+    /// See `ProjectRecord::path_bytes`
+    pub path_bytes: Option<Vec<u8>>,
+}
+
+/// One project's flagged files, as reported by `audit secrets`.
+#[derive(Debug, Clone, serde::Serialize)]
+pub struct SecretsAuditRow {
+    pub project_id: i64,
+    pub name: String,
+    pub path: String,
+    pub findings: Vec<SecretFinding>,
+}
+
+/// Summary over every project matching a filter, for the chips above the results.
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProjectAggregates {
+    pub count: u32,
+    pub total_size_bytes: i64,
+    pub total_loc: i64,
+    /// Most common type first
+    pub by_type: Vec<TypeCount>,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct TypeCount {
+    pub project_type: Option<String>,
+    pub count: u32,
+}
+
+/// Filters applied by `query_projects`/`count_projects`. All set fields must match.
+#[derive(Debug, Clone, Default)]
+pub struct ProjectFilter {
+    /// Substring match on name or path
+    pub search: Option<String>,
+    /// Only projects whose last dependency audit found at least one vulnerability
+    pub vulnerable: bool,
+    /// Only projects whose highest audited severity is at least this
+    pub min_severity: Option<Severity>,
+    pub has_tests: Option<bool>,
+    pub has_ci: Option<bool>,
+    /// Exact current branch name
+    pub branch: Option<String>,
+    /// `Some(false)` selects git repos without any remote
+    pub has_remote: Option<bool>,
+    /// Working tree has uncommitted or untracked changes
+    pub dirty: Option<bool>,
+    /// `(key, value)` pairs matched against user-defined and hook-provided fields
+    pub fields: Vec<(String, String)>,
+    /// Advanced filter expression (see `query`)
+    pub query: Option<Query>,
+}
+
+impl ProjectFilter {
+    pub fn search(q: Option<&str>) -> Self {
+        Self {
+            search: q.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Render as a ` WHERE ...` clause (or empty) plus its positional parameters.
+    fn to_sql(&self) -> (String, Vec<Value>) {
+        let mut clauses: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        if let Some(q) = &self.search {
+            clauses.push("(p.name LIKE ? OR p.path LIKE ?)".into());
+            let pat = format!("%{q}%");
+            values.push(Value::Text(pat.clone()));
+            values.push(Value::Text(pat));
+        }
+        if self.vulnerable {
+            clauses.push("d.vuln_count > 0".into());
+        }
+        if let Some(min) = self.min_severity {
+            let levels = Severity::ALL
+                .iter()
+                .filter(|s| **s >= min)
+                .map(|s| format!("'{}'", s.as_str()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            clauses.push(format!("d.severity_max IN ({levels})"));
+        }
+        for (col, want) in [("m.has_tests", self.has_tests), ("m.has_ci", self.has_ci)] {
+            if let Some(want) = want {
+                clauses.push(format!("COALESCE({col}, 0) = ?"));
+                values.push(Value::Integer(want as i64));
+            }
+        }
+        if let Some(branch) = &self.branch {
+            clauses.push("g.branch = ?".into());
+            values.push(Value::Text(branch.clone()));
+        }
+        match self.has_remote {
+            Some(true) => clauses.push("g.remote_url IS NOT NULL".into()),
+            Some(false) => clauses.push("(p.is_git_repo = 1 AND g.remote_url IS NULL)".into()),
+            None => {}
+        }
+        if let Some(dirty) = self.dirty {
+            clauses.push("g.dirty = ?".into());
+            values.push(Value::Integer(dirty as i64));
+        }
+        for (key, want) in &self.fields {
+            clauses.push(field_eq_clause(key, want, &mut values));
+        }
+        if let Some(query) = &self.query {
+            clauses.push(query.to_sql(&mut values));
+        }
+        if clauses.is_empty() {
+            (String::new(), values)
+        } else {
+            (format!(" WHERE {}", clauses.join(" AND ")), values)
+        }
+    }
 }
 
-#[derive(Debug, Clone, Copy)]
+/// Columns selected for every ProjectRecord query; keep in sync with `project_from_row`.
+const PROJECT_COLUMNS: &str = "p.id, p.name, p.path, p.type, p.is_git_repo, \
+     m.size_bytes, m.files_count, m.last_edited_at, m.loc, m.secrets_count, \
+     d.vuln_count, d.severity_max, \
+     m.has_tests, m.test_files_count, m.has_ci, m.ci_providers, \
+     g.last_commit_at, g.branch, g.remote_url, g.dirty, m.size_estimated, p.parent_id, \
+     (SELECT COUNT(*) FROM projects c WHERE c.parent_id = p.id), m.artifact_bytes, p.owner, \
+     m.loc_truncated, m.analysis_skipped, p.path_bytes, m.notebooks_count, \
+     m.has_devcontainer, m.has_dockerfile, m.has_compose, m.has_nix_shell, m.has_tool_versions, \
+     m.source_files_count, m.avg_file_bytes, m.max_depth, m.top_level_modules, m.asset_bytes, \
+     m.ml_bytes, p.machine, p.volume_id";
+const PROJECT_FROM: &str = "projects p \
+     LEFT JOIN metrics m ON m.project_id = p.id \
+     LEFT JOIN dep_audit d ON d.project_id = p.id \
+     LEFT JOIN git_info g ON g.project_id = p.id";
+/// Per-project frecency score as a subquery; `?1` is the current unix time
+pub(crate) const FRECENCY_SQL: &str = "(SELECT project_id, SUM(CASE \
+       WHEN ?1 - opened_at < 3600 THEN 4.0 \
+       WHEN ?1 - opened_at < 86400 THEN 2.0 \
+       WHEN ?1 - opened_at < 604800 THEN 1.0 \
+       ELSE 0.25 END) AS score \
+     FROM project_opens GROUP BY project_id)";
+/// Opens older than this (90 days) are pruned and no longer count towards frecency
+const FRECENCY_HORIZON_SECS: i64 = 90 * 86400;
+pub(crate) const SEVERITY_RANK: &str =
+    "CASE d.severity_max WHEN 'critical' THEN 4 WHEN 'high' THEN 3 \
+     WHEN 'moderate' THEN 2 WHEN 'low' THEN 1 ELSE 0 END";
+
+fn project_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ProjectRecord> {
+    let secrets_count: Option<i64> = row.get(9)?;
+    Ok(ProjectRecord {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        path: row.get(2)?,
+        project_type: row.get(3)?,
+        is_git_repo: {
+            let v: i64 = row.get(4)?;
+            v != 0
+        },
+        size_bytes: row.get(5)?,
+        size_estimated: opt_bool(row, 20)?.unwrap_or(false),
+        files_count: row.get(6)?,
+        last_edited_at: row.get(7)?,
+        loc: row.get(8)?,
+        loc_truncated: opt_bool(row, 25)?.unwrap_or(false),
+        has_secrets: secrets_count.unwrap_or(0) > 0,
+        secrets_count,
+        vuln_count: row.get(10)?,
+        vuln_severity: row.get(11)?,
+        has_tests: opt_bool(row, 12)?,
+        test_files_count: row.get(13)?,
+        notebooks_count: row.get(28)?,
+        has_ci: opt_bool(row, 14)?,
+        ci_providers: row.get(15)?,
+        has_devcontainer: opt_bool(row, 29)?,
+        has_dockerfile: opt_bool(row, 30)?,
+        has_compose: opt_bool(row, 31)?,
+        has_nix_shell: opt_bool(row, 32)?,
+        has_tool_versions: opt_bool(row, 33)?,
+        source_files_count: row.get(34)?,
+        avg_file_bytes: row.get(35)?,
+        max_depth: row.get(36)?,
+        top_level_modules: row.get(37)?,
+        asset_bytes: row.get(38)?,
+        ml_bytes: row.get(39)?,
+        last_commit_at: row.get(16)?,
+        branch: row.get(17)?,
+        remote_url: row.get(18)?,
+        git_dirty: opt_bool(row, 19)?,
+        parent_id: row.get(21)?,
+        child_count: row.get(22)?,
+        artifact_bytes: row.get(23)?,
+        owner: row.get(24)?,
+        machine: row.get(40)?,
+        volume_id: row.get(41)?,
+        analysis_skipped: row.get(26)?,
+        path_bytes: row.get(27)?,
+    })
+}
+
+fn opt_bool(row: &rusqlite::Row<'_>, idx: usize) -> rusqlite::Result<Option<bool>> {
+    Ok(row.get::<_, Option<i64>>(idx)?.map(|v| v != 0))
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum SortKey {
+    #[default]
     Recent,
     Size,
     Name,
     Type,
     Loc,
+    Vulns,
+    LastCommit,
+    SourceFiles,
+    AvgFileSize,
+    Depth,
+    Modules,
+    Assets,
+}
+
+fn nulls_last(col: &str, direction: &str) -> String {
+    format!("CASE WHEN {col} IS NULL THEN 1 ELSE 0 END, {col} {direction}")
+}
+
+/// `SELECT ... WHERE ... ORDER BY ...` for project listings, without LIMIT/OFFSET.
+fn select_projects_sql(
+    filter: &ProjectFilter,
+    sort: SortKey,
+    ascending: bool,
+) -> (String, Vec<Value>) {
+    let direction = if ascending { "ASC" } else { "DESC" };
+    // Emulate NULLS LAST via CASE
+    let order = match sort {
+        SortKey::Recent => {
+            format!(
+                "CASE WHEN m.last_edited_at IS NULL THEN 1 ELSE 0 END, m.last_edited_at {direction}"
+            )
+        }
+        SortKey::Size => format!(
+            "CASE WHEN m.size_bytes IS NULL THEN 1 ELSE 0 END, m.size_bytes {direction}"
+        ),
+        SortKey::Name => format!("p.name {direction}"),
+        SortKey::Type => format!("p.type {direction}, p.name {direction}"),
+        SortKey::Loc => format!("CASE WHEN m.loc IS NULL THEN 1 ELSE 0 END, m.loc {direction}"),
+        SortKey::LastCommit => format!(
+            "CASE WHEN g.last_commit_at IS NULL THEN 1 ELSE 0 END, g.last_commit_at {direction}"
+        ),
+        SortKey::SourceFiles => nulls_last("m.source_files_count", direction),
+        SortKey::AvgFileSize => nulls_last("m.avg_file_bytes", direction),
+        SortKey::Depth => nulls_last("m.max_depth", direction),
+        SortKey::Modules => nulls_last("m.top_level_modules", direction),
+        SortKey::Assets => nulls_last("m.asset_bytes", direction),
+        SortKey::Vulns => format!(
+            "CASE WHEN d.vuln_count IS NULL THEN 1 ELSE 0 END, d.vuln_count {direction}, {SEVERITY_RANK} {direction}"
+        ),
+    };
+    let (where_sql, values) = filter.to_sql();
+    (
+        format!("SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM}{where_sql} ORDER BY {order}"),
+        values,
+    )
 }
 
 impl Db {
@@ -41,7 +395,7 @@ impl Db {
     }
 
     pub fn open(path: &Path) -> Result<Self> {
-        let conn = Connection::open(path)?;
+        let conn = crate::encryption::open_connection(path, OpenFlags::default())?;
         let db = Self {
             conn,
             path: path.to_path_buf(),
@@ -100,12 +454,169 @@ impl Db {
               PRIMARY KEY(project_id, language),
               FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
             );
+
+            -- files flagged by the opt-in secrets heuristic
+            CREATE TABLE IF NOT EXISTS secret_findings (
+              project_id INTEGER NOT NULL,
+              path TEXT NOT NULL,
+              kind TEXT NOT NULL,
+              PRIMARY KEY(project_id, path),
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- last dependency vulnerability audit per project
+            CREATE TABLE IF NOT EXISTS dep_audit (
+              project_id INTEGER PRIMARY KEY,
+              tool TEXT NOT NULL,
+              vuln_count INTEGER NOT NULL,
+              severity_max TEXT,
+              audited_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_dep_audit_vulns ON dep_audit(vuln_count);
+
+            -- third-party dependencies extracted from manifests/lockfiles
+            CREATE TABLE IF NOT EXISTS dependencies (
+              project_id INTEGER NOT NULL,
+              ecosystem TEXT NOT NULL,
+              name TEXT NOT NULL,
+              version TEXT,
+              dev INTEGER NOT NULL DEFAULT 0,
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_dependencies_project ON dependencies(project_id);
+            CREATE INDEX IF NOT EXISTS idx_dependencies_name ON dependencies(ecosystem, name);
+
+            -- free-form results from pluggable analyzers
+            CREATE TABLE IF NOT EXISTS analyzer_results (
+              project_id INTEGER NOT NULL,
+              analyzer TEXT NOT NULL,
+              data TEXT NOT NULL,
+              updated_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+              PRIMARY KEY(project_id, analyzer),
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- metadata attached by scan hooks; value is JSON, source names the producer
+            CREATE TABLE IF NOT EXISTS custom_fields (
+              project_id INTEGER NOT NULL,
+              key TEXT NOT NULL,
+              value TEXT NOT NULL,
+              source TEXT NOT NULL,
+              PRIMARY KEY(project_id, key),
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- user-defined metadata; never touched by scans. value is JSON (string/number/bool)
+            CREATE TABLE IF NOT EXISTS project_fields (
+              project_id INTEGER NOT NULL,
+              key TEXT NOT NULL,
+              value TEXT NOT NULL,
+              updated_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+              PRIMARY KEY(project_id, key),
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_project_fields_key ON project_fields(key, value);
+
+            -- one row per launch from the app, for frecency ranking
+            CREATE TABLE IF NOT EXISTS project_opens (
+              project_id INTEGER NOT NULL,
+              opened_at INTEGER NOT NULL,
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_project_opens_project ON project_opens(project_id);
+
+            -- build/dependency output directories measured by scans (see scan::ARTIFACT_DIRS)
+            CREATE TABLE IF NOT EXISTS artifacts (
+              project_id INTEGER NOT NULL,
+              dir TEXT NOT NULL,
+              bytes INTEGER NOT NULL,
+              PRIMARY KEY(project_id, dir),
+              FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+            );
+
+            -- desktop UI preferences (sort, filters, column widths, last search) as JSON values
+            CREATE TABLE IF NOT EXISTS ui_state (
+              key TEXT PRIMARY KEY,
+              value TEXT NOT NULL,
+              updated_at INTEGER
+            );
         "#,
         )?;
+        self.conn.execute_batch(crate::journal::SCHEMA)?;
+        self.conn.execute_batch(crate::search::SCHEMA)?;
+        self.ensure_column("metrics", "secrets_count", "INTEGER")?;
+        self.ensure_column("metrics", "has_tests", "INTEGER")?;
+        self.ensure_column("metrics", "test_files_count", "INTEGER")?;
+        self.ensure_column("metrics", "has_ci", "INTEGER")?;
+        self.ensure_column("metrics", "ci_providers", "TEXT")?;
+        self.ensure_column("git_info", "dirty", "INTEGER")?;
+        self.ensure_column("metrics", "size_estimated", "INTEGER")?;
+        self.ensure_column("projects", "fingerprint", "TEXT")?;
+        self.ensure_column(
+            "projects",
+            "parent_id",
+            "INTEGER REFERENCES projects(id) ON DELETE SET NULL",
+        )?;
+        self.conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_projects_parent ON projects(parent_id);",
+        )?;
+        self.ensure_column("metrics", "artifact_bytes", "INTEGER")?;
+        self.ensure_column("artifacts", "fingerprint", "INTEGER")?;
+        self.ensure_column("projects", "owner", "TEXT")?;
+        self.ensure_column("projects", "machine", "TEXT")?;
+        self.ensure_column("projects", "volume_id", "TEXT")?;
+        self.ensure_column("projects", "enrich_pending", "INTEGER NOT NULL DEFAULT 0")?;
+        self.ensure_column("git_info", "ahead", "INTEGER")?;
+        self.ensure_column("git_info", "default_branch", "TEXT")?;
+        self.conn.execute_batch(crate::branches::SCHEMA)?;
+        self.conn.execute_batch(crate::hours::SCHEMA)?;
+        self.conn.execute_batch(crate::commits::SCHEMA)?;
+        self.conn.execute_batch(crate::assets::SCHEMA)?;
+        self.conn.execute_batch(crate::ml::SCHEMA)?;
+        self.conn.execute_batch(crate::policies::SCHEMA)?;
+        self.conn.execute_batch(crate::scan_runs::SCHEMA)?;
+        self.conn.execute_batch(crate::resume::SCHEMA)?;
+        self.ensure_column("metrics", "loc_truncated", "INTEGER")?;
+        self.ensure_column("metrics", "analysis_skipped", "TEXT")?;
+        self.ensure_column("projects", "path_bytes", "BLOB")?;
+        self.ensure_column("metrics", "notebooks_count", "INTEGER")?;
+        for col in [
+            "has_devcontainer",
+            "has_dockerfile",
+            "has_compose",
+            "has_nix_shell",
+            "has_tool_versions",
+            "source_files_count",
+            "avg_file_bytes",
+            "max_depth",
+            "top_level_modules",
+            "asset_bytes",
+            "ml_bytes",
+        ] {
+            self.ensure_column("metrics", col, "INTEGER")?;
+        }
+        self.conn.execute_batch(crate::offsite::SCHEMA)?;
+        self.conn.execute_batch(crate::activity::SCHEMA)?;
+        self.conn.execute_batch(crate::toolchains::SCHEMA)?;
+        self.conn.execute_batch(crate::actions::SCHEMA)?;
+        self.ensure_column("action_runs", "deadline", "INTEGER")?;
+        self.fail_abandoned_action_runs()?;
+
+        // One-off data migrations, tracked in `PRAGMA user_version`
+        let version: i64 = self
+            .conn
+            .query_row("PRAGMA user_version", [], |r| r.get(0))?;
+        if version < 1 {
+            let changed = self.canonicalize_paths()?;
+            if changed > 0 {
+                tracing::info!(changed, "rewrote stored project paths into canonical form");
+            }
+            self.conn.execute_batch("PRAGMA user_version = 1")?;
+        }
         Ok(())
     }
 
-    #[allow(dead_code)]
     fn ensure_column(&self, table: &str, col: &str, ty: &str) -> Result<()> {
         let mut stmt = self.conn.prepare(&format!("PRAGMA table_info({table})"))?;
         let mut exists = false;
@@ -152,25 +663,384 @@ impl Db {
         Ok(id)
     }
 
+    pub fn project_id_by_path(&self, path: &str) -> Result<Option<i64>> {
+        Ok(self
+            .conn
+            .query_row(
+                "SELECT id FROM projects WHERE path = ?1",
+                params![path],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    pub fn set_fingerprint(&self, project_id: i64, fingerprint: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET fingerprint = ?2 WHERE id = ?1",
+            params![project_id, fingerprint],
+        )?;
+        Ok(())
+    }
+
+    /// Store the exact bytes of a path that is not valid UTF-8 (see `paths::raw_bytes`).
+    pub fn set_path_bytes(&self, project_id: i64, bytes: Option<&[u8]>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET path_bytes = ?2 WHERE id = ?1",
+            params![project_id, bytes],
+        )?;
+        Ok(())
+    }
+
+    /// Link `project_id` to the workspace it is a member of, or unlink it.
+    pub fn set_parent(&self, project_id: i64, parent_id: Option<i64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET parent_id = ?2 WHERE id = ?1 AND parent_id IS NOT ?2",
+            params![project_id, parent_id],
+        )?;
+        Ok(())
+    }
+
+    /// Workspace members of `project_id`, by name.
+    pub fn children_of(&self, project_id: i64) -> Result<Vec<ProjectRecord>> {
+        let sql = format!(
+            "SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM} WHERE p.parent_id = ?1 ORDER BY p.name"
+        );
+        self.collect_projects(&sql, vec![Value::Integer(project_id)])
+    }
+
+    /// Enclosing workspaces of `project_id`, outermost first (a breadcrumb).
+    pub fn ancestors_of(&self, project_id: i64) -> Result<Vec<ProjectRecord>> {
+        let mut out = Vec::new();
+        let mut next = self.project_by_id(project_id)?.and_then(|p| p.parent_id);
+        while let Some(id) = next {
+            // A cycle can only come from hand-edited data; stop rather than loop
+            if id == project_id || out.iter().any(|p: &ProjectRecord| p.id == id) {
+                break;
+            }
+            let Some(p) = self.project_by_id(id)? else {
+                break;
+            };
+            next = p.parent_id;
+            out.push(p);
+        }
+        out.reverse();
+        Ok(out)
+    }
+
+    /// Identity of every project, for move detection.
+    pub fn project_identities(&self) -> Result<Vec<ProjectIdentity>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.path, g.remote_url, p.fingerprint, p.path_bytes \
+             FROM projects p LEFT JOIN git_info g ON g.project_id = p.id",
+        )?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ProjectIdentity {
+                    id: row.get(0)?,
+                    path: row.get(1)?,
+                    remote_url: row.get(2)?,
+                    fingerprint: row.get(3)?,
+                    path_bytes: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Fold `remove_id` into `keep_id` and delete it: user fields, custom fields and open
+    /// history move over (the kept project's values win on conflicting keys) and the earlier
+    /// `created_at` is kept. Scan results stay with `keep_id`, whose path is the one that
+    /// gets rescanned. Undoable through the journal.
+    pub fn merge_projects(&self, keep_id: i64, remove_id: i64) -> Result<MergeReport> {
+        if keep_id == remove_id {
+            anyhow::bail!("cannot merge project {keep_id} into itself");
+        }
+        let mut paths = Vec::new();
+        for id in [keep_id, remove_id] {
+            match self.project_by_id(id)? {
+                Some(p) => paths.push(p.path),
+                None => anyhow::bail!("no project with id {id}"),
+            }
+        }
+        let summary = format!("merged {} into {}", paths[1], paths[0]);
+        self.journaled("merge", &summary, &[keep_id, remove_id], |tx| {
+            let fields = tx.execute(
+                "INSERT OR IGNORE INTO project_fields (project_id, key, value, updated_at) \
+                 SELECT ?1, key, value, updated_at FROM project_fields WHERE project_id = ?2",
+                params![keep_id, remove_id],
+            )?;
+            let custom_fields = tx.execute(
+                "INSERT OR IGNORE INTO custom_fields (project_id, key, value, source) \
+                 SELECT ?1, key, value, source FROM custom_fields WHERE project_id = ?2",
+                params![keep_id, remove_id],
+            )?;
+            let opens = tx.execute(
+                "UPDATE project_opens SET project_id = ?1 WHERE project_id = ?2",
+                params![keep_id, remove_id],
+            )?;
+            tx.execute(
+                "UPDATE projects SET created_at = MIN(created_at, \
+                   (SELECT created_at FROM projects WHERE id = ?2)) WHERE id = ?1",
+                params![keep_id, remove_id],
+            )?;
+            tx.execute(
+                "UPDATE projects SET parent_id = ?1 WHERE parent_id = ?2 AND id != ?1",
+                params![keep_id, remove_id],
+            )?;
+            // Everything else hanging off the removed project cascades
+            tx.execute("DELETE FROM projects WHERE id = ?1", params![remove_id])?;
+            Ok(MergeReport {
+                fields: fields as u32,
+                custom_fields: custom_fields as u32,
+                opens: opens as u32,
+            })
+        })
+    }
+
+    /// Drop a project and everything attached to it from the index (the directory is left
+    /// alone). Undoable through the journal. Returns false when `project_id` is unknown.
+    pub fn delete_project(&self, project_id: i64) -> Result<bool> {
+        let Some(p) = self.project_by_id(project_id)? else {
+            return Ok(false);
+        };
+        self.journaled(
+            "delete",
+            &format!("forgot {}", p.path),
+            &[project_id],
+            |tx| {
+                tx.execute("DELETE FROM projects WHERE id = ?1", params![project_id])?;
+                Ok(true)
+            },
+        )
+    }
+
+    /// Other projects that look like the same one: same name or same git remote.
+    pub fn duplicates_of(&self, project_id: i64) -> Result<Vec<ProjectRecord>> {
+        let sql = format!(
+            "SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM}, projects me \
+             LEFT JOIN git_info mg ON mg.project_id = me.id \
+             WHERE me.id = ?1 AND p.id != me.id \
+               AND (p.name = me.name OR g.remote_url = mg.remote_url) \
+             ORDER BY p.path"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params![project_id], project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Repoint a project at a new directory, keeping its id and everything attached to it.
+    pub fn move_project(&self, project_id: i64, name: &str, path: &str) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET name = ?2, path = ?3, updated_at = strftime('%s','now') \
+             WHERE id = ?1",
+            params![project_id, name, path],
+        )?;
+        Ok(())
+    }
+
     pub fn upsert_metrics(
         &self,
         project_id: i64,
         size_bytes: Option<i64>,
         files_count: Option<i64>,
         last_edited_at: Option<i64>,
-        loc: Option<i64>,
     ) -> Result<()> {
         self.conn.execute(
             r#"
-            INSERT INTO metrics (project_id, size_bytes, files_count, last_edited_at, loc)
-            VALUES (?1, ?2, ?3, ?4, ?5)
+            INSERT INTO metrics (project_id, size_bytes, files_count, last_edited_at)
+            VALUES (?1, ?2, ?3, ?4)
             ON CONFLICT(project_id) DO UPDATE SET
               size_bytes=excluded.size_bytes,
               files_count=excluded.files_count,
-              last_edited_at=excluded.last_edited_at,
-              loc=excluded.loc
+              last_edited_at=excluded.last_edited_at
+        "#,
+            params![project_id, size_bytes, files_count, last_edited_at],
+        )?;
+        Ok(())
+    }
+
+    /// Replace the project's measured artifact directories; `None` (sizes off) clears them.
+    pub fn set_artifacts(&self, project_id: i64, dirs: Option<&[(String, i64)]>) -> Result<()> {
+        let dirs: Option<Vec<ArtifactSize>> = dirs.map(|dirs| {
+            dirs.iter()
+                .map(|(dir, bytes)| ArtifactSize {
+                    dir: dir.clone(),
+                    bytes: *bytes,
+                    fingerprint: None,
+                })
+                .collect()
+        });
+        self.store_artifacts(project_id, dirs.as_deref())
+    }
+
+    /// [`Db::set_artifacts`] keeping each directory's fingerprint for the next scan.
+    pub(crate) fn store_artifacts(
+        &self,
+        project_id: i64,
+        dirs: Option<&[ArtifactSize]>,
+    ) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM artifacts WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for a in dirs.unwrap_or_default() {
+            tx.execute(
+                "INSERT INTO artifacts (project_id, dir, bytes, fingerprint) \
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, a.dir, a.bytes, a.fingerprint],
+            )?;
+        }
+        let total = dirs
+            .filter(|d| !d.is_empty())
+            .map(|d| d.iter().map(|a| a.bytes).sum::<i64>());
+        tx.execute(
+            "UPDATE metrics SET artifact_bytes = ?2 WHERE project_id = ?1",
+            params![project_id, total],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// The project's artifact directories as the last scan measured them.
+    pub(crate) fn artifact_sizes(&self, project_id: i64) -> Result<Vec<ArtifactSize>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT dir, bytes, fingerprint FROM artifacts WHERE project_id = ?1")?;
+        let rows = stmt
+            .query_map(params![project_id], |r| {
+                Ok(ArtifactSize {
+                    dir: r.get(0)?,
+                    bytes: r.get(1)?,
+                    fingerprint: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// `(directory, bytes)` of the project's artifact directories, largest first.
+    pub fn artifacts_of(&self, project_id: i64) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT dir, bytes FROM artifacts WHERE project_id = ?1 ORDER BY bytes DESC, dir",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub fn set_size_estimated(&self, project_id: i64, estimated: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE metrics SET size_estimated = ?2 WHERE project_id = ?1",
+            params![project_id, estimated as i32],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_analysis_skipped(&self, project_id: i64, reason: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE metrics SET analysis_skipped = ?2 WHERE project_id = ?1",
+            params![project_id, reason],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_loc_truncated(&self, project_id: i64, truncated: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE metrics SET loc_truncated = ?2 WHERE project_id = ?1",
+            params![project_id, truncated as i32],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_loc(&self, project_id: i64, loc: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE metrics SET loc = ?2 WHERE project_id = ?1",
+            params![project_id, loc],
+        )?;
+        Ok(())
+    }
+
+    pub fn upsert_analyzer_result(
+        &self,
+        project_id: i64,
+        analyzer: &str,
+        data: &serde_json::Value,
+    ) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO analyzer_results (project_id, analyzer, data, updated_at)
+            VALUES (?1, ?2, ?3, strftime('%s','now'))
+            ON CONFLICT(project_id, analyzer) DO UPDATE SET
+              data=excluded.data,
+              updated_at=excluded.updated_at
         "#,
-            params![project_id, size_bytes, files_count, last_edited_at, loc],
+            params![project_id, analyzer, data.to_string()],
+        )?;
+        Ok(())
+    }
+
+    pub fn analyzer_result(
+        &self,
+        project_id: i64,
+        analyzer: &str,
+    ) -> Result<Option<serde_json::Value>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT data FROM analyzer_results WHERE project_id = ?1 AND analyzer = ?2")?;
+        let mut rows = stmt.query(params![project_id, analyzer])?;
+        match rows.next()? {
+            Some(row) => {
+                let s: String = row.get(0)?;
+                Ok(Some(serde_json::from_str(&s)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    pub fn set_notebooks_count(&self, project_id: i64, count: i64) -> Result<()> {
+        self.conn.execute(
+            "UPDATE metrics SET notebooks_count = ?2 WHERE project_id = ?1",
+            params![project_id, count],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_dev_env(&self, project_id: i64, info: &DevEnvInfo) -> Result<()> {
+        self.conn.execute(
+            r#"
+            UPDATE metrics SET has_devcontainer = ?2, has_dockerfile = ?3, has_compose = ?4,
+              has_nix_shell = ?5, has_tool_versions = ?6
+            WHERE project_id = ?1
+        "#,
+            params![
+                project_id,
+                info.devcontainer as i32,
+                info.dockerfile as i32,
+                info.compose as i32,
+                info.nix_shell as i32,
+                info.tool_versions as i32
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn update_test_ci(&self, project_id: i64, info: &TestCiInfo) -> Result<()> {
+        self.conn.execute(
+            r#"
+            UPDATE metrics SET has_tests = ?2, test_files_count = ?3, has_ci = ?4, ci_providers = ?5
+            WHERE project_id = ?1
+        "#,
+            params![
+                project_id,
+                info.has_tests as i32,
+                info.test_files_count,
+                info.has_ci as i32,
+                (!info.ci_providers.is_empty()).then(|| info.ci_providers.join(","))
+            ],
         )?;
         Ok(())
     }
@@ -181,168 +1051,158 @@ impl Db {
         last_commit_at: Option<i64>,
         branch: Option<&str>,
         remote_url: Option<&str>,
+        dirty: Option<bool>,
     ) -> Result<()> {
         self.conn.execute(
             r#"
-            INSERT INTO git_info (project_id, last_commit_at, branch, remote_url)
-            VALUES (?1, ?2, ?3, ?4)
+            INSERT INTO git_info (project_id, last_commit_at, branch, remote_url, dirty)
+            VALUES (?1, ?2, ?3, ?4, ?5)
             ON CONFLICT(project_id) DO UPDATE SET
               last_commit_at=excluded.last_commit_at,
               branch=excluded.branch,
-              remote_url=excluded.remote_url
+              remote_url=excluded.remote_url,
+              dirty=excluded.dirty
         "#,
-            params![project_id, last_commit_at, branch, remote_url],
+            params![project_id, last_commit_at, branch, remote_url, dirty],
+        )?;
+        Ok(())
+    }
+
+    pub fn set_git_ahead(&self, project_id: i64, ahead: Option<i64>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE git_info SET ahead = ?2 WHERE project_id = ?1",
+            params![project_id, ahead],
         )?;
         Ok(())
     }
 
     pub fn list_projects(&self, sort: SortKey, limit: usize) -> Result<Vec<ProjectRecord>> {
-        let order = match sort {
-            // Emulate NULLS LAST via CASE
-            SortKey::Recent => {
-                "CASE WHEN m.last_edited_at IS NULL THEN 1 ELSE 0 END, m.last_edited_at DESC"
-            }
-            SortKey::Size => "CASE WHEN m.size_bytes IS NULL THEN 1 ELSE 0 END, m.size_bytes DESC",
-            SortKey::Name => "p.name ASC",
-            SortKey::Type => "p.type ASC, p.name ASC",
-            SortKey::Loc => "CASE WHEN m.loc IS NULL THEN 1 ELSE 0 END, m.loc DESC",
-        };
-        let mut stmt = self.conn.prepare(&format!(
-            r#"
-            SELECT p.id, p.name, p.path, p.type, p.is_git_repo,
-                   m.size_bytes, m.files_count, m.last_edited_at, m.loc
-            FROM projects p
-            LEFT JOIN metrics m ON m.project_id = p.id
-            ORDER BY {order}
-            LIMIT ?1
-        "#
-        ))?;
-        let rows = stmt
-            .query_map(params![limit as i64], |row| {
-                Ok(ProjectRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    path: row.get(2)?,
-                    project_type: row.get(3)?,
-                    is_git_repo: {
-                        let v: i64 = row.get(4)?;
-                        v != 0
-                    },
-                    size_bytes: row.get(5)?,
-                    files_count: row.get(6)?,
-                    last_edited_at: row.get(7)?,
-                    loc: row.get(8)?,
-                })
-            })?
-            .collect::<Result<Vec<_>, _>>()?;
-        Ok(rows)
+        let ascending = matches!(sort, SortKey::Name | SortKey::Type);
+        self.query_projects(&ProjectFilter::default(), sort, ascending, 0, limit as u32)
     }
 
-    pub fn count_projects(&self, search: Option<&str>) -> Result<u32> {
-        let mut sql = String::from("SELECT COUNT(*) FROM projects p");
-        let mut params_vec: Vec<String> = Vec::new();
+    /// Resolve a user-supplied project reference: exact path first, then exact name.
+    pub fn find_project(&self, key: &str) -> Result<Option<ProjectRecord>> {
+        let sql = format!(
+            "SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM} \
+             WHERE p.path = ?1 OR p.name = ?1 \
+             ORDER BY CASE WHEN p.path = ?1 THEN 0 ELSE 1 END LIMIT 1"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query_map(params![key], project_from_row)?;
+        Ok(rows.next().transpose()?)
+    }
 
-        if let Some(q) = search {
-            sql.push_str(" WHERE p.name LIKE ?1 OR p.path LIKE ?1");
-            params_vec.push(format!("%{q}%"));
-        }
+    pub fn project_by_id(&self, id: i64) -> Result<Option<ProjectRecord>> {
+        let sql = format!("SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM} WHERE p.id = ?1");
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query_map(params![id], project_from_row)?;
+        Ok(rows.next().transpose()?)
+    }
 
-        let count: i64 = if params_vec.is_empty() {
-            self.conn.query_row(&sql, [], |row| row.get(0))?
-        } else {
-            self.conn
-                .query_row(&sql, [&params_vec[0]], |row| row.get(0))?
-        };
+    /// The deepest indexed project whose path is `path` or an ancestor of it.
+    pub fn project_containing(&self, path: &str) -> Result<Option<ProjectRecord>> {
+        let sql = format!(
+            "SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM} \
+             WHERE p.path = ?1 \
+                OR substr(?1, 1, length(p.path) + 1) IN (p.path || '/', p.path || '\\') \
+             ORDER BY length(p.path) DESC LIMIT 1"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query_map(params![path], project_from_row)?;
+        Ok(rows.next().transpose()?)
+    }
+
+    /// `find_project`, falling back to the project containing `key` as a path.
+    pub fn resolve_project(&self, key: &str) -> Result<Option<ProjectRecord>> {
+        match self.find_project(key)? {
+            Some(p) => Ok(Some(p)),
+            None => self.project_containing(&crate::paths::canonical_string(Path::new(key))),
+        }
+    }
 
+    pub fn count_projects(&self, filter: &ProjectFilter) -> Result<u32> {
+        let (where_sql, values) = filter.to_sql();
+        let sql = format!("SELECT COUNT(*) FROM {PROJECT_FROM}{where_sql}");
+        let count: i64 = self
+            .conn
+            .query_row(&sql, params_from_iter(values), |row| row.get(0))?;
         Ok(count as u32)
     }
 
+    /// Totals and per-type counts for `filter`, in a single grouped query.
+    pub fn aggregate_projects(&self, filter: &ProjectFilter) -> Result<ProjectAggregates> {
+        let (where_sql, values) = filter.to_sql();
+        let sql = format!(
+            "SELECT p.type, COUNT(*), COALESCE(SUM(m.size_bytes), 0), COALESCE(SUM(m.loc), 0) \
+             FROM {PROJECT_FROM}{where_sql} GROUP BY p.type ORDER BY COUNT(*) DESC, p.type"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_from_iter(values))?;
+        let mut agg = ProjectAggregates::default();
+        while let Some(row) = rows.next()? {
+            let count: i64 = row.get(1)?;
+            agg.count += count as u32;
+            agg.total_size_bytes += row.get::<_, i64>(2)?;
+            agg.total_loc += row.get::<_, i64>(3)?;
+            agg.by_type.push(TypeCount {
+                project_type: row.get(0)?,
+                count: count as u32,
+            });
+        }
+        Ok(agg)
+    }
+
     pub fn query_projects(
         &self,
-        search: Option<&str>,
+        filter: &ProjectFilter,
         sort: SortKey,
         ascending: bool,
         page: u32,
         page_size: u32,
     ) -> Result<Vec<ProjectRecord>> {
-        let direction = if ascending { "ASC" } else { "DESC" };
-        let order = match sort {
-            SortKey::Recent => {
-                format!(
-                    "CASE WHEN m.last_edited_at IS NULL THEN 1 ELSE 0 END, m.last_edited_at {direction}"
-                )
-            }
-            SortKey::Size => format!(
-                "CASE WHEN m.size_bytes IS NULL THEN 1 ELSE 0 END, m.size_bytes {direction}"
-            ),
-            SortKey::Name => format!("p.name {direction}"),
-            SortKey::Type => format!("p.type {direction}, p.name {direction}"),
-            SortKey::Loc => format!("CASE WHEN m.loc IS NULL THEN 1 ELSE 0 END, m.loc {direction}"),
-        };
-        let mut sql = String::from(
-            "SELECT p.id, p.name, p.path, p.type, p.is_git_repo,\n                   m.size_bytes, m.files_count, m.last_edited_at, m.loc\n             FROM projects p LEFT JOIN metrics m ON m.project_id = p.id",
-        );
-        let mut params_vec: Vec<String> = Vec::new();
-        let mut has_where = false;
-        if let Some(q) = search {
-            sql.push_str(" WHERE p.name LIKE ?1 OR p.path LIKE ?1");
-            params_vec.push(format!("%{q}%"));
-            has_where = true;
-        }
-        // Append ORDER/LIMIT/OFFSET; adjust indices based on whether a search param is present.
-        let lim_idx = if has_where { 2 } else { 1 };
-        let off_idx = lim_idx + 1;
-        sql.push_str(&format!(
-            " ORDER BY {order} LIMIT ?{lim_idx} OFFSET ?{off_idx}"
-        ));
+        let started = std::time::Instant::now();
+        let (sql, mut values) = select_projects_sql(filter, sort, ascending);
+        let sql = format!("{sql} LIMIT ? OFFSET ?");
+        values.push(Value::Integer(page_size as i64));
+        values.push(Value::Integer((page as i64) * (page_size as i64)));
 
         let mut stmt = self.conn.prepare(&sql)?;
-
-        // Build final params list as rusqlite params! requires concrete types.
-        let limit_i = page_size as i64;
-        let offset_i = (page as i64) * (page_size as i64);
-
-        let rows = if has_where {
-            let mapped =
-                stmt.query_map(params![params_vec[0].as_str(), limit_i, offset_i], |row| {
-                    Ok(ProjectRecord {
-                        id: row.get(0)?,
-                        name: row.get(1)?,
-                        path: row.get(2)?,
-                        project_type: row.get(3)?,
-                        is_git_repo: {
-                            let v: i64 = row.get(4)?;
-                            v != 0
-                        },
-                        size_bytes: row.get(5)?,
-                        files_count: row.get(6)?,
-                        last_edited_at: row.get(7)?,
-                        loc: row.get(8)?,
-                    })
-                })?;
-            mapped.collect::<Result<Vec<_>, _>>()?
-        } else {
-            let mapped = stmt.query_map(params![limit_i, offset_i], |row| {
-                Ok(ProjectRecord {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    path: row.get(2)?,
-                    project_type: row.get(3)?,
-                    is_git_repo: {
-                        let v: i64 = row.get(4)?;
-                        v != 0
-                    },
-                    size_bytes: row.get(5)?,
-                    files_count: row.get(6)?,
-                    last_edited_at: row.get(7)?,
-                    loc: row.get(8)?,
-                })
-            })?;
-            mapped.collect::<Result<Vec<_>, _>>()?
-        };
+        let rows = stmt
+            .query_map(params_from_iter(values), project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        crate::metrics::record_query(started.elapsed());
         Ok(rows)
     }
 
+    /// Like `query_projects`, but hands each row to `cb` as it is read instead of collecting
+    /// them, so exports of very large indexes run in constant memory. `limit: None` means
+    /// every match. Returns the number of rows visited; an error from `cb` stops the scan.
+    pub fn stream_projects<F>(
+        &self,
+        filter: &ProjectFilter,
+        sort: SortKey,
+        ascending: bool,
+        limit: Option<u32>,
+        mut cb: F,
+    ) -> Result<usize>
+    where
+        F: FnMut(ProjectRecord) -> Result<()>,
+    {
+        let (sql, mut values) = select_projects_sql(filter, sort, ascending);
+        let sql = format!("{sql} LIMIT ?");
+        values.push(Value::Integer(limit.map(i64::from).unwrap_or(-1)));
+
+        let mut stmt = self.conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_from_iter(values))?;
+        let mut n = 0usize;
+        while let Some(row) = rows.next()? {
+            cb(project_from_row(row)?)?;
+            n += 1;
+        }
+        Ok(n)
+    }
+
     pub fn replace_loc_breakdown(
         &self,
         project_id: i64,
@@ -361,4 +1221,307 @@ impl Db {
         }
         Ok(())
     }
+
+    /// Per-language code lines for a project, largest first.
+    pub fn loc_breakdown(&self, project_id: i64) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT language, COALESCE(code, 0) FROM loc_lang WHERE project_id = ?1 \
+             ORDER BY code DESC, language",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Replace the stored secret findings for a project and update its count on metrics.
+    pub fn replace_secret_findings(
+        &self,
+        project_id: i64,
+        findings: &[SecretFinding],
+    ) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM secret_findings WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        let mut stmt = self.conn.prepare(
+            "INSERT OR REPLACE INTO secret_findings (project_id, path, kind) VALUES (?1, ?2, ?3)",
+        )?;
+        for f in findings {
+            stmt.execute(params![project_id, f.path, f.kind.as_str()])?;
+        }
+        self.conn.execute(
+            "UPDATE metrics SET secrets_count = ?2 WHERE project_id = ?1",
+            params![project_id, findings.len() as i64],
+        )?;
+        Ok(())
+    }
+
+    /// Projects with at least one secret finding, most findings first.
+    pub fn secrets_audit(&self) -> Result<Vec<SecretsAuditRow>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT p.id, p.name, p.path, f.path, f.kind
+            FROM secret_findings f
+            JOIN projects p ON p.id = f.project_id
+            JOIN metrics m ON m.project_id = p.id
+            ORDER BY m.secrets_count DESC, p.name ASC, f.path ASC
+        "#,
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut out: Vec<SecretsAuditRow> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let project_id: i64 = row.get(0)?;
+            let kind: String = row.get(4)?;
+            let finding = SecretFinding {
+                path: row.get(3)?,
+                kind: SecretKind::parse(&kind).unwrap_or(SecretKind::EnvFile),
+            };
+            match out.last_mut() {
+                Some(last) if last.project_id == project_id => last.findings.push(finding),
+                _ => out.push(SecretsAuditRow {
+                    project_id,
+                    name: row.get(1)?,
+                    path: row.get(2)?,
+                    findings: vec![finding],
+                }),
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn upsert_dep_audit(&self, project_id: i64, summary: &VulnSummary) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO dep_audit (project_id, tool, vuln_count, severity_max, audited_at)
+            VALUES (?1, ?2, ?3, ?4, strftime('%s','now'))
+            ON CONFLICT(project_id) DO UPDATE SET
+              tool=excluded.tool,
+              vuln_count=excluded.vuln_count,
+              severity_max=excluded.severity_max,
+              audited_at=excluded.audited_at
+        "#,
+            params![
+                project_id,
+                summary.tool,
+                summary.vuln_count,
+                summary.severity_max.map(|s| s.as_str())
+            ],
+        )?;
+        Ok(())
+    }
+
+    pub fn replace_dependencies(&self, project_id: i64, deps: &[Dependency]) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM dependencies WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        let mut stmt = self.conn.prepare(
+            "INSERT INTO dependencies (project_id, ecosystem, name, version, dev) VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for d in deps {
+            stmt.execute(params![
+                project_id,
+                d.ecosystem,
+                d.name,
+                d.version,
+                d.dev as i32
+            ])?;
+        }
+        Ok(())
+    }
+
+    pub fn dependencies(&self, project_id: i64) -> Result<Vec<Dependency>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT ecosystem, name, version, dev FROM dependencies WHERE project_id = ?1 ORDER BY ecosystem, name, version",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |row| {
+                Ok(Dependency {
+                    ecosystem: row.get(0)?,
+                    name: row.get(1)?,
+                    version: row.get(2)?,
+                    dev: row.get::<_, i64>(3)? != 0,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Replace every custom field previously written by `source` with `fields`.
+    pub fn replace_custom_fields(
+        &self,
+        project_id: i64,
+        source: &str,
+        fields: &serde_json::Map<String, serde_json::Value>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "DELETE FROM custom_fields WHERE project_id = ?1 AND source = ?2",
+            params![project_id, source],
+        )?;
+        let mut stmt = self.conn.prepare(
+            "INSERT OR REPLACE INTO custom_fields (project_id, key, value, source) VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for (k, v) in fields {
+            stmt.execute(params![project_id, k, v.to_string(), source])?;
+        }
+        Ok(())
+    }
+
+    pub fn custom_fields(
+        &self,
+        project_id: i64,
+    ) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM custom_fields WHERE project_id = ?1 ORDER BY key")?;
+        let mut rows = stmt.query(params![project_id])?;
+        let mut out = serde_json::Map::new();
+        while let Some(row) = rows.next()? {
+            let key: String = row.get(0)?;
+            let value: String = row.get(1)?;
+            out.insert(key, serde_json::from_str(&value)?);
+        }
+        Ok(out)
+    }
+
+    pub fn set_project_field(&self, project_id: i64, key: &str, value: &FieldValue) -> Result<()> {
+        self.conn.execute(
+            r#"
+            INSERT INTO project_fields (project_id, key, value, updated_at)
+            VALUES (?1, ?2, ?3, strftime('%s','now'))
+            ON CONFLICT(project_id, key) DO UPDATE SET
+              value=excluded.value,
+              updated_at=excluded.updated_at
+        "#,
+            params![project_id, key, value.to_json().to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Returns false when the field did not exist.
+    pub fn remove_project_field(&self, project_id: i64, key: &str) -> Result<bool> {
+        let n = self.conn.execute(
+            "DELETE FROM project_fields WHERE project_id = ?1 AND key = ?2",
+            params![project_id, key],
+        )?;
+        Ok(n > 0)
+    }
+
+    /// User-defined fields only.
+    pub fn project_fields(
+        &self,
+        project_id: i64,
+    ) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM project_fields WHERE project_id = ?1 ORDER BY key")?;
+        let mut rows = stmt.query(params![project_id])?;
+        let mut out = serde_json::Map::new();
+        while let Some(row) = rows.next()? {
+            let key: String = row.get(0)?;
+            let value: String = row.get(1)?;
+            out.insert(key, serde_json::from_str(&value)?);
+        }
+        Ok(out)
+    }
+
+    /// UI state values by key; every stored key when `keys` is empty.
+    pub fn ui_state(&self, keys: &[String]) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT key, value FROM ui_state ORDER BY key")?;
+        let mut rows = stmt.query([])?;
+        let mut out = serde_json::Map::new();
+        while let Some(row) = rows.next()? {
+            let key: String = row.get(0)?;
+            if !keys.is_empty() && !keys.contains(&key) {
+                continue;
+            }
+            let value: String = row.get(1)?;
+            out.insert(key, serde_json::from_str(&value)?);
+        }
+        Ok(out)
+    }
+
+    /// Store a UI state value; `null` removes the key.
+    pub fn set_ui_state(&self, key: &str, value: &serde_json::Value) -> Result<()> {
+        if value.is_null() {
+            self.conn
+                .execute("DELETE FROM ui_state WHERE key = ?1", params![key])?;
+            return Ok(());
+        }
+        self.conn.execute(
+            r#"
+            INSERT INTO ui_state (key, value, updated_at)
+            VALUES (?1, ?2, strftime('%s','now'))
+            ON CONFLICT(key) DO UPDATE SET
+              value=excluded.value,
+              updated_at=excluded.updated_at
+        "#,
+            params![key, value.to_string()],
+        )?;
+        Ok(())
+    }
+
+    /// Note that a project was opened. Opens older than `FRECENCY_HORIZON_SECS` no longer
+    /// affect ranking and are dropped here.
+    pub fn record_open(&self, project_id: i64) -> Result<()> {
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        self.conn.execute(
+            "INSERT INTO project_opens (project_id, opened_at) VALUES (?1, ?2)",
+            params![project_id, now],
+        )?;
+        self.conn.execute(
+            "DELETE FROM project_opens WHERE opened_at < ?1",
+            params![now - FRECENCY_HORIZON_SECS],
+        )?;
+        Ok(())
+    }
+
+    /// Opened projects matching `filter`, most frecent first: every open counts, recent
+    /// ones more (4 within the hour, 2 within the day, 1 within the week, 0.25 after).
+    pub fn frecent_projects(
+        &self,
+        filter: &ProjectFilter,
+        limit: u32,
+    ) -> Result<Vec<ProjectRecord>> {
+        let (where_sql, filter_values) = filter.to_sql();
+        let sql = format!(
+            "SELECT {PROJECT_COLUMNS} FROM {PROJECT_FROM} \
+             JOIN {FRECENCY_SQL} f ON f.project_id = p.id{where_sql} \
+             ORDER BY f.score DESC, p.name LIMIT ?"
+        );
+        let mut values = vec![Value::Integer(OffsetDateTime::now_utc().unix_timestamp())];
+        values.extend(filter_values);
+        values.push(Value::Integer(limit as i64));
+        self.collect_projects(&sql, values)
+    }
+
+    fn collect_projects(&self, sql: &str, values: Vec<Value>) -> Result<Vec<ProjectRecord>> {
+        let mut stmt = self.conn.prepare(sql)?;
+        let rows = stmt
+            .query_map(params_from_iter(values), project_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Projects whose `favorite` field is true, by name.
+    pub fn favorite_projects(&self, limit: u32) -> Result<Vec<ProjectRecord>> {
+        let filter = ProjectFilter {
+            fields: vec![(FAVORITE_FIELD.to_string(), "true".to_string())],
+            ..Default::default()
+        };
+        self.query_projects(&filter, SortKey::Name, true, 0, limit)
+    }
+
+    /// Hook-provided fields overlaid with user-defined ones; the user wins on conflicts.
+    pub fn all_fields(
+        &self,
+        project_id: i64,
+    ) -> Result<serde_json::Map<String, serde_json::Value>> {
+        let mut out = self.custom_fields(project_id)?;
+        out.extend(self.project_fields(project_id)?);
+        Ok(out)
+    }
 }
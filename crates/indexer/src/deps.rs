@@ -0,0 +1,213 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// A declared or locked third-party dependency of a project.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Dependency {
+    /// Package ecosystem in purl terms: cargo, npm, pypi, golang
+    pub ecosystem: String,
+    pub name: String,
+    /// Exact version when read from a lockfile, otherwise the declared requirement
+    pub version: Option<String>,
+    pub dev: bool,
+}
+
+impl Dependency {
+    /// Package URL (https://github.com/package-url/purl-spec) used by both SBOM formats.
+    pub fn purl(&self) -> String {
+        let name = match self.ecosystem.as_str() {
+            "npm" => self.name.replacen('@', "%40", 1),
+            "pypi" => self.name.to_ascii_lowercase().replace('_', "-"),
+            _ => self.name.clone(),
+        };
+        match &self.version {
+            Some(v) if is_exact_version(v) => format!("pkg:{}/{}@{}", self.ecosystem, name, v),
+            _ => format!("pkg:{}/{}", self.ecosystem, name),
+        }
+    }
+}
+
+fn is_exact_version(v: &str) -> bool {
+    v.chars()
+        .next()
+        .map(|c| c.is_ascii_digit() || c == 'v')
+        .unwrap_or(false)
+        && !v.contains([' ', ',', '*', '<', '>', '^', '~', '='])
+}
+
+/// Extract dependencies from the manifests/lockfiles for a project's detected type.
+/// Lockfiles win over manifests because they pin exact versions.
+pub fn extract_dependencies(dir: &Path, project_type: &str) -> Vec<Dependency> {
+    match project_type {
+        "rust" => from_cargo_lock(dir).unwrap_or_else(|| from_cargo_toml(dir)),
+        "node" | "deno" | "bun" => from_package_lock(dir).unwrap_or_else(|| from_package_json(dir)),
+        "python" => from_requirements(dir),
+        "go" => from_go_mod(dir),
+        _ => Vec::new(),
+    }
+}
+
+fn from_cargo_lock(dir: &Path) -> Option<Vec<Dependency>> {
+    let s = fs::read_to_string(dir.join("Cargo.lock")).ok()?;
+    let v: toml::Value = toml::from_str(&s).ok()?;
+    let pkgs = v.get("package")?.as_array()?;
+    Some(
+        pkgs.iter()
+            // Workspace members and path crates have no source; they are not third-party.
+            .filter(|p| p.get("source").is_some())
+            .filter_map(|p| {
+                Some(Dependency {
+                    ecosystem: "cargo".into(),
+                    name: p.get("name")?.as_str()?.to_string(),
+                    version: p.get("version").and_then(|v| v.as_str()).map(String::from),
+                    dev: false,
+                })
+            })
+            .collect(),
+    )
+}
+
+fn from_cargo_toml(dir: &Path) -> Vec<Dependency> {
+    let Some(v) = fs::read_to_string(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|s| toml::from_str::<toml::Value>(&s).ok())
+    else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for (table, dev) in [("dependencies", false), ("dev-dependencies", true)] {
+        let Some(deps) = v.get(table).and_then(|d| d.as_table()) else {
+            continue;
+        };
+        for (name, spec) in deps {
+            // Skip path/workspace-inherited deps without a version
+            let version = match spec {
+                toml::Value::String(s) => Some(s.clone()),
+                toml::Value::Table(t) => {
+                    t.get("version").and_then(|v| v.as_str()).map(String::from)
+                }
+                _ => None,
+            };
+            if version.is_none() && spec.get("path").is_some() {
+                continue;
+            }
+            out.push(Dependency {
+                ecosystem: "cargo".into(),
+                name: name.clone(),
+                version,
+                dev,
+            });
+        }
+    }
+    out
+}
+
+fn from_package_lock(dir: &Path) -> Option<Vec<Dependency>> {
+    let s = fs::read_to_string(dir.join("package-lock.json")).ok()?;
+    let v: serde_json::Value = serde_json::from_str(&s).ok()?;
+    // lockfileVersion >= 2: "packages" keyed by install path; "" is the root project
+    let pkgs = v.get("packages")?.as_object()?;
+    Some(
+        pkgs.iter()
+            .filter(|(k, p)| !k.is_empty() && !p["link"].as_bool().unwrap_or(false))
+            .filter_map(|(k, p)| {
+                let name = k.rsplit_once("node_modules/").map(|(_, n)| n)?;
+                Some(Dependency {
+                    ecosystem: "npm".into(),
+                    name: name.to_string(),
+                    version: p["version"].as_str().map(String::from),
+                    dev: p["dev"].as_bool().unwrap_or(false),
+                })
+            })
+            .collect(),
+    )
+}
+
+fn from_package_json(dir: &Path) -> Vec<Dependency> {
+    let Some(v) = fs::read_to_string(dir.join("package.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    for (field, dev) in [("dependencies", false), ("devDependencies", true)] {
+        if let Some(deps) = v[field].as_object() {
+            for (name, ver) in deps {
+                out.push(Dependency {
+                    ecosystem: "npm".into(),
+                    name: name.clone(),
+                    version: ver.as_str().map(String::from),
+                    dev,
+                });
+            }
+        }
+    }
+    out
+}
+
+fn from_requirements(dir: &Path) -> Vec<Dependency> {
+    let Ok(s) = fs::read_to_string(dir.join("requirements.txt")) else {
+        return Vec::new();
+    };
+    s.lines()
+        .map(|l| l.split('#').next().unwrap_or("").trim())
+        .filter(|l| !l.is_empty() && !l.starts_with('-'))
+        .filter_map(|l| {
+            // strip environment markers and extras: "pkg[extra]==1.0; python_version<'3.9'"
+            let l = l.split(';').next().unwrap_or(l).trim();
+            let split = l.find(['=', '<', '>', '~', '!']).unwrap_or(l.len());
+            let name = l[..split].split('[').next().unwrap_or("").trim();
+            if name.is_empty() {
+                return None;
+            }
+            let spec = l[split..].trim();
+            let version = spec
+                .strip_prefix("==")
+                .map(|v| v.trim().to_string())
+                .or_else(|| (!spec.is_empty()).then(|| spec.to_string()));
+            Some(Dependency {
+                ecosystem: "pypi".into(),
+                name: name.to_string(),
+                version,
+                dev: false,
+            })
+        })
+        .collect()
+}
+
+fn from_go_mod(dir: &Path) -> Vec<Dependency> {
+    let Ok(s) = fs::read_to_string(dir.join("go.mod")) else {
+        return Vec::new();
+    };
+    let mut out = Vec::new();
+    let mut in_block = false;
+    for line in s.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        let spec = if in_block {
+            if line == ")" {
+                in_block = false;
+                continue;
+            }
+            line
+        } else if line == "require (" {
+            in_block = true;
+            continue;
+        } else if let Some(rest) = line.strip_prefix("require ") {
+            rest.trim()
+        } else {
+            continue;
+        };
+        let mut parts = spec.split_whitespace();
+        if let (Some(name), Some(version)) = (parts.next(), parts.next()) {
+            out.push(Dependency {
+                ecosystem: "golang".into(),
+                name: name.to_string(),
+                version: Some(version.to_string()),
+                dev: false,
+            });
+        }
+    }
+    out
+}
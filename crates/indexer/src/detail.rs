@@ -0,0 +1,123 @@
+//! Everything the project detail view shows for one project: the indexed record, its README,
+//! the per-language LOC breakdown, user fields, workspace parents and members, possible
+//! duplicates and related projects.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+use crate::assets::AssetTotal;
+use crate::db::{Db, ProjectRecord};
+use crate::launcher::ProjectLauncher;
+use crate::ml::MlArtifact;
+use crate::related::RelatedProject;
+use crate::toolchains::ToolchainPin;
+
+/// READMEs are shown as text; anything past this is cut off
+const MAX_README_BYTES: u64 = 256 * 1024;
+
+/// Related projects listed in the detail view
+const MAX_RELATED: usize = 8;
+
+/// Checked in order; the first existing file wins
+const README_NAMES: &[&str] = &[
+    "README.md",
+    "readme.md",
+    "Readme.md",
+    "README.markdown",
+    "README.rst",
+    "README.txt",
+    "README",
+];
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProjectDetail {
+    pub project: ProjectRecord,
+    /// File name of the README shown, relative to the project
+    pub readme_name: Option<String>,
+    pub readme: Option<String>,
+    /// True when the README was longer than the detail view shows
+    pub readme_truncated: bool,
+    /// `(language, code lines)`, largest first; empty until LOC has been computed
+    pub loc_by_language: Vec<(String, i64)>,
+    /// Pinned or required toolchain versions, by language
+    pub toolchains: Vec<ToolchainPin>,
+    /// Media and binary assets by category, largest first
+    pub assets: Vec<AssetTotal>,
+    /// Checkpoints, datasets, experiment runs and environments, largest first
+    pub ml_artifacts: Vec<MlArtifact>,
+    pub fields: serde_json::Map<String, serde_json::Value>,
+    /// Own editor and launch command (also among `fields`)
+    pub launcher: ProjectLauncher,
+    /// Other records with the same name or git remote, candidates for merging
+    pub duplicates: Vec<ProjectRecord>,
+    /// Companion projects: shared dependencies, same owner, similar languages, nearby
+    pub related: Vec<RelatedProject>,
+    /// Enclosing workspaces, outermost first
+    pub ancestors: Vec<ProjectRecord>,
+    /// Workspace members, by name
+    pub children: Vec<ProjectRecord>,
+    /// Sums over `children`
+    pub children_totals: ChildTotals,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ChildTotals {
+    pub size_bytes: i64,
+    pub files_count: i64,
+    pub loc: i64,
+}
+
+/// Detail for `project_id`, or `None` if it is no longer indexed.
+pub fn project_detail(db: &Db, project_id: i64) -> Result<Option<ProjectDetail>> {
+    let Some(project) = db.project_by_id(project_id)? else {
+        return Ok(None);
+    };
+    let (readme_name, readme, readme_truncated) = match read_readme(&project.dir()) {
+        Some((name, text, truncated)) => (Some(name), Some(text), truncated),
+        None => (None, None, false),
+    };
+    let children = db.children_of(project_id)?;
+    let children_totals = children
+        .iter()
+        .fold(ChildTotals::default(), |t, c| ChildTotals {
+            size_bytes: t.size_bytes + c.size_bytes.unwrap_or(0),
+            files_count: t.files_count + c.files_count.unwrap_or(0),
+            loc: t.loc + c.loc.unwrap_or(0),
+        });
+    Ok(Some(ProjectDetail {
+        ancestors: db.ancestors_of(project_id)?,
+        children,
+        children_totals,
+        loc_by_language: db.loc_breakdown(project_id)?,
+        toolchains: db.toolchains(project_id)?,
+        assets: db.assets(project_id)?,
+        ml_artifacts: db.ml_artifacts(project_id)?,
+        fields: db.all_fields(project_id)?,
+        launcher: db.project_launcher(project_id)?,
+        duplicates: db.duplicates_of(project_id)?,
+        related: db.related_projects(project_id, MAX_RELATED)?,
+        project,
+        readme_name,
+        readme,
+        readme_truncated,
+    }))
+}
+
+pub(crate) fn read_readme(dir: &Path) -> Option<(String, String, bool)> {
+    let name = README_NAMES.iter().find(|n| dir.join(n).is_file())?;
+    let file = fs::File::open(dir.join(name)).ok()?;
+    let mut buf = Vec::new();
+    file.take(MAX_README_BYTES + 1).read_to_end(&mut buf).ok()?;
+    let truncated = buf.len() as u64 > MAX_README_BYTES;
+    buf.truncate(MAX_README_BYTES as usize);
+    Some((
+        name.to_string(),
+        String::from_utf8_lossy(&buf).into_owned(),
+        truncated,
+    ))
+}
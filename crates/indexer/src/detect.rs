@@ -5,12 +5,27 @@ use std::path::Path;
 pub enum ProjectType {
     Rust,
     NodeJs,
+    Deno,
+    Bun,
     Python,
     Go,
     Java,
     DotNet,
     Terraform,
     Ansible,
+    /// Xcode projects and Swift packages
+    Ios,
+    Android,
+    Unity,
+    Unreal,
+    Godot,
+    Zig,
+    Haskell,
+    OCaml,
+    Scala,
+    Julia,
+    /// Notebooks, DVC pipelines or a conda environment
+    DataScience,
     Other,
 }
 
@@ -19,66 +34,160 @@ impl ProjectType {
         match self {
             ProjectType::Rust => "rust",
             ProjectType::NodeJs => "node",
+            ProjectType::Deno => "deno",
+            ProjectType::Bun => "bun",
             ProjectType::Python => "python",
             ProjectType::Go => "go",
             ProjectType::Java => "java",
             ProjectType::DotNet => ".net",
             ProjectType::Terraform => "terraform",
             ProjectType::Ansible => "ansible",
+            ProjectType::Ios => "ios",
+            ProjectType::Android => "android",
+            ProjectType::Unity => "unity",
+            ProjectType::Unreal => "unreal",
+            ProjectType::Godot => "godot",
+            ProjectType::Zig => "zig",
+            ProjectType::Haskell => "haskell",
+            ProjectType::OCaml => "ocaml",
+            ProjectType::Scala => "scala",
+            ProjectType::Julia => "julia",
+            ProjectType::DataScience => "data-science",
             ProjectType::Other => "other",
         }
     }
 }
 
-pub fn detect_project_type(dir: &Path) -> Option<ProjectType> {
-    // Markers per language/ecosystem
-    let candidates = [
-        (ProjectType::Rust, &["Cargo.toml"][..]),
-        (ProjectType::NodeJs, &["package.json"][..]),
-        (ProjectType::Python, &["pyproject.toml", "requirements.txt"]),
-        (ProjectType::Go, &["go.mod"][..]),
-        (ProjectType::Java, &["pom.xml", "build.gradle", "gradlew"]),
-        (ProjectType::DotNet, &["global.json"][..]),
+/// Marker files per type. `*.ext` matches any entry with that extension, optionally under a
+/// subdirectory; `a+b` matches when both `a` and `b` are present.
+pub const MARKERS: &[(ProjectType, &[&str])] = &[
+    (ProjectType::Rust, &["Cargo.toml"]),
+    (ProjectType::NodeJs, &["package.json"]),
+    (ProjectType::Deno, &["deno.json", "deno.jsonc"]),
+    (ProjectType::Bun, &["bun.lockb", "bun.lock", "bunfig.toml"]),
+    (ProjectType::Python, &["pyproject.toml", "requirements.txt"]),
+    (ProjectType::Go, &["go.mod"]),
+    (ProjectType::Java, &["pom.xml", "build.gradle", "gradlew"]),
+    (ProjectType::DotNet, &["global.json", "*.csproj"]),
+    (
+        ProjectType::Terraform,
+        &["main.tf", "variables.tf", "outputs.tf"],
+    ),
+    (ProjectType::Ansible, &["ansible/*.yml", "ansible/*.yaml"]),
+    (
+        ProjectType::Ios,
+        &["*.xcodeproj", "*.xcworkspace", "Package.swift"],
+    ),
+    (
+        ProjectType::Android,
+        &[
+            "app/src/main/AndroidManifest.xml",
+            "src/main/AndroidManifest.xml",
+            "AndroidManifest.xml",
+        ],
+    ),
+    (ProjectType::Unity, &["Assets/+ProjectSettings/"]),
+    (ProjectType::Unreal, &["*.uproject"]),
+    (ProjectType::Godot, &["project.godot"]),
+    (ProjectType::Zig, &["build.zig"]),
+    (
+        ProjectType::Haskell,
+        &["*.cabal", "cabal.project", "stack.yaml"],
+    ),
+    (ProjectType::OCaml, &["dune-project"]),
+    (ProjectType::Scala, &["build.sbt"]),
+    (ProjectType::Julia, &["Project.toml", "Manifest.toml"]),
+    (
+        ProjectType::DataScience,
+        // A lone notebook is too weak a sign (one turns up in any downloads folder): it
+        // takes a `notebooks/` directory, or notebooks next to `requirements.txt`
+        &[
+            "notebooks/*.ipynb",
+            "requirements.txt+*.ipynb",
+            "dvc.yaml",
+            ".dvc",
+            "environment.yml",
+        ],
+    ),
+];
+
+/// Which type a project matching several is stored, shown and grouped as, unless
+/// `type_priority` says otherwise. Compiled languages come before the tooling usually found
+/// next to them, so a Tauri app with both `Cargo.toml` and `package.json` is `rust`.
+pub const DEFAULT_TYPE_PRIORITY: &[ProjectType] = &[
+    ProjectType::Rust,
+    // Android projects are Gradle (Java) builds too
+    ProjectType::Ios,
+    ProjectType::Android,
+    // Engines generate `.csproj` and `.sln` files for their scripts
+    ProjectType::Unity,
+    ProjectType::Unreal,
+    ProjectType::Godot,
+    ProjectType::Zig,
+    ProjectType::Haskell,
+    ProjectType::OCaml,
+    ProjectType::Scala,
+    ProjectType::Julia,
+    // Runtimes that also read `package.json`
+    ProjectType::Deno,
+    ProjectType::Bun,
+    ProjectType::NodeJs,
+    ProjectType::Python,
+    // After Python, so a package with an example notebook stays `python`
+    ProjectType::DataScience,
+    ProjectType::Go,
+    ProjectType::Java,
+    ProjectType::DotNet,
+    ProjectType::Terraform,
+    ProjectType::Ansible,
+];
+
+/// Every type with a marker present in `dir`, in [`MARKERS`] order.
+pub fn detect_project_types(dir: &Path) -> Vec<ProjectType> {
+    MARKERS
+        .iter()
+        .filter(|(_, markers)| markers.iter().any(|m| marker_present(dir, m)))
+        .map(|(ptype, _)| *ptype)
+        .collect()
+}
+
+/// The one type for a project matching `types`: the first listed in `priority` (type names
+/// such as `"node"`), then by [`DEFAULT_TYPE_PRIORITY`].
+pub fn primary_type(types: &[ProjectType], priority: &[String]) -> Option<ProjectType> {
+    let rank = |t: &ProjectType| {
+        let configured = priority.iter().position(|name| name == t.as_str());
+        let default = DEFAULT_TYPE_PRIORITY.iter().position(|d| d == t);
         (
-            ProjectType::Terraform,
-            &["main.tf", "variables.tf", "outputs.tf"][..],
-        ),
-        (ProjectType::Ansible, &[]), // Special case - handled below
-    ];
+            configured.unwrap_or(usize::MAX),
+            default.unwrap_or(usize::MAX),
+        )
+    };
+    types.iter().copied().min_by_key(rank)
+}
 
-    for (ptype, files) in candidates.iter() {
-        if files.iter().any(|f| dir.join(f).exists()) {
-            return Some(*ptype);
-        }
-        // .NET: also check for *.csproj
-        if matches!(ptype, ProjectType::DotNet) {
-            if let Ok(rd) = fs::read_dir(dir) {
-                for entry in rd.flatten() {
-                    if let Some(ext) = entry.path().extension() {
-                        if ext == "csproj" {
-                            return Some(ProjectType::DotNet);
-                        }
-                    }
-                }
-            }
-        }
-    }
+/// [`detect_project_types`] narrowed to the primary type by `priority`.
+pub fn detect_primary_type(dir: &Path, priority: &[String]) -> Option<ProjectType> {
+    primary_type(&detect_project_types(dir), priority)
+}
 
-    // Special detection for Ansible
-    let ansible_dir = dir.join("ansible");
-    if ansible_dir.is_dir() {
-        if let Ok(entries) = fs::read_dir(&ansible_dir) {
-            for entry in entries.flatten() {
-                if let Some(ext) = entry.path().extension() {
-                    if ext == "yml" || ext == "yaml" {
-                        return Some(ProjectType::Ansible);
-                    }
-                }
-            }
-        }
-    }
+/// The primary type under the default priority.
+pub fn detect_project_type(dir: &Path) -> Option<ProjectType> {
+    detect_primary_type(dir, &[])
+}
 
-    None
+pub fn marker_present(dir: &Path, marker: &str) -> bool {
+    if let Some((first, rest)) = marker.split_once('+') {
+        return marker_present(dir, first) && marker_present(dir, rest);
+    }
+    match marker.split_once("*.") {
+        Some((sub, ext)) => fs::read_dir(dir.join(sub))
+            .map(|rd| {
+                rd.flatten()
+                    .any(|e| e.path().extension().is_some_and(|x| x == ext))
+            })
+            .unwrap_or(false),
+        None => dir.join(marker).exists(),
+    }
 }
 
 pub fn is_git_repo(dir: &Path) -> bool {
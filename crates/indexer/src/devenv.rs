@@ -0,0 +1,60 @@
+//! Container and dev-environment configs at a project's root: a devcontainer, a Dockerfile,
+//! a Compose file, a Nix dev shell or asdf's `.tool-versions`. Stored as badges on the record
+//! so queries like `devcontainer:true` find projects ready to open in a container.
+
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct DevEnvInfo {
+    /// `.devcontainer/devcontainer.json`, `.devcontainer.json` or a named configuration in
+    /// `.devcontainer/<name>/devcontainer.json`
+    pub devcontainer: bool,
+    /// `Dockerfile`, `Containerfile` or variants like `Dockerfile.dev` and `app.dockerfile`
+    pub dockerfile: bool,
+    /// `compose.yaml` or `docker-compose.yml`, including `.override`/environment variants
+    pub compose: bool,
+    /// `shell.nix`, or a `flake.nix` declaring a `devShell`
+    pub nix_shell: bool,
+    pub tool_versions: bool,
+}
+
+pub fn detect_dev_env(root: &Path) -> DevEnvInfo {
+    let names: Vec<String> = fs::read_dir(root)
+        .map(|rd| {
+            rd.flatten()
+                .map(|e| e.file_name().to_string_lossy().into_owned())
+                .collect()
+        })
+        .unwrap_or_default();
+    let any_name = |pred: &dyn Fn(&str) -> bool| names.iter().any(|n| pred(n));
+
+    let devcontainer = root.join(".devcontainer/devcontainer.json").is_file()
+        || root.join(".devcontainer.json").is_file()
+        || fs::read_dir(root.join(".devcontainer"))
+            .map(|rd| {
+                rd.flatten()
+                    .any(|e| e.path().join("devcontainer.json").is_file())
+            })
+            .unwrap_or(false);
+    let dockerfile = any_name(&|n| {
+        n.starts_with("Dockerfile")
+            || n.starts_with("Containerfile")
+            || n.to_ascii_lowercase().ends_with(".dockerfile")
+    });
+    let compose = any_name(&|n| {
+        (n.starts_with("compose.") || n.starts_with("docker-compose."))
+            && (n.ends_with(".yml") || n.ends_with(".yaml"))
+    });
+    let nix_shell = root.join("shell.nix").is_file()
+        || fs::read_to_string(root.join("flake.nix")).is_ok_and(|s| s.contains("devShell"));
+
+    DevEnvInfo {
+        devcontainer,
+        dockerfile,
+        compose,
+        nix_shell,
+        tool_versions: root.join(".tool-versions").is_file(),
+    }
+}
@@ -0,0 +1,175 @@
+//! `diagnose --bundle` and the app's "Export diagnostics": one zip with what a bug report
+//! usually needs (build, schema version, database stats, the last scan, the effective config
+//! and recent logs), redacted with [`Redactor`] so it can be attached to a public issue.
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+use zip::write::SimpleFileOptions;
+
+use crate::config::AppConfig;
+use crate::db::Db;
+use crate::redact::{redact_config, Redactor};
+use crate::scan_runs::ScanRun;
+
+/// Log lines included in a bundle
+const LOG_LINES: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Diagnostics {
+    pub version: &'static str,
+    pub os: &'static str,
+    pub arch: &'static str,
+    /// Optional features this build was made with
+    pub features: Vec<&'static str>,
+    /// `PRAGMA user_version`: how many one-off data migrations have run
+    pub schema_version: i64,
+    pub db: DbStats,
+    pub last_scan: Option<ScanRun>,
+    /// The effective config
+    pub config: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DbStats {
+    pub path: String,
+    pub file_bytes: u64,
+    pub wal_bytes: u64,
+    pub encrypted: bool,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    /// Rows per table
+    pub tables: BTreeMap<String, i64>,
+}
+
+fn features() -> Vec<&'static str> {
+    [
+        ("git", cfg!(feature = "git")),
+        ("analyzers", cfg!(feature = "analyzers")),
+        ("metrics", cfg!(feature = "metrics")),
+        ("serve", cfg!(feature = "serve")),
+        ("update", cfg!(feature = "update")),
+        ("encryption", cfg!(feature = "encryption")),
+    ]
+    .into_iter()
+    .filter_map(|(name, on)| on.then_some(name))
+    .collect()
+}
+
+/// Everything but the logs, with paths, remotes and emails redacted.
+pub fn collect(db: &Db, cfg: &AppConfig, redactor: &Redactor) -> Result<Diagnostics> {
+    let pragma =
+        |name: &str| -> Result<i64> { Ok(db.conn.pragma_query_value(None, name, |r| r.get(0))?) };
+    let mut wal = db.path.as_os_str().to_owned();
+    wal.push("-wal");
+    let mut config = serde_json::to_value(cfg)?;
+    redact_config(redactor, &mut config);
+    let last_scan = db.last_scan_run()?.map(|run| ScanRun {
+        roots: run.roots.iter().map(|r| redactor.path(r)).collect(),
+        error: run.error.as_deref().map(|e| redactor.text(e)),
+        ..run
+    });
+    Ok(Diagnostics {
+        version: env!("CARGO_PKG_VERSION"),
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        features: features(),
+        schema_version: pragma("user_version")?,
+        db: DbStats {
+            path: redactor.path(&db.path.to_string_lossy()),
+            file_bytes: fs::metadata(&db.path).map_or(0, |md| md.len()),
+            wal_bytes: fs::metadata(PathBuf::from(wal)).map_or(0, |md| md.len()),
+            encrypted: crate::encryption::is_encrypted(&db.path)?,
+            page_size: pragma("page_size")?,
+            page_count: pragma("page_count")?,
+            freelist_count: pragma("freelist_count")?,
+            tables: table_counts(db)?,
+        },
+        last_scan,
+        config,
+    })
+}
+
+fn table_counts(db: &Db) -> Result<BTreeMap<String, i64>> {
+    let names = db
+        .conn
+        .prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%'",
+        )?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let mut out = BTreeMap::new();
+    for name in names {
+        let count = db.conn.query_row(
+            &format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\"")),
+            [],
+            |r| r.get(0),
+        )?;
+        out.insert(name, count);
+    }
+    Ok(out)
+}
+
+/// A [`Redactor`] for log lines, which also hashes the names of indexed projects, configured
+/// actions and policies, and the people in `authors`, wherever they appear.
+pub fn log_redactor(db: &Db, cfg: &AppConfig) -> Result<Redactor> {
+    let projects = db
+        .conn
+        .prepare("SELECT DISTINCT name FROM projects")?
+        .query_map([], |r| r.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    let authors = cfg.authors.aliases.iter().flat_map(|(k, v)| [k, v]);
+    Ok(Redactor::new().with_names(
+        projects
+            .into_iter()
+            .chain(cfg.actions.keys().cloned())
+            .chain(cfg.policies.keys().cloned())
+            .chain(authors.cloned())
+            .chain(cfg.authors.me.iter().cloned()),
+    ))
+}
+
+/// Write a bundle to `out`: `diagnostics.json` from [`collect`] and the last `LOG_LINES`
+/// lines of the log file as `logs.txt`, both redacted. Returns what was collected.
+pub fn write_bundle(db: &Db, cfg: &AppConfig, out: &Path) -> Result<Diagnostics> {
+    let redactor = log_redactor(db, cfg)?;
+    let diagnostics = collect(db, cfg, &redactor)?;
+    let log_path = crate::logging::log_path(&cfg.logging)?;
+    let logs = crate::logging::tail_lines(&log_path, LOG_LINES)?;
+
+    let file = fs::File::create(out).with_context(|| format!("creating {}", out.display()))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let opts = SimpleFileOptions::default();
+    zip.start_file("diagnostics.json", opts)?;
+    serde_json::to_writer_pretty(&mut zip, &diagnostics)?;
+    zip.start_file("logs.txt", opts)?;
+    for line in &logs {
+        writeln!(zip, "{}", redactor.text(line))?;
+    }
+    zip.finish()?;
+    Ok(diagnostics)
+}
+
+/// `project-browser-diagnostics-<timestamp>.zip` in the downloads folder, or else the home
+/// directory.
+pub fn default_bundle_path() -> PathBuf {
+    let now = OffsetDateTime::now_utc();
+    let name = format!(
+        "project-browser-diagnostics-{:04}{:02}{:02}-{:02}{:02}{:02}.zip",
+        now.year(),
+        u8::from(now.month()),
+        now.day(),
+        now.hour(),
+        now.minute(),
+        now.second()
+    );
+    dirs_next::download_dir()
+        .or_else(dirs_next::home_dir)
+        .unwrap_or_default()
+        .join(name)
+}
@@ -0,0 +1,189 @@
+//! At-rest encryption of the index (the `encryption` feature), for indexes holding client
+//! project names, notes and custom fields that should not sit on disk in plaintext. The file
+//! is SQLCipher-encrypted with a random raw key kept in the OS keychain (macOS Keychain,
+//! Windows Credential Manager, Secret Service on Linux); `PROJECT_BROWSER_DB_KEY` supplies
+//! the key instead on machines without one.
+//!
+//! `db encrypt` converts an existing database and `db decrypt` reverts it. Whether a file is
+//! encrypted is told from its header, so everything else opens either kind the same way.
+//! Backups of an encrypted database are encrypted with the same key.
+
+use anyhow::{bail, Context, Result};
+use rusqlite::{Connection, OpenFlags};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
+
+/// First bytes of every plaintext SQLite database
+const SQLITE_HEADER: &[u8; 16] = b"SQLite format 3\0";
+
+/// Environment variable that overrides the keychain: the key as 64 hex digits.
+pub const KEY_ENV: &str = "PROJECT_BROWSER_DB_KEY";
+
+/// Whether `path` holds an encrypted database: it exists, is not empty, and does not start
+/// with the plaintext SQLite header.
+pub fn is_encrypted(path: &Path) -> Result<bool> {
+    let mut header = [0u8; 16];
+    let read = match fs::File::open(path) {
+        Ok(mut f) => f.read(&mut header)?,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(e) => return Err(e).with_context(|| format!("reading {}", path.display())),
+    };
+    Ok(read > 0 && &header != SQLITE_HEADER)
+}
+
+/// Open `path`, unlocking it first when it is encrypted.
+pub(crate) fn open_connection(path: &Path, flags: OpenFlags) -> Result<Connection> {
+    let encrypted = is_encrypted(path)?;
+    let conn = Connection::open_with_flags(path, flags)
+        .with_context(|| format!("opening {}", path.display()))?;
+    if encrypted {
+        unlock(&conn, path)?;
+    }
+    Ok(conn)
+}
+
+#[cfg(not(feature = "encryption"))]
+fn unlock(_conn: &Connection, path: &Path) -> Result<()> {
+    bail!(
+        "{} is encrypted (or not a database); this build was made without the `encryption` feature",
+        path.display()
+    )
+}
+
+#[cfg(feature = "encryption")]
+fn unlock(conn: &Connection, path: &Path) -> Result<()> {
+    let key = stored_key()?.with_context(|| {
+        format!(
+            "{} is encrypted but no key was found in the OS keychain or {KEY_ENV}",
+            path.display()
+        )
+    })?;
+    apply_key(conn, &key)?;
+    // A wrong key only shows once a page is read
+    conn.query_row("SELECT COUNT(*) FROM sqlite_master", [], |_| Ok(()))
+        .with_context(|| format!("the key does not unlock {}", path.display()))?;
+    Ok(())
+}
+
+#[cfg(feature = "encryption")]
+mod sqlcipher {
+    use super::*;
+    use rusqlite::DatabaseName;
+    use std::path::PathBuf;
+
+    const KEYCHAIN_SERVICE: &str = "project-browser";
+    const KEYCHAIN_ACCOUNT: &str = "database-key";
+
+    fn keychain() -> Result<keyring::Entry> {
+        keyring::Entry::new(KEYCHAIN_SERVICE, KEYCHAIN_ACCOUNT).context("opening the OS keychain")
+    }
+
+    /// The key from `PROJECT_BROWSER_DB_KEY` or else the keychain, if there is one.
+    pub(super) fn stored_key() -> Result<Option<String>> {
+        if let Ok(key) = std::env::var(KEY_ENV) {
+            return Ok(Some(key));
+        }
+        match keychain()?.get_password() {
+            Ok(key) => Ok(Some(key)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(e).context("reading the database key from the OS keychain"),
+        }
+    }
+
+    /// `key` in SQLCipher's raw key syntax, which skips its passphrase derivation.
+    fn raw_key(key: &str) -> Result<String> {
+        if key.len() != 64 || !key.bytes().all(|b| b.is_ascii_hexdigit()) {
+            bail!("the database key must be 64 hex digits");
+        }
+        Ok(format!("x'{key}'"))
+    }
+
+    pub(super) fn apply_key(conn: &Connection, key: &str) -> Result<()> {
+        conn.pragma_update(None, "key", raw_key(key)?)?;
+        Ok(())
+    }
+
+    /// Encrypt the plaintext database at `path` in place, creating and storing a key unless
+    /// one already exists. No `Db` may be open on `path` while this runs. Returns the
+    /// backups next to it, which stay plaintext until they are deleted.
+    pub fn encrypt(path: &Path) -> Result<Vec<PathBuf>> {
+        if is_encrypted(path)? {
+            bail!("{} is already encrypted", path.display());
+        }
+        let key = match stored_key()? {
+            Some(key) => key,
+            None => {
+                let conn = Connection::open_in_memory()?;
+                let key: String =
+                    conn.query_row("SELECT lower(hex(randomblob(32)))", [], |r| r.get(0))?;
+                keychain()?
+                    .set_password(&key)
+                    .context("storing the database key in the OS keychain")?;
+                key
+            }
+        };
+        rewrite(path, &raw_key(&key)?)?;
+        let backups = crate::backup::list_backups(&crate::backup::backup_dir(path))?;
+        Ok(backups.into_iter().map(|b| b.path).collect())
+    }
+
+    /// Decrypt the database at `path` in place. The key stays in the keychain for encrypted
+    /// backups. No `Db` may be open on `path` while this runs.
+    pub fn decrypt(path: &Path) -> Result<()> {
+        if !is_encrypted(path)? {
+            bail!("{} is not encrypted", path.display());
+        }
+        // An empty key makes the copy plaintext
+        rewrite(path, "")
+    }
+
+    /// Copy the database at `path` into a new file keyed with `target_key`, then put the
+    /// copy in its place.
+    fn rewrite(path: &Path, target_key: &str) -> Result<()> {
+        let mut target = path.as_os_str().to_owned();
+        target.push(".converting");
+        let target = PathBuf::from(target);
+        let _ = fs::remove_file(&target);
+        {
+            let conn = open_connection(path, OpenFlags::default())?;
+            conn.execute(
+                "ATTACH DATABASE ?1 AS converted KEY ?2",
+                rusqlite::params![target.to_string_lossy(), target_key],
+            )?;
+            conn.query_row("SELECT sqlcipher_export('converted')", [], |_| Ok(()))?;
+            // Not part of the export, but it tracks which data migrations have run
+            let version: i64 = conn.pragma_query_value(None, "user_version", |r| r.get(0))?;
+            conn.pragma_update(
+                Some(DatabaseName::Attached("converted")),
+                "user_version",
+                version,
+            )?;
+            conn.execute("DETACH DATABASE converted", [])?;
+        }
+        // Stale WAL/shared-memory files belong to the old file
+        for suffix in ["-wal", "-shm"] {
+            let mut side = path.as_os_str().to_owned();
+            side.push(suffix);
+            let _ = fs::remove_file(PathBuf::from(side));
+        }
+        fs::rename(&target, path)
+            .with_context(|| format!("replacing {} with its converted copy", path.display()))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "encryption")]
+use sqlcipher::{apply_key, stored_key};
+#[cfg(feature = "encryption")]
+pub use sqlcipher::{decrypt, encrypt};
+
+#[cfg(not(feature = "encryption"))]
+pub fn encrypt(_path: &Path) -> Result<Vec<std::path::PathBuf>> {
+    bail!("this build was made without the `encryption` feature")
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn decrypt(_path: &Path) -> Result<()> {
+    bail!("this build was made without the `encryption` feature")
+}
@@ -0,0 +1,160 @@
+//! Why a directory is or is not indexed (`explain <path>`): the markers detection checked,
+//! the type it assigned, whether the root walk skips the directory, and whether a project
+//! above it stops the walk before reaching it.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+
+use crate::config::{AppConfig, NestedPolicy};
+use crate::db::Db;
+use crate::detect::{marker_present, primary_type, ProjectType, MARKERS};
+use crate::scan::{root_walker, ARTIFACT_DIRS};
+use crate::workspace::workspace_members;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MarkerCheck {
+    pub project_type: String,
+    pub marker: String,
+    pub matched: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Explanation {
+    pub path: String,
+    /// The configured root the scan reaches this directory from
+    pub root: Option<String>,
+    /// Every marker checked, in detection order
+    pub markers: Vec<MarkerCheck>,
+    /// Every type with a matching marker, by `type_priority`
+    pub matched_types: Vec<String>,
+    /// The type a scan assigns: the first of `matched_types`
+    pub project_type: Option<String>,
+    /// Why the root walk never reaches this directory
+    pub ignored: Option<String>,
+    /// The project root above this directory where the walk stops descending (only with
+    /// `nested: skip`)
+    pub shadowed_by: Option<String>,
+    /// Indexed anyway as a declared workspace member of the project above it
+    pub workspace_member: bool,
+    /// Its row in the index, if any
+    pub indexed_id: Option<i64>,
+}
+
+pub fn explain(db: &Db, cfg: &AppConfig, path: &Path) -> Result<Explanation> {
+    let path = crate::paths::canonical(path);
+    anyhow::ensure!(path.is_dir(), "{} is not a directory", path.display());
+    let mut markers = Vec::new();
+    let mut matched: Vec<ProjectType> = Vec::new();
+    for (ptype, names) in MARKERS {
+        for marker in *names {
+            let found = marker_present(&path, marker);
+            if found && !matched.contains(ptype) {
+                matched.push(*ptype);
+            }
+            markers.push(MarkerCheck {
+                project_type: ptype.as_str().to_string(),
+                marker: marker.to_string(),
+                matched: found,
+            });
+        }
+    }
+    // Primary type first, then the rest in the order they would win
+    let mut matched_types = Vec::new();
+    while let Some(next) = primary_type(&matched, &cfg.type_priority) {
+        matched.retain(|t| *t != next);
+        matched_types.push(next.as_str().to_string());
+    }
+
+    // The deepest configured root wins, as a scan of it would find the directory last
+    let root = cfg
+        .roots
+        .iter()
+        .map(|r| crate::paths::canonical(r))
+        .filter(|r| path.starts_with(r))
+        .max_by_key(|r| r.components().count());
+
+    let (ignored, shadowed_by) = match &root {
+        Some(root) => walk_to(cfg, root, &path),
+        None => (Some("not under any configured root".to_string()), None),
+    };
+    let workspace_member = shadowed_by
+        .as_ref()
+        .is_some_and(|parent| is_member(Path::new(parent), &path));
+    let key = path.to_string_lossy();
+    let indexed_id = db
+        .find_project(&key)?
+        .filter(|p| p.path == key)
+        .map(|p| p.id);
+
+    Ok(Explanation {
+        path: key.into_owned(),
+        root: root.map(|r| r.to_string_lossy().into_owned()),
+        markers,
+        project_type: matched_types.first().cloned(),
+        matched_types,
+        ignored,
+        shadowed_by,
+        workspace_member,
+        indexed_id,
+    })
+}
+
+/// Walk from `root` down to `target` the way discovery does, returning why the walk skips
+/// `target` and the first project root above it.
+fn walk_to(cfg: &AppConfig, root: &Path, target: &Path) -> (Option<String>, Option<String>) {
+    let wb = root_walker(cfg, root, Some(target.to_path_buf()), None);
+    let mut reached: Vec<PathBuf> = Vec::new();
+    for entry in wb.build().flatten() {
+        if entry.file_type().is_some_and(|ft| ft.is_dir()) {
+            reached.push(entry.path().to_path_buf());
+        }
+    }
+    let shadowed_by = reached
+        .iter()
+        .filter(|_| cfg.nested == NestedPolicy::Skip)
+        .filter(|dir| dir.as_path() != target && !globally_ignored(cfg, dir))
+        .find(|dir| crate::detect::detect_project_type(dir).is_some())
+        .map(|dir| dir.to_string_lossy().into_owned());
+
+    let ignored = if reached.iter().any(|d| d == target) {
+        globally_ignored(cfg, target).then(|| {
+            let name = target.file_name().unwrap_or_default().to_string_lossy();
+            format!("`{name}` is in global_ignores")
+        })
+    } else {
+        // The first directory below the deepest one reached is where the walk stopped
+        let deepest = reached.iter().max_by_key(|d| d.components().count());
+        let skipped = deepest
+            .and_then(|d| target.strip_prefix(d).ok())
+            .and_then(|rest| rest.components().next())
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Some(if skipped.starts_with('.') {
+            format!("hidden directory `{skipped}`")
+        } else if globally_ignored(cfg, Path::new(&skipped)) || ARTIFACT_DIRS.contains(&&*skipped) {
+            format!(
+                "`{skipped}` holds dependencies or build output, which `nested` does not search"
+            )
+        } else {
+            format!("`{skipped}` is excluded by .gitignore, .ignore or the app ignore file")
+        })
+    };
+    (ignored, shadowed_by)
+}
+
+/// Discovery neither detects nor reports directories named in `global_ignores`.
+fn globally_ignored(cfg: &AppConfig, dir: &Path) -> bool {
+    dir.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|name| cfg.global_ignores.iter().any(|ign| ign == name))
+}
+
+/// Whether `dir` is a declared workspace member of `parent`, directly or through nested
+/// workspaces.
+fn is_member(parent: &Path, dir: &Path) -> bool {
+    workspace_members(parent).iter().any(|m| {
+        let m = crate::paths::canonical(m);
+        m == dir || (dir.starts_with(&m) && is_member(&m, dir))
+    })
+}
@@ -0,0 +1,221 @@
+//! Fetching or pulling many repositories at once (`git fetch`/`git pull` in the CLI, the app's
+//! "Fetch all"). Runs the `git` CLI so credential helpers, SSH agents and `insteadOf` rules
+//! work as they do in a terminal; prompts are turned off so a repo that needs a password fails
+//! instead of hanging the batch.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+
+use crate::db::{Db, ProjectRecord};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum FetchMode {
+    /// `git fetch`: update remote-tracking branches, leave the working tree alone
+    Fetch,
+    /// `git pull --ff-only`: also fast-forward the current branch
+    Pull,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum FetchOutcome {
+    /// New commits arrived (on the upstream for a fetch, on the branch for a pull)
+    Updated,
+    UpToDate,
+    /// A pull could not fast-forward: the branch diverged or local changes are in the way
+    Conflict,
+    Failed,
+    /// Not a git repository or no remote to fetch from
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FetchResult {
+    pub project_id: i64,
+    pub name: String,
+    pub path: String,
+    pub outcome: FetchOutcome,
+    /// Commits that arrived; None when the branch has no upstream to compare
+    pub new_commits: Option<i64>,
+    /// git's error for conflicts and failures
+    pub message: Option<String>,
+}
+
+/// Fetch or pull every project in `projects`, `jobs` at a time, calling `on_result` as each
+/// finishes. Stored git info (last commit, commits ahead) is refreshed for the ones that
+/// updated. Results come back in completion order.
+pub fn fetch_projects(
+    db: &Db,
+    projects: &[ProjectRecord],
+    mode: FetchMode,
+    jobs: usize,
+    mut on_result: impl FnMut(&FetchResult),
+) -> Result<Vec<FetchResult>> {
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+    let mut results = Vec::with_capacity(projects.len());
+    std::thread::scope(|s| -> Result<()> {
+        for _ in 0..jobs.clamp(1, projects.len().max(1)) {
+            let tx = tx.clone();
+            let next = &next;
+            s.spawn(move || {
+                while let Some(p) = projects.get(next.fetch_add(1, Ordering::Relaxed)) {
+                    if tx.send(fetch_one(p, mode)).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(tx);
+        for result in rx {
+            if result.outcome == FetchOutcome::Updated {
+                refresh_git_info(db, result.project_id, Path::new(&result.path))?;
+            }
+            on_result(&result);
+            results.push(result);
+        }
+        Ok(())
+    })?;
+    Ok(results)
+}
+
+fn fetch_one(p: &ProjectRecord, mode: FetchMode) -> FetchResult {
+    let mut result = FetchResult {
+        project_id: p.id,
+        name: p.name.clone(),
+        path: p.path.clone(),
+        outcome: FetchOutcome::Skipped,
+        new_commits: None,
+        message: None,
+    };
+    let dir = p.dir();
+    if !p.is_git_repo || git(&dir, &["remote"]).is_none_or(|r| r.is_empty()) {
+        return result;
+    }
+    // What to compare before and after: the upstream for a fetch, the branch for a pull
+    let rev = match mode {
+        FetchMode::Fetch => "@{upstream}",
+        FetchMode::Pull => "HEAD",
+    };
+    let before = git(&dir, &["rev-parse", "--verify", "--quiet", rev]);
+    let args: &[&str] = match mode {
+        FetchMode::Fetch => &["fetch", "--quiet"],
+        FetchMode::Pull => &["pull", "--ff-only", "--quiet"],
+    };
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(&dir)
+        .args(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .envs(ssh_command(&dir).map(|ssh| ("GIT_SSH_COMMAND", ssh)))
+        .stdin(Stdio::null())
+        .output();
+    let out = match out {
+        Ok(out) => out,
+        Err(err) => {
+            result.outcome = FetchOutcome::Failed;
+            result.message = Some(format!("could not run git: {err}"));
+            return result;
+        }
+    };
+    if !out.status.success() {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        result.outcome = if mode == FetchMode::Pull && is_conflict(&stderr) {
+            FetchOutcome::Conflict
+        } else {
+            FetchOutcome::Failed
+        };
+        result.message = stderr
+            .lines()
+            .map(str::trim)
+            .rfind(|l| !l.is_empty())
+            .map(|l| l.trim_start_matches("fatal: ").to_string());
+        return result;
+    }
+    let after = git(&dir, &["rev-parse", "--verify", "--quiet", rev]);
+    result.new_commits = match (&before, &after) {
+        (Some(b), Some(a)) if b == a => Some(0),
+        (Some(b), Some(a)) => {
+            git(&dir, &["rev-list", "--count", &format!("{b}..{a}")]).and_then(|n| n.parse().ok())
+        }
+        // The branch just gained an upstream; everything on it is new
+        (None, Some(a)) => git(&dir, &["rev-list", "--count", a]).and_then(|n| n.parse().ok()),
+        (_, None) => None,
+    };
+    result.outcome = if before != after && after.is_some() {
+        FetchOutcome::Updated
+    } else {
+        FetchOutcome::UpToDate
+    };
+    result
+}
+
+/// The ssh command git would use, told not to ask for a passphrase or host key confirmation
+/// either. `None` when `GIT_SSH` names another program (e.g. plink), which would lose to
+/// `GIT_SSH_COMMAND`; the null stdin and `GIT_TERMINAL_PROMPT=0` have to do there.
+fn ssh_command(dir: &Path) -> Option<String> {
+    let ssh = match std::env::var("GIT_SSH_COMMAND") {
+        Ok(ssh) => ssh,
+        Err(_) if std::env::var_os("GIT_SSH").is_some() => return None,
+        Err(_) => git(dir, &["config", "core.sshCommand"]).unwrap_or_else(|| "ssh".to_string()),
+    };
+    Some(format!("{ssh} -o BatchMode=yes"))
+}
+
+fn is_conflict(stderr: &str) -> bool {
+    [
+        "Not possible to fast-forward",
+        "diverg",
+        "would be overwritten",
+        "CONFLICT",
+        "unstaged changes",
+    ]
+    .iter()
+    .any(|needle| stderr.contains(needle))
+}
+
+/// Trimmed stdout of a successful `git -C dir args…`.
+fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .output()
+        .ok()?;
+    out.status
+        .success()
+        .then(|| String::from_utf8_lossy(&out.stdout).trim().to_string())
+}
+
+#[cfg(feature = "git")]
+fn refresh_git_info(db: &Db, project_id: i64, dir: &Path) -> Result<()> {
+    let info = crate::vcs::read_git_info(dir);
+    db.upsert_git_info(
+        project_id,
+        info.last_commit_at,
+        info.branch.as_deref(),
+        info.remote_url.as_deref(),
+        info.dirty,
+    )?;
+    db.set_git_ahead(project_id, info.ahead)?;
+    db.replace_git_branches(
+        project_id,
+        info.default_branch.as_deref(),
+        &info.local_branches,
+    )
+}
+
+/// Without the `git` feature scans store no git info, so there is nothing to refresh.
+#[cfg(not(feature = "git"))]
+fn refresh_git_info(_db: &Db, _project_id: i64, _dir: &Path) -> Result<()> {
+    Ok(())
+}
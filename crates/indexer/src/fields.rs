@@ -0,0 +1,66 @@
+use anyhow::{bail, Result};
+use serde_json::Value;
+
+/// User field that pins a project to the favorites list (tray menu, quick launcher)
+pub const FAVORITE_FIELD: &str = "favorite";
+
+/// User field naming the editor a project opens in instead of `launcher.editor`
+pub const EDITOR_FIELD: &str = "editor";
+
+/// User field holding a shell command the app can run in a terminal, e.g. `make dev`
+pub const LAUNCH_COMMAND_FIELD: &str = "launch_command";
+
+/// A user-defined project field value. Stored as JSON so numbers and booleans round-trip.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    Text(String),
+    Number(serde_json::Number),
+    Bool(bool),
+}
+
+impl FieldValue {
+    /// Infer the type from command-line input: `true`/`false`, then numbers, else text.
+    pub fn parse(s: &str) -> Self {
+        match s {
+            "true" => return FieldValue::Bool(true),
+            "false" => return FieldValue::Bool(false),
+            _ => {}
+        }
+        if let Ok(i) = s.parse::<i64>() {
+            return FieldValue::Number(i.into());
+        }
+        if let Some(n) = s.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+            return FieldValue::Number(n);
+        }
+        FieldValue::Text(s.to_string())
+    }
+
+    /// Accept only scalar JSON values; objects, arrays and null are rejected.
+    pub fn from_json(v: &Value) -> Result<Self> {
+        Ok(match v {
+            Value::String(s) => FieldValue::Text(s.clone()),
+            Value::Number(n) => FieldValue::Number(n.clone()),
+            Value::Bool(b) => FieldValue::Bool(*b),
+            other => bail!("field values must be a string, number or bool, got {other}"),
+        })
+    }
+
+    pub fn to_json(&self) -> Value {
+        match self {
+            FieldValue::Text(s) => Value::String(s.clone()),
+            FieldValue::Number(n) => Value::Number(n.clone()),
+            FieldValue::Bool(b) => Value::Bool(*b),
+        }
+    }
+}
+
+/// Parse a `field:<key>=<value>` filter expression into its key and raw value.
+pub fn parse_field_filter(expr: &str) -> Result<(String, String)> {
+    let Some(rest) = expr.strip_prefix("field:") else {
+        bail!("unsupported filter {expr:?}; expected field:<key>=<value>");
+    };
+    match rest.split_once('=') {
+        Some((k, v)) if !k.trim().is_empty() => Ok((k.trim().to_string(), v.trim().to_string())),
+        _ => bail!("malformed filter {expr:?}; expected field:<key>=<value>"),
+    }
+}
@@ -0,0 +1,230 @@
+//! Deterministic synthetic project trees for integration tests, benchmarks and
+//! `cli bench seed`. The same `FixtureSpec` (including `seed`) always yields the same tree,
+//! so a slow scan reported on a 10k-project root can be reproduced exactly.
+
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::detect::ProjectType;
+
+/// What to generate. `Default` gives a small mixed tree suitable for tests.
+#[derive(Debug, Clone)]
+pub struct FixtureSpec {
+    pub projects: usize,
+    pub seed: u64,
+    /// Projects per `group-NNN` parent directory
+    pub per_group: usize,
+    /// Upper bound on source files in the largest size class
+    pub max_files: usize,
+    /// Fraction of projects that are git repositories (0.0..=1.0)
+    pub git_ratio: f64,
+    /// Create real commits with the `git` CLI; otherwise (or if git is missing) git
+    /// projects get an empty `.git` skeleton with no history.
+    pub git_commits: bool,
+    /// Add gitignored/globally-ignored directories (node_modules, target) to some projects
+    pub ignored_dirs: bool,
+}
+
+impl Default for FixtureSpec {
+    fn default() -> Self {
+        Self {
+            projects: 20,
+            seed: 0x5eed,
+            per_group: 100,
+            max_files: 40,
+            git_ratio: 0.5,
+            git_commits: false,
+            ignored_dirs: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GitState {
+    None,
+    /// `.git` exists but HEAD has no commits
+    Empty,
+    Clean,
+    /// Committed, then an untracked file added
+    Dirty,
+}
+
+/// One generated project and what the scanner is expected to find.
+#[derive(Debug, Clone)]
+pub struct FixtureProject {
+    pub path: PathBuf,
+    pub project_type: ProjectType,
+    pub git: GitState,
+    /// Files counted by the scanner (excludes `.git` and ignored dirs)
+    pub files: usize,
+    pub bytes: u64,
+}
+
+const TYPES: &[(ProjectType, &str, &str)] = &[
+    (ProjectType::Rust, "Cargo.toml", "rs"),
+    (ProjectType::NodeJs, "package.json", "js"),
+    (ProjectType::Python, "pyproject.toml", "py"),
+    (ProjectType::Go, "go.mod", "go"),
+    (ProjectType::Java, "pom.xml", "java"),
+    (ProjectType::DotNet, "App.csproj", "cs"),
+    (ProjectType::Terraform, "main.tf", "tf"),
+    (ProjectType::Ansible, "ansible/site.yml", "yml"),
+];
+
+/// Fixed timestamp for generated commits so history is reproducible.
+const COMMIT_DATE: &str = "2024-01-01T00:00:00Z";
+
+pub fn generate(root: &Path, spec: &FixtureSpec) -> Result<Vec<FixtureProject>> {
+    let mut rng = fastrand::Rng::with_seed(spec.seed);
+    let git_cli = spec.git_commits && git_available();
+    if spec.git_commits && !git_cli {
+        tracing::warn!("git not found on PATH; fixture repos will have no commits");
+    }
+    let mut out = Vec::with_capacity(spec.projects);
+    for i in 0..spec.projects {
+        let (ptype, marker, ext) = TYPES[rng.usize(..TYPES.len())];
+        let dir = root
+            .join(format!("group-{:03}", i / spec.per_group.max(1)))
+            .join(format!("{}-{i:05}", ptype.as_str().trim_start_matches('.')));
+        fs::create_dir_all(dir.join("src"))?;
+
+        let mut files = 0usize;
+        let mut bytes = 0u64;
+        let mut write = |rel: &str, content: String| -> Result<()> {
+            let p = dir.join(rel);
+            if let Some(parent) = p.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            bytes += content.len() as u64;
+            files += 1;
+            fs::write(&p, content).with_context(|| format!("writing {}", p.display()))
+        };
+
+        write(marker, marker_contents(ptype, i))?;
+        // Size classes: mostly small, some medium, a few large
+        let max = spec.max_files.max(1);
+        let n_files = match rng.u8(..10) {
+            0 => rng.usize(max / 2..=max),
+            1..=3 => rng.usize(1..=(max / 4).max(1)),
+            _ => rng.usize(1..=3.min(max)),
+        };
+        for f in 0..n_files {
+            let lines = rng.usize(5..400);
+            write(
+                &format!("src/file{f:03}.{ext}"),
+                format!("// fixture {i}/{f}\n").repeat(lines),
+            )?;
+        }
+
+        if spec.ignored_dirs && rng.u8(..4) == 0 {
+            let ignored = if ptype == ProjectType::Rust {
+                "target/debug"
+            } else {
+                "node_modules/dep"
+            };
+            fs::create_dir_all(dir.join(ignored))?;
+            fs::write(dir.join(ignored).join("blob.bin"), vec![0u8; 4096])?;
+        }
+
+        let git = if rng.f64() < spec.git_ratio {
+            if git_cli {
+                init_repo(&dir)?;
+                if rng.u8(..3) == 0 {
+                    write("UNTRACKED.md", "wip\n".into())?;
+                    GitState::Dirty
+                } else {
+                    GitState::Clean
+                }
+            } else {
+                init_skeleton(&dir)?;
+                GitState::Empty
+            }
+        } else {
+            GitState::None
+        };
+
+        out.push(FixtureProject {
+            path: dir,
+            project_type: ptype,
+            git,
+            files,
+            bytes,
+        });
+    }
+    Ok(out)
+}
+
+fn marker_contents(ptype: ProjectType, i: usize) -> String {
+    match ptype {
+        ProjectType::Rust => {
+            format!("[package]\nname = \"fixture-{i}\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = \"1\"\n")
+        }
+        ProjectType::NodeJs => {
+            format!("{{\"name\":\"fixture-{i}\",\"dependencies\":{{\"left-pad\":\"^1.3.0\"}}}}\n")
+        }
+        ProjectType::Python => format!("[project]\nname = \"fixture-{i}\"\n"),
+        ProjectType::Go => {
+            format!("module example.com/fixture{i}\n\nrequire golang.org/x/text v0.14.0\n")
+        }
+        ProjectType::Ansible => "- hosts: all\n".into(),
+        _ => String::new(),
+    }
+}
+
+fn git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<()> {
+    let status = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .env("GIT_AUTHOR_NAME", "Fixture")
+        .env("GIT_AUTHOR_EMAIL", "fixture@example.com")
+        .env("GIT_AUTHOR_DATE", COMMIT_DATE)
+        .env("GIT_COMMITTER_NAME", "Fixture")
+        .env("GIT_COMMITTER_EMAIL", "fixture@example.com")
+        .env("GIT_COMMITTER_DATE", COMMIT_DATE)
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .with_context(|| format!("running git {args:?}"))?;
+    anyhow::ensure!(status.success(), "git {args:?} failed in {}", dir.display());
+    Ok(())
+}
+
+fn init_repo(dir: &Path) -> Result<()> {
+    git(dir, &["init", "-q", "-b", "main"])?;
+    git(dir, &["add", "-A"])?;
+    git(
+        dir,
+        &[
+            "-c",
+            "commit.gpgsign=false",
+            "commit",
+            "-q",
+            "-m",
+            "fixture",
+        ],
+    )
+}
+
+/// Minimal layout that both `detect::is_git_repo` and libgit2 accept as an unborn repo.
+fn init_skeleton(dir: &Path) -> Result<()> {
+    let git = dir.join(".git");
+    fs::create_dir_all(git.join("objects"))?;
+    fs::create_dir_all(git.join("refs/heads"))?;
+    fs::write(git.join("HEAD"), "ref: refs/heads/main\n")?;
+    fs::write(
+        git.join("config"),
+        "[core]\n\trepositoryformatversion = 0\n\tbare = false\n",
+    )?;
+    Ok(())
+}
@@ -0,0 +1,155 @@
+use anyhow::{Context, Result};
+use serde_json::{Map, Value};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerOutput};
+use crate::config::{AppConfig, ConfigStore};
+
+/// Runs `hooks.post_detect` commands for each project and merges their JSON stdout into
+/// the project's custom fields.
+///
+/// Templates are split into arguments before `{path}`, `{type}` and `{name}` are
+/// substituted, so project paths never pass through a shell.
+pub struct HooksAnalyzer;
+
+impl Analyzer for HooksAnalyzer {
+    fn name(&self) -> &'static str {
+        "hooks"
+    }
+
+    fn enabled_by_default(&self, cfg: &AppConfig) -> bool {
+        !cfg.hooks.post_detect.is_empty()
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        let mut fields = Map::new();
+        for template in &ctx.cfg.hooks.post_detect {
+            match run_hook(template, root, ctx) {
+                Ok(Value::Object(obj)) => fields.extend(obj),
+                Ok(other) => {
+                    tracing::warn!(hook = %template, output = %other, "hook output is not a JSON object; ignoring")
+                }
+                Err(err) => tracing::warn!(%err, hook = %template, ?root, "hook failed"),
+            }
+        }
+        Ok(AnalyzerOutput::Fields(fields))
+    }
+}
+
+fn run_hook(template: &str, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<Value> {
+    let name = root
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let path = root.to_string_lossy();
+    let args = split_command(template)
+        .into_iter()
+        .map(|a| {
+            a.replace("{path}", &path)
+                .replace("{type}", ctx.project_type)
+                .replace("{name}", &name)
+        })
+        .collect::<Vec<_>>();
+    let (program, rest) = args
+        .split_first()
+        .with_context(|| format!("empty hook command: {template:?}"))?;
+
+    let mut child = Command::new(resolve_program(program))
+        .args(rest)
+        .current_dir(root)
+        .env("PB_PROJECT_PATH", root)
+        .env("PB_PROJECT_TYPE", ctx.project_type)
+        .env("PB_PROJECT_NAME", &name)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .with_context(|| format!("failed to spawn hook {program}"))?;
+
+    // Drain stdout concurrently so a chatty hook cannot block on a full pipe.
+    let mut out = child.stdout.take().context("hook stdout not captured")?;
+    let reader = std::thread::spawn(move || {
+        let mut buf = String::new();
+        out.read_to_string(&mut buf).map(|_| buf)
+    });
+
+    let timeout = Duration::from_secs(ctx.cfg.hooks.timeout_secs);
+    let started = Instant::now();
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if started.elapsed() > timeout {
+            let _ = child.kill();
+            let _ = child.wait();
+            anyhow::bail!("hook timed out after {}s", timeout.as_secs());
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    };
+    let stdout = reader
+        .join()
+        .map_err(|_| anyhow::anyhow!("hook stdout reader panicked"))??;
+    if !status.success() {
+        anyhow::bail!("hook exited with {status}");
+    }
+    let stdout = stdout.trim();
+    if stdout.is_empty() {
+        return Ok(Value::Object(Map::new()));
+    }
+    serde_json::from_str(stdout).context("hook stdout is not valid JSON")
+}
+
+/// Relative programs with a path component (`./bin/owner.sh`) resolve against the config dir.
+fn resolve_program(program: &str) -> PathBuf {
+    let p = PathBuf::from(shellexpand::tilde(program).to_string());
+    if p.is_relative() && p.components().count() > 1 {
+        if let Ok(dir) = ConfigStore::config_dir() {
+            return dir.join(p);
+        }
+    }
+    p
+}
+
+/// Minimal shell-style word splitting: whitespace separates words, single and double
+/// quotes group, backslash escapes the next character outside single quotes.
+pub fn split_command(s: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut cur = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('\''), c) => cur.push(c),
+            (_, '\\') => {
+                if let Some(n) = chars.next() {
+                    cur.push(n);
+                }
+                in_word = true;
+            }
+            (Some(_), c) => cur.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut cur));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                cur.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if in_word {
+        words.push(cur);
+    }
+    words
+}
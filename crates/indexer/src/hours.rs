@@ -0,0 +1,259 @@
+//! A rough "where did my year go" estimate of time spent per project, from commit timestamps
+//! (the `hours` analyzer). Commits less than `hours.session_gap_minutes` apart are one work
+//! session, which lasts from its first commit to its last plus `hours.first_commit_minutes`
+//! for the work before that first commit.
+//!
+//! Sessions are stored per project in `work_sessions`; `stats --hours` sums them by project,
+//! type and month, and the `hours` query key filters on a project's total.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerOutput};
+use crate::config::{AppConfig, HoursConfig};
+use crate::db::Db;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS work_sessions (
+      project_id INTEGER NOT NULL,
+      started_at INTEGER NOT NULL,
+      ended_at INTEGER NOT NULL,
+      commits INTEGER NOT NULL,
+      -- Estimated time spent, including the lead-in before the first commit
+      secs INTEGER NOT NULL,
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_work_sessions_project ON work_sessions(project_id, started_at);
+"#;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorkSession {
+    /// First and last commit time
+    pub started_at: i64,
+    pub ended_at: i64,
+    pub commits: i64,
+    pub secs: i64,
+}
+
+pub struct HoursAnalyzer;
+
+impl Analyzer for HoursAnalyzer {
+    fn name(&self) -> &'static str {
+        "hours"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        if !ctx.is_git_repo {
+            return Ok(AnalyzerOutput::Skipped);
+        }
+        let Some(times) = commit_times(root, ctx.cfg) else {
+            return Ok(AnalyzerOutput::Skipped);
+        };
+        Ok(AnalyzerOutput::WorkSessions(work_sessions(
+            times,
+            &ctx.cfg.hours,
+        )))
+    }
+}
+
+/// Commit times of the most recent non-merge commits, only yours when `hours.only_mine` and
+/// `authors.me` are set.
+fn commit_times(root: &Path, cfg: &AppConfig) -> Option<Vec<i64>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(root)
+        .args(["log", "--no-merges", "--format=%at%x09%aN%x09%aE"])
+        .arg(format!("--max-count={}", cfg.hours.max_commits))
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    let me = if cfg.hours.only_mine {
+        cfg.authors.me.as_slice()
+    } else {
+        &[]
+    };
+    let times = String::from_utf8_lossy(&out.stdout)
+        .lines()
+        .filter_map(|l| {
+            let mut parts = l.splitn(3, '\t');
+            let at = parts.next()?.parse().ok()?;
+            let (name, email) = (parts.next().unwrap_or(""), parts.next().unwrap_or(""));
+            let mine = me.is_empty()
+                || me
+                    .iter()
+                    .any(|m| m.eq_ignore_ascii_case(name) || m.eq_ignore_ascii_case(email));
+            mine.then_some(at)
+        })
+        .collect();
+    Some(times)
+}
+
+/// Cluster commit times (any order) into sessions, oldest first.
+pub fn work_sessions(mut times: Vec<i64>, cfg: &HoursConfig) -> Vec<WorkSession> {
+    let gap = cfg.session_gap_minutes as i64 * 60;
+    let lead_in = cfg.first_commit_minutes as i64 * 60;
+    times.sort_unstable();
+    let mut sessions: Vec<WorkSession> = Vec::new();
+    for at in times {
+        match sessions.last_mut() {
+            Some(s) if at - s.ended_at <= gap => {
+                s.ended_at = at;
+                s.commits += 1;
+            }
+            _ => sessions.push(WorkSession {
+                started_at: at,
+                ended_at: at,
+                commits: 1,
+                secs: 0,
+            }),
+        }
+    }
+    for s in &mut sessions {
+        s.secs = s.ended_at - s.started_at + lead_in;
+    }
+    sessions
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HoursRow {
+    /// Project type or `YYYY-MM` (UTC); `None` when the type is unknown
+    pub name: Option<String>,
+    pub hours: f64,
+    pub sessions: i64,
+    pub projects: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectHours {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub project_type: Option<String>,
+    pub hours: f64,
+    pub sessions: i64,
+    pub commits: i64,
+    pub last_session_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HoursStats {
+    /// Sessions starting at or after this time are counted
+    pub since: i64,
+    pub hours: f64,
+    pub sessions: i64,
+    /// Most hours first
+    pub by_type: Vec<HoursRow>,
+    /// Oldest month first
+    pub by_month: Vec<HoursRow>,
+    /// Most hours first
+    pub projects: Vec<ProjectHours>,
+}
+
+const HOURS_SQL: &str = "SELECT {key}, COALESCE(SUM(w.secs), 0) / 3600.0, COUNT(*), \
+       COUNT(DISTINCT w.project_id) \
+     FROM work_sessions w JOIN projects p ON p.id = w.project_id \
+     WHERE w.started_at >= ?1 \
+     GROUP BY 1 \
+     ORDER BY {order}";
+
+impl Db {
+    pub fn replace_work_sessions(&self, project_id: i64, sessions: &[WorkSession]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM work_sessions WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for s in sessions {
+            tx.execute(
+                "INSERT INTO work_sessions (project_id, started_at, ended_at, commits, secs) \
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![project_id, s.started_at, s.ended_at, s.commits, s.secs],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// `project_id`'s sessions, oldest first.
+    pub fn work_sessions(&self, project_id: i64) -> Result<Vec<WorkSession>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT started_at, ended_at, commits, secs FROM work_sessions \
+             WHERE project_id = ?1 ORDER BY started_at",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |r| {
+                Ok(WorkSession {
+                    started_at: r.get(0)?,
+                    ended_at: r.get(1)?,
+                    commits: r.get(2)?,
+                    secs: r.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Estimated time spent since `since`, with the `top` projects it went to.
+    pub fn hours_stats(&self, since: i64, top: usize) -> Result<HoursStats> {
+        let (hours, sessions) = self.conn.query_row(
+            "SELECT COALESCE(SUM(secs), 0) / 3600.0, COUNT(*) FROM work_sessions \
+             WHERE started_at >= ?1",
+            params![since],
+            |r| Ok((r.get(0)?, r.get(1)?)),
+        )?;
+        let mut stmt = self.conn.prepare(
+            "SELECT p.id, p.name, p.path, p.type, SUM(w.secs) / 3600.0, COUNT(*), \
+               SUM(w.commits), MAX(w.started_at) \
+             FROM work_sessions w JOIN projects p ON p.id = w.project_id \
+             WHERE w.started_at >= ?1 \
+             GROUP BY p.id \
+             ORDER BY SUM(w.secs) DESC, p.name \
+             LIMIT ?2",
+        )?;
+        let projects = stmt
+            .query_map(params![since, top as i64], |r| {
+                Ok(ProjectHours {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    path: r.get(2)?,
+                    project_type: r.get(3)?,
+                    hours: r.get(4)?,
+                    sessions: r.get(5)?,
+                    commits: r.get(6)?,
+                    last_session_at: r.get(7)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(HoursStats {
+            since,
+            hours,
+            sessions,
+            by_type: self.hours_by("p.type", "SUM(w.secs) DESC, 1", since)?,
+            by_month: self.hours_by("strftime('%Y-%m', w.started_at, 'unixepoch')", "1", since)?,
+            projects,
+        })
+    }
+
+    fn hours_by(&self, key: &str, order: &str, since: i64) -> Result<Vec<HoursRow>> {
+        let sql = HOURS_SQL.replace("{key}", key).replace("{order}", order);
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params![since], |r| {
+                Ok(HoursRow {
+                    name: r.get(0)?,
+                    hours: r.get(1)?,
+                    sessions: r.get(2)?,
+                    projects: r.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,262 @@
+//! Undo journal for destructive index operations. Before a delete or merge runs, every row
+//! belonging to the affected projects is copied into the journal; `undo` puts those rows
+//! back exactly as they were. Entries older than `journal.retention_days` are pruned and can
+//! no longer be undone.
+
+use anyhow::{anyhow, Result};
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, params_from_iter, Connection, OptionalExtension};
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::db::Db;
+
+/// Tables keyed by `project_id` whose rows are snapshotted along with the project row
+pub(crate) const PROJECT_TABLES: &[&str] = &[
+    "metrics",
+    "git_info",
+    "loc_lang",
+    "secret_findings",
+    "dep_audit",
+    "dependencies",
+    "analyzer_results",
+    "custom_fields",
+    "project_fields",
+    "project_opens",
+    "backup_status",
+    "artifacts",
+    "activity",
+    "toolchains",
+    "action_runs",
+    "git_branches",
+    "work_sessions",
+    "commits",
+    "project_assets",
+    "ml_artifacts",
+    "policy_violations",
+];
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS journal (
+      id INTEGER PRIMARY KEY,
+      kind TEXT NOT NULL,
+      summary TEXT NOT NULL,
+      created_at INTEGER NOT NULL,
+      undone_at INTEGER
+    );
+    CREATE TABLE IF NOT EXISTS journal_rows (
+      entry_id INTEGER NOT NULL,
+      table_name TEXT NOT NULL,
+      row TEXT NOT NULL,
+      FOREIGN KEY(entry_id) REFERENCES journal(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_journal_rows_entry ON journal_rows(entry_id);
+"#;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct JournalEntry {
+    pub id: i64,
+    /// `delete` or `merge`
+    pub kind: String,
+    pub summary: String,
+    pub created_at: i64,
+    pub undone_at: Option<i64>,
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+impl Db {
+    /// Snapshot `project_ids`, then run `op` in the same transaction and record it as an
+    /// undoable journal entry.
+    pub(crate) fn journaled<T>(
+        &self,
+        kind: &str,
+        summary: &str,
+        project_ids: &[i64],
+        op: impl FnOnce(&Connection) -> Result<T>,
+    ) -> Result<T> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "INSERT INTO journal (kind, summary, created_at) VALUES (?1, ?2, ?3)",
+            params![kind, summary, now()],
+        )?;
+        let entry = tx.last_insert_rowid();
+        for &id in project_ids {
+            let mut tables = vec![("projects", "id")];
+            tables.extend(PROJECT_TABLES.iter().map(|t| (*t, "project_id")));
+            for (table, key) in tables {
+                for row in select_rows(&tx, table, key, id)? {
+                    tx.execute(
+                        "INSERT INTO journal_rows (entry_id, table_name, row) VALUES (?1, ?2, ?3)",
+                        params![entry, table, row.to_string()],
+                    )?;
+                }
+            }
+        }
+        let out = op(&tx)?;
+        tx.commit()?;
+        Ok(out)
+    }
+
+    /// Most recent entries first.
+    pub fn journal(&self, limit: u32) -> Result<Vec<JournalEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, kind, summary, created_at, undone_at FROM journal \
+             ORDER BY id DESC LIMIT ?1",
+        )?;
+        let rows = stmt
+            .query_map(params![limit], |row| {
+                Ok(JournalEntry {
+                    id: row.get(0)?,
+                    kind: row.get(1)?,
+                    summary: row.get(2)?,
+                    created_at: row.get(3)?,
+                    undone_at: row.get(4)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Drop entries older than `retention_days`.
+    pub fn prune_journal(&self, retention_days: u32) -> Result<usize> {
+        let cutoff = now() - retention_days as i64 * 86400;
+        Ok(self
+            .conn
+            .execute("DELETE FROM journal WHERE created_at < ?1", params![cutoff])?)
+    }
+
+    /// Undo the most recent entry that has not been undone yet, restoring every row it
+    /// touched to its state before the operation. Returns `None` when there is nothing
+    /// left to undo within `retention_days`.
+    pub fn undo_last(&self, retention_days: u32) -> Result<Option<JournalEntry>> {
+        self.prune_journal(retention_days)?;
+        let Some(entry) = self
+            .journal(u32::MAX)?
+            .into_iter()
+            .find(|e| e.undone_at.is_none())
+        else {
+            return Ok(None);
+        };
+
+        let rows: Vec<(String, serde_json::Map<String, serde_json::Value>)> = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT table_name, row FROM journal_rows WHERE entry_id = ?1")?;
+            let raw = stmt
+                .query_map(params![entry.id], |row| {
+                    Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            raw.into_iter()
+                .map(|(table, row)| Ok((table, serde_json::from_str(&row)?)))
+                .collect::<Result<_>>()?
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+        // Clear whatever the affected projects look like now; the cascade takes their rows
+        // in every project table with them
+        for (_, row) in rows.iter().filter(|(t, _)| t == "projects") {
+            let id = row.get("id").and_then(|v| v.as_i64());
+            let path = row.get("path").and_then(|v| v.as_str()).unwrap_or_default();
+            let taken: Option<i64> = tx
+                .query_row(
+                    "SELECT id FROM projects WHERE path = ?1 AND id IS NOT ?2",
+                    params![path, id],
+                    |r| r.get(0),
+                )
+                .optional()?;
+            if let Some(other) = taken {
+                anyhow::bail!(
+                    "cannot undo {}: {path} is now indexed as project {other}; merge or forget it first",
+                    entry.kind
+                );
+            }
+            tx.execute("DELETE FROM projects WHERE id = ?1", params![id])?;
+        }
+        // Project rows first so the foreign keys of the rest resolve
+        let (projects, rest): (Vec<_>, Vec<_>) = rows.iter().partition(|(t, _)| t == "projects");
+        for (table, row) in projects.into_iter().chain(rest) {
+            if !(*table == "projects" || PROJECT_TABLES.contains(&table.as_str())) {
+                return Err(anyhow!(
+                    "unexpected table {table} in journal entry {}",
+                    entry.id
+                ));
+            }
+            insert_row(&tx, table, row)?;
+        }
+        tx.execute(
+            "UPDATE journal SET undone_at = ?2 WHERE id = ?1",
+            params![entry.id, now()],
+        )?;
+        tx.commit()?;
+        Ok(Some(JournalEntry {
+            undone_at: Some(now()),
+            ..entry
+        }))
+    }
+}
+
+/// Rows of `table` where `key = id`, as JSON objects keyed by column name.
+fn select_rows(
+    conn: &Connection,
+    table: &str,
+    key: &str,
+    id: i64,
+) -> Result<Vec<serde_json::Value>> {
+    let mut stmt = conn.prepare(&format!("SELECT * FROM {table} WHERE {key} = ?1"))?;
+    let columns: Vec<String> = stmt.column_names().into_iter().map(String::from).collect();
+    let rows = stmt
+        .query_map(params![id], |row| {
+            let mut obj = serde_json::Map::new();
+            for (i, col) in columns.iter().enumerate() {
+                let v = match row.get_ref(i)? {
+                    ValueRef::Null => serde_json::Value::Null,
+                    ValueRef::Integer(n) => n.into(),
+                    ValueRef::Real(f) => f.into(),
+                    ValueRef::Text(t) => String::from_utf8_lossy(t).into_owned().into(),
+                    ValueRef::Blob(b) => b.to_vec().into(),
+                };
+                obj.insert(col.clone(), v);
+            }
+            Ok(serde_json::Value::Object(obj))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(rows)
+}
+
+fn insert_row(
+    conn: &Connection,
+    table: &str,
+    row: &serde_json::Map<String, serde_json::Value>,
+) -> Result<()> {
+    let columns = row.keys().cloned().collect::<Vec<_>>();
+    let values = row.values().map(|v| match v {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(b) => Value::Integer(*b as i64),
+        serde_json::Value::Number(n) => match n.as_i64() {
+            Some(i) => Value::Integer(i),
+            None => Value::Real(n.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(s) => Value::Text(s.clone()),
+        serde_json::Value::Array(bytes) => Value::Blob(
+            bytes
+                .iter()
+                .map(|b| b.as_u64().unwrap_or_default() as u8)
+                .collect(),
+        ),
+        serde_json::Value::Object(_) => Value::Null,
+    });
+    let placeholders = vec!["?"; columns.len()].join(", ");
+    conn.execute(
+        &format!(
+            "INSERT INTO {table} ({}) VALUES ({placeholders})",
+            columns.join(", ")
+        ),
+        params_from_iter(values),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,253 @@
+//! JSON-RPC 2.0 over stdio (`project-browser rpc`) so editor plugins (Neovim, VS Code) can
+//! use the index as a picker source from one long-lived process instead of running the
+//! CLI on every keystroke. One request per line in, one response per line out;
+//! notifications (no `id`) get no response.
+//!
+//! Methods; `project` is an id, a path or name, or any path inside an indexed project:
+//! - `search {query, limit?}`: projects ranked as in the quick-open palette
+//! - `detail {project}`: README, languages, workspace members and related projects
+//! - `record_open {project}`: count an open towards frecency
+//! - `refresh {path?}`: rescan the project containing `path`, or scan `path` (default: every
+//!   configured root) as a root, through the `watch` daemon when one is running
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::analyzers::AnalyzerRegistry;
+use crate::config::AppConfig;
+use crate::daemon::DaemonClient;
+use crate::db::{Db, ProjectRecord};
+use crate::queue::{ScanPriority, ScanQueue};
+use crate::rpc::{Backend, ScanOutcome};
+use crate::scan::{scan_project, ScanOptions};
+
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+/// Anything that went wrong while running a valid request
+const SERVER_ERROR: i64 = -32000;
+
+fn default_limit() -> u32 {
+    20
+}
+
+#[derive(Deserialize)]
+struct SearchParams {
+    #[serde(default)]
+    query: String,
+    #[serde(default = "default_limit")]
+    limit: u32,
+}
+
+#[derive(Deserialize)]
+struct ProjectParams {
+    project: ProjectRef,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ProjectRef {
+    Id(i64),
+    Key(String),
+}
+
+#[derive(Deserialize, Default)]
+struct RefreshParams {
+    path: Option<PathBuf>,
+}
+
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+impl RpcError {
+    fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(err: anyhow::Error) -> Self {
+        Self::new(SERVER_ERROR, format!("{err:#}"))
+    }
+}
+
+fn params<T: for<'de> Deserialize<'de>>(params: Value) -> Result<T, RpcError> {
+    // Omitted params are the same as `{}`
+    let params = if params.is_null() { json!({}) } else { params };
+    serde_json::from_value(params).map_err(|e| RpcError::new(INVALID_PARAMS, e.to_string()))
+}
+
+pub struct RpcServer {
+    db: Db,
+    cfg: AppConfig,
+    /// The daemon's control socket; `None` is the one for the current data directory
+    socket: Option<PathBuf>,
+}
+
+impl RpcServer {
+    pub fn new(db: Db, cfg: AppConfig) -> Self {
+        Self {
+            db,
+            cfg,
+            socket: None,
+        }
+    }
+
+    /// Look for the daemon on `socket` instead of the default one.
+    pub fn with_socket(mut self, socket: PathBuf) -> Self {
+        self.socket = Some(socket);
+        self
+    }
+
+    /// The running daemon, if any.
+    fn daemon(&self) -> Result<Option<Backend>> {
+        let client = match &self.socket {
+            Some(socket) => DaemonClient::connect_to(socket)?,
+            None => DaemonClient::connect()?,
+        };
+        Ok(client.map(|client| Backend::Daemon(Mutex::new(client))))
+    }
+
+    /// Answer requests from `input` until it closes.
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(resp) = self.handle_line(&line) {
+                writeln!(output, "{resp}")?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The response to one request line, or None for a notification.
+    pub fn handle_line(&self, line: &str) -> Option<String> {
+        let msg: Value = match serde_json::from_str(line) {
+            Ok(v) => v,
+            Err(e) => {
+                return Some(response(
+                    Value::Null,
+                    Err(RpcError::new(PARSE_ERROR, e.to_string())),
+                ))
+            }
+        };
+        let id = msg.get("id").cloned();
+        let result = match msg.get("method").and_then(Value::as_str) {
+            Some(method) => {
+                let params = msg.get("params").cloned().unwrap_or(Value::Null);
+                self.call(method, params)
+            }
+            None => Err(RpcError::new(INVALID_REQUEST, "missing method")),
+        };
+        if let Err(err) = &result {
+            tracing::debug!(code = err.code, message = %err.message, "rpc request failed");
+        }
+        id.map(|id| response(id, result))
+    }
+
+    fn call(&self, method: &str, p: Value) -> Result<Value, RpcError> {
+        match method {
+            "search" => {
+                let p: SearchParams = params(p)?;
+                let hits = self.db.quick_open(&p.query, p.limit, &self.cfg.ranking)?;
+                Ok(serde_json::to_value(hits).map_err(anyhow::Error::from)?)
+            }
+            "detail" => {
+                let p: ProjectParams = params(p)?;
+                let project = self.resolve(&p.project)?;
+                let detail = crate::detail::project_detail(&self.db, project.id)?;
+                Ok(serde_json::to_value(detail).map_err(anyhow::Error::from)?)
+            }
+            "record_open" => {
+                let p: ProjectParams = params(p)?;
+                let project = self.resolve(&p.project)?;
+                self.db.record_open(project.id)?;
+                Ok(Value::Null)
+            }
+            "refresh" => {
+                let p: RefreshParams = params(p)?;
+                Ok(self.refresh(p.path.as_deref())?)
+            }
+            _ => Err(RpcError::new(
+                METHOD_NOT_FOUND,
+                format!("unknown method {method}"),
+            )),
+        }
+    }
+
+    fn resolve(&self, project: &ProjectRef) -> Result<ProjectRecord, RpcError> {
+        let found = match project {
+            ProjectRef::Id(id) => self.db.project_by_id(*id)?,
+            ProjectRef::Key(key) => self.db.resolve_project(key)?,
+        };
+        found.ok_or_else(|| RpcError::new(INVALID_PARAMS, "no such project"))
+    }
+
+    fn refresh(&self, path: Option<&Path>) -> Result<Value> {
+        // Scanning here while the daemon runs would race its own scans of the same rows
+        let daemon = self.daemon()?;
+        let mut roots: Vec<(PathBuf, ScanPriority)> = Vec::new();
+        if let Some(path) = path {
+            let path = crate::paths::canonical(path);
+            match self.db.project_containing(&path.to_string_lossy())? {
+                Some(project) if daemon.is_none() => {
+                    let record = scan_project(&self.db, &self.cfg, &project.dir())?;
+                    return Ok(json!({ "project": record }));
+                }
+                Some(project) => roots.push((project.dir(), ScanPriority::High)),
+                None if !path.is_dir() => {
+                    return Err(anyhow!("{} is not a directory", path.display()));
+                }
+                None => roots.push((path, ScanPriority::High)),
+            }
+        } else {
+            roots.extend(
+                self.cfg
+                    .roots
+                    .iter()
+                    .map(|r| (r.clone(), self.cfg.priority_of(r))),
+            );
+        }
+        let analyzers = AnalyzerRegistry::with_builtins();
+        let opts = ScanOptions::default();
+        let outcome = match daemon {
+            Some(daemon) => daemon.scan(&self.cfg, &roots, &opts, &analyzers)?,
+            None => {
+                let queue = ScanQueue::default();
+                for (root, priority) in roots {
+                    queue.enqueue(root, priority);
+                }
+                ScanOutcome::Scanned(queue.run(&self.db, &self.cfg, &opts, &analyzers)?)
+            }
+        };
+        Ok(match outcome {
+            ScanOutcome::Queued(pending) => json!({ "queued": pending }),
+            ScanOutcome::Scanned(report) => json!({ "scanned": report.projects }),
+        })
+    }
+}
+
+fn response(id: Value, result: Result<Value, RpcError>) -> String {
+    let body = match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(err) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": err.code, "message": err.message },
+        }),
+    };
+    body.to_string()
+}
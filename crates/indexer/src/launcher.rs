@@ -0,0 +1,98 @@
+//! Per-project launch settings: the editor a project opens in (overriding `launcher.editor`)
+//! and a command such as `make dev` or `docker compose up` to run in a terminal. Both are
+//! user fields (`editor`, `launch_command`), so they show up, merge and back up with the rest.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+
+use crate::db::Db;
+use crate::fields::{FieldValue, EDITOR_FIELD, LAUNCH_COMMAND_FIELD};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProjectLauncher {
+    /// Editor command for this project; None uses `launcher.editor`
+    pub editor: Option<String>,
+    /// Shell command run in a terminal from the project directory
+    pub command: Option<String>,
+}
+
+impl Db {
+    pub fn project_launcher(&self, project_id: i64) -> Result<ProjectLauncher> {
+        let fields = self.project_fields(project_id)?;
+        let text = |key: &str| {
+            fields
+                .get(key)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(String::from)
+        };
+        Ok(ProjectLauncher {
+            editor: text(EDITOR_FIELD),
+            command: text(LAUNCH_COMMAND_FIELD),
+        })
+    }
+
+    /// Store `launcher`; a None or blank entry removes that setting.
+    pub fn set_project_launcher(&self, project_id: i64, launcher: &ProjectLauncher) -> Result<()> {
+        for (key, value) in [
+            (EDITOR_FIELD, &launcher.editor),
+            (LAUNCH_COMMAND_FIELD, &launcher.command),
+        ] {
+            match value.as_deref().map(str::trim).filter(|s| !s.is_empty()) {
+                Some(v) => self.set_project_field(project_id, key, &FieldValue::Text(v.into()))?,
+                None => {
+                    self.remove_project_field(project_id, key)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A process that opens a terminal in `dir` running `command` through the shell, left open
+/// at a shell prompt once the command exits. `terminal` is `launcher.terminal`: the program
+/// and arguments to put before `sh -c <command>`, or empty for Terminal.app, a `cmd` window
+/// or `x-terminal-emulator`.
+pub fn terminal_command(terminal: &[String], dir: &Path, command: &str) -> Command {
+    let keep_open = format!("{command}; exec \"${{SHELL:-sh}}\"");
+    if let Some((program, args)) = terminal.split_first() {
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .args(["sh", "-c", &keep_open])
+            .current_dir(dir);
+        return cmd;
+    }
+    if cfg!(target_os = "macos") {
+        // Terminal.app starts in the home directory, so the script changes directory itself
+        let script = format!("cd {} && {command}", shell_quote(&dir.to_string_lossy()));
+        let mut cmd = Command::new("osascript");
+        cmd.args([
+            "-e",
+            &format!(
+                "tell application \"Terminal\" to do script \"{}\"",
+                script.replace('\\', "\\\\").replace('"', "\\\"")
+            ),
+            "-e",
+            "tell application \"Terminal\" to activate",
+        ]);
+        cmd
+    } else if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", "cmd", "/K", command])
+            .current_dir(dir);
+        cmd
+    } else {
+        let mut cmd = Command::new("x-terminal-emulator");
+        cmd.args(["-e", "sh", "-c", &keep_open]).current_dir(dir);
+        cmd
+    }
+}
+
+/// `s` single-quoted for `sh`.
+fn shell_quote(s: &str) -> String {
+    format!("'{}'", s.replace('\'', r"'\''"))
+}
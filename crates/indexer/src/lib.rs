@@ -1,12 +1,78 @@
-#[cfg(feature = "analyzers")]
+pub mod actions;
+pub mod activity;
 pub mod analyzers;
+pub mod assets;
+pub mod authors;
+pub mod autostart;
+pub mod backup;
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod branches;
+pub mod cleanup;
+pub mod columns;
+pub mod commits;
 pub mod config;
+pub mod daemon;
 pub mod db;
+pub mod deps;
+pub mod detail;
 pub mod detect;
+pub mod devenv;
+pub mod diagnose;
+pub mod encryption;
+pub mod explain;
+pub mod fetch;
+pub mod fields;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod hooks;
+pub mod hours;
+pub mod journal;
+pub mod jsonrpc;
+pub mod launcher;
+pub mod links;
+pub mod location;
+pub mod logging;
+pub mod metrics;
+pub mod ml;
+pub mod mobile;
+pub mod moves;
+pub mod nice;
+pub mod notify;
+pub mod offsite;
+pub mod paths;
+pub mod policies;
+pub mod power;
+pub mod preview;
+pub mod quality;
+pub mod query;
+pub mod queue;
+pub mod ranking;
+pub mod reconcile;
+pub mod redact;
+pub mod related;
+pub mod repair;
+pub mod resume;
+pub mod roots;
+pub mod rpc;
+pub mod sbom;
 pub mod scan;
-#[cfg(feature = "git")]
+pub mod scan_runs;
+pub mod search;
+pub mod secrets;
+pub mod serve;
+pub mod shape;
+pub mod stats;
+pub mod system;
+pub mod toolchains;
+#[cfg(feature = "update")]
+pub mod update;
 pub mod vcs;
+pub mod vulns;
+pub mod workspace;
 
+pub use analyzers::{Analyzer, AnalyzerContext, AnalyzerOutput, AnalyzerRegistry};
 pub use config::{AppConfig, ConfigStore};
-pub use db::{Db, ProjectRecord, SortKey};
-pub use scan::{scan_roots, ScanOptions};
+pub use db::{Db, ProjectAggregates, ProjectFilter, ProjectRecord, SortKey};
+pub use queue::{ScanPriority, ScanQueue};
+pub use scan::{scan_project, scan_roots, scan_roots_with, ScanOptions, ScanProgress, ScanReport};
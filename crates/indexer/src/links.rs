@@ -0,0 +1,113 @@
+//! `projectbrowser://` deep links, so scripts, bookmarks and launchers can drive the app:
+//!
+//! - `projectbrowser://open?path=/src/app[&editor=cursor]`
+//! - `projectbrowser://reveal?path=/src/app`
+//! - `projectbrowser://search?q=type:rust&advanced=1`
+//!
+//! Links come from anywhere on the machine, so `open`/`reveal` only accept paths inside an
+//! indexed project and `editor` is limited to well-known editor commands.
+
+use anyhow::{anyhow, bail, Result};
+use std::path::{Component, Path};
+
+use crate::db::{Db, ProjectRecord};
+
+pub const SCHEME: &str = "projectbrowser";
+
+/// Editors a link may ask for besides the configured `launcher.editor`
+pub const KNOWN_EDITORS: &[&str] = &["code", "cursor", "windsurf", "zed", "subl", "idea"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum LinkAction {
+    Open {
+        path: String,
+        editor: Option<String>,
+    },
+    Reveal {
+        path: String,
+    },
+    Search {
+        q: String,
+        advanced: bool,
+    },
+}
+
+/// Parse a `projectbrowser://<action>?<query>` URL. Unknown actions and missing
+/// parameters are errors; unknown parameters are ignored.
+pub fn parse(url: &str) -> Result<LinkAction> {
+    let rest = url
+        .strip_prefix(SCHEME)
+        .and_then(|r| r.strip_prefix("://"))
+        .ok_or_else(|| anyhow!("not a {SCHEME}:// link: {url}"))?;
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let params: Vec<(String, String)> = query
+        .split('&')
+        .filter(|kv| !kv.is_empty())
+        .map(|kv| {
+            let (k, v) = kv.split_once('=').unwrap_or((kv, ""));
+            Ok((decode(k)?, decode(v)?))
+        })
+        .collect::<Result<_>>()?;
+    let get = |key: &str| {
+        params
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.clone())
+    };
+    let path = || get("path").ok_or_else(|| anyhow!("{action} link needs a path parameter"));
+
+    match action.trim_end_matches('/') {
+        "open" => {
+            let editor = get("editor").filter(|e| !e.is_empty());
+            Ok(LinkAction::Open {
+                path: path()?,
+                editor,
+            })
+        }
+        "reveal" => Ok(LinkAction::Reveal { path: path()? }),
+        "search" => Ok(LinkAction::Search {
+            q: get("q").unwrap_or_default(),
+            advanced: matches!(get("advanced").as_deref(), Some("1" | "true")),
+        }),
+        other => bail!("unsupported link action {other:?}"),
+    }
+}
+
+/// The indexed project containing `path` (or equal to it). Relative paths and `..`
+/// components are rejected so a link cannot climb out of a project.
+pub fn resolve_path(db: &Db, path: &str) -> Result<ProjectRecord> {
+    let p = Path::new(path);
+    if !p.is_absolute() || p.components().any(|c| matches!(c, Component::ParentDir)) {
+        bail!("link paths must be absolute without '..': {path}");
+    }
+    db.project_containing(path)?
+        .ok_or_else(|| anyhow!("{path} is not inside an indexed project"))
+}
+
+/// Whether a link may launch `editor`: the configured one or a well-known editor command.
+pub fn editor_allowed(editor: &str, configured: &str) -> bool {
+    editor == configured || KNOWN_EDITORS.contains(&editor)
+}
+
+/// Percent-decoding for query components; `+` is a space.
+fn decode(s: &str) -> Result<String> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = s
+                    .get(i + 1..i + 3)
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                    .ok_or_else(|| anyhow!("bad percent-escape in {s:?}"))?;
+                out.push(hex);
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    Ok(String::from_utf8(out)?)
+}
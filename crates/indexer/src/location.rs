@@ -0,0 +1,99 @@
+//! Where a project physically lives: the machine that scanned it and the volume it is on,
+//! recorded at discovery so an index merged or synced from several machines can tell "on
+//! the laptop's external drive" apart from a path that merely looks the same.
+//!
+//! A volume is identified by its filesystem UUID where the OS exposes one without extra
+//! tools (Linux, via `/dev/disk/by-uuid`), and otherwise by its mount point
+//! (`/Volumes/Archive`) or drive (`D:`).
+
+use anyhow::Result;
+use rusqlite::params;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use crate::db::Db;
+
+/// This machine's host name, looked up once.
+pub fn machine_name() -> Option<&'static str> {
+    static NAME: OnceLock<Option<String>> = OnceLock::new();
+    NAME.get_or_init(|| host_name().filter(|n| !n.is_empty()))
+        .as_deref()
+}
+
+#[cfg(unix)]
+fn host_name() -> Option<String> {
+    let mut buf = [0u8; 256];
+    // SAFETY: the buffer outlives the call and its length is passed along
+    let rc = unsafe { libc::gethostname(buf.as_mut_ptr().cast(), buf.len()) };
+    if rc != 0 {
+        return None;
+    }
+    let len = buf.iter().position(|b| *b == 0).unwrap_or(buf.len());
+    Some(String::from_utf8_lossy(&buf[..len]).into_owned())
+}
+
+#[cfg(not(unix))]
+fn host_name() -> Option<String> {
+    std::env::var("COMPUTERNAME").ok()
+}
+
+/// The identity of the volume holding `path`, if it can be told.
+#[cfg(unix)]
+pub fn volume_id(path: &Path) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    let dev = std::fs::metadata(path).ok()?.dev();
+    if let Some(uuid) = filesystem_uuid(dev) {
+        return Some(uuid);
+    }
+    // The mount point is the topmost ancestor still on the same device
+    let mut mount = path;
+    while let Some(parent) = mount.parent() {
+        match std::fs::metadata(parent) {
+            Ok(md) if md.dev() == dev => mount = parent,
+            _ => break,
+        }
+    }
+    Some(mount.to_string_lossy().into_owned())
+}
+
+/// The UUID of the block device `dev`; `None` for network, virtual and btrfs-subvolume
+/// filesystems, whose device numbers are not backed by a disk.
+#[cfg(target_os = "linux")]
+fn filesystem_uuid(dev: u64) -> Option<String> {
+    use std::os::unix::fs::MetadataExt;
+    std::fs::read_dir("/dev/disk/by-uuid")
+        .ok()?
+        .flatten()
+        .find(|e| std::fs::metadata(e.path()).is_ok_and(|md| md.rdev() == dev))
+        .map(|e| e.file_name().to_string_lossy().into_owned())
+}
+
+#[cfg(all(unix, not(target_os = "linux")))]
+fn filesystem_uuid(_dev: u64) -> Option<String> {
+    None
+}
+
+#[cfg(not(unix))]
+pub fn volume_id(path: &Path) -> Option<String> {
+    use std::path::Component;
+    match path.components().next()? {
+        Component::Prefix(prefix) => Some(prefix.as_os_str().to_string_lossy().into_owned()),
+        _ => None,
+    }
+}
+
+impl Db {
+    pub fn set_location(
+        &self,
+        project_id: i64,
+        machine: Option<&str>,
+        volume_id: Option<&str>,
+    ) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET machine = ?2, volume_id = ?3 \
+             WHERE id = ?1 AND (machine IS NOT ?2 OR volume_id IS NOT ?3)",
+            params![project_id, machine, volume_id],
+        )?;
+        Ok(())
+    }
+}
@@ -0,0 +1,139 @@
+//! Size-rotated log file for the app and other long-running processes. The writer is a
+//! plain `io::Write`, so callers plug it into whatever subscriber they build.
+
+use anyhow::{Context, Result};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{ConfigStore, LoggingConfig};
+
+/// Appends to `path`; once it would exceed `max_bytes` the file is shifted to `path.1`,
+/// `path.1` to `path.2` and so on, keeping at most `max_files` rotated files.
+/// Rotation happens between writes, so write whole records at a time. Clones share the file.
+#[derive(Clone)]
+pub struct RotatingFile {
+    inner: Arc<Mutex<Inner>>,
+}
+
+struct Inner {
+    path: PathBuf,
+    max_bytes: u64,
+    max_files: usize,
+    file: File,
+    len: u64,
+}
+
+impl RotatingFile {
+    pub fn open(path: &Path, max_bytes: u64, max_files: usize) -> Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+        }
+        let file = open_append(path)?;
+        let len = file.metadata()?.len();
+        Ok(Self {
+            inner: Arc::new(Mutex::new(Inner {
+                path: path.to_path_buf(),
+                max_bytes: max_bytes.max(1),
+                max_files,
+                file,
+                len,
+            })),
+        })
+    }
+
+    pub fn from_config(cfg: &LoggingConfig) -> Result<Self> {
+        Self::open(&log_path(cfg)?, cfg.max_bytes, cfg.max_files)
+    }
+}
+
+fn open_append(path: &Path) -> Result<File> {
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("opening log file {}", path.display()))
+}
+
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(format!(".{n}"));
+    PathBuf::from(s)
+}
+
+impl Inner {
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        if self.max_files == 0 {
+            self.file = File::create(&self.path)?;
+        } else {
+            let _ = fs::remove_file(rotated(&self.path, self.max_files));
+            for n in (1..self.max_files).rev() {
+                let from = rotated(&self.path, n);
+                if from.exists() {
+                    fs::rename(&from, rotated(&self.path, n + 1))?;
+                }
+            }
+            fs::rename(&self.path, rotated(&self.path, 1))?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        self.len = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if inner.len > 0 && inner.len + buf.len() as u64 > inner.max_bytes {
+            inner.rotate()?;
+        }
+        let n = inner.file.write(buf)?;
+        inner.len += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .file
+            .flush()
+    }
+}
+
+/// `logging.path`, or `logs/project-browser.log` under the data dir.
+pub fn log_path(cfg: &LoggingConfig) -> Result<PathBuf> {
+    match &cfg.path {
+        Some(p) => Ok(PathBuf::from(
+            shellexpand::tilde(&p.to_string_lossy()).as_ref(),
+        )),
+        None => Ok(ConfigStore::data_dir()?
+            .join("logs")
+            .join("project-browser.log")),
+    }
+}
+
+/// Last `n` lines across the current file and, if it is short, the most recent rotation.
+pub fn tail_lines(path: &Path, n: usize) -> Result<Vec<String>> {
+    let mut lines = read_lines(path)?;
+    if lines.len() < n {
+        let mut older = read_lines(&rotated(path, 1))?;
+        older.append(&mut lines);
+        lines = older;
+    }
+    let skip = lines.len().saturating_sub(n);
+    Ok(lines.split_off(skip))
+}
+
+fn read_lines(path: &Path) -> Result<Vec<String>> {
+    match File::open(path) {
+        Ok(f) => Ok(BufReader::new(f).lines().map_while(|l| l.ok()).collect()),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Vec::new()),
+        Err(e) => Err(e).with_context(|| format!("reading {}", path.display())),
+    }
+}
@@ -0,0 +1,174 @@
+//! Process-wide counters and histograms for long-running processes (the app, daemons).
+//! Recording is always on and costs a few atomic adds; exporting over HTTP in the
+//! Prometheus text format needs the `metrics` feature and `metrics.enabled` in config.
+
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Upper bounds in seconds, shared by every histogram.
+const BUCKETS: [f64; 12] = [
+    0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 5.0, 30.0, 120.0,
+];
+
+pub struct Histogram {
+    buckets: [AtomicU64; BUCKETS.len()],
+    count: AtomicU64,
+    sum_micros: AtomicU64,
+}
+
+impl Histogram {
+    const fn new() -> Self {
+        #[allow(clippy::declare_interior_mutable_const)]
+        const ZERO: AtomicU64 = AtomicU64::new(0);
+        Self {
+            buckets: [ZERO; BUCKETS.len()],
+            count: AtomicU64::new(0),
+            sum_micros: AtomicU64::new(0),
+        }
+    }
+
+    pub fn observe(&self, d: Duration) {
+        let secs = d.as_secs_f64();
+        for (i, le) in BUCKETS.iter().enumerate() {
+            if secs <= *le {
+                self.buckets[i].fetch_add(1, Ordering::Relaxed);
+            }
+        }
+        self.count.fetch_add(1, Ordering::Relaxed);
+        self.sum_micros
+            .fetch_add(d.as_micros() as u64, Ordering::Relaxed);
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {name} {help}\n# TYPE {name} histogram");
+        for (i, le) in BUCKETS.iter().enumerate() {
+            let n = self.buckets[i].load(Ordering::Relaxed);
+            let _ = writeln!(out, "{name}_bucket{{le=\"{le}\"}} {n}");
+        }
+        let count = self.count.load(Ordering::Relaxed);
+        let sum = self.sum_micros.load(Ordering::Relaxed) as f64 / 1e6;
+        let _ = writeln!(out, "{name}_bucket{{le=\"+Inf\"}} {count}");
+        let _ = writeln!(out, "{name}_sum {sum}\n{name}_count {count}");
+    }
+}
+
+pub struct Metrics {
+    pub scans_total: AtomicU64,
+    pub scan_failures_total: AtomicU64,
+    pub projects_indexed_total: AtomicU64,
+    pub queries_total: AtomicU64,
+    pub scan_duration: Histogram,
+    pub query_duration: Histogram,
+}
+
+pub static METRICS: Metrics = Metrics {
+    scans_total: AtomicU64::new(0),
+    scan_failures_total: AtomicU64::new(0),
+    projects_indexed_total: AtomicU64::new(0),
+    queries_total: AtomicU64::new(0),
+    scan_duration: Histogram::new(),
+    query_duration: Histogram::new(),
+};
+
+pub fn record_scan(elapsed: Duration, projects: Option<usize>) {
+    METRICS.scans_total.fetch_add(1, Ordering::Relaxed);
+    match projects {
+        Some(n) => {
+            METRICS
+                .projects_indexed_total
+                .fetch_add(n as u64, Ordering::Relaxed);
+        }
+        None => {
+            METRICS.scan_failures_total.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    METRICS.scan_duration.observe(elapsed);
+}
+
+pub fn record_query(elapsed: Duration) {
+    METRICS.queries_total.fetch_add(1, Ordering::Relaxed);
+    METRICS.query_duration.observe(elapsed);
+}
+
+/// Current values in the Prometheus text exposition format.
+pub fn render_prometheus() -> String {
+    let mut out = String::new();
+    for (name, help, counter) in [
+        (
+            "project_browser_scans_total",
+            "Scans started",
+            &METRICS.scans_total,
+        ),
+        (
+            "project_browser_scan_failures_total",
+            "Scans that returned an error",
+            &METRICS.scan_failures_total,
+        ),
+        (
+            "project_browser_projects_indexed_total",
+            "Projects found across all scans",
+            &METRICS.projects_indexed_total,
+        ),
+        (
+            "project_browser_queries_total",
+            "Project list queries served",
+            &METRICS.queries_total,
+        ),
+    ] {
+        let _ = writeln!(
+            out,
+            "# HELP {name} {help}\n# TYPE {name} counter\n{name} {}",
+            counter.load(Ordering::Relaxed)
+        );
+    }
+    METRICS.scan_duration.render(
+        &mut out,
+        "project_browser_scan_duration_seconds",
+        "Wall time of a full scan",
+    );
+    METRICS.query_duration.render(
+        &mut out,
+        "project_browser_query_duration_seconds",
+        "Latency of project list queries",
+    );
+    out
+}
+
+/// Serve `GET /metrics` on `addr` from a background thread.
+#[cfg(feature = "metrics")]
+pub fn serve_prometheus(addr: &str) -> anyhow::Result<std::thread::JoinHandle<()>> {
+    let server = tiny_http::Server::http(addr)
+        .map_err(|e| anyhow::anyhow!("metrics endpoint on {addr}: {e}"))?;
+    tracing::info!(%addr, "serving Prometheus metrics on /metrics");
+    Ok(std::thread::spawn(move || {
+        for req in server.incoming_requests() {
+            let resp = if req.url() == "/metrics" {
+                let header = tiny_http::Header::from_bytes(
+                    &b"Content-Type"[..],
+                    &b"text/plain; version=0.0.4"[..],
+                )
+                .expect("static header");
+                tiny_http::Response::from_string(render_prometheus()).with_header(header)
+            } else {
+                tiny_http::Response::from_string("not found").with_status_code(404)
+            };
+            if let Err(err) = req.respond(resp) {
+                tracing::debug!(%err, "metrics response failed");
+            }
+        }
+    }))
+}
+
+/// Start the exporter if the feature is compiled in and `metrics.enabled` is set.
+pub fn start_from_config(cfg: &crate::config::MetricsConfig) {
+    if !cfg.enabled {
+        return;
+    }
+    #[cfg(feature = "metrics")]
+    if let Err(err) = serve_prometheus(&cfg.listen) {
+        tracing::warn!(%err, "failed to start metrics endpoint");
+    }
+    #[cfg(not(feature = "metrics"))]
+    tracing::warn!("metrics.enabled is set but this build lacks the `metrics` feature");
+}
@@ -0,0 +1,244 @@
+//! Machine-learning leftovers per project (the `ml` analyzer): model checkpoints, dataset
+//! directories, experiment-tracker runs and Python environments. Their size is recorded
+//! apart from build artifacts (`ml_bytes`), and idle projects get a cleanup recommendation
+//! for them.
+//!
+//! Unlike the metrics walk this one looks inside gitignored directories, which is where
+//! checkpoints usually live, so it only runs for the project types in `ml.types` and only
+//! `ml.max_depth` levels deep. Directories measured as build artifacts (`node_modules`, ...)
+//! are not descended into, except that virtualenvs among them (`.venv`) are recorded as
+//! environments.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use walkdir::WalkDir;
+
+use crate::analyzers::{Analyzer, AnalyzerContext, AnalyzerOutput};
+use crate::config::MlConfig;
+use crate::db::Db;
+use crate::scan::ARTIFACT_DIRS;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS ml_artifacts (
+      project_id INTEGER NOT NULL,
+      kind TEXT NOT NULL,
+      -- Relative to the project
+      path TEXT NOT NULL,
+      bytes INTEGER NOT NULL,
+      PRIMARY KEY(project_id, path),
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+"#;
+
+const CHECKPOINT_EXTENSIONS: &[&str] = &[
+    "safetensors",
+    "ckpt",
+    "pt",
+    "pth",
+    "onnx",
+    "h5",
+    "keras",
+    "tflite",
+    "gguf",
+    "ggml",
+    "pkl",
+    "joblib",
+];
+const DATASET_DIRS: &[&str] = &["datasets", "dataset"];
+const RUN_DIRS: &[&str] = &["wandb", "mlruns", "lightning_logs", "tb_logs"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+#[serde(rename_all = "snake_case")]
+pub enum MlArtifactKind {
+    /// A weights or checkpoint file of at least `ml.min_file_mb`
+    Checkpoint,
+    /// A `datasets/` directory; often not re-downloadable
+    Dataset,
+    /// Experiment-tracker output such as `wandb/` or `mlruns/`
+    Runs,
+    /// A virtualenv or conda environment of at least `ml.min_env_mb`
+    Environment,
+}
+
+impl MlArtifactKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MlArtifactKind::Checkpoint => "checkpoint",
+            MlArtifactKind::Dataset => "dataset",
+            MlArtifactKind::Runs => "runs",
+            MlArtifactKind::Environment => "environment",
+        }
+    }
+
+    fn parse(s: &str) -> Self {
+        match s {
+            "checkpoint" => MlArtifactKind::Checkpoint,
+            "dataset" => MlArtifactKind::Dataset,
+            "runs" => MlArtifactKind::Runs,
+            _ => MlArtifactKind::Environment,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct MlArtifact {
+    pub kind: MlArtifactKind,
+    /// Relative to the project
+    pub path: String,
+    pub bytes: i64,
+}
+
+pub struct MlAnalyzer;
+
+impl Analyzer for MlAnalyzer {
+    fn name(&self) -> &'static str {
+        "ml"
+    }
+
+    fn analyze(&self, root: &Path, ctx: &AnalyzerContext<'_>) -> Result<AnalyzerOutput> {
+        if !ctx.cfg.ml.types.iter().any(|t| t == ctx.project_type) {
+            return Ok(AnalyzerOutput::Skipped);
+        }
+        Ok(AnalyzerOutput::MlArtifacts(detect_ml_artifacts(
+            root,
+            &ctx.cfg.ml,
+        )))
+    }
+}
+
+/// ML artifacts under `root`, largest first.
+pub fn detect_ml_artifacts(root: &Path, cfg: &MlConfig) -> Vec<MlArtifact> {
+    let min_file = cfg.min_file_mb as i64 * 1024 * 1024;
+    let min_env = cfg.min_env_mb as i64 * 1024 * 1024;
+    let mut out = Vec::new();
+    let mut walk = WalkDir::new(root)
+        .follow_links(false)
+        .max_depth(cfg.max_depth)
+        .into_iter();
+    while let Some(entry) = walk.next() {
+        crate::nice::pace();
+        let Ok(entry) = entry else { continue };
+        let path = entry.path();
+        let rel = || {
+            path.strip_prefix(root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/")
+        };
+        if entry.file_type().is_dir() {
+            if entry.depth() == 0 {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy();
+            // Before the artifact check: `.venv` and `venv` are artifacts too
+            let kind = if path.join("pyvenv.cfg").is_file() || path.join("conda-meta").is_dir() {
+                Some(MlArtifactKind::Environment)
+            } else if name == ".git" || ARTIFACT_DIRS.contains(&name.as_ref()) {
+                walk.skip_current_dir();
+                continue;
+            } else if is_dataset_dir(entry.depth(), &name, path) {
+                Some(MlArtifactKind::Dataset)
+            } else if RUN_DIRS.contains(&name.as_ref()) {
+                Some(MlArtifactKind::Runs)
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                walk.skip_current_dir();
+                let bytes = dir_bytes(path);
+                if kind != MlArtifactKind::Environment || bytes >= min_env {
+                    out.push(MlArtifact {
+                        kind,
+                        path: rel(),
+                        bytes,
+                    });
+                }
+            }
+            continue;
+        }
+        if !entry.file_type().is_file() || !is_checkpoint(path) {
+            continue;
+        }
+        let bytes = entry.metadata().map_or(0, |md| md.len() as i64);
+        if bytes >= min_file {
+            out.push(MlArtifact {
+                kind: MlArtifactKind::Checkpoint,
+                path: rel(),
+                bytes,
+            });
+        }
+    }
+    out.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.path.cmp(&b.path)));
+    out
+}
+
+/// `datasets/` at the project root, unless it is a Python package (`src/datasets/` or a
+/// root-level `datasets/__init__.py` holds loaders, not data).
+fn is_dataset_dir(depth: usize, name: &str, path: &Path) -> bool {
+    depth == 1 && DATASET_DIRS.contains(&name) && !path.join("__init__.py").is_file()
+}
+
+fn is_checkpoint(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()).is_some_and(|e| {
+        CHECKPOINT_EXTENSIONS
+            .iter()
+            .any(|c| c.eq_ignore_ascii_case(e))
+    })
+}
+
+/// Apparent size of every file under `dir`.
+fn dir_bytes(dir: &Path) -> i64 {
+    WalkDir::new(dir)
+        .follow_links(false)
+        .into_iter()
+        .flatten()
+        .filter(|e| e.file_type().is_file())
+        .filter_map(|e| e.metadata().ok())
+        .map(|md| md.len() as i64)
+        .sum()
+}
+
+impl Db {
+    /// Replace `project_id`'s ML artifacts; their total goes to `metrics.ml_bytes`.
+    pub fn replace_ml_artifacts(&self, project_id: i64, artifacts: &[MlArtifact]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM ml_artifacts WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for a in artifacts {
+            tx.execute(
+                "INSERT INTO ml_artifacts (project_id, kind, path, bytes) VALUES (?1, ?2, ?3, ?4)",
+                params![project_id, a.kind.as_str(), a.path, a.bytes],
+            )?;
+        }
+        tx.execute(
+            "UPDATE metrics SET ml_bytes = ?2 WHERE project_id = ?1",
+            params![project_id, artifacts.iter().map(|a| a.bytes).sum::<i64>()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// `project_id`'s ML artifacts, largest first.
+    pub fn ml_artifacts(&self, project_id: i64) -> Result<Vec<MlArtifact>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT kind, path, bytes FROM ml_artifacts WHERE project_id = ?1 \
+             ORDER BY bytes DESC, path",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |r| {
+                Ok(MlArtifact {
+                    kind: MlArtifactKind::parse(&r.get::<_, String>(0)?),
+                    path: r.get(1)?,
+                    bytes: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,79 @@
+//! Build output of iOS and Android projects that `ARTIFACT_DIRS` does not cover: Gradle
+//! module `build` directories below the project root, and Xcode's DerivedData, which lives
+//! under `~/Library/Developer/Xcode/DerivedData` rather than in the project.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Android module output, relative to the project: `build` and `.cxx` (native builds) in
+/// every directory with a Gradle build script.
+pub fn android_build_dirs(root: &Path) -> Vec<String> {
+    let mut out = Vec::new();
+    let Ok(entries) = fs::read_dir(root) else {
+        return out;
+    };
+    let mut modules: Vec<PathBuf> = entries
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.is_dir())
+        .filter(|p| p.join("build.gradle").is_file() || p.join("build.gradle.kts").is_file())
+        .collect();
+    modules.sort();
+    for module in modules {
+        let name = module.file_name().unwrap_or_default().to_string_lossy();
+        for sub in ["build", ".cxx"] {
+            if module.join(sub).is_dir() {
+                out.push(format!("{name}/{sub}"));
+            }
+        }
+    }
+    out
+}
+
+/// Where Xcode keeps DerivedData by default.
+pub fn default_derived_data_dir() -> Option<PathBuf> {
+    dirs_next::home_dir().map(|home| home.join("Library/Developer/Xcode/DerivedData"))
+}
+
+/// DerivedData folders under `derived_data` built from a project or workspace in `root`.
+/// Each `<Name>-<hash>` folder records the workspace it belongs to in its `info.plist`.
+pub fn xcode_derived_data(derived_data: &Path, root: &Path) -> Vec<PathBuf> {
+    derived_data_by_project(derived_data)
+        .remove(root)
+        .unwrap_or_default()
+}
+
+/// Every DerivedData folder under `derived_data`, by the directory holding the project or
+/// workspace it was built from. Reading each `info.plist` is the slow part, so a scan does
+/// this once rather than per project.
+pub fn derived_data_by_project(derived_data: &Path) -> HashMap<PathBuf, Vec<PathBuf>> {
+    let mut out: HashMap<PathBuf, Vec<PathBuf>> = HashMap::new();
+    let Ok(entries) = fs::read_dir(derived_data) else {
+        return out;
+    };
+    for dir in entries.flatten().map(|e| e.path()) {
+        let Some(ws) = workspace_path(&dir.join("info.plist")) else {
+            continue;
+        };
+        if let Some(project) = Path::new(&ws).parent() {
+            out.entry(project.to_path_buf()).or_default().push(dir);
+        }
+    }
+    for dirs in out.values_mut() {
+        dirs.sort();
+    }
+    out
+}
+
+/// `WorkspacePath` from a DerivedData `info.plist` (XML form).
+fn workspace_path(plist: &Path) -> Option<String> {
+    let text = fs::read_to_string(plist).ok()?;
+    let after_key = text.split_once("<key>WorkspacePath</key>")?.1;
+    let value = after_key
+        .split_once("<string>")?
+        .1
+        .split_once("</string>")?
+        .0;
+    Some(value.trim().to_string())
+}
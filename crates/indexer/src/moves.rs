@@ -0,0 +1,149 @@
+//! Move and rename detection. A project found at a path the index has never seen is matched
+//! against indexed projects whose directory has disappeared, by git remote or by a
+//! fingerprint of its top-level files, and the existing row is repointed instead of a
+//! duplicate being inserted, so fields, notes and open history carry over.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::db::{Db, ProjectIdentity};
+use crate::vcs::remote_url;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProjectMove {
+    pub project_id: i64,
+    pub from: String,
+    pub to: String,
+    pub matched_by: MatchedBy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedBy {
+    /// Same git remote URL
+    Remote,
+    /// Same top-level file names and sizes
+    Fingerprint,
+}
+
+impl MatchedBy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MatchedBy::Remote => "remote",
+            MatchedBy::Fingerprint => "fingerprint",
+        }
+    }
+}
+
+/// Tracks vanished projects over one scan; they are looked up the first time an unknown
+/// path turns up, so scans that find nothing new never stat the whole index.
+#[derive(Default)]
+pub(crate) struct MoveDetector {
+    /// Indexed projects whose path no longer exists
+    vanished: Option<Vec<ProjectIdentity>>,
+    moves: Vec<ProjectMove>,
+}
+
+impl MoveDetector {
+    /// If `path` is new to the index and matches exactly one vanished project, repoint that
+    /// project's row at `path`. Ambiguous matches (several clones of one remote, say) are
+    /// left alone and `path` is indexed as a new project.
+    pub(crate) fn detect(
+        &mut self,
+        db: &Db,
+        path: &Path,
+        name: &str,
+        git: bool,
+        fingerprint: Option<&str>,
+    ) -> Result<Option<&ProjectMove>> {
+        let path_str = path.to_string_lossy();
+        if db.project_id_by_path(&path_str)?.is_some() {
+            return Ok(None);
+        }
+        let vanished = match &mut self.vanished {
+            Some(v) => v,
+            None => self.vanished.insert(
+                db.project_identities()?
+                    .into_iter()
+                    .filter(|p| match &p.path_bytes {
+                        Some(bytes) => !crate::paths::from_raw_bytes(bytes).exists(),
+                        None => !Path::new(&p.path).exists(),
+                    })
+                    .collect(),
+            ),
+        };
+        if vanished.is_empty() {
+            return Ok(None);
+        }
+
+        let unique = |pred: &dyn Fn(&ProjectIdentity) -> bool| {
+            let mut hits = vanished.iter().enumerate().filter(|(_, v)| pred(v));
+            match (hits.next(), hits.next()) {
+                (Some((i, _)), None) => Some(i),
+                _ => None,
+            }
+        };
+        let remote = if git { remote_url(path) } else { None };
+        let found = remote
+            .as_deref()
+            .and_then(|r| unique(&|v| v.remote_url.as_deref() == Some(r)))
+            .map(|i| (i, MatchedBy::Remote))
+            .or_else(|| {
+                let fp = fingerprint?;
+                unique(&|v| v.fingerprint.as_deref() == Some(fp))
+                    .map(|i| (i, MatchedBy::Fingerprint))
+            });
+        let Some((i, matched_by)) = found else {
+            return Ok(None);
+        };
+
+        let old = vanished.remove(i);
+        db.move_project(old.id, name, &path_str)?;
+        tracing::info!(from = %old.path, to = %path_str, by = matched_by.as_str(), "project moved");
+        self.moves.push(ProjectMove {
+            project_id: old.id,
+            from: old.path,
+            to: path_str.into_owned(),
+            matched_by,
+        });
+        Ok(self.moves.last())
+    }
+
+    pub(crate) fn into_moves(self) -> Vec<ProjectMove> {
+        self.moves
+    }
+}
+
+/// Stable hash of a directory's top-level file names and sizes, which survive a move
+/// unchanged. `None` for directories without files.
+pub fn fingerprint(dir: &Path) -> Option<String> {
+    let mut files: Vec<(String, u64)> = fs::read_dir(dir)
+        .ok()?
+        .flatten()
+        .filter_map(|e| {
+            let md = e.metadata().ok()?;
+            md.is_file()
+                .then(|| (e.file_name().to_string_lossy().into_owned(), md.len()))
+        })
+        .collect();
+    if files.is_empty() {
+        return None;
+    }
+    files.sort();
+    // FNV-1a: fixed across releases, unlike std's hasher, since fingerprints are stored
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let mut feed = |bytes: &[u8]| {
+        for b in bytes {
+            hash ^= *b as u64;
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    };
+    for (name, len) in &files {
+        feed(name.as_bytes());
+        feed(&[0]);
+        feed(&len.to_le_bytes());
+    }
+    Some(format!("{hash:016x}"))
+}
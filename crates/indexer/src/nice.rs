@@ -0,0 +1,119 @@
+//! Low-impact scans (`ScanOptions::nice`): directory walking and file stats are paced by a
+//! token bucket, and the scanning thread drops to background CPU and IO priority where the
+//! platform allows, so a scan in the background does not spin the fans or slow a build.
+//!
+//! The walks call [`pace`] once per entry; it does nothing unless a [`NiceScan`] is active
+//! on the calling thread.
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+/// Shortest sleep once the bucket is empty, so pacing costs a few syscalls per second
+/// rather than one per entry
+const MIN_SLEEP: Duration = Duration::from_millis(50);
+
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    fn new(ops_per_sec: u32) -> Self {
+        let rate = f64::from(ops_per_sec.max(1));
+        let burst = (rate * MIN_SLEEP.as_secs_f64()).max(1.0);
+        Self {
+            rate,
+            burst,
+            tokens: burst,
+            last: Instant::now(),
+        }
+    }
+
+    fn take(&mut self) {
+        let now = Instant::now();
+        self.tokens =
+            (self.tokens + now.duration_since(self.last).as_secs_f64() * self.rate).min(self.burst);
+        self.last = now;
+        if self.tokens < 1.0 {
+            let wait = Duration::from_secs_f64((self.burst - self.tokens) / self.rate);
+            std::thread::sleep(wait.max(MIN_SLEEP));
+            self.tokens = self.burst;
+            self.last = Instant::now();
+        }
+        self.tokens -= 1.0;
+    }
+}
+
+thread_local! {
+    static BUCKET: RefCell<Option<TokenBucket>> = const { RefCell::new(None) };
+}
+
+/// Wait for the next walk or stat when a nice scan is running on this thread.
+pub fn pace() {
+    BUCKET.with(|b| {
+        if let Some(bucket) = b.borrow_mut().as_mut() {
+            bucket.take();
+        }
+    });
+}
+
+/// Paces this thread's walks until dropped. Entering also lowers the thread's CPU and IO
+/// priority for the rest of its life: unprivileged processes cannot raise it again.
+pub struct NiceScan {
+    previous: Option<TokenBucket>,
+}
+
+impl NiceScan {
+    pub fn enter(ops_per_sec: u32) -> Self {
+        lower_thread_priority();
+        let previous = BUCKET.with(|b| b.replace(Some(TokenBucket::new(ops_per_sec))));
+        Self { previous }
+    }
+}
+
+impl Drop for NiceScan {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        BUCKET.with(|b| *b.borrow_mut() = previous);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn lower_thread_priority() {
+    /// `ioprio_set(IOPRIO_WHO_PROCESS, 0, IOPRIO_CLASS_IDLE)`: who 0 is the calling thread
+    const IOPRIO_WHO_PROCESS: libc::c_long = 1;
+    const IOPRIO_CLASS_IDLE: libc::c_long = 3;
+    const IOPRIO_CLASS_SHIFT: libc::c_long = 13;
+    // SAFETY: plain syscalls on the calling thread with constant arguments
+    let (cpu, io) = unsafe {
+        (
+            libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, 10),
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0,
+                IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            ),
+        )
+    };
+    if cpu != 0 || io != 0 {
+        tracing::debug!(cpu, io, "could not lower scan thread priority");
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn lower_thread_priority() {
+    // Background band: lowest CPU priority and throttled disk IO for this thread
+    // SAFETY: plain syscall on the calling thread with constant arguments
+    let rc = unsafe { libc::setpriority(libc::PRIO_DARWIN_THREAD, 0, libc::PRIO_DARWIN_BG) };
+    if rc != 0 {
+        tracing::debug!(rc, "could not lower scan thread priority");
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn lower_thread_priority() {
+    tracing::debug!("thread priority is left unchanged on this platform");
+}
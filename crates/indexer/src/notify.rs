@@ -0,0 +1,162 @@
+//! Notable changes between the index before and after a scan (new projects, big size jumps,
+//! repos that turned dirty, new retention policy violations), which the app turns into desktop
+//! notifications.
+
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use crate::config::NotificationsConfig;
+use crate::db::{Db, ProjectFilter, SortKey};
+
+/// Notifications list at most this many project names before summarising the rest
+const MAX_NAMES: usize = 3;
+
+#[derive(Debug, Clone)]
+struct ProjectState {
+    size_bytes: Option<i64>,
+    git_dirty: Option<bool>,
+}
+
+/// Per-project state taken before a scan, to diff against afterwards.
+#[derive(Debug, Clone, Default)]
+pub struct IndexSnapshot {
+    projects: HashMap<i64, ProjectState>,
+    /// `(policy, project id)`
+    violations: HashSet<(String, i64)>,
+}
+
+impl IndexSnapshot {
+    pub fn take(db: &Db) -> Result<Self> {
+        let mut projects = HashMap::new();
+        db.stream_projects(&ProjectFilter::default(), SortKey::Name, true, None, |p| {
+            projects.insert(
+                p.id,
+                ProjectState {
+                    size_bytes: p.size_bytes,
+                    git_dirty: p.git_dirty,
+                },
+            );
+            Ok(())
+        })?;
+        Ok(Self {
+            projects,
+            violations: db.violation_keys()?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScanEvent {
+    Completed { projects: usize },
+    NewProjects { names: Vec<String> },
+    Grew { name: String, grown_bytes: i64 },
+    BecameDirty { names: Vec<String> },
+    PolicyViolated { policy: String, names: Vec<String> },
+}
+
+impl ScanEvent {
+    pub fn title(&self) -> String {
+        match self {
+            ScanEvent::Completed { .. } => "Scan finished".into(),
+            ScanEvent::NewProjects { names } => match names.len() {
+                1 => "New project found".into(),
+                n => format!("{n} new projects found"),
+            },
+            ScanEvent::Grew { name, .. } => format!("{name} grew"),
+            ScanEvent::BecameDirty { names } => match names.len() {
+                1 => "Uncommitted changes".into(),
+                n => format!("{n} repos have uncommitted changes"),
+            },
+            ScanEvent::PolicyViolated { policy, .. } => format!("Retention policy: {policy}"),
+        }
+    }
+
+    pub fn body(&self) -> String {
+        match self {
+            ScanEvent::Completed { projects } => format!("{projects} project(s) indexed"),
+            ScanEvent::NewProjects { names }
+            | ScanEvent::BecameDirty { names }
+            | ScanEvent::PolicyViolated { names, .. } => name_list(names),
+            ScanEvent::Grew { grown_bytes, .. } => {
+                format!(
+                    "+{:.1} GB since the last scan",
+                    *grown_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+                )
+            }
+        }
+    }
+}
+
+fn name_list(names: &[String]) -> String {
+    if names.len() <= MAX_NAMES {
+        return names.join(", ");
+    }
+    format!(
+        "{} and {} more",
+        names[..MAX_NAMES].join(", "),
+        names.len() - MAX_NAMES
+    )
+}
+
+/// Events worth a notification for a scan that indexed `projects` projects, comparing the
+/// index now with `before`. Disabled event kinds are left out.
+pub fn notable_changes(
+    db: &Db,
+    before: &IndexSnapshot,
+    projects: usize,
+    cfg: &NotificationsConfig,
+) -> Result<Vec<ScanEvent>> {
+    let mut new = Vec::new();
+    let mut grew = Vec::new();
+    let mut dirty = Vec::new();
+    db.stream_projects(&ProjectFilter::default(), SortKey::Name, true, None, |p| {
+        let Some(old) = before.projects.get(&p.id) else {
+            new.push(p.name);
+            return Ok(());
+        };
+        if let (Some(was), Some(now)) = (old.size_bytes, p.size_bytes) {
+            if now - was >= cfg.size_growth_bytes {
+                grew.push(ScanEvent::Grew {
+                    name: p.name.clone(),
+                    grown_bytes: now - was,
+                });
+            }
+        }
+        if old.git_dirty == Some(false) && p.git_dirty == Some(true) {
+            dirty.push(p.name);
+        }
+        Ok(())
+    })?;
+
+    let mut events = Vec::new();
+    if cfg.scan_complete {
+        events.push(ScanEvent::Completed { projects });
+    }
+    // The very first scan finds everything; that is what `scan_complete` is for
+    if cfg.new_projects && !new.is_empty() && !before.projects.is_empty() {
+        events.push(ScanEvent::NewProjects { names: new });
+    }
+    if cfg.size_growth {
+        events.extend(grew);
+    }
+    if cfg.became_dirty && !dirty.is_empty() {
+        events.push(ScanEvent::BecameDirty { names: dirty });
+    }
+    if cfg.policy_violations {
+        let mut violated: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        for v in db.policy_violations()? {
+            if !before
+                .violations
+                .contains(&(v.policy.clone(), v.project.id))
+            {
+                violated.entry(v.policy).or_default().push(v.project.name);
+            }
+        }
+        events.extend(
+            violated
+                .into_iter()
+                .map(|(policy, names)| ScanEvent::PolicyViolated { policy, names }),
+        );
+    }
+    Ok(events)
+}
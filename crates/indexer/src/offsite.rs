@@ -0,0 +1,223 @@
+//! Whether a project would survive losing this disk: covered by a backup (Time Machine, an
+//! rclone sync, a synced folder such as Dropbox) or pushed to a git remote. `audit
+//! unbacked-up` lists the ones with work that exists nowhere else.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::OffsiteConfig;
+use crate::db::{Db, ProjectRecord};
+
+/// Last backup check per project; `backed_up_by` is NULL when no backup covers it.
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS backup_status (
+      project_id INTEGER PRIMARY KEY,
+      backed_up_by TEXT,
+      checked_at INTEGER NOT NULL DEFAULT (strftime('%s','now')),
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+"#;
+
+/// Paths passed to one `tmutil isexcluded` call
+const TMUTIL_BATCH: usize = 64;
+
+/// A project whose work exists only on this disk.
+#[derive(Debug, Clone, Serialize)]
+pub struct Unbacked {
+    pub project: ProjectRecord,
+    /// e.g. "uncommitted changes", "3 unpushed commits", "no git remote"
+    pub reasons: Vec<String>,
+}
+
+/// What backs up `path` among the configured rclone syncs and synced folders, if anything.
+pub fn covering_location(path: &Path, cfg: &OffsiteConfig) -> Option<String> {
+    let expand = |p: &Path| PathBuf::from(shellexpand::tilde(&p.to_string_lossy()).as_ref());
+    for sync in &cfg.rclone {
+        if path.starts_with(expand(&sync.local)) {
+            return Some(format!("rclone:{}", sync.remote));
+        }
+    }
+    for dir in &cfg.synced_dirs {
+        let dir = expand(dir);
+        if path.starts_with(&dir) {
+            return Some(format!("synced:{}", dir.display()));
+        }
+    }
+    None
+}
+
+/// Paths among `paths` that Time Machine backs up: a destination is configured and the
+/// path is not excluded. Empty off macOS or when `tmutil` is unavailable.
+fn time_machine_included(paths: &[&str]) -> Vec<String> {
+    if !cfg!(target_os = "macos") || paths.is_empty() {
+        return Vec::new();
+    }
+    let configured = Command::new("tmutil")
+        .arg("destinationinfo")
+        .output()
+        .is_ok_and(|o| o.status.success());
+    if !configured {
+        return Vec::new();
+    }
+    let mut included = Vec::new();
+    for batch in paths.chunks(TMUTIL_BATCH) {
+        let Ok(out) = Command::new("tmutil")
+            .arg("isexcluded")
+            .args(batch)
+            .output()
+        else {
+            continue;
+        };
+        // One "[Included]    /path" or "[Excluded]    /path" line per path
+        for line in String::from_utf8_lossy(&out.stdout).lines() {
+            if let Some(path) = line.strip_prefix("[Included]") {
+                included.push(path.trim().to_string());
+            }
+        }
+    }
+    included
+}
+
+/// A project's git state as stored by scans.
+pub(crate) struct RemoteState {
+    pub is_git_repo: bool,
+    /// A `git_info` row exists; scans without the `git` feature write none
+    pub scanned: bool,
+    pub remote_url: Option<String>,
+    pub dirty: Option<bool>,
+    pub ahead: Option<i64>,
+}
+
+/// Columns for `RemoteState::from_row`, with `git_info` joined as `g`
+pub(crate) const REMOTE_STATE_COLUMNS: &str =
+    "p.is_git_repo, g.project_id IS NOT NULL, g.remote_url, g.dirty, g.ahead";
+
+impl RemoteState {
+    pub(crate) fn from_row(r: &rusqlite::Row<'_>, first: usize) -> rusqlite::Result<Self> {
+        Ok(Self {
+            is_git_repo: r.get(first)?,
+            scanned: r.get(first + 1)?,
+            remote_url: r.get(first + 2)?,
+            dirty: r.get(first + 3)?,
+            ahead: r.get(first + 4)?,
+        })
+    }
+
+    /// Why some of the work is not on a git remote; empty when everything is pushed.
+    pub(crate) fn gaps(&self) -> Vec<String> {
+        if !self.is_git_repo {
+            return vec!["not under version control".to_string()];
+        }
+        if !self.scanned {
+            return vec!["git status unknown".to_string()];
+        }
+        let mut gaps = Vec::new();
+        if self.remote_url.is_none() {
+            gaps.push("no git remote".to_string());
+        }
+        if self.dirty == Some(true) {
+            gaps.push("uncommitted changes".to_string());
+        }
+        match self.ahead {
+            Some(n) if n > 0 => gaps.push(format!(
+                "{n} unpushed commit{}",
+                if n == 1 { "" } else { "s" }
+            )),
+            Some(_) => {}
+            None if self.remote_url.is_some() => gaps.push("branch has no upstream".to_string()),
+            None => {}
+        }
+        gaps
+    }
+}
+
+impl Db {
+    /// Work out which backup covers each project and store it. Returns how many are
+    /// covered.
+    pub fn check_backups(&self, cfg: &OffsiteConfig) -> Result<usize> {
+        let projects: Vec<(i64, String)> = {
+            let mut stmt = self.conn.prepare("SELECT id, path FROM projects")?;
+            let rows = stmt
+                .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let time_machine = if cfg.time_machine {
+            let paths: Vec<&str> = projects.iter().map(|(_, p)| p.as_str()).collect();
+            time_machine_included(&paths)
+        } else {
+            Vec::new()
+        };
+
+        let tx = self.conn.unchecked_transaction()?;
+        let mut covered = 0;
+        for (id, path) in &projects {
+            let by = covering_location(Path::new(path), cfg).or_else(|| {
+                time_machine
+                    .contains(path)
+                    .then(|| "time-machine".to_string())
+            });
+            covered += usize::from(by.is_some());
+            tx.execute(
+                "INSERT INTO backup_status (project_id, backed_up_by, checked_at) \
+                 VALUES (?1, ?2, strftime('%s','now')) \
+                 ON CONFLICT(project_id) DO UPDATE SET \
+                   backed_up_by = excluded.backed_up_by, checked_at = excluded.checked_at",
+                params![id, by],
+            )?;
+        }
+        tx.commit()?;
+        Ok(covered)
+    }
+
+    /// The backup last found covering `project_id`; None when none does or it was never
+    /// checked.
+    pub fn backed_up_by(&self, project_id: i64) -> Result<Option<String>> {
+        let by = self
+            .conn
+            .query_row(
+                "SELECT backed_up_by FROM backup_status WHERE project_id = ?1",
+                params![project_id],
+                |r| r.get(0),
+            )
+            .or_else(|e| match e {
+                rusqlite::Error::QueryReturnedNoRows => Ok(None),
+                e => Err(e),
+            })?;
+        Ok(by)
+    }
+
+    /// Projects no backup covers that also have work missing from any git remote:
+    /// uncommitted changes, unpushed commits, or no remote at all. Most recently edited
+    /// first. Run `check_backups` first for current backup coverage.
+    pub fn unbacked_up(&self) -> Result<Vec<Unbacked>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT p.id, {REMOTE_STATE_COLUMNS} FROM projects p \
+             LEFT JOIN git_info g ON g.project_id = p.id \
+             LEFT JOIN backup_status b ON b.project_id = p.id \
+             LEFT JOIN metrics m ON m.project_id = p.id \
+             WHERE b.backed_up_by IS NULL \
+             ORDER BY m.last_edited_at DESC, p.name"
+        ))?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok((r.get::<_, i64>(0)?, RemoteState::from_row(r, 1)?))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut out = Vec::new();
+        for (id, state) in rows {
+            let reasons = state.gaps();
+            if reasons.is_empty() {
+                continue;
+            }
+            if let Some(project) = self.project_by_id(id)? {
+                out.push(Unbacked { project, reasons });
+            }
+        }
+        Ok(out)
+    }
+}
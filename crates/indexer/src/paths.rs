@@ -0,0 +1,132 @@
+//! The one form project paths are stored in, so the same directory reached through a
+//! symlink, with a trailing separator or in a different Unicode form maps to one row.
+
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::db::Db;
+
+/// Absolute path with symlinks resolved and no trailing separator; on macOS, names are
+/// NFC-normalized. Paths that cannot be resolved (e.g. gone from disk) are only made
+/// absolute and trimmed.
+pub fn canonical(path: &Path) -> PathBuf {
+    let resolved = dunce::canonicalize(path).unwrap_or_else(|_| {
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            std::env::current_dir()
+                .map(|cwd| cwd.join(path))
+                .unwrap_or_else(|_| path.to_path_buf())
+        }
+    });
+    match resolved.to_str() {
+        Some(s) => PathBuf::from(normalize_str(s)),
+        // Names that are not valid UTF-8 are kept byte for byte, see [`raw_bytes`]
+        None => resolved,
+    }
+}
+
+/// The bytes of a path that is not valid UTF-8. `projects.path` holds such paths lossily
+/// (for display and lookups), so these are stored next to it to find the directory again.
+/// None for every other path, and always on Windows.
+pub fn raw_bytes(path: &Path) -> Option<Vec<u8>> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        path.to_str()
+            .is_none()
+            .then(|| path.as_os_str().as_bytes().to_vec())
+    }
+    #[cfg(not(unix))]
+    {
+        let _ = path;
+        None
+    }
+}
+
+/// The path [`raw_bytes`] returned the bytes of.
+pub fn from_raw_bytes(bytes: &[u8]) -> PathBuf {
+    #[cfg(unix)]
+    {
+        use std::os::unix::ffi::OsStrExt;
+        PathBuf::from(std::ffi::OsStr::from_bytes(bytes))
+    }
+    #[cfg(not(unix))]
+    {
+        PathBuf::from(String::from_utf8_lossy(bytes).into_owned())
+    }
+}
+
+/// [`canonical`] as the string stored in `projects.path`.
+pub fn canonical_string(path: &Path) -> String {
+    canonical(path).to_string_lossy().into_owned()
+}
+
+/// `path` without trailing separators, keeping bare roots (`/`, `C:\`) intact.
+pub(crate) fn trim_separators(path: &str) -> &str {
+    let t = path.trim_end_matches(['/', '\\']);
+    if t.is_empty() || t.ends_with(':') {
+        path
+    } else {
+        t
+    }
+}
+
+fn normalize_str(s: &str) -> String {
+    let trimmed = trim_separators(s);
+    #[cfg(target_os = "macos")]
+    {
+        use unicode_normalization::UnicodeNormalization;
+        trimmed.nfc().collect()
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        trimmed.to_string()
+    }
+}
+
+impl Db {
+    /// Rewrite stored paths into [`canonical`] form. Records that turn out to be the same
+    /// directory are merged into the most recently updated one (undoable like any merge).
+    /// Returns the number of rows rewritten or merged away.
+    pub fn canonicalize_paths(&self) -> Result<usize> {
+        let rows = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, path FROM projects ORDER BY updated_at DESC, id DESC")?;
+            let rows = stmt
+                .query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let mut kept: HashMap<String, i64> = HashMap::new();
+        let mut renames = Vec::new();
+        let mut changed = 0;
+        for (id, path) in rows {
+            let canon = canonical_string(Path::new(&path));
+            match kept.get(&canon) {
+                Some(&keep_id) => {
+                    self.merge_projects(keep_id, id)?;
+                    changed += 1;
+                }
+                None => {
+                    kept.insert(canon.clone(), id);
+                    if canon != path {
+                        renames.push((id, canon));
+                    }
+                }
+            }
+        }
+        // After the merges, so no other row still holds a target path
+        for (id, canon) in renames {
+            self.conn.execute(
+                "UPDATE projects SET path = ?2 WHERE id = ?1",
+                params![id, canon],
+            )?;
+            changed += 1;
+        }
+        Ok(changed)
+    }
+}
@@ -0,0 +1,118 @@
+//! Retention policies (`policies` in config): each is a filter expression such as
+//! `edited>18mo AND size>5gb` ("untouched for 18 months and over 5 GB"), checked after every
+//! scan. Projects matching one are kept in `policy_violations` for `audit policies`, and the
+//! app notifies about projects that newly match.
+
+use anyhow::{Context, Result};
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use time::OffsetDateTime;
+
+use crate::config::PolicyConfig;
+use crate::db::{Db, ProjectFilter, ProjectRecord, SortKey};
+use crate::query::Query;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS policy_violations (
+      policy TEXT NOT NULL,
+      project_id INTEGER NOT NULL,
+      -- When the project started matching; kept for as long as it keeps matching
+      first_seen_at INTEGER NOT NULL,
+      PRIMARY KEY(policy, project_id),
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+"#;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyViolation {
+    pub policy: String,
+    pub first_seen_at: i64,
+    pub project: ProjectRecord,
+}
+
+impl Db {
+    /// Check every project against `policies` and record the result: new matches are added,
+    /// projects that no longer match (and policies no longer configured) are dropped.
+    /// Returns the number of violations. Fails before changing anything when a policy's query
+    /// does not parse.
+    pub fn evaluate_policies(&self, policies: &BTreeMap<String, PolicyConfig>) -> Result<usize> {
+        let parsed = policies
+            .iter()
+            .map(|(name, policy)| {
+                let query = Query::parse(&policy.query)
+                    .with_context(|| format!("retention policy {name:?}"))?;
+                Ok((name.as_str(), query))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut matching: HashSet<(String, i64)> = HashSet::new();
+        for (name, query) in parsed {
+            let filter = ProjectFilter {
+                query: Some(query),
+                ..Default::default()
+            };
+            self.stream_projects(&filter, SortKey::Name, true, None, |p| {
+                matching.insert((name.to_string(), p.id));
+                Ok(())
+            })?;
+        }
+
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let tx = self.conn.unchecked_transaction()?;
+        for key in self.violation_keys()?.difference(&matching) {
+            tx.execute(
+                "DELETE FROM policy_violations WHERE policy = ?1 AND project_id = ?2",
+                params![key.0, key.1],
+            )?;
+        }
+        for (policy, project_id) in &matching {
+            tx.execute(
+                "INSERT OR IGNORE INTO policy_violations (policy, project_id, first_seen_at) \
+                 VALUES (?1, ?2, ?3)",
+                params![policy, project_id, now],
+            )?;
+        }
+        tx.commit()?;
+        Ok(matching.len())
+    }
+
+    /// Recorded violations by policy, longest-standing first.
+    pub fn policy_violations(&self) -> Result<Vec<PolicyViolation>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT v.policy, v.first_seen_at, v.project_id FROM policy_violations v \
+             JOIN projects p ON p.id = v.project_id \
+             ORDER BY v.policy, v.first_seen_at, p.name",
+        )?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok((
+                    r.get::<_, String>(0)?,
+                    r.get::<_, i64>(1)?,
+                    r.get::<_, i64>(2)?,
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut out = Vec::new();
+        for (policy, first_seen_at, id) in rows {
+            if let Some(project) = self.project_by_id(id)? {
+                out.push(PolicyViolation {
+                    policy,
+                    first_seen_at,
+                    project,
+                });
+            }
+        }
+        Ok(out)
+    }
+
+    /// `(policy, project id)` of every recorded violation.
+    pub(crate) fn violation_keys(&self) -> Result<HashSet<(String, i64)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT policy, project_id FROM policy_violations")?;
+        let rows = stmt
+            .query_map([], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<HashSet<_>, _>>()?;
+        Ok(rows)
+    }
+}
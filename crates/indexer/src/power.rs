@@ -0,0 +1,105 @@
+//! Power state for deferring scheduled scans (`power` config): on battery below a threshold
+//! or in a power-save mode, the `watch` daemon's periodic rounds wait. Scans someone asks
+//! for (`scan`, `daemon rescan`, the app's Rescan Now) always run.
+//!
+//! Read from `/sys/class/power_supply` and `/sys/firmware/acpi/platform_profile` on Linux
+//! and `pmset` on macOS; elsewhere the machine always counts as plugged in.
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::PowerConfig;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PowerStatus {
+    pub on_battery: bool,
+    /// Charge of the internal battery, when there is one
+    pub battery_percent: Option<u8>,
+    /// Low Power Mode (macOS) or the `low-power` platform profile (Linux)
+    pub power_save: bool,
+}
+
+/// Why a scheduled scan should wait under `status`, or None to go ahead.
+pub fn defer_reason(cfg: &PowerConfig, status: &PowerStatus) -> Option<String> {
+    if cfg.defer_in_power_save && status.power_save {
+        return Some("power-save mode is on".into());
+    }
+    if !cfg.defer_on_battery || !status.on_battery {
+        return None;
+    }
+    match status.battery_percent {
+        Some(pct) if pct < cfg.min_battery_percent => Some(format!(
+            "on battery at {pct}% (below {}%)",
+            cfg.min_battery_percent
+        )),
+        None if cfg.min_battery_percent > 0 => Some("on battery".into()),
+        _ => None,
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn power_status() -> PowerStatus {
+    use std::fs;
+    let read = |p: &std::path::Path| {
+        fs::read_to_string(p)
+            .map(|s| s.trim().to_string())
+            .unwrap_or_default()
+    };
+    let mut status = PowerStatus::default();
+    let mut mains_online = false;
+    let mut discharging = false;
+    for supply in fs::read_dir("/sys/class/power_supply")
+        .into_iter()
+        .flatten()
+        .flatten()
+    {
+        let dir = supply.path();
+        // Mice, keyboards and phones report batteries too, with scope "Device"
+        if read(&dir.join("scope")) == "Device" {
+            continue;
+        }
+        match read(&dir.join("type")).as_str() {
+            "Mains" | "USB" => mains_online |= read(&dir.join("online")) == "1",
+            "Battery" => {
+                discharging |= read(&dir.join("status")) == "Discharging";
+                status.battery_percent = read(&dir.join("capacity")).parse().ok();
+            }
+            _ => {}
+        }
+    }
+    status.on_battery = discharging && !mains_online;
+    status.power_save =
+        read(std::path::Path::new("/sys/firmware/acpi/platform_profile")) == "low-power";
+    status
+}
+
+#[cfg(target_os = "macos")]
+pub fn power_status() -> PowerStatus {
+    let pmset = |args: &[&str]| {
+        std::process::Command::new("pmset")
+            .args(args)
+            .output()
+            .ok()
+            .filter(|o| o.status.success())
+            .map(|o| String::from_utf8_lossy(&o.stdout).into_owned())
+            .unwrap_or_default()
+    };
+    // "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=…)\t35%; discharging; …"
+    let batt = pmset(&["-g", "batt"]);
+    let battery_percent = batt
+        .split(|c: char| c.is_whitespace() || c == ';')
+        .find_map(|word| word.strip_suffix('%')?.parse().ok());
+    let power_save = pmset(&["-g"]).lines().any(|line| {
+        let mut words = line.split_whitespace();
+        words.next() == Some("lowpowermode") && words.next() == Some("1")
+    });
+    PowerStatus {
+        on_battery: batt.contains("'Battery Power'"),
+        battery_percent,
+        power_save,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub fn power_status() -> PowerStatus {
+    PowerStatus::default()
+}
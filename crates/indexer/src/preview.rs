@@ -0,0 +1,146 @@
+//! What a scan would change (`scan --dry-run`): the projects a dry run found compared with
+//! the index, as projects it would add, fields it would update and rows it no longer finds.
+
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use crate::db::{Db, ProjectFilter, ProjectRecord, SortKey};
+
+/// One project a dry run found, with the values a real scan would store.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectPreview {
+    pub name: String,
+    pub path: String,
+    pub project_type: String,
+    pub is_git_repo: bool,
+    pub size_bytes: Option<i64>,
+    pub files_count: Option<i64>,
+    pub last_edited_at: Option<i64>,
+    pub artifact_bytes: Option<i64>,
+    /// Git enrichment; only with the `git` feature
+    pub branch: Option<String>,
+    pub remote_url: Option<String>,
+    pub git_dirty: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectUpdate {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanDiff {
+    pub added: Vec<ProjectPreview>,
+    pub updated: Vec<ProjectUpdate>,
+    /// Indexed under the scanned roots but not found. Scans keep these rows (`forget`
+    /// removes them); a project that moved shows up here and under `added`.
+    pub missing: Vec<ProjectRecord>,
+    pub unchanged: usize,
+}
+
+impl Db {
+    /// Compare a dry run's `found` projects under `roots` with the index.
+    pub fn scan_diff(&self, roots: &[PathBuf], found: &[ProjectPreview]) -> Result<ScanDiff> {
+        let mut diff = ScanDiff::default();
+        for preview in found {
+            match self
+                .find_project(&preview.path)?
+                .filter(|p| p.path == preview.path)
+            {
+                None => diff.added.push(preview.clone()),
+                Some(existing) => {
+                    let changes = field_changes(&existing, preview);
+                    if changes.is_empty() {
+                        diff.unchanged += 1;
+                    } else {
+                        diff.updated.push(ProjectUpdate {
+                            id: existing.id,
+                            name: preview.name.clone(),
+                            path: preview.path.clone(),
+                            changes,
+                        });
+                    }
+                }
+            }
+        }
+        let seen: HashSet<&str> = found.iter().map(|p| p.path.as_str()).collect();
+        let roots: Vec<PathBuf> = roots.iter().map(|r| crate::paths::canonical(r)).collect();
+        let indexed =
+            self.query_projects(&ProjectFilter::default(), SortKey::Name, true, 0, u32::MAX)?;
+        diff.missing = indexed
+            .into_iter()
+            .filter(|p| !seen.contains(p.path.as_str()))
+            .filter(|p| roots.iter().any(|r| PathBuf::from(&p.path).starts_with(r)))
+            .collect();
+        diff.missing.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(diff)
+    }
+}
+
+fn field_changes(old: &ProjectRecord, new: &ProjectPreview) -> Vec<FieldChange> {
+    let mut changes = Vec::new();
+    let mut compare = |field: &str, old: Value, new: Value| {
+        if old != new {
+            changes.push(FieldChange {
+                field: field.to_string(),
+                old,
+                new,
+            });
+        }
+    };
+    compare("name", old.name.as_str().into(), new.name.as_str().into());
+    compare(
+        "project_type",
+        old.project_type.as_deref().into(),
+        new.project_type.as_str().into(),
+    );
+    compare(
+        "is_git_repo",
+        old.is_git_repo.into(),
+        new.is_git_repo.into(),
+    );
+    compare("size_bytes", old.size_bytes.into(), new.size_bytes.into());
+    compare(
+        "files_count",
+        old.files_count.into(),
+        new.files_count.into(),
+    );
+    compare(
+        "last_edited_at",
+        old.last_edited_at.into(),
+        new.last_edited_at.into(),
+    );
+    compare(
+        "artifact_bytes",
+        old.artifact_bytes.into(),
+        new.artifact_bytes.into(),
+    );
+    // Without the `git` feature scans leave these columns alone
+    if cfg!(feature = "git") && new.is_git_repo {
+        compare(
+            "branch",
+            old.branch.as_deref().into(),
+            new.branch.as_deref().into(),
+        );
+        compare(
+            "remote_url",
+            old.remote_url.as_deref().into(),
+            new.remote_url.as_deref().into(),
+        );
+        compare("git_dirty", old.git_dirty.into(), new.git_dirty.into());
+    }
+    changes
+}
@@ -0,0 +1,136 @@
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::config::AppConfig;
+
+/// Whether a project has tests and CI configured.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct TestCiInfo {
+    pub has_tests: bool,
+    /// Files that look like tests by name or location
+    pub test_files_count: i64,
+    pub has_ci: bool,
+    /// Detected CI systems, e.g. ["github-actions", "gitlab-ci"]
+    pub ci_providers: Vec<String>,
+}
+
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec", "testing"];
+const TEST_CONFIGS: &[&str] = &[
+    "jest.config.js",
+    "jest.config.ts",
+    "jest.config.mjs",
+    "jest.config.cjs",
+    "vitest.config.ts",
+    "vitest.config.js",
+    "vitest.config.mts",
+    "karma.conf.js",
+    "pytest.ini",
+    "tox.ini",
+    "conftest.py",
+    "phpunit.xml",
+    ".rspec",
+];
+const TEST_SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "go", "py", "js", "jsx", "ts", "tsx", "mjs", "rb", "java", "kt", "cs", "php", "swift",
+];
+/// (provider, marker relative to the project root); directories count if non-empty
+const CI_MARKERS: &[(&str, &str)] = &[
+    ("github-actions", ".github/workflows"),
+    ("gitlab-ci", ".gitlab-ci.yml"),
+    ("circleci", ".circleci/config.yml"),
+    ("azure-pipelines", "azure-pipelines.yml"),
+    ("jenkins", "Jenkinsfile"),
+    ("travis", ".travis.yml"),
+    ("bitbucket-pipelines", "bitbucket-pipelines.yml"),
+    ("buildkite", ".buildkite"),
+    ("drone", ".drone.yml"),
+];
+
+pub fn detect_tests_ci(root: &Path, cfg: &AppConfig) -> TestCiInfo {
+    let mut info = TestCiInfo::default();
+
+    for (provider, marker) in CI_MARKERS {
+        let p = root.join(marker);
+        let present = if p.is_dir() {
+            fs::read_dir(&p)
+                .map(|mut rd| rd.next().is_some())
+                .unwrap_or(false)
+        } else {
+            p.is_file()
+        };
+        if present {
+            info.ci_providers.push(provider.to_string());
+        }
+    }
+    info.has_ci = !info.ci_providers.is_empty();
+
+    let has_config =
+        TEST_CONFIGS.iter().any(|f| root.join(f).is_file()) || package_json_has_jest(root);
+
+    let ignores = cfg.global_ignores.clone();
+    let walk = WalkBuilder::new(root)
+        .git_ignore(true)
+        .hidden(true)
+        .ignore(true)
+        .filter_entry(move |e| {
+            let is_dir = e.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+            !(is_dir
+                && e.file_name()
+                    .to_str()
+                    .map(|n| ignores.iter().any(|ign| ign == n))
+                    .unwrap_or(false))
+        })
+        .build();
+    for entry in walk.flatten() {
+        crate::nice::pace();
+        if !entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(root).unwrap_or(entry.path());
+        if is_test_file(rel) {
+            info.test_files_count += 1;
+        }
+    }
+    info.has_tests = has_config || info.test_files_count > 0;
+    info
+}
+
+fn package_json_has_jest(root: &Path) -> bool {
+    fs::read_to_string(root.join("package.json"))
+        .ok()
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .map(|v| v.get("jest").is_some())
+        .unwrap_or(false)
+}
+
+fn is_test_file(rel: &Path) -> bool {
+    let in_test_dir = rel
+        .parent()
+        .map(|parent| {
+            parent.components().any(|c| {
+                c.as_os_str()
+                    .to_str()
+                    .map(|s| TEST_DIRS.contains(&s))
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false);
+    let Some(name) = rel.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    let (stem, ext) = name.rsplit_once('.').unwrap_or((name, ""));
+    let is_source = TEST_SOURCE_EXTENSIONS.contains(&ext);
+    if !is_source {
+        return false;
+    }
+    in_test_dir
+        || stem.ends_with("_test")
+        || stem.starts_with("test_")
+        || stem.ends_with(".test")
+        || stem.ends_with(".spec")
+        || stem.ends_with("_spec")
+        || stem.ends_with("Test")
+        || stem.ends_with("Tests")
+}
@@ -0,0 +1,634 @@
+//! Filter expressions such as `type:rust AND loc>5000 AND (tag:work OR owner:acme) AND edited<90d`,
+//! parsed once and rendered into the `WHERE` clause of project queries.
+//!
+//! Terms are `key<op>value` with `:`/`=` (match), `!=`, `>`, `>=`, `<`, `<=`. Terms next to
+//! each other are ANDed; `OR`, `NOT`/`-term` and parentheses work as expected. A bare word
+//! matches name or path. Toolchain keys (`node<16`, `python:3.8`, `rust-edition:2018`) compare
+//! the lowest version a project pins. Unknown keys match custom fields (`owner:acme`);
+//! `field:` reaches one named like a built-in key (`field:size>10`).
+
+use anyhow::{bail, Context, Result};
+use rusqlite::types::Value;
+
+use crate::toolchains;
+use crate::vulns::Severity;
+
+/// A parsed filter expression; see the module docs for the syntax.
+#[derive(Debug, Clone)]
+pub struct Query {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+    Not(Box<Expr>),
+    Cond(Cond),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Match,
+    Ne,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    fn sql(self) -> &'static str {
+        match self {
+            Op::Match => "=",
+            Op::Ne => "!=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        }
+    }
+
+    fn is_ordering(self) -> bool {
+        matches!(self, Op::Gt | Op::Ge | Op::Lt | Op::Le)
+    }
+
+    /// `edited<90d` means "less than 90 days ago", i.e. a timestamp *after* the cutoff.
+    fn flipped(self) -> Op {
+        match self {
+            Op::Gt => Op::Lt,
+            Op::Ge => Op::Le,
+            Op::Lt => Op::Gt,
+            Op::Le => Op::Ge,
+            other => other,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Cond {
+    /// Bare word: substring of name or path
+    Text(String),
+    /// name:/path: substring, =/!= exact
+    Like {
+        col: &'static str,
+        op: Op,
+        value: String,
+    },
+    Type {
+        op: Op,
+        value: String,
+    },
+    Num {
+        col: &'static str,
+        op: Op,
+        value: i64,
+    },
+    Bool {
+        col: &'static str,
+        want: bool,
+    },
+    Severity {
+        op: Op,
+        rank: i64,
+    },
+    Field {
+        key: String,
+        op: Op,
+        value: String,
+    },
+    /// Member of the comma-separated `tags` field
+    Tag(String),
+    /// A toolchain pin of `language` whose lowest version is in `lo..=hi` (`version_key`s)
+    Toolchain {
+        language: &'static str,
+        op: Op,
+        lo: i64,
+        hi: i64,
+    },
+}
+
+impl Query {
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        let mut p = Parser { tokens, pos: 0 };
+        let expr = p.parse_or()?;
+        if let Some(tok) = p.tokens.get(p.pos) {
+            bail!("unexpected {tok:?} in query");
+        }
+        Ok(Self { expr })
+    }
+
+    /// Render as a SQL boolean expression over `PROJECT_FROM`, appending positional values.
+    pub(crate) fn to_sql(&self, values: &mut Vec<Value>) -> String {
+        expr_sql(&self.expr, values)
+    }
+}
+
+fn expr_sql(expr: &Expr, values: &mut Vec<Value>) -> String {
+    match expr {
+        Expr::And(items) => join_sql(items, " AND ", values),
+        Expr::Or(items) => join_sql(items, " OR ", values),
+        Expr::Not(inner) => format!("NOT COALESCE({}, 0)", expr_sql(inner, values)),
+        Expr::Cond(cond) => cond_sql(cond, values),
+    }
+}
+
+fn join_sql(items: &[Expr], sep: &str, values: &mut Vec<Value>) -> String {
+    let parts = items
+        .iter()
+        .map(|e| expr_sql(e, values))
+        .collect::<Vec<_>>();
+    format!("({})", parts.join(sep))
+}
+
+fn cond_sql(cond: &Cond, values: &mut Vec<Value>) -> String {
+    match cond {
+        Cond::Text(s) => {
+            let pat = Value::Text(format!("%{s}%"));
+            values.push(pat.clone());
+            values.push(pat);
+            "(p.name LIKE ? OR p.path LIKE ?)".into()
+        }
+        Cond::Like { col, op, value } => match op {
+            Op::Match => {
+                values.push(Value::Text(format!("%{value}%")));
+                format!("{col} LIKE ?")
+            }
+            op => {
+                values.push(Value::Text(value.clone()));
+                format!("{col} {} ?", op.sql())
+            }
+        },
+        Cond::Type { op, value } => {
+            values.push(Value::Text(value.to_ascii_lowercase()));
+            format!("LOWER(p.type) {} ?", op.sql())
+        }
+        Cond::Num { col, op, value } => {
+            values.push(Value::Integer(*value));
+            format!("{col} {} ?", op.sql())
+        }
+        Cond::Bool { col, want } => {
+            values.push(Value::Integer(*want as i64));
+            format!("COALESCE({col}, 0) = ?")
+        }
+        Cond::Severity { op, rank } => {
+            values.push(Value::Integer(*rank));
+            format!(
+                "d.severity_max IS NOT NULL AND {} {} ?",
+                crate::db::SEVERITY_RANK,
+                op.sql()
+            )
+        }
+        Cond::Field { key, op, value } => field_clause(key, *op, value, values),
+        Cond::Toolchain {
+            language,
+            op,
+            lo,
+            hi,
+        } => {
+            let (range, bounds) = match op {
+                Op::Match | Op::Ne => ("version_key BETWEEN ? AND ?", vec![*lo, *hi]),
+                Op::Lt => ("version_key < ?", vec![*lo]),
+                Op::Le => ("version_key <= ?", vec![*hi]),
+                Op::Gt => ("version_key > ?", vec![*hi]),
+                Op::Ge => ("version_key >= ?", vec![*lo]),
+            };
+            values.push(Value::Text(language.to_string()));
+            values.extend(bounds.into_iter().map(Value::Integer));
+            format!(
+                "p.id {}IN (SELECT project_id FROM toolchains WHERE language = ? AND {range})",
+                if *op == Op::Ne { "NOT " } else { "" }
+            )
+        }
+        Cond::Tag(tag) => {
+            let cond =
+                "(',' || REPLACE(REPLACE(TRIM(value, '\"'), ', ', ','), ' ,', ',') || ',') LIKE ?";
+            let pat = Value::Text(format!("%,{tag},%"));
+            values.push(Value::Text("tags".into()));
+            values.push(pat.clone());
+            values.push(Value::Text("tags".into()));
+            values.push(pat);
+            format!(
+                "p.id IN (SELECT project_id FROM project_fields WHERE key = ? AND {cond} \
+                 UNION SELECT project_id FROM custom_fields WHERE key = ? AND {cond})"
+            )
+        }
+    }
+}
+
+/// Match user-defined and hook-provided fields. Equality compares the JSON encoding so
+/// strings, numbers and bools all work; ordering operators compare numerically.
+fn field_clause(key: &str, op: Op, want: &str, values: &mut Vec<Value>) -> String {
+    let value_cond = if op.is_ordering() {
+        format!("CAST(value AS REAL) {} ?", op.sql())
+    } else {
+        "value IN (?, ?)".to_string()
+    };
+    for _ in 0..2 {
+        values.push(Value::Text(key.to_string()));
+        if op.is_ordering() {
+            values.push(Value::Real(
+                want.parse()
+                    .expect("ordering values are checked by field_cond"),
+            ));
+        } else {
+            values.push(Value::Text(
+                serde_json::Value::String(want.to_string()).to_string(),
+            ));
+            values.push(Value::Text(want.to_string()));
+        }
+    }
+    format!(
+        "p.id {}IN (SELECT project_id FROM project_fields WHERE key = ? AND {value_cond} \
+         UNION SELECT project_id FROM custom_fields WHERE key = ? AND {value_cond})",
+        if op == Op::Ne { "NOT " } else { "" }
+    )
+}
+
+/// Equality match on a field, as used by `ProjectFilter::fields`.
+pub(crate) fn field_eq_clause(key: &str, want: &str, values: &mut Vec<Value>) -> String {
+    field_clause(key, Op::Match, want, values)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word(String),
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            _ => {
+                let mut word = String::new();
+                let mut quoted = false;
+                while let Some(&c) = chars.peek() {
+                    if c == '"' {
+                        chars.next();
+                        quoted = true;
+                        loop {
+                            match chars.next() {
+                                Some('"') => break,
+                                Some(c) => word.push(c),
+                                None => bail!("unterminated quote in query"),
+                            }
+                        }
+                    } else if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    } else {
+                        word.push(c);
+                        chars.next();
+                    }
+                }
+                tokens.push(match word.as_str() {
+                    _ if quoted => Token::Word(word),
+                    "AND" | "and" | "&&" => Token::And,
+                    "OR" | "or" | "||" => Token::Or,
+                    "NOT" | "not" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Expr::Or(items)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.pos += 1;
+                    items.push(self.parse_unary()?);
+                }
+                // Juxtaposition is an implicit AND
+                Some(Token::Word(_) | Token::Not | Token::LParen) => {
+                    items.push(self.parse_unary()?)
+                }
+                _ => break,
+            }
+        }
+        Ok(if items.len() == 1 {
+            items.remove(0)
+        } else {
+            Expr::And(items)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let tok = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .context("query ended unexpectedly")?;
+        self.pos += 1;
+        match tok {
+            Token::Not => Ok(Expr::Not(Box::new(self.parse_unary()?))),
+            Token::LParen => {
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::RParen) {
+                    bail!("missing closing parenthesis in query");
+                }
+                self.pos += 1;
+                Ok(inner)
+            }
+            Token::Word(w) => match w.strip_prefix('-') {
+                Some(rest) if !rest.is_empty() => {
+                    Ok(Expr::Not(Box::new(Expr::Cond(parse_term(rest)?))))
+                }
+                _ => Ok(Expr::Cond(parse_term(&w)?)),
+            },
+            other => bail!("unexpected {other:?} in query"),
+        }
+    }
+}
+
+/// `key`, operator and value of a `key<op>value` term; `None` for a bare word.
+fn split_term(word: &str) -> Result<Option<(&str, Op, &str)>> {
+    let Some(idx) = word.find([':', '=', '<', '>', '!']) else {
+        return Ok(None);
+    };
+    let key = &word[..idx];
+    if key.is_empty()
+        || !key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+    {
+        return Ok(None);
+    }
+    let rest = &word[idx..];
+    let (op, value) = [
+        (">=", Op::Ge),
+        ("<=", Op::Le),
+        ("!=", Op::Ne),
+        (":", Op::Match),
+        ("=", Op::Match),
+        (">", Op::Gt),
+        ("<", Op::Lt),
+    ]
+    .iter()
+    .find_map(|(sym, op)| rest.strip_prefix(sym).map(|v| (*op, v)))
+    .with_context(|| format!("bad operator in {word:?}"))?;
+    if value.is_empty() {
+        bail!("missing value in {word:?}");
+    }
+    Ok(Some((key, op, value)))
+}
+
+fn parse_term(word: &str) -> Result<Cond> {
+    let Some((key, op, value)) = split_term(word)? else {
+        return Ok(Cond::Text(word.to_string()));
+    };
+    let key = key.to_ascii_lowercase();
+
+    let numeric = |col: &'static str, binary: bool| -> Result<Cond> {
+        Ok(Cond::Num {
+            col,
+            op,
+            value: parse_quantity(value, binary)
+                .with_context(|| format!("{key} expects a number, got {value:?}"))?,
+        })
+    };
+    let boolean = |col: &'static str| -> Result<Cond> {
+        if op.is_ordering() {
+            bail!("{key} only supports `:`/`=`/`!=`");
+        }
+        let want = parse_bool(value).with_context(|| format!("{key} expects true/false"))?;
+        Ok(Cond::Bool {
+            col,
+            want: want != (op == Op::Ne),
+        })
+    };
+
+    match key.as_str() {
+        "name" => Ok(Cond::Like {
+            col: "p.name",
+            op,
+            value: value.to_string(),
+        }),
+        "path" => Ok(Cond::Like {
+            col: "p.path",
+            op,
+            value: value.to_string(),
+        }),
+        "branch" => Ok(Cond::Like {
+            col: "g.branch",
+            op,
+            value: value.to_string(),
+        }),
+        "machine" => Ok(Cond::Like {
+            col: "p.machine",
+            op,
+            value: value.to_string(),
+        }),
+        "volume" => Ok(Cond::Like {
+            col: "p.volume_id",
+            op,
+            value: value.to_string(),
+        }),
+        "type" => {
+            if op.is_ordering() {
+                bail!("type only supports `:`/`=`/`!=`");
+            }
+            Ok(Cond::Type {
+                op,
+                value: value.to_string(),
+            })
+        }
+        "loc" => numeric("m.loc", false),
+        "size" => numeric("m.size_bytes", true),
+        "files" => numeric("m.files_count", false),
+        "notebooks" => numeric("m.notebooks_count", false),
+        "source_files" => numeric("m.source_files_count", false),
+        "avg_file_size" => numeric("m.avg_file_bytes", true),
+        "depth" => numeric("m.max_depth", false),
+        "modules" => numeric("m.top_level_modules", false),
+        "assets" => numeric("m.asset_bytes", true),
+        "ml" => numeric("m.ml_bytes", true),
+        "hours" => numeric(
+            "(SELECT COALESCE(SUM(w.secs), 0) / 3600 FROM work_sessions w \
+              WHERE w.project_id = p.id)",
+            false,
+        ),
+        "vulns" => numeric("d.vuln_count", false),
+        "git" => boolean("p.is_git_repo"),
+        "tests" => boolean("m.has_tests"),
+        "ci" => boolean("m.has_ci"),
+        "devcontainer" => boolean("m.has_devcontainer"),
+        "docker" => boolean("m.has_dockerfile"),
+        "compose" => boolean("m.has_compose"),
+        "nix" => boolean("m.has_nix_shell"),
+        "tool-versions" => boolean("m.has_tool_versions"),
+        "secrets" => boolean("(m.secrets_count > 0)"),
+        "remote" => boolean("(g.remote_url IS NOT NULL)"),
+        "dirty" => boolean("g.dirty"),
+        "severity" => {
+            let sev =
+                Severity::parse(value).with_context(|| format!("unknown severity {value:?}"))?;
+            Ok(Cond::Severity {
+                op,
+                rank: severity_rank(sev),
+            })
+        }
+        "edited" => {
+            let (op, ts) = parse_when(op, value).with_context(|| {
+                format!("edited expects an age like 90d or a date, got {value:?}")
+            })?;
+            Ok(Cond::Num {
+                col: "m.last_edited_at",
+                op,
+                value: ts,
+            })
+        }
+        lang if toolchains::LANGUAGES.contains(&lang) => {
+            let (lo, hi) = toolchains::version_range(value).with_context(|| {
+                format!("{key} expects a version like 14 or 3.8, got {value:?}")
+            })?;
+            let language = toolchains::LANGUAGES
+                .iter()
+                .copied()
+                .find(|l| *l == lang)
+                .expect("matched above");
+            Ok(Cond::Toolchain {
+                language,
+                op,
+                lo,
+                hi,
+            })
+        }
+        "tag" | "tags" => {
+            if op != Op::Match {
+                bail!("tag only supports `:`");
+            }
+            Ok(Cond::Tag(value.to_string()))
+        }
+        // `field:size>10` reaches a custom field named like a built-in key
+        "field" if op == Op::Match => {
+            let (key, op, value) = split_term(value)?
+                .with_context(|| format!("field expects <key><op><value>, got {value:?}"))?;
+            field_cond(key.to_ascii_lowercase(), op, value)
+        }
+        _ => field_cond(key, op, value),
+    }
+}
+
+fn field_cond(key: String, op: Op, value: &str) -> Result<Cond> {
+    if op.is_ordering() && value.parse::<f64>().is_err() {
+        bail!("{key} expects a number, got {value:?}");
+    }
+    Ok(Cond::Field {
+        key,
+        op,
+        value: value.to_string(),
+    })
+}
+
+/// Keep in sync with `db::SEVERITY_RANK`.
+fn severity_rank(s: Severity) -> i64 {
+    match s {
+        Severity::Unknown => 0,
+        Severity::Low => 1,
+        Severity::Moderate => 2,
+        Severity::High => 3,
+        Severity::Critical => 4,
+    }
+}
+
+fn parse_bool(s: &str) -> Option<bool> {
+    match s.to_ascii_lowercase().as_str() {
+        "true" | "yes" | "1" => Some(true),
+        "false" | "no" | "0" => Some(false),
+        _ => None,
+    }
+}
+
+/// `5000`, `5k`, `1.5m`; with `binary`, `500kb`/`2g`/`1gb` use powers of 1024.
+fn parse_quantity(s: &str, binary: bool) -> Option<i64> {
+    let lower = s.to_ascii_lowercase();
+    let trimmed = if binary {
+        lower.strip_suffix('b').unwrap_or(&lower)
+    } else {
+        &lower
+    };
+    let (num, unit) = match trimmed.char_indices().last()? {
+        (i, c) if c.is_ascii_alphabetic() => (&trimmed[..i], Some(c)),
+        _ => (trimmed, None),
+    };
+    let base: f64 = if binary { 1024.0 } else { 1000.0 };
+    let mult = match unit {
+        None => 1.0,
+        Some('k') => base,
+        Some('m') => base.powi(2),
+        Some('g') => base.powi(3),
+        Some('t') => base.powi(4),
+        Some(_) => return None,
+    };
+    let n: f64 = num.parse().ok()?;
+    Some((n * mult) as i64)
+}
+
+/// Ages (`90d`, `2w`, `6mo`, `1y`, `12h`) compare against "now"; `YYYY-MM-DD` is absolute.
+fn parse_when(op: Op, s: &str) -> Option<(Op, i64)> {
+    if let Ok(date) =
+        time::Date::parse(s, time::macros::format_description!("[year]-[month]-[day]"))
+    {
+        // `edited:2024-01-01` reads as "since that day"
+        let op = if op == Op::Match { Op::Ge } else { op };
+        return Some((op, date.midnight().assume_utc().unix_timestamp()));
+    }
+    let (num, unit) = s.split_at(s.find(|c: char| !c.is_ascii_digit())?);
+    let n: i64 = num.parse().ok()?;
+    let secs = match unit {
+        "h" => 3600,
+        "d" => 86_400,
+        "w" => 7 * 86_400,
+        "mo" => 30 * 86_400,
+        "y" => 365 * 86_400,
+        _ => return None,
+    };
+    let now = time::OffsetDateTime::now_utc().unix_timestamp();
+    // `edited:90d` reads as "within the last 90 days"
+    let op = if op == Op::Match { Op::Lt } else { op };
+    Some((op.flipped(), now - n * secs))
+}
@@ -0,0 +1,232 @@
+//! One queue of roots waiting to be scanned. Callers (the CLI, the app's Scan button and
+//! tray, later requests while a scan is running) enqueue roots with a priority; whoever runs
+//! the queue scans the highest-priority root next, so an active work root is refreshed before
+//! a slow archive volume.
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Instant;
+use time::OffsetDateTime;
+
+use crate::analyzers::AnalyzerRegistry;
+use crate::config::AppConfig;
+pub use crate::config::ScanPriority;
+use crate::db::Db;
+use crate::moves::MoveDetector;
+use crate::scan::{discover_root, enrich, pending_projects, ScanOptions, ScanProgress, ScanReport};
+use crate::scan_runs::ScanRun;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QueuedRoot {
+    pub root: PathBuf,
+    pub priority: ScanPriority,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    /// Queued roots with their arrival order
+    pending: Vec<(QueuedRoot, u64)>,
+    next_seq: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct ScanQueue {
+    state: Mutex<State>,
+}
+
+impl ScanQueue {
+    /// Queue `root`. A root that is already waiting keeps its place and is only moved up
+    /// if `priority` is higher. Returns whether the queue changed.
+    pub fn enqueue(&self, root: PathBuf, priority: ScanPriority) -> bool {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some((queued, _)) = state.pending.iter_mut().find(|(q, _)| q.root == root) {
+            if priority <= queued.priority {
+                return false;
+            }
+            queued.priority = priority;
+            return true;
+        }
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.pending.push((QueuedRoot { root, priority }, seq));
+        true
+    }
+
+    /// Queue every configured root at its `root_priority`.
+    pub fn enqueue_configured(&self, cfg: &AppConfig) {
+        for root in &cfg.roots {
+            self.enqueue(root.clone(), cfg.priority_of(root));
+        }
+    }
+
+    /// Remove and return the root to scan next.
+    pub fn pop(&self) -> Option<QueuedRoot> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let next = state
+            .pending
+            .iter()
+            .enumerate()
+            .max_by(|(_, (a, a_seq)), (_, (b, b_seq))| {
+                a.priority.cmp(&b.priority).then(b_seq.cmp(a_seq))
+            })
+            .map(|(i, _)| i)?;
+        Some(state.pending.remove(next).0)
+    }
+
+    /// Waiting roots in the order they will be scanned.
+    pub fn pending(&self) -> Vec<QueuedRoot> {
+        let state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        let mut pending = state.pending.clone();
+        pending
+            .sort_by(|(a, a_seq), (b, b_seq)| b.priority.cmp(&a.priority).then(a_seq.cmp(b_seq)));
+        pending.into_iter().map(|(q, _)| q).collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.state
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .pending
+            .is_empty()
+    }
+
+    /// Scan queued roots until the queue is empty, including roots queued while running.
+    /// On error the failed root is dropped and the rest stay queued.
+    pub fn run(
+        &self,
+        db: &Db,
+        cfg: &AppConfig,
+        opts: &ScanOptions,
+        analyzers: &AnalyzerRegistry,
+    ) -> Result<ScanReport> {
+        self.run_with_progress(db, cfg, opts, analyzers, &mut |_| {})
+    }
+
+    /// `run`, reporting progress: every queued root is discovered first, then the projects
+    /// found are enriched. Roots queued during enrichment get their own round afterwards.
+    pub fn run_with_progress(
+        &self,
+        db: &Db,
+        cfg: &AppConfig,
+        opts: &ScanOptions,
+        analyzers: &AnalyzerRegistry,
+        progress: &mut dyn FnMut(ScanProgress),
+    ) -> Result<ScanReport> {
+        let started = Instant::now();
+        let started_at = OffsetDateTime::now_utc().unix_timestamp();
+        let roots = self.pending();
+        let _nice = opts
+            .nice
+            .then(|| crate::nice::NiceScan::enter(cfg.nice.ops_per_sec));
+        if !opts.dry_run {
+            // A failed backup should not block the scan, but it is worth knowing about
+            if let Err(err) = db.backup_if_due(&cfg.backup) {
+                tracing::warn!(%err, "database backup failed");
+            }
+        }
+        let result = self.drain(db, cfg, opts, analyzers, progress);
+        if result.is_ok() && !opts.dry_run {
+            // Like the backup, policies are a side job that must not fail the scan
+            if let Err(err) = db.evaluate_policies(&cfg.policies) {
+                tracing::warn!(%err, "could not evaluate retention policies");
+            }
+        }
+        if !opts.dry_run {
+            let run = ScanRun {
+                started_at,
+                finished_at: OffsetDateTime::now_utc().unix_timestamp(),
+                roots: roots
+                    .iter()
+                    .map(|q| q.root.to_string_lossy().into_owned())
+                    .collect(),
+                projects: result.as_ref().map_or(0, |r| r.projects as i64),
+                moves: result.as_ref().map_or(0, |r| r.moves.len() as i64),
+                discover_only: opts.discover_only,
+                total_ms: started.elapsed().as_secs_f64() * 1000.0,
+                timings: result.as_ref().map_or(Vec::new(), |r| {
+                    r.timings.by_cost().into_iter().cloned().collect()
+                }),
+                error: result.as_ref().err().map(|e| format!("{e:#}")),
+            };
+            if let Err(err) = db.record_scan_run(&run) {
+                tracing::warn!(%err, "could not record the scan run");
+            }
+        }
+        crate::metrics::record_scan(started.elapsed(), result.as_ref().ok().map(|r| r.projects));
+        result
+    }
+
+    fn drain(
+        &self,
+        db: &Db,
+        cfg: &AppConfig,
+        opts: &ScanOptions,
+        analyzers: &AnalyzerRegistry,
+        progress: &mut dyn FnMut(ScanProgress),
+    ) -> Result<ScanReport> {
+        let started = Instant::now();
+        let mut report = ScanReport::default();
+        let mut moves = MoveDetector::default();
+        let mut roots = Vec::new();
+        let mut first_round = true;
+        loop {
+            let mut discovered = Vec::new();
+            while let Some(next) = self.pop() {
+                tracing::debug!(root = %next.root.display(), priority = ?next.priority, "dequeued root");
+                discovered.extend(discover_root(
+                    db,
+                    cfg,
+                    opts,
+                    &next.root,
+                    &mut moves,
+                    &mut report,
+                )?);
+                roots.push(next.root);
+            }
+            // Left unfinished by a scan that died; enriched along with this one's
+            if first_round && !opts.dry_run && !opts.discover_only {
+                let pending = pending_projects(db, cfg, &discovered)?;
+                if !pending.is_empty() {
+                    tracing::info!(
+                        projects = pending.len(),
+                        "enriching projects an interrupted scan left"
+                    );
+                }
+                discovered.extend(pending);
+            }
+            first_round = false;
+            if discovered.is_empty() {
+                break;
+            }
+            progress(ScanProgress::Discovered {
+                projects: report.projects,
+            });
+            if opts.discover_only {
+                continue;
+            }
+            enrich(
+                db,
+                cfg,
+                opts,
+                analyzers,
+                &discovered,
+                &mut report,
+                &mut |done, total| progress(ScanProgress::Enriched { done, total }),
+            )?;
+        }
+        if !opts.dry_run {
+            db.clear_checkpoints(&roots)?;
+        }
+        report.moves = moves.into_moves();
+        report.timings.total_ms = started.elapsed().as_secs_f64() * 1000.0;
+        tracing::info!(
+            projects = report.projects,
+            total_ms = report.timings.total_ms,
+            "scan finished"
+        );
+        Ok(report)
+    }
+}
@@ -0,0 +1,149 @@
+//! Quick-open ranking: blends how well a project matches the query with how it is used —
+//! frecency, favorites and staleness — using the weights in `ranking.*`.
+
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::HashMap;
+use time::OffsetDateTime;
+
+use crate::config::RankingConfig;
+use crate::db::{Db, ProjectRecord, FRECENCY_SQL};
+use crate::fields::FAVORITE_FIELD;
+use crate::search::{fts_query, BM25};
+
+/// Match scores: a name starting with the query is the best match; full-text hits are
+/// scaled below it by BM25 (which is unreliable on small indexes, where common words get
+/// near-zero weight); a bare substring of the name or path (e.g. `ments` in `payments`)
+/// counts least.
+const NAME_PREFIX_MATCH: f64 = 1.0;
+const FULL_TEXT_MATCH: f64 = 0.75;
+const SUBSTRING_MATCH: f64 = 0.25;
+
+struct Signals {
+    name: String,
+    frecency: f64,
+    favorite: bool,
+    /// Newest of last edit and last open, Unix seconds (0 when neither is known)
+    last_touched: i64,
+}
+
+impl Db {
+    /// Ranking for the quick-open palette: every project whose name or path contains
+    /// `query`, or whose name, path or README matches it word by word, best first. An empty
+    /// query ranks every project on usage alone.
+    pub fn quick_open(
+        &self,
+        query: &str,
+        limit: u32,
+        weights: &RankingConfig,
+    ) -> Result<Vec<ProjectRecord>> {
+        let query = query.trim();
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let signals = self.ranking_signals(now)?;
+
+        // Match quality per candidate, 0..1
+        let mut matched: HashMap<i64, f64> = HashMap::new();
+        if query.is_empty() {
+            matched.extend(signals.keys().map(|id| (*id, 0.0)));
+        } else {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id FROM projects WHERE name LIKE ?1 OR path LIKE ?1")?;
+            let ids = stmt
+                .query_map(params![format!("%{query}%")], |r| r.get::<_, i64>(0))?
+                .collect::<Result<Vec<_>, _>>()?;
+            matched.extend(ids.into_iter().map(|id| (id, SUBSTRING_MATCH)));
+            let bm25 = self.bm25_scores(query)?;
+            let best = bm25.iter().map(|(_, s)| *s).fold(0.0, f64::max);
+            for (id, score) in bm25 {
+                let scaled = if best > 0.0 { score / best } else { 1.0 };
+                let m = matched.entry(id).or_insert(0.0);
+                *m = m.max(FULL_TEXT_MATCH * scaled);
+            }
+            let prefix = query.to_lowercase();
+            for (id, m) in matched.iter_mut() {
+                if signals
+                    .get(id)
+                    .is_some_and(|s| s.name.to_lowercase().starts_with(&prefix))
+                {
+                    *m = NAME_PREFIX_MATCH;
+                }
+            }
+        }
+
+        let max_frecency = signals.values().map(|s| s.frecency).fold(0.0, f64::max);
+        let stale_secs = (weights.stale_after_days.max(1) as f64) * 86400.0;
+        let mut ranked: Vec<(f64, &str, i64)> = matched
+            .into_iter()
+            .filter_map(|(id, m)| {
+                let s = signals.get(&id)?;
+                let frecency = if max_frecency > 0.0 {
+                    (1.0 + s.frecency).ln() / (1.0 + max_frecency).ln()
+                } else {
+                    0.0
+                };
+                let staleness = if s.last_touched > 0 {
+                    ((now - s.last_touched).max(0) as f64 / stale_secs).min(1.0)
+                } else {
+                    1.0
+                };
+                let score = weights.match_weight * m
+                    + weights.frecency_weight * frecency
+                    + weights.favorite_weight * f64::from(u8::from(s.favorite))
+                    - weights.staleness_weight * staleness;
+                Some((score, s.name.as_str(), id))
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(b.1)));
+
+        let mut out = Vec::new();
+        for (_, _, id) in ranked.into_iter().take(limit as usize) {
+            if let Some(p) = self.project_by_id(id)? {
+                out.push(p);
+            }
+        }
+        Ok(out)
+    }
+
+    fn ranking_signals(&self, now: i64) -> Result<HashMap<i64, Signals>> {
+        let sql = format!(
+            "SELECT p.id, p.name, COALESCE(f.score, 0), \
+               EXISTS(SELECT 1 FROM project_fields pf \
+                      WHERE pf.project_id = p.id AND pf.key = ?2 AND pf.value = 'true'), \
+               MAX(COALESCE(m.last_edited_at, 0), \
+                   COALESCE((SELECT MAX(opened_at) FROM project_opens o WHERE o.project_id = p.id), 0)) \
+             FROM projects p \
+             LEFT JOIN metrics m ON m.project_id = p.id \
+             LEFT JOIN {FRECENCY_SQL} f ON f.project_id = p.id"
+        );
+        let mut stmt = self.conn.prepare(&sql)?;
+        let rows = stmt
+            .query_map(params![now, FAVORITE_FIELD], |r| {
+                Ok((
+                    r.get::<_, i64>(0)?,
+                    Signals {
+                        name: r.get(1)?,
+                        frecency: r.get(2)?,
+                        favorite: r.get(3)?,
+                        last_touched: r.get(4)?,
+                    },
+                ))
+            })?
+            .collect::<Result<HashMap<_, _>, _>>()?;
+        Ok(rows)
+    }
+
+    /// `(project id, BM25 score)` for full-text matches of `query`; higher is better.
+    fn bm25_scores(&self, query: &str) -> Result<Vec<(i64, f64)>> {
+        let Some(fts) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT rowid, -{BM25} FROM project_search WHERE project_search MATCH ?1"
+        ))?;
+        let rows = stmt
+            .query_map(params![fts], |r| Ok((r.get(0)?, r.get(1)?)))?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,133 @@
+//! Matching a list of remote URLs (say, every repo in a GitHub org) against the index
+//! (`reconcile <file>`): which are cloned and where, which are not cloned anywhere, and which
+//! local repos have no remote in the list.
+//!
+//! URLs are compared by host and path, so `git@github.com:acme/api.git`,
+//! `https://github.com/acme/api` and `ssh://git@github.com/acme/api.git` are the same remote.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::db::{Db, ProjectFilter};
+use crate::SortKey;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClonedRemote {
+    /// As written in the list
+    pub url: String,
+    /// Every local clone, by path
+    pub paths: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct UnlistedRepo {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    /// None for repos without a remote
+    pub remote_url: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Reconciliation {
+    pub cloned: Vec<ClonedRemote>,
+    /// Listed URLs with no local clone, in list order
+    pub missing: Vec<String>,
+    /// Local git repos whose remote is not in the list
+    pub unlisted: Vec<UnlistedRepo>,
+}
+
+/// `host/owner/repo` for comparing remotes: lowercase, without scheme, user, port, `.git` or
+/// trailing slashes. None for lines that are not a remote URL.
+pub fn remote_key(url: &str) -> Option<String> {
+    let url = url.trim();
+    let (host, path) = match url.split_once("://") {
+        Some((_, rest)) => {
+            let (authority, path) = rest.split_once('/')?;
+            let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+            (host.split(':').next()?, path)
+        }
+        // scp-like `user@host:owner/repo`
+        None => {
+            let (authority, path) = url.split_once(':')?;
+            (
+                authority.rsplit_once('@').map_or(authority, |(_, h)| h),
+                path,
+            )
+        }
+    };
+    let path = path.trim_matches('/');
+    let path = path
+        .strip_suffix(".git")
+        .unwrap_or(path)
+        .trim_end_matches('/');
+    (!host.is_empty() && !path.is_empty() && !host.contains(char::is_whitespace))
+        .then(|| format!("{}/{}", host.to_lowercase(), path.to_lowercase()))
+}
+
+/// Remote URLs from a list file: one per line; blank lines and `#` comments are skipped.
+pub fn parse_remote_list(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|l| l.split_once('#').map_or(l, |(url, _)| url).trim())
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl Db {
+    /// Reconcile `urls` with the indexed git repos. Needs remotes recorded by a scan with the
+    /// `git` feature.
+    pub fn reconcile_remotes(&self, urls: &[String]) -> Result<Reconciliation> {
+        let mut clones: BTreeMap<String, Vec<String>> = BTreeMap::new();
+        let mut repos = Vec::new();
+        self.stream_projects(&ProjectFilter::default(), SortKey::Name, true, None, |p| {
+            if p.is_git_repo {
+                if let Some(key) = p.remote_url.as_deref().and_then(remote_key) {
+                    clones.entry(key).or_default().push(p.path.clone());
+                }
+                repos.push(p);
+            }
+            Ok(())
+        })?;
+
+        let mut out = Reconciliation::default();
+        let mut listed = HashSet::new();
+        for url in urls {
+            let Some(key) = remote_key(url) else {
+                tracing::warn!(%url, "not a remote URL; skipped");
+                continue;
+            };
+            if !listed.insert(key.clone()) {
+                continue;
+            }
+            match clones.get(&key) {
+                Some(paths) => {
+                    let mut paths = paths.clone();
+                    paths.sort();
+                    out.cloned.push(ClonedRemote {
+                        url: url.clone(),
+                        paths,
+                    });
+                }
+                None => out.missing.push(url.clone()),
+            }
+        }
+        out.unlisted = repos
+            .into_iter()
+            .filter(|p| {
+                p.remote_url
+                    .as_deref()
+                    .and_then(remote_key)
+                    .is_none_or(|key| !listed.contains(&key))
+            })
+            .map(|p| UnlistedRepo {
+                id: p.id,
+                name: p.name,
+                path: p.path,
+                remote_url: p.remote_url,
+            })
+            .collect();
+        Ok(out)
+    }
+}
@@ -0,0 +1,345 @@
+//! Privacy scrubbing for anything that leaves the machine (`--redact` on exports, logs and
+//! diagnostics): paths, project names, remote URLs and email addresses are replaced by short
+//! stable hashes, so a bug report still shows structure ("these two paths share a parent",
+//! "same remote owner") without naming clients.
+//!
+//! Hashes are unsalted and the same on every run, so a name can be confirmed by hashing a
+//! guess; they hide names from a casual reader, not from someone who already suspects them.
+
+use std::path::Path;
+
+use crate::db::ProjectRecord;
+
+/// Characters that end a word when scrubbing free text
+const DELIMITERS: &[char] = &[
+    '"', '\'', '`', '(', ')', '<', '>', '[', ']', '{', '}', ',', ';', '=',
+];
+
+/// Trailing punctuation kept out of a word (`/home/me/x:` in an error message)
+const TRAILING: &[char] = &[':', '.', ',', '!', '?'];
+
+/// Replaces identifying strings with stable hashes; see the module docs.
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    /// Home directory, written as `~` and then hashed below
+    home: Option<String>,
+    /// Names hashed wherever they appear in free text (projects, actions), longest first
+    names: Vec<String>,
+}
+
+impl Redactor {
+    /// For the current user's home directory.
+    pub fn new() -> Self {
+        Self::with_home(dirs_next::home_dir().as_deref())
+    }
+
+    pub fn with_home(home: Option<&Path>) -> Self {
+        let home = home
+            .map(|h| {
+                h.to_string_lossy()
+                    .trim_end_matches(['/', '\\'])
+                    .to_string()
+            })
+            .filter(|h| !h.is_empty());
+        Self {
+            home,
+            names: Vec::new(),
+        }
+    }
+
+    /// Also hash these names where [`Redactor::text`] finds them as whole words, as log lines
+    /// name projects and actions outside of any path (`action=deploy-acme`).
+    pub fn with_names(mut self, names: impl IntoIterator<Item = String>) -> Self {
+        self.names
+            .extend(names.into_iter().filter(|n| !n.trim().is_empty()));
+        self.names
+            .sort_by(|a, b| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+        self.names.dedup();
+        self
+    }
+
+    /// `~/` and hashed components for paths under home; elsewhere the first component is kept
+    /// (`/Volumes`, `/mnt`, `C:`) and the rest hashed.
+    pub fn path(&self, path: &str) -> String {
+        let (prefix, rest) = match self.home.as_deref().and_then(|h| strip_dir(path, h)) {
+            Some(rest) => ("~".to_string(), rest),
+            None => match path.strip_prefix("~") {
+                Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => {
+                    ("~".to_string(), rest)
+                }
+                _ => split_first(path),
+            },
+        };
+        let sep = if path.contains('\\') && !path.contains('/') {
+            '\\'
+        } else {
+            '/'
+        };
+        let mut out = prefix;
+        for part in rest.split(['/', '\\']).filter(|p| !p.is_empty()) {
+            out.push(sep);
+            out.push_str(&hash(part));
+        }
+        out
+    }
+
+    /// A project name, hashed like the last component of its path so the two line up.
+    pub fn name(&self, name: &str) -> String {
+        hash(name)
+    }
+
+    /// Scheme and host are kept; credentials are dropped and path segments hashed (a
+    /// trailing `.git` stays).
+    pub fn remote_url(&self, url: &str) -> String {
+        let (head, path, sep) = if let Some((scheme, rest)) = url.split_once("://") {
+            let (authority, path) = rest.split_once('/').unwrap_or((rest, ""));
+            let host = authority.rsplit_once('@').map_or(authority, |(_, h)| h);
+            (format!("{scheme}://{host}"), path, '/')
+        } else if let Some((authority, path)) = url.split_once(':') {
+            // scp-like `git@github.com:owner/repo.git`
+            (authority.to_string(), path, ':')
+        } else {
+            return hash(url);
+        };
+        let segments: Vec<String> = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| match s.strip_suffix(".git") {
+                Some(stem) => format!("{}.git", hash(stem)),
+                None => hash(s),
+            })
+            .collect();
+        if segments.is_empty() {
+            return head;
+        }
+        format!("{head}{sep}{}", segments.join("/"))
+    }
+
+    /// `user-<hash>@redacted`, one hash per address.
+    pub fn email(&self, email: &str) -> String {
+        format!("user-{}@redacted", hash(&email.to_lowercase()))
+    }
+
+    /// A person: an email as in [`Redactor::email`], anything else (a name, `Name <email>`)
+    /// hashed whole.
+    pub fn identity(&self, identity: &str) -> String {
+        if is_email(identity) && !identity.contains(char::is_whitespace) {
+            self.email(identity)
+        } else {
+            self.name(identity)
+        }
+    }
+
+    /// Scrub free text such as a log line: every word that looks like a path, URL, remote or
+    /// email is replaced. A path containing spaces is only scrubbed up to the first space.
+    pub fn text(&self, text: &str) -> String {
+        // Names that span several words are replaced before the text is split into words
+        let mut text = std::borrow::Cow::Borrowed(text);
+        for name in self.names.iter().filter(|n| n.contains(is_delimiter)) {
+            if text.contains(name.as_str()) {
+                text = replace_words(&text, name, &self.name(name)).into();
+            }
+        }
+        let mut out = String::with_capacity(text.len());
+        let mut word = String::new();
+        for c in text.chars() {
+            if is_delimiter(c) {
+                out.push_str(&self.word(&word));
+                word.clear();
+                out.push(c);
+            } else {
+                word.push(c);
+            }
+        }
+        out.push_str(&self.word(&word));
+        out
+    }
+
+    fn word(&self, word: &str) -> String {
+        let core = word.trim_end_matches(TRAILING);
+        let tail = &word[core.len()..];
+        let scrubbed = if core.contains("://") || is_scp_remote(core) {
+            self.remote_url(core)
+        } else if is_email(core) {
+            self.email(core)
+        } else if is_path(core) {
+            self.path(core)
+        } else if self.names.iter().any(|n| n == core) {
+            self.name(core)
+        } else {
+            return word.to_string();
+        };
+        scrubbed + tail
+    }
+
+    /// `record` with everything that can name a client (name, path, remote, owner, branch,
+    /// machine and volume) redacted; counts, sizes and dates are kept.
+    pub fn project(&self, record: &ProjectRecord) -> ProjectRecord {
+        ProjectRecord {
+            name: self.name(&record.name),
+            path: self.path(&record.path),
+            remote_url: record.remote_url.as_deref().map(|u| self.remote_url(u)),
+            owner: record.owner.as_deref().map(|o| self.name(o)),
+            branch: record.branch.as_deref().map(|b| self.name(b)),
+            machine: record.machine.as_deref().map(|m| self.name(m)),
+            volume_id: record.volume_id.as_deref().map(|v| self.path(v)),
+            path_bytes: None,
+            ..record.clone()
+        }
+    }
+}
+
+impl Default for Redactor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn is_delimiter(c: char) -> bool {
+    c.is_whitespace() || DELIMITERS.contains(&c)
+}
+
+/// `text` with every occurrence of `name` that is not part of a longer word replaced.
+fn replace_words(text: &str, name: &str, with: &str) -> String {
+    let bounded = |c: Option<char>| c.is_none_or(|c| !c.is_alphanumeric());
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(i) = rest.find(name) {
+        let after = &rest[i + name.len()..];
+        out.push_str(&rest[..i]);
+        if bounded(out.chars().next_back()) && bounded(after.chars().next()) {
+            out.push_str(with);
+        } else {
+            out.push_str(name);
+        }
+        rest = after;
+    }
+    out.push_str(rest);
+    out
+}
+
+/// `path` below `dir`, if it is `dir` or inside it.
+fn strip_dir<'a>(path: &'a str, dir: &str) -> Option<&'a str> {
+    let rest = path.strip_prefix(dir)?;
+    (rest.is_empty() || rest.starts_with(['/', '\\'])).then_some(rest)
+}
+
+/// The root and first component of a path outside home, and the rest.
+fn split_first(path: &str) -> (String, &str) {
+    let trimmed = path.trim_start_matches(['/', '\\']);
+    let root = &path[..path.len() - trimmed.len()];
+    let (first, rest) = match trimmed.find(['/', '\\']) {
+        Some(i) => trimmed.split_at(i),
+        None => (trimmed, ""),
+    };
+    (format!("{root}{first}"), rest)
+}
+
+fn is_path(word: &str) -> bool {
+    let b = word.as_bytes();
+    word.starts_with('/')
+        || word.starts_with("~/")
+        || word.starts_with("\\\\")
+        || (b.len() > 2
+            && b[0].is_ascii_alphabetic()
+            && b[1] == b':'
+            && matches!(b[2], b'\\' | b'/'))
+}
+
+fn is_email(word: &str) -> bool {
+    match word.split_once('@') {
+        Some((user, domain)) => {
+            !user.is_empty() && !user.contains('/') && domain.contains('.') && !domain.contains(':')
+        }
+        None => false,
+    }
+}
+
+/// `git@github.com:owner/repo.git`
+fn is_scp_remote(word: &str) -> bool {
+    match word.split_once('@') {
+        Some((user, rest)) => {
+            !user.is_empty()
+                && !user.contains('/')
+                && rest
+                    .split_once(':')
+                    .is_some_and(|(h, p)| h.contains('.') && !p.is_empty() && !p.starts_with('/'))
+        }
+        None => false,
+    }
+}
+
+/// 8 hex digits of FNV-1a, stable across runs and platforms.
+fn hash(s: &str) -> String {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in s.bytes() {
+        h ^= u64::from(b);
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    format!("{:08x}", (h >> 32) as u32 ^ h as u32)
+}
+
+/// Redact every string in `value`, object keys included, with [`Redactor::text`].
+pub fn redact_json(redactor: &Redactor, value: &mut serde_json::Value) {
+    redact_json_with(value, &|s| redactor.text(s), &|k| redactor.text(k));
+}
+
+/// Redact a serialized [`AppConfig`](crate::config::AppConfig): [`redact_json`], except
+/// that `authors` holds nothing but people (every key and string is an
+/// [identity](Redactor::identity)) and `actions` and `policies` are keyed by names the user
+/// chose, which are hashed.
+pub fn redact_config(redactor: &Redactor, config: &mut serde_json::Value) {
+    let serde_json::Value::Object(sections) = config else {
+        return redact_json(redactor, config);
+    };
+    for (section, value) in sections.iter_mut() {
+        match section.as_str() {
+            "authors" => {
+                let identity = |s: &str| redactor.identity(s);
+                // Keys are the section's own fields; below them everything is a person
+                if let serde_json::Value::Object(fields) = value {
+                    for field in fields.values_mut() {
+                        redact_json_with(field, &identity, &identity);
+                    }
+                }
+            }
+            "actions" | "policies" => {
+                if let serde_json::Value::Object(named) = value {
+                    *named = std::mem::take(named)
+                        .into_iter()
+                        .map(|(name, mut item)| {
+                            redact_json(redactor, &mut item);
+                            (redactor.name(&name), item)
+                        })
+                        .collect();
+                }
+            }
+            _ => redact_json(redactor, value),
+        }
+    }
+}
+
+fn redact_json_with(
+    value: &mut serde_json::Value,
+    string: &dyn Fn(&str) -> String,
+    key: &dyn Fn(&str) -> String,
+) {
+    match value {
+        serde_json::Value::String(s) => *s = string(s),
+        serde_json::Value::Array(items) => {
+            for item in items {
+                redact_json_with(item, string, key);
+            }
+        }
+        serde_json::Value::Object(map) => {
+            *map = std::mem::take(map)
+                .into_iter()
+                .map(|(k, mut item)| {
+                    redact_json_with(&mut item, string, key);
+                    (key(&k), item)
+                })
+                .collect();
+        }
+        _ => {}
+    }
+}
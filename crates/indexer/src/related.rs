@@ -0,0 +1,200 @@
+//! "Related to this one": other indexed projects scored by shared dependencies (rare ones
+//! count more), the same git remote owner, a similar language mix and nearby paths.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::db::{Db, ProjectRecord};
+
+/// Share of the score each signal can contribute
+const DEPS_WEIGHT: f64 = 0.4;
+const OWNER_WEIGHT: f64 = 0.25;
+const LANGUAGE_WEIGHT: f64 = 0.2;
+const PATH_WEIGHT: f64 = 0.15;
+/// Anything scoring lower is not worth showing
+const MIN_SCORE: f64 = 0.15;
+/// Shared dependencies named in a reason
+const NAMED_DEPS: usize = 3;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RelatedProject {
+    pub project: ProjectRecord,
+    /// 0..1
+    pub score: f64,
+    /// Why it is related, e.g. "same owner github.com/acme"
+    pub reasons: Vec<String>,
+}
+
+/// `host/owner` of a git remote, for `https://host/owner/repo` and `git@host:owner/repo`.
+pub fn remote_owner(url: &str) -> Option<String> {
+    let rest = url.split_once("://").map_or(url, |(_, r)| r);
+    let rest = rest.rsplit_once('@').map_or(rest, |(_, r)| r);
+    let (host, path) = rest.split_once(['/', ':'])?;
+    let owner = path.trim_start_matches('/').split('/').next()?;
+    (!host.is_empty() && !owner.is_empty())
+        .then(|| format!("{}/{}", host.to_lowercase(), owner.to_lowercase()))
+}
+
+struct Candidate {
+    id: i64,
+    path: String,
+    owner: Option<String>,
+}
+
+fn cosine(a: &HashMap<String, f64>, b: &HashMap<String, f64>) -> f64 {
+    let dot: f64 = a.iter().filter_map(|(k, x)| b.get(k).map(|y| x * y)).sum();
+    let norm = |v: &HashMap<String, f64>| v.values().map(|x| x * x).sum::<f64>().sqrt();
+    let n = norm(a) * norm(b);
+    if n > 0.0 {
+        dot / n
+    } else {
+        0.0
+    }
+}
+
+/// 1 for siblings, 0.5 for projects sharing a grandparent, else 0.
+fn path_proximity(a: &str, b: &str) -> f64 {
+    let (a, b) = (Path::new(a), Path::new(b));
+    match (a.parent(), b.parent()) {
+        (Some(pa), Some(pb)) if pa == pb => 1.0,
+        (Some(pa), Some(pb)) if pa.parent().is_some() && pa.parent() == pb.parent() => 0.5,
+        _ => 0.0,
+    }
+}
+
+impl Db {
+    /// Projects related to `project_id`, most related first.
+    pub fn related_projects(&self, project_id: i64, limit: usize) -> Result<Vec<RelatedProject>> {
+        let candidates: Vec<Candidate> = {
+            let mut stmt = self.conn.prepare(
+                "SELECT p.id, p.path, g.remote_url FROM projects p \
+                 LEFT JOIN git_info g ON g.project_id = p.id",
+            )?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok(Candidate {
+                        id: r.get(0)?,
+                        path: r.get(1)?,
+                        owner: r
+                            .get::<_, Option<String>>(2)?
+                            .as_deref()
+                            .and_then(remote_owner),
+                    })
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+        let Some(me) = candidates.iter().find(|c| c.id == project_id) else {
+            return Ok(Vec::new());
+        };
+
+        let mut deps: HashMap<i64, HashSet<String>> = HashMap::new();
+        {
+            let mut stmt = self.conn.prepare(
+                "SELECT DISTINCT project_id, ecosystem || ':' || name FROM dependencies",
+            )?;
+            for row in stmt.query_map([], |r| Ok((r.get::<_, i64>(0)?, r.get::<_, String>(1)?)))? {
+                let (id, dep) = row?;
+                deps.entry(id).or_default().insert(dep);
+            }
+        }
+        let mut langs: HashMap<i64, HashMap<String, f64>> = HashMap::new();
+        {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT project_id, language, code FROM loc_lang WHERE code > 0")?;
+            for row in stmt.query_map([], |r| {
+                Ok((
+                    r.get::<_, i64>(0)?,
+                    r.get::<_, String>(1)?,
+                    r.get::<_, f64>(2)?,
+                ))
+            })? {
+                let (id, lang, code) = row?;
+                langs.entry(id).or_default().insert(lang, code);
+            }
+        }
+
+        // Rare dependencies say more than ones every project has
+        let total = candidates.len().max(1) as f64;
+        let mut df: HashMap<&str, f64> = HashMap::new();
+        for set in deps.values() {
+            for d in set {
+                *df.entry(d.as_str()).or_default() += 1.0;
+            }
+        }
+        let idf = |d: &str| (total / df.get(d).copied().unwrap_or(1.0)).ln().max(0.0) + 0.1;
+        let empty = HashSet::new();
+        let my_deps = deps.get(&project_id).unwrap_or(&empty);
+        let my_weight: f64 = my_deps.iter().map(|d| idf(d)).sum();
+        let my_langs = langs.get(&project_id);
+
+        let mut scored = Vec::new();
+        for c in candidates.iter().filter(|c| c.id != project_id) {
+            let mut score = 0.0;
+            let mut reasons = Vec::new();
+
+            if let Some(theirs) = deps.get(&c.id).filter(|_| my_weight > 0.0) {
+                let mut shared: Vec<&String> = my_deps.intersection(theirs).collect();
+                if !shared.is_empty() {
+                    shared.sort_by(|a, b| idf(b).total_cmp(&idf(a)).then_with(|| a.cmp(b)));
+                    let weight: f64 = shared.iter().map(|d| idf(d)).sum();
+                    score += DEPS_WEIGHT * (weight / my_weight).min(1.0);
+                    let names: Vec<&str> = shared
+                        .iter()
+                        .take(NAMED_DEPS)
+                        .map(|d| d.split_once(':').map_or(d.as_str(), |(_, n)| n))
+                        .collect();
+                    reasons.push(format!(
+                        "shares {} dependenc{} ({})",
+                        shared.len(),
+                        if shared.len() == 1 { "y" } else { "ies" },
+                        names.join(", ")
+                    ));
+                }
+            }
+            if let (Some(a), Some(b)) = (&me.owner, &c.owner) {
+                if a == b {
+                    score += OWNER_WEIGHT;
+                    reasons.push(format!("same owner {a}"));
+                }
+            }
+            if let (Some(a), Some(b)) = (my_langs, langs.get(&c.id)) {
+                let sim = cosine(a, b);
+                score += LANGUAGE_WEIGHT * sim;
+                if sim >= 0.8 {
+                    reasons.push("similar language mix".into());
+                }
+            }
+            let near = path_proximity(&me.path, &c.path);
+            if near > 0.0 {
+                score += PATH_WEIGHT * near;
+                if let Some(parent) = Path::new(&c.path).parent().filter(|_| near == 1.0) {
+                    reasons.push(format!("next to it in {}", parent.display()));
+                } else {
+                    reasons.push("nearby path".into());
+                }
+            }
+
+            if score >= MIN_SCORE {
+                scored.push((score, c.id, reasons));
+            }
+        }
+        scored.sort_by(|a, b| b.0.total_cmp(&a.0).then_with(|| a.1.cmp(&b.1)));
+
+        let mut out = Vec::new();
+        for (score, id, reasons) in scored.into_iter().take(limit) {
+            if let Some(project) = self.project_by_id(id)? {
+                out.push(RelatedProject {
+                    project,
+                    score,
+                    reasons,
+                });
+            }
+        }
+        Ok(out)
+    }
+}
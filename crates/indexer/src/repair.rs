@@ -0,0 +1,171 @@
+//! `db repair`: find and fix rows that the schema should not allow but older builds or
+//! outside edits left behind — child rows whose project is gone, the same directory indexed
+//! twice under paths differing by a trailing slash or letter case, and blank names.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::db::Db;
+use crate::journal::PROJECT_TABLES;
+use crate::paths::trim_separators;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RepairReport {
+    /// Rows removed per table because their project no longer exists
+    pub orphans: BTreeMap<String, usize>,
+    /// Duplicate records folded into the one kept
+    pub merged: Vec<MergedDuplicate>,
+    /// Paths rewritten without a trailing slash
+    pub paths_normalized: Vec<String>,
+    /// Projects whose blank name was replaced by their directory name
+    pub names_fixed: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MergedDuplicate {
+    pub kept: String,
+    pub removed: String,
+}
+
+impl RepairReport {
+    pub fn is_clean(&self) -> bool {
+        self.orphans.is_empty()
+            && self.merged.is_empty()
+            && self.paths_normalized.is_empty()
+            && self.names_fixed.is_empty()
+    }
+}
+
+/// Whether two stored paths that differ only by case name the same directory, i.e. live on a
+/// case-insensitive volume.
+fn same_dir(a: &str, b: &str) -> bool {
+    if trim_separators(a) == trim_separators(b) {
+        return true;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        match (std::fs::metadata(a), std::fs::metadata(b)) {
+            (Ok(x), Ok(y)) => x.dev() == y.dev() && x.ino() == y.ino(),
+            _ => false,
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+            (Ok(x), Ok(y)) => x == y,
+            _ => false,
+        }
+    }
+}
+
+impl Db {
+    /// Check the index and, unless `dry_run`, fix what is found. Duplicate records are merged
+    /// with [`Db::merge_projects`], so each merge can be undone; the most recently updated
+    /// record is kept.
+    pub fn repair(&self, dry_run: bool) -> Result<RepairReport> {
+        let mut report = RepairReport::default();
+
+        for table in PROJECT_TABLES {
+            let where_sql = "project_id NOT IN (SELECT id FROM projects)";
+            let n: usize = self.conn.query_row(
+                &format!("SELECT COUNT(*) FROM {table} WHERE {where_sql}"),
+                [],
+                |r| r.get(0),
+            )?;
+            if n > 0 {
+                if !dry_run {
+                    self.conn
+                        .execute(&format!("DELETE FROM {table} WHERE {where_sql}"), [])?;
+                }
+                report.orphans.insert(table.to_string(), n);
+            }
+        }
+
+        let rows = {
+            let mut stmt = self
+                .conn
+                .prepare("SELECT id, path, name FROM projects ORDER BY updated_at DESC, id DESC")?;
+            let rows = stmt
+                .query_map([], |r| {
+                    Ok((
+                        r.get::<_, i64>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, Option<String>>(2)?,
+                    ))
+                })?
+                .collect::<Result<Vec<_>, _>>()?;
+            rows
+        };
+
+        // Newest first, so the first record seen in a group is the one kept
+        let mut groups: BTreeMap<String, Vec<(i64, String)>> = BTreeMap::new();
+        for (id, path, _) in &rows {
+            groups
+                .entry(trim_separators(path).to_lowercase())
+                .or_default()
+                .push((*id, path.clone()));
+        }
+        let mut removed = Vec::new();
+        for members in groups.values() {
+            // Split the group into records that really share a directory
+            let mut clusters: Vec<Vec<&(i64, String)>> = Vec::new();
+            for m in members {
+                match clusters.iter_mut().find(|c| same_dir(&c[0].1, &m.1)) {
+                    Some(c) => c.push(m),
+                    None => clusters.push(vec![m]),
+                }
+            }
+            for cluster in clusters {
+                let (keep_id, keep_path) = cluster[0];
+                for (id, path) in &cluster[1..] {
+                    if !dry_run {
+                        self.merge_projects(*keep_id, *id)?;
+                    }
+                    removed.push(*id);
+                    report.merged.push(MergedDuplicate {
+                        kept: keep_path.clone(),
+                        removed: path.clone(),
+                    });
+                }
+                let trimmed = trim_separators(keep_path);
+                // Only safe once nothing else holds the trimmed path
+                let taken = members
+                    .iter()
+                    .any(|(id, p)| id != keep_id && !removed.contains(id) && p == trimmed);
+                if trimmed != keep_path && !taken {
+                    if !dry_run {
+                        self.conn.execute(
+                            "UPDATE projects SET path = ?2 WHERE id = ?1",
+                            params![keep_id, trimmed],
+                        )?;
+                    }
+                    report.paths_normalized.push(trimmed.to_string());
+                }
+            }
+        }
+
+        for (id, path, name) in &rows {
+            if removed.contains(id) || name.as_deref().is_some_and(|n| !n.trim().is_empty()) {
+                continue;
+            }
+            let trimmed = trim_separators(path);
+            let fixed = Path::new(trimmed)
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_else(|| trimmed.to_string());
+            if !dry_run {
+                self.conn.execute(
+                    "UPDATE projects SET name = ?2 WHERE id = ?1",
+                    params![id, fixed],
+                )?;
+            }
+            report.names_fixed.push(trimmed.to_string());
+        }
+
+        Ok(report)
+    }
+}
@@ -0,0 +1,165 @@
+//! Picking up after a scan that died (laptop asleep, out of memory, killed). Discovery
+//! checkpoints each root as it goes: the last project found, and whether the root is done.
+//! `scan --resume` skips finished roots and continues the others from their checkpoint.
+//! The walk is sorted by name, so everything before the checkpoint has been seen.
+//!
+//! Enrichment is tracked per project instead: discovery flags each project
+//! `enrich_pending` and enrichment clears the flag, so projects a dead scan found but never
+//! measured are enriched by whichever scan runs next, resumed or not.
+
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+use crate::db::Db;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS scan_checkpoints (
+      root TEXT PRIMARY KEY,
+      -- The last project discovered, as walked; NULL before the first
+      last_path TEXT,
+      done INTEGER NOT NULL DEFAULT 0,
+      updated_at INTEGER NOT NULL
+    );
+"#;
+
+/// How far an interrupted scan got through one root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ScanCheckpoint {
+    pub root: String,
+    pub last_path: Option<String>,
+    /// Discovery finished; only enrichment may be left
+    pub done: bool,
+    pub updated_at: i64,
+}
+
+/// Where discovery of a root starts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum RootStart {
+    Fresh,
+    /// Continue from this project
+    After(PathBuf),
+    /// Discovered by the interrupted scan already
+    Done,
+}
+
+fn now() -> i64 {
+    OffsetDateTime::now_utc().unix_timestamp()
+}
+
+fn key(root: &Path) -> String {
+    root.to_string_lossy().into_owned()
+}
+
+/// Whether a walk resuming from `last` can skip `dir`: everything sorted before it, except
+/// the directories leading down to it. `last` itself is walked again, as the scan may have
+/// died before reaching the projects nested inside it.
+pub(crate) fn walked_before(dir: &Path, last: &Path) -> bool {
+    dir < last && !last.starts_with(dir)
+}
+
+impl Db {
+    /// Checkpoints left by a scan that did not finish.
+    pub fn interrupted_scan(&self) -> Result<Vec<ScanCheckpoint>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT {CHECKPOINT_COLUMNS} FROM scan_checkpoints ORDER BY updated_at"
+        ))?;
+        let rows = stmt
+            .query_map([], checkpoint_from_row)?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    pub(crate) fn scan_checkpoint(&self, root: &Path) -> Result<Option<ScanCheckpoint>> {
+        Ok(self
+            .conn
+            .query_row(
+                &format!("SELECT {CHECKPOINT_COLUMNS} FROM scan_checkpoints WHERE root = ?1"),
+                params![key(root)],
+                checkpoint_from_row,
+            )
+            .optional()?)
+    }
+
+    /// Start `root` over, or when `resume` is set continue from its existing checkpoint.
+    pub(crate) fn begin_root(&self, root: &Path, resume: bool) -> Result<RootStart> {
+        if resume {
+            match self.scan_checkpoint(root)? {
+                Some(c) if c.done => return Ok(RootStart::Done),
+                Some(ScanCheckpoint {
+                    last_path: Some(last),
+                    ..
+                }) => return Ok(RootStart::After(PathBuf::from(last))),
+                _ => {}
+            }
+        }
+        self.conn.execute(
+            "INSERT INTO scan_checkpoints (root, last_path, done, updated_at) \
+             VALUES (?1, NULL, 0, ?2) \
+             ON CONFLICT(root) DO UPDATE SET last_path = NULL, done = 0, updated_at = ?2",
+            params![key(root), now()],
+        )?;
+        Ok(RootStart::Fresh)
+    }
+
+    pub(crate) fn checkpoint_root(&self, root: &Path, last_path: &Path) -> Result<()> {
+        self.conn.execute(
+            "UPDATE scan_checkpoints SET last_path = ?2, updated_at = ?3 WHERE root = ?1",
+            params![key(root), key(last_path), now()],
+        )?;
+        Ok(())
+    }
+
+    pub(crate) fn finish_root(&self, root: &Path) -> Result<()> {
+        self.conn.execute(
+            "UPDATE scan_checkpoints SET done = 1, updated_at = ?2 WHERE root = ?1",
+            params![key(root), now()],
+        )?;
+        Ok(())
+    }
+
+    /// Forget the checkpoints of `roots` once a scan of them has finished.
+    pub(crate) fn clear_checkpoints(&self, roots: &[PathBuf]) -> Result<()> {
+        for root in roots {
+            self.conn.execute(
+                "DELETE FROM scan_checkpoints WHERE root = ?1",
+                params![key(root)],
+            )?;
+        }
+        Ok(())
+    }
+
+    pub(crate) fn set_enrich_pending(&self, project_id: i64, pending: bool) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET enrich_pending = ?2 WHERE id = ?1",
+            params![project_id, pending],
+        )?;
+        Ok(())
+    }
+
+    /// Projects discovered by a scan that died before enriching them.
+    pub fn pending_enrichment(&self) -> Result<Vec<(i64, PathBuf)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, path FROM projects WHERE enrich_pending = 1 ORDER BY id")?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok((r.get::<_, i64>(0)?, PathBuf::from(r.get::<_, String>(1)?)))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
+
+const CHECKPOINT_COLUMNS: &str = "root, last_path, done, updated_at";
+
+fn checkpoint_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ScanCheckpoint> {
+    Ok(ScanCheckpoint {
+        root: row.get(0)?,
+        last_path: row.get(1)?,
+        done: row.get(2)?,
+        updated_at: row.get(3)?,
+    })
+}
@@ -0,0 +1,189 @@
+//! First-run root discovery: look at the usual places people keep code and estimate how
+//! many projects each holds, so setup can offer them instead of asking for paths.
+
+use anyhow::{anyhow, Result};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::AppConfig;
+use crate::detect::{detect_primary_type, ProjectType};
+
+/// Directories under the home directory worth suggesting
+pub const CANDIDATE_DIRS: &[&str] = &[
+    "Code",
+    "code",
+    "Projects",
+    "projects",
+    "src",
+    "Developer",
+    "dev",
+    "work",
+    "Work",
+    "repos",
+    "git",
+    "workspace",
+];
+
+/// How deep below a candidate to look for projects
+const SAMPLE_DEPTH: usize = 3;
+/// Directories visited per candidate before giving up and reporting a lower bound
+const SAMPLE_BUDGET: usize = 2000;
+const QUICK_SCAN_DEPTH: usize = 4;
+const QUICK_SCAN_BUDGET: usize = 5000;
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct RootSuggestion {
+    pub path: PathBuf,
+    /// Projects found within `SAMPLE_DEPTH` levels
+    pub estimated_projects: u32,
+    /// The sample hit its budget, so there are probably more
+    pub truncated: bool,
+    /// Already one of (or inside one of) the configured roots
+    pub configured: bool,
+}
+
+/// `suggest_roots` for the current user's home directory.
+pub fn suggest_home_roots(cfg: &AppConfig) -> Result<Vec<RootSuggestion>> {
+    let home = dirs_next::home_dir().ok_or_else(|| anyhow!("could not resolve home directory"))?;
+    Ok(suggest_roots(&home, cfg))
+}
+
+/// Candidate roots under `home` that exist and contain at least one project, most projects
+/// first.
+pub fn suggest_roots(home: &Path, cfg: &AppConfig) -> Vec<RootSuggestion> {
+    let mut seen: Vec<PathBuf> = Vec::new();
+    let mut out = Vec::new();
+    for name in CANDIDATE_DIRS {
+        let path = home.join(name);
+        if !path.is_dir() {
+            continue;
+        }
+        // `work` and `Work` are the same directory on case-insensitive filesystems
+        if seen.iter().any(|s| same_dir(s, &path)) {
+            continue;
+        }
+        seen.push(path.clone());
+        let (estimated_projects, truncated) = sample(&path, cfg);
+        if estimated_projects == 0 {
+            continue;
+        }
+        out.push(RootSuggestion {
+            configured: cfg.roots.iter().any(|r| path.starts_with(r)),
+            path,
+            estimated_projects,
+            truncated,
+        });
+    }
+    out.sort_by_key(|s| std::cmp::Reverse(s.estimated_projects));
+    out
+}
+
+#[cfg(unix)]
+fn same_dir(a: &Path, b: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    match (fs::metadata(a), fs::metadata(b)) {
+        (Ok(a), Ok(b)) => a.dev() == b.dev() && a.ino() == b.ino(),
+        _ => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn same_dir(a: &Path, b: &Path) -> bool {
+    match (fs::canonicalize(a), fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// A project found by `quick_scan`; nothing is written to the index.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct FoundProject {
+    pub name: String,
+    pub path: PathBuf,
+    pub project_type: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct QuickScan {
+    pub projects: Vec<FoundProject>,
+    /// Stopped at the project limit or the directory budget; a full scan will find more
+    pub truncated: bool,
+}
+
+/// Detection-only preview of `root`: the first `limit` projects within a few levels, fast
+/// enough for first-run setup. Metrics, git info and analyzers are left to the real scan.
+pub fn quick_scan(root: &Path, cfg: &AppConfig, limit: usize) -> QuickScan {
+    let mut scan = QuickScan::default();
+    scan.truncated = walk_projects(root, cfg, QUICK_SCAN_DEPTH, QUICK_SCAN_BUDGET, |dir, ty| {
+        scan.projects.push(FoundProject {
+            name: dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            path: dir.to_path_buf(),
+            project_type: ty.as_str().to_string(),
+        });
+        scan.projects.len() < limit
+    });
+    scan
+}
+
+/// Count project directories below `root`; true when the budget ran out first.
+fn sample(root: &Path, cfg: &AppConfig) -> (u32, bool) {
+    let mut projects = 0u32;
+    let truncated = walk_projects(root, cfg, SAMPLE_DEPTH, SAMPLE_BUDGET, |_, _| {
+        projects += 1;
+        true
+    });
+    (projects, truncated)
+}
+
+/// Breadth-first walk below `root` calling `found` for each project directory, without
+/// descending into projects. Stops when `found` returns false or after visiting `budget`
+/// directories; returns whether it stopped early.
+fn walk_projects(
+    root: &Path,
+    cfg: &AppConfig,
+    max_depth: usize,
+    budget: usize,
+    mut found: impl FnMut(&Path, ProjectType) -> bool,
+) -> bool {
+    let mut queue = VecDeque::from([(root.to_path_buf(), 0usize)]);
+    let mut visited = 0usize;
+    while let Some((dir, depth)) = queue.pop_front() {
+        visited += 1;
+        if visited > budget {
+            return true;
+        }
+        if depth > 0 {
+            if let Some(ty) = detect_primary_type(&dir, &cfg.type_priority) {
+                if !found(&dir, ty) {
+                    return true;
+                }
+                continue;
+            }
+        }
+        if depth == max_depth {
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || cfg.global_ignores.iter().any(|ign| *ign == name) {
+                continue;
+            }
+            if entry.file_type().map(|t| t.is_dir()).unwrap_or(false) {
+                queue.push_back((entry.path(), depth + 1));
+            }
+        }
+    }
+    false
+}
@@ -0,0 +1,180 @@
+//! Index access for the CLI and the app: through the `watch` daemon when one is listening,
+//! so scans are queued with it instead of racing it for the database, and in this process
+//! otherwise. The same `Db` methods answer either way.
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::analyzers::AnalyzerRegistry;
+use crate::config::AppConfig;
+use crate::daemon::{DaemonClient, DaemonStatus, Request};
+use crate::db::{Db, ProjectAggregates, ProjectFilter, ProjectRecord, SortKey};
+use crate::query::Query;
+use crate::queue::{QueuedRoot, ScanPriority, ScanQueue};
+use crate::scan::{ScanOptions, ScanReport};
+use crate::search::SearchHit;
+
+/// One page of the project list, filtered and sorted.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProjectsRequest {
+    /// Substring of name or path
+    pub search: Option<String>,
+    /// Filter expression (see `query`)
+    pub query: Option<String>,
+    pub branch: Option<String>,
+    pub has_remote: Option<bool>,
+    pub dirty: Option<bool>,
+    pub sort: SortKey,
+    pub ascending: bool,
+    pub page: u32,
+    pub page_size: u32,
+    /// Also summarise every match (count, size, LOC, types)
+    pub aggregates: bool,
+}
+
+impl ProjectsRequest {
+    pub fn filter(&self) -> Result<ProjectFilter> {
+        Ok(ProjectFilter {
+            search: self.search.clone(),
+            query: self.query.as_deref().map(Query::parse).transpose()?,
+            branch: self.branch.clone(),
+            has_remote: self.has_remote,
+            dirty: self.dirty,
+            ..Default::default()
+        })
+    }
+}
+
+/// One page of `ProjectsRequest` results.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ProjectsPage {
+    pub items: Vec<ProjectRecord>,
+    pub page: u32,
+    pub page_size: u32,
+    pub total_count: u32,
+    /// Present when requested with `aggregates: true`
+    pub aggregates: Option<ProjectAggregates>,
+}
+
+impl Db {
+    pub fn projects_page(&self, req: &ProjectsRequest) -> Result<ProjectsPage> {
+        let filter = req.filter()?;
+        // The aggregate pass already counts matches, so skip the separate COUNT(*)
+        let (total_count, aggregates) = if req.aggregates {
+            let agg = self.aggregate_projects(&filter)?;
+            (agg.count, Some(agg))
+        } else {
+            (self.count_projects(&filter)?, None)
+        };
+        let items =
+            self.query_projects(&filter, req.sort, req.ascending, req.page, req.page_size)?;
+        Ok(ProjectsPage {
+            items,
+            page: req.page,
+            page_size: req.page_size,
+            total_count,
+            aggregates,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum ScanOutcome {
+    /// Handed to the daemon; these roots are waiting there
+    Queued(Vec<QueuedRoot>),
+    /// Scanned in this process
+    Scanned(ScanReport),
+}
+
+pub enum Backend {
+    Daemon(Mutex<DaemonClient>),
+    Local(Db),
+}
+
+impl Backend {
+    /// The daemon for the current data directory if one is running, else the default
+    /// database opened here.
+    pub fn connect() -> Result<Self> {
+        match DaemonClient::connect()? {
+            Some(client) => Ok(Self::Daemon(Mutex::new(client))),
+            None => Ok(Self::Local(Db::open_default()?)),
+        }
+    }
+
+    /// The running daemon, without the fallback.
+    pub fn daemon() -> Result<Option<Self>> {
+        Ok(DaemonClient::connect()?.map(|client| Self::Daemon(Mutex::new(client))))
+    }
+
+    pub fn is_daemon(&self) -> bool {
+        matches!(self, Self::Daemon(_))
+    }
+
+    /// The daemon's status; None when running locally.
+    pub fn status(&self) -> Result<Option<DaemonStatus>> {
+        match self {
+            Self::Daemon(client) => Ok(Some(ask(client, &Request::Status)?)),
+            Self::Local(_) => Ok(None),
+        }
+    }
+
+    /// Scan `roots`, highest priority first: queued with the daemon (which applies its own
+    /// options) or run here with `opts` before returning.
+    pub fn scan(
+        &self,
+        cfg: &AppConfig,
+        roots: &[(PathBuf, ScanPriority)],
+        opts: &ScanOptions,
+        analyzers: &AnalyzerRegistry,
+    ) -> Result<ScanOutcome> {
+        match self {
+            Self::Daemon(client) => {
+                for (root, priority) in roots {
+                    let req = Request::Rescan {
+                        root: root.clone(),
+                        priority: Some(*priority),
+                    };
+                    ask::<DaemonStatus>(client, &req)?;
+                }
+                let status: DaemonStatus = ask(client, &Request::Status)?;
+                Ok(ScanOutcome::Queued(status.queued))
+            }
+            Self::Local(db) => {
+                let queue = ScanQueue::default();
+                for (root, priority) in roots {
+                    queue.enqueue(root.clone(), *priority);
+                }
+                Ok(ScanOutcome::Scanned(queue.run(db, cfg, opts, analyzers)?))
+            }
+        }
+    }
+
+    pub fn projects(&self, req: &ProjectsRequest) -> Result<ProjectsPage> {
+        match self {
+            Self::Daemon(client) => ask(client, &Request::Projects(req.clone())),
+            Self::Local(db) => db.projects_page(req),
+        }
+    }
+
+    pub fn search(&self, q: &str, limit: u32) -> Result<Vec<SearchHit>> {
+        match self {
+            Self::Daemon(client) => ask(
+                client,
+                &Request::Search {
+                    q: q.to_string(),
+                    limit,
+                },
+            ),
+            Self::Local(db) => db.search(q, limit),
+        }
+    }
+}
+
+fn ask<T: DeserializeOwned>(client: &Mutex<DaemonClient>, req: &Request) -> Result<T> {
+    let result = client.lock().unwrap_or_else(|e| e.into_inner()).call(req)?;
+    Ok(serde_json::from_value(result)?)
+}
@@ -0,0 +1,174 @@
+use serde_json::{json, Value};
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+use crate::db::ProjectRecord;
+use crate::deps::Dependency;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    CycloneDx,
+    /// SPDX 2.3 JSON
+    SpdxJson,
+}
+
+impl SbomFormat {
+    /// Conventional file suffix for the format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SbomFormat::CycloneDx => "cdx.json",
+            SbomFormat::SpdxJson => "spdx.json",
+        }
+    }
+}
+
+const TOOL_NAME: &str = "project-browser";
+const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub fn render(format: SbomFormat, project: &ProjectRecord, deps: &[Dependency]) -> Value {
+    // Lockfiles can list the same package at several install paths; one entry per purl.
+    let mut seen = std::collections::HashSet::new();
+    let deps = deps
+        .iter()
+        .filter(|d| seen.insert(d.purl()))
+        .cloned()
+        .collect::<Vec<_>>();
+    match format {
+        SbomFormat::CycloneDx => cyclonedx(project, &deps),
+        SbomFormat::SpdxJson => spdx(project, &deps),
+    }
+}
+
+fn now_rfc3339() -> String {
+    OffsetDateTime::now_utc()
+        .replace_nanosecond(0)
+        .ok()
+        .and_then(|t| t.format(&Rfc3339).ok())
+        .unwrap_or_default()
+}
+
+fn cyclonedx(project: &ProjectRecord, deps: &[Dependency]) -> Value {
+    let root_ref = format!("project:{}", project.name);
+    let components = deps
+        .iter()
+        .map(|d| {
+            let mut c = json!({
+                "type": "library",
+                "bom-ref": d.purl(),
+                "name": d.name,
+                "purl": d.purl(),
+            });
+            if let Some(v) = &d.version {
+                c["version"] = json!(v);
+            }
+            if d.dev {
+                c["scope"] = json!("optional");
+            }
+            c
+        })
+        .collect::<Vec<_>>();
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": now_rfc3339(),
+            "tools": [{ "name": TOOL_NAME, "version": TOOL_VERSION }],
+            "component": {
+                "type": "application",
+                "bom-ref": root_ref,
+                "name": project.name,
+                "properties": [
+                    { "name": "project-browser:path", "value": project.path },
+                    { "name": "project-browser:type", "value": project.project_type },
+                ],
+            },
+        },
+        "components": components,
+        "dependencies": [{
+            "ref": root_ref,
+            "dependsOn": deps.iter().map(|d| d.purl()).collect::<Vec<_>>(),
+        }],
+    })
+}
+
+fn spdx_id(s: &str) -> String {
+    // SPDX identifiers only allow letters, numbers, '.' and '-'
+    s.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+fn spdx(project: &ProjectRecord, deps: &[Dependency]) -> Value {
+    let created = now_rfc3339();
+    let root_id = format!("SPDXRef-Project-{}", spdx_id(&project.name));
+    let mut packages = vec![json!({
+        "SPDXID": root_id,
+        "name": project.name,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "comment": project.path,
+    })];
+    let mut relationships = vec![json!({
+        "spdxElementId": "SPDXRef-DOCUMENT",
+        "relationshipType": "DESCRIBES",
+        "relatedSpdxElement": root_id,
+    })];
+    for (i, d) in deps.iter().enumerate() {
+        let id = format!("SPDXRef-Package-{}-{}", i + 1, spdx_id(&d.name));
+        let mut pkg = json!({
+            "SPDXID": id,
+            "name": d.name,
+            "downloadLocation": "NOASSERTION",
+            "filesAnalyzed": false,
+            "externalRefs": [{
+                "referenceCategory": "PACKAGE-MANAGER",
+                "referenceType": "purl",
+                "referenceLocator": d.purl(),
+            }],
+        });
+        if let Some(v) = &d.version {
+            pkg["versionInfo"] = json!(v);
+        }
+        packages.push(pkg);
+        // DEV_DEPENDENCY_OF points from the dependency to the dependent
+        relationships.push(if d.dev {
+            json!({
+                "spdxElementId": id,
+                "relationshipType": "DEV_DEPENDENCY_OF",
+                "relatedSpdxElement": root_id,
+            })
+        } else {
+            json!({
+                "spdxElementId": root_id,
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": id,
+            })
+        });
+    }
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": project.name,
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/{}-{}-{}",
+            TOOL_NAME,
+            spdx_id(&project.name),
+            created.replace(':', "")
+        ),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: {TOOL_NAME}-{TOOL_VERSION}")],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
@@ -1,59 +1,366 @@
 use anyhow::Result;
 use ignore::{Walk, WalkBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
-#[cfg(feature = "analyzers")]
-use crate::analyzers::{compute_loc, compute_loc_breakdown};
-use crate::config::{AppConfig, ConfigStore, SizeMode};
-use crate::db::Db;
-use crate::detect::{detect_project_type, is_git_repo};
+use crate::activity::calendar_start;
+use crate::analyzers::{AnalyzerContext, AnalyzerRegistry};
+use crate::assets::{asset_category, AssetCounter, AssetTotal};
+use crate::config::{AppConfig, ConfigStore, NestedPolicy, RecencyMode, SizeMode};
+use crate::db::{Db, ProjectRecord};
+use crate::detect::{detect_primary_type, is_git_repo, ProjectType};
+use crate::moves::{fingerprint, MoveDetector, ProjectMove};
+use crate::preview::ProjectPreview;
+use crate::queue::ScanQueue;
+use crate::resume::{walked_before, RootStart};
+use crate::shape::{ShapeCounter, ShapeMetrics};
+#[cfg(not(feature = "git"))]
+use crate::vcs::last_commit_at;
 #[cfg(feature = "git")]
 use crate::vcs::read_git_info;
+use crate::vcs::{commit_times_since, tracked_files};
+use crate::workspace::workspace_members;
 
 #[derive(Debug, Clone, Default)]
 pub struct ScanOptions {
     pub dry_run: bool,
+    /// Stop after discovery: projects are stored with name, path and type only and keep
+    /// whatever metrics an earlier scan left
+    pub discover_only: bool,
+    /// Attribute every discovered project to this user (system-wide scans)
+    pub owner: Option<String>,
+    /// Pace walks and stats to `nice.ops_per_sec` and lower the scanning thread's CPU and IO
+    /// priority (see `nice`)
+    pub nice: bool,
+    /// Continue the scan that was interrupted instead of starting each root over (see
+    /// `resume`)
+    pub resume: bool,
 }
 
-pub fn scan_roots(db: &Db, cfg: &AppConfig, opts: &ScanOptions) -> Result<usize> {
-    let mut found: usize = 0;
-    for root in &cfg.roots {
-        if !root.exists() {
-            tracing::warn!(?root, "root does not exist; skipping");
-            continue;
-        }
-        let mut wb = WalkBuilder::new(root);
-        wb.git_ignore(true).hidden(true).ignore(true);
-        // Per-user/app ignore files if present
-        if let Ok(app_ign) = ConfigStore::app_ignore_path() {
-            if app_ign.exists() {
-                wb.add_ignore(app_ign);
+/// Build and dependency output directories, measured (per directory and as
+/// `artifact_bytes`) when found directly inside a project: regenerable, so the first thing to reclaim.
+pub const ARTIFACT_DIRS: &[&str] = &[
+    "node_modules",
+    "target",
+    "build",
+    "dist",
+    ".next",
+    ".nuxt",
+    ".venv",
+    "venv",
+    "__pycache__",
+    ".tox",
+    ".gradle",
+    "Pods",
+    "DerivedData",
+];
+
+/// An artifact directory as a scan measured it: its size, and a fingerprint of its top
+/// levels so the next scan can tell whether it needs measuring again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactSize {
+    /// Relative to the project, or absolute (Xcode DerivedData)
+    pub dir: String,
+    pub bytes: i64,
+    /// See `tree_fingerprint`
+    pub fingerprint: Option<i64>,
+}
+
+/// What artifact measurement shares across the projects of one scan.
+#[derive(Default)]
+pub(crate) struct ArtifactScan {
+    /// Xcode DerivedData folders by project directory, listed on first use
+    derived_data: std::cell::OnceCell<HashMap<PathBuf, Vec<PathBuf>>>,
+}
+
+impl ArtifactScan {
+    fn derived_data(&self, root: &Path) -> Vec<String> {
+        self.derived_data
+            .get_or_init(|| {
+                crate::mobile::default_derived_data_dir()
+                    .map(|dd| crate::mobile::derived_data_by_project(&dd))
+                    .unwrap_or_default()
+            })
+            .get(root)
+            .into_iter()
+            .flatten()
+            .map(|d| d.to_string_lossy().into_owned())
+            .collect()
+    }
+}
+
+/// Caches only some project types produce, measured as artifacts of those types alone:
+/// names like `Library` or `Intermediate` mean nothing elsewhere.
+pub const TYPE_ARTIFACT_DIRS: &[(ProjectType, &[&str])] = &[
+    (ProjectType::Unity, &["Library", "Temp", "obj"]),
+    // Not `Saved` (save games, editor config) or `Binaries` (plugins often ship theirs
+    // prebuilt): neither is safe to delete
+    (ProjectType::Unreal, &["Intermediate", "DerivedDataCache"]),
+    (ProjectType::Godot, &[".godot", ".import"]),
+];
+
+/// Where a scan is up to, for callers that want to refresh as rows land. Discovery upserts
+/// every project in every queued root before enrichment starts, so the list can be shown
+/// after `Discovered` and fills in as `Enriched` reports come in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum ScanProgress {
+    Discovered { projects: usize },
+    Enriched { done: usize, total: usize },
+}
+
+/// A project found by discovery, waiting for enrichment.
+#[derive(Debug, Clone)]
+pub(crate) struct Discovered {
+    /// `None` on dry runs, where nothing is written
+    id: Option<i64>,
+    path: PathBuf,
+    ptype: ProjectType,
+    git: bool,
+}
+
+/// Outcome of a scan: how many projects were found and where the time went.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanReport {
+    pub projects: usize,
+    pub timings: ScanTimings,
+    /// Indexed projects found at a new path and updated in place
+    pub moves: Vec<ProjectMove>,
+    /// Dry runs only: every project found, with the values a real scan would store
+    pub previews: Vec<ProjectPreview>,
+}
+
+/// Wall time per scan phase, summed over all projects. Analyzer phases are named
+/// `analyzer:<name>`; `walk` is directory traversal outside of project processing.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanTimings {
+    pub total_ms: f64,
+    pub phases: Vec<PhaseTiming>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PhaseTiming {
+    pub phase: String,
+    pub total_ms: f64,
+    /// How many times the phase ran (usually once per project)
+    pub calls: u64,
+}
+
+impl ScanTimings {
+    fn add(&mut self, phase: &str, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        match self.phases.iter_mut().find(|p| p.phase == phase) {
+            Some(p) => {
+                p.total_ms += ms;
+                p.calls += 1;
             }
+            None => self.phases.push(PhaseTiming {
+                phase: phase.to_string(),
+                total_ms: ms,
+                calls: 1,
+            }),
         }
-        {
-            let legacy = ConfigStore::user_ignore_path_legacy();
-            if legacy.exists() {
-                wb.add_ignore(legacy);
-            }
+    }
+
+    /// Run `f` inside a `phase` span, recording its duration on the span and in the totals.
+    fn time<T>(&mut self, phase: &str, f: impl FnOnce() -> T) -> T {
+        let span = tracing::debug_span!("phase", phase, elapsed_ms = tracing::field::Empty);
+        let _enter = span.enter();
+        let started = Instant::now();
+        let out = f();
+        let elapsed = started.elapsed();
+        span.record("elapsed_ms", elapsed.as_secs_f64() * 1000.0);
+        self.add(phase, elapsed);
+        out
+    }
+
+    fn phase_ms(&self) -> f64 {
+        self.phases.iter().map(|p| p.total_ms).sum()
+    }
+
+    /// Phases sorted by time spent, largest first.
+    pub fn by_cost(&self) -> Vec<&PhaseTiming> {
+        let mut phases = self.phases.iter().collect::<Vec<_>>();
+        phases.sort_by(|a, b| b.total_ms.total_cmp(&a.total_ms));
+        phases
+    }
+}
+
+pub fn scan_roots(db: &Db, cfg: &AppConfig, opts: &ScanOptions) -> Result<usize> {
+    Ok(scan_roots_with(db, cfg, opts, &AnalyzerRegistry::with_builtins())?.projects)
+}
+
+/// Like `scan_roots`, but runs the given analyzers for each project instead of the built-ins
+/// and returns the full report including per-phase timings. Roots are scanned in
+/// `root_priority` order.
+pub fn scan_roots_with(
+    db: &Db,
+    cfg: &AppConfig,
+    opts: &ScanOptions,
+    analyzers: &AnalyzerRegistry,
+) -> Result<ScanReport> {
+    let queue = ScanQueue::default();
+    queue.enqueue_configured(cfg);
+    queue.run(db, cfg, opts, analyzers)
+}
+
+/// Phase 1 for one root: walk it and store every project it contains with name, path and
+/// type only, adding to `report`. Returns the projects for `enrich`.
+pub(crate) fn discover_root(
+    db: &Db,
+    cfg: &AppConfig,
+    opts: &ScanOptions,
+    root: &Path,
+    moves: &mut MoveDetector,
+    report: &mut ScanReport,
+) -> Result<Vec<Discovered>> {
+    if !root.exists() {
+        tracing::warn!(?root, "root does not exist; skipping");
+        return Ok(Vec::new());
+    }
+    let start = if opts.dry_run {
+        RootStart::Fresh
+    } else {
+        db.begin_root(root, opts.resume)?
+    };
+    let after = match start {
+        RootStart::Fresh => None,
+        RootStart::After(last) => {
+            tracing::info!(root = %root.display(), after = %last.display(), "resuming root");
+            Some(last)
         }
-        let walk = wb.build();
-        found += scan_one_root(db, cfg, opts, walk, root)?;
+        RootStart::Done => {
+            tracing::info!(root = %root.display(), "root already discovered; resuming enrichment only");
+            return Ok(Vec::new());
+        }
+    };
+    let walk = root_walker(cfg, root, None, after).build();
+    let span =
+        tracing::info_span!("scan_root", root = %root.display(), projects = tracing::field::Empty);
+    let _enter = span.enter();
+    let root_started = Instant::now();
+    let before = report.timings.phase_ms();
+    let found = discover_one_root(db, cfg, opts, root, walk, moves, &mut report.timings)?;
+    if !opts.dry_run {
+        db.finish_root(root)?;
     }
+    span.record("projects", found.len());
+    // Whatever the per-project phases did not account for was spent walking
+    let walk_ms =
+        root_started.elapsed().as_secs_f64() * 1000.0 - (report.timings.phase_ms() - before);
+    report
+        .timings
+        .add("walk", Duration::from_secs_f64(walk_ms.max(0.0) / 1000.0));
+    report.projects += found.len();
     Ok(found)
 }
 
-fn scan_one_root(
+/// The walk discovery makes over a root: hidden and ignored (gitignore, `.ignore`, the app
+/// ignore file) directories are skipped. When `nested` looks inside projects, dependency
+/// and build output directories are not descended into either, or every package under
+/// `node_modules` would be indexed. `toward` limits the walk to the ancestors of one path;
+/// `after` skips everything walked before a checkpointed project. Entries come in
+/// name order. Links and other volumes are followed as `walk` says, and a directory already
+/// walked through another path is not walked again.
+pub(crate) fn root_walker(
+    cfg: &AppConfig,
+    root: &Path,
+    toward: Option<PathBuf>,
+    after: Option<PathBuf>,
+) -> WalkBuilder {
+    let mut wb = WalkBuilder::new(root);
+    wb.sort_by_file_name(|a, b| a.cmp(b))
+        .git_ignore(true)
+        .hidden(true)
+        .ignore(true)
+        .follow_links(cfg.walk.follow_links)
+        .same_file_system(!cfg.walk.cross_mounts);
+    let prune: Vec<String> = match cfg.nested {
+        NestedPolicy::Skip => Vec::new(),
+        NestedPolicy::Index | NestedPolicy::IndexWithParentLink => cfg
+            .global_ignores
+            .iter()
+            .map(String::as_str)
+            .chain(ARTIFACT_DIRS.iter().copied())
+            .map(String::from)
+            .collect(),
+    };
+    let seen = std::sync::Mutex::new(HashSet::new());
+    wb.filter_entry(move |e| {
+        let is_dir = e.file_type().is_some_and(|ft| ft.is_dir());
+        if !is_dir {
+            return toward.is_none();
+        }
+        let name = e.file_name().to_string_lossy();
+        let pruned = e.depth() > 0 && prune.iter().any(|p| *p == name);
+        if pruned || !toward.as_ref().is_none_or(|t| t.starts_with(e.path())) {
+            return false;
+        }
+        if after.as_ref().is_some_and(|last| walked_before(e.path(), last)) {
+            return false;
+        }
+        let first_visit = dir_identity(e).is_none_or(|id| {
+            seen.lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .insert(id)
+        });
+        if !first_visit {
+            tracing::debug!(path = %e.path().display(), "directory already walked through another path");
+        }
+        first_visit
+    });
+    // Per-user/app ignore files if present
+    if let Ok(app_ign) = ConfigStore::app_ignore_path() {
+        if app_ign.exists() {
+            wb.add_ignore(app_ign);
+        }
+    }
+    if let Some(legacy) = ConfigStore::user_ignore_path_legacy() {
+        if legacy.exists() {
+            wb.add_ignore(legacy);
+        }
+    }
+    wb
+}
+
+/// What identifies a directory however it was reached: device and inode on Unix, which a
+/// firmlink or bind mount shares with the original.
+#[cfg(unix)]
+fn dir_identity(e: &ignore::DirEntry) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+    let md = e.metadata().ok()?;
+    Some((md.dev(), md.ino()))
+}
+
+/// Elsewhere, the resolved path of a link or junction; other directories are only reached
+/// one way.
+#[cfg(not(unix))]
+fn dir_identity(e: &ignore::DirEntry) -> Option<PathBuf> {
+    if e.path_is_symlink() {
+        dunce::canonicalize(e.path()).ok()
+    } else {
+        Some(e.path().to_path_buf())
+    }
+}
+
+fn discover_one_root(
     db: &Db,
     cfg: &AppConfig,
     opts: &ScanOptions,
+    root: &Path,
     walk: Walk,
-    _root: &Path,
-) -> Result<usize> {
-    let mut processed_roots: Vec<PathBuf> = Vec::new();
-    let mut count = 0usize;
+    moves: &mut MoveDetector,
+    timings: &mut ScanTimings,
+) -> Result<Vec<Discovered>> {
+    // Project roots seen so far, as walked, with their ids
+    let mut processed_roots: Vec<(PathBuf, Option<i64>)> = Vec::new();
+    let mut found: Vec<Discovered> = Vec::new();
 
     for res in walk {
+        crate::nice::pace();
         let entry = match res {
             Ok(e) => e,
             Err(err) => {
@@ -64,11 +371,15 @@ fn scan_one_root(
 
         let p = entry.path();
         if !entry.file_type().map(|ft| ft.is_dir()).unwrap_or(false) {
+            if entry.path_is_symlink() && !cfg.walk.follow_links && p.is_dir() {
+                tracing::debug!(path = %p.display(), "not following link (walk.follow_links)");
+            }
             continue;
         }
 
         // Skip directories under previously processed project roots to avoid double work
-        if processed_roots.iter().any(|r| p.starts_with(r)) {
+        if cfg.nested == NestedPolicy::Skip && processed_roots.iter().any(|(r, _)| p.starts_with(r))
+        {
             continue;
         }
 
@@ -80,82 +391,419 @@ fn scan_one_root(
         }
 
         // Detect project
-        if let Some(ptype) = detect_project_type(p) {
-            let name = p
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string();
-            let path_str = p.to_string_lossy().to_string();
-            let git = is_git_repo(p);
-
-            #[allow(unused_mut)]
-            let (size_bytes, files_count, mut last_edited_at) =
-                compute_metrics(p, cfg, git).unwrap_or((None, None, None));
-            #[cfg(feature = "analyzers")]
-            let loc = compute_loc(p);
-            #[cfg(not(feature = "analyzers"))]
-            let loc: Option<i64> = None;
-
-            // If available, use git last commit to improve recency
-            #[cfg(feature = "git")]
-            let git_info = {
-                let info = read_git_info(p);
-                if let Some(ts) = info.last_commit_at {
-                    if let Some(le) = last_edited_at {
-                        if ts > le {
-                            last_edited_at = Some(ts);
-                        }
-                    } else {
-                        last_edited_at = Some(ts);
-                    }
-                }
-                Some(info)
+        if let Some(ptype) = timings.time("detect", || detect_primary_type(p, &cfg.type_priority)) {
+            // Already indexed as a workspace member of a project above
+            let canonical = crate::paths::canonical(p);
+            if let Some(member) = found.iter().find(|d| d.path == canonical) {
+                processed_roots.push((p.to_path_buf(), member.id));
+                continue;
+            }
+            let parent = match cfg.nested {
+                NestedPolicy::IndexWithParentLink => processed_roots
+                    .iter()
+                    .filter(|(r, _)| p.starts_with(r))
+                    .max_by_key(|(r, _)| r.components().count())
+                    .and_then(|(_, id)| *id),
+                NestedPolicy::Skip | NestedPolicy::Index => None,
             };
-            #[cfg(not(feature = "git"))]
-            let _git_info: Option<()> = None;
-
-            if opts.dry_run {
-                tracing::info!(
-                    name=%name,
-                    path=%path_str,
-                    project_type=%ptype.as_str(),
-                    git=git,
-                    size=?size_bytes,
-                    files=?files_count,
-                    last_edited=?last_edited_at,
-                    "found project"
-                );
+            let project = discover_project(db, opts, p, ptype, moves, timings)?;
+            if let Some(id) = project.id {
+                // Top-level here (or under its enclosing project), even if it used to be a
+                // workspace member
+                db.set_parent(id, parent)?;
+            }
+            let (id, dir) = (project.id, project.path.clone());
+            found.push(project);
+            discover_members(db, cfg, opts, &dir, id, 0, moves, timings, &mut found)?;
+            processed_roots.push((p.to_path_buf(), id));
+            if !opts.dry_run {
+                db.checkpoint_root(root, p)?;
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// Workspaces nested deeper than this are not followed
+const MAX_WORKSPACE_DEPTH: usize = 3;
+
+/// Index the declared workspace members of the project at `dir` (which the root walk will
+/// not descend into) as children of `parent`, and their members in turn.
+#[allow(clippy::too_many_arguments)]
+fn discover_members(
+    db: &Db,
+    cfg: &AppConfig,
+    opts: &ScanOptions,
+    dir: &Path,
+    parent: Option<i64>,
+    depth: usize,
+    moves: &mut MoveDetector,
+    timings: &mut ScanTimings,
+    found: &mut Vec<Discovered>,
+) -> Result<()> {
+    if depth >= MAX_WORKSPACE_DEPTH {
+        return Ok(());
+    }
+    let mut linked = Vec::new();
+    for member in timings.time("workspace", || workspace_members(dir)) {
+        let Some(ptype) = timings.time("detect", || {
+            detect_primary_type(&member, &cfg.type_priority)
+        }) else {
+            continue;
+        };
+        let project = discover_project(db, opts, &member, ptype, moves, timings)?;
+        if let (Some(id), Some(parent)) = (project.id, parent) {
+            db.set_parent(id, Some(parent))?;
+            linked.push(id);
+        }
+        let (id, dir) = (project.id, project.path.clone());
+        found.push(project);
+        discover_members(db, cfg, opts, &dir, id, depth + 1, moves, timings, found)?;
+    }
+    // Members dropped from the manifest stay indexed but no longer belong to it
+    if let Some(parent) = parent.filter(|_| !opts.dry_run) {
+        for child in db.children_of(parent)? {
+            if !linked.contains(&child.id) {
+                db.set_parent(child.id, None)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Phase 2: metrics, git info and analyzers for each discovered project, committed one
+/// project at a time so readers see rows fill in. `progress` gets `(done, total)` after
+/// each project.
+pub(crate) fn enrich(
+    db: &Db,
+    cfg: &AppConfig,
+    opts: &ScanOptions,
+    analyzers: &AnalyzerRegistry,
+    projects: &[Discovered],
+    report: &mut ScanReport,
+    progress: &mut dyn FnMut(usize, usize),
+) -> Result<()> {
+    let artifact_scan = ArtifactScan::default();
+    for (i, project) in projects.iter().enumerate() {
+        let timings = &mut report.timings;
+        enrich_project(
+            db,
+            cfg,
+            opts,
+            analyzers,
+            project,
+            &artifact_scan,
+            timings,
+            &mut report.previews,
+        )?;
+        progress(i + 1, projects.len());
+    }
+    Ok(())
+}
+
+/// Projects left flagged `enrich_pending` by a scan that died, other than those in
+/// `discovered`, ready for `enrich`. Ones that are gone or no longer projects are unflagged.
+pub(crate) fn pending_projects(
+    db: &Db,
+    cfg: &AppConfig,
+    discovered: &[Discovered],
+) -> Result<Vec<Discovered>> {
+    let mut out = Vec::new();
+    for (id, path) in db.pending_enrichment()? {
+        if discovered.iter().any(|d| d.id == Some(id)) {
+            continue;
+        }
+        match detect_primary_type(&path, &cfg.type_priority) {
+            Some(ptype) => out.push(Discovered {
+                id: Some(id),
+                git: is_git_repo(&path),
+                path,
+                ptype,
+            }),
+            None => db.set_enrich_pending(id, false)?,
+        }
+    }
+    Ok(out)
+}
+
+/// Index a single directory outside of a root walk (e.g. one dropped onto the app).
+/// Returns the stored record, or `None` when `path` is not a recognised project.
+pub fn scan_project(db: &Db, cfg: &AppConfig, path: &Path) -> Result<Option<ProjectRecord>> {
+    if !path.is_dir() {
+        anyhow::bail!("{} is not a directory", path.display());
+    }
+    let Some(ptype) = detect_primary_type(path, &cfg.type_priority) else {
+        return Ok(None);
+    };
+    let analyzers = AnalyzerRegistry::with_builtins();
+    let opts = ScanOptions::default();
+    let mut timings = ScanTimings::default();
+    let project = discover_project(
+        db,
+        &opts,
+        path,
+        ptype,
+        &mut MoveDetector::default(),
+        &mut timings,
+    )?;
+    let id = enrich_project(
+        db,
+        cfg,
+        &opts,
+        &analyzers,
+        &project,
+        &ArtifactScan::default(),
+        &mut timings,
+        &mut Vec::new(),
+    )?;
+    match id {
+        Some(id) => db.project_by_id(id),
+        None => Ok(None),
+    }
+}
+
+/// Detection has already matched `p` as `ptype`: store the bare project row (unless this
+/// is a dry run) so it is listed before its metrics are known. A path new to the index
+/// may be a project that moved, in which case its old row is reused.
+fn discover_project(
+    db: &Db,
+    opts: &ScanOptions,
+    p: &Path,
+    ptype: ProjectType,
+    moves: &mut MoveDetector,
+    timings: &mut ScanTimings,
+) -> Result<Discovered> {
+    let canonical = crate::paths::canonical(p);
+    let p = canonical.as_path();
+    let git = is_git_repo(p);
+    let id = if opts.dry_run {
+        None
+    } else {
+        let name = p
+            .file_name()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        let path_str = p.to_string_lossy().to_string();
+        let fingerprint = timings.time("fingerprint", || fingerprint(p));
+        let volume = timings.time("location", || crate::location::volume_id(p));
+        Some(timings.time("db", || -> Result<i64> {
+            moves.detect(db, p, &name, git, fingerprint.as_deref())?;
+            let id = db.upsert_project(&name, &path_str, Some(ptype.as_str()), git)?;
+            db.set_path_bytes(id, crate::paths::raw_bytes(p).as_deref())?;
+            db.set_fingerprint(id, fingerprint.as_deref())?;
+            if let Some(owner) = &opts.owner {
+                db.set_owner(id, Some(owner))?;
+            }
+            db.set_location(id, crate::location::machine_name(), volume.as_deref())?;
+            if !opts.discover_only {
+                db.set_enrich_pending(id, true)?;
+            }
+            Ok(id)
+        })?)
+    };
+    Ok(Discovered {
+        id,
+        path: p.to_path_buf(),
+        ptype,
+        git,
+    })
+}
+
+/// Compute metrics and git info for a discovered project, store them and run the
+/// analyzers. Returns the project id unless this is a dry run, which instead adds what it
+/// would have stored to `previews`.
+#[allow(clippy::too_many_arguments)]
+fn enrich_project(
+    db: &Db,
+    cfg: &AppConfig,
+    opts: &ScanOptions,
+    analyzers: &AnalyzerRegistry,
+    project: &Discovered,
+    artifact_scan: &ArtifactScan,
+    timings: &mut ScanTimings,
+    previews: &mut Vec<ProjectPreview>,
+) -> Result<Option<i64>> {
+    let p = project.path.as_path();
+    let (ptype, git) = (project.ptype, project.git);
+    let _project = tracing::debug_span!("project", path = %p.display()).entered();
+
+    let ((size_bytes, files_count, mut last_edited_at), tree) = timings
+        .time("metrics", || walk_metrics(p, cfg))
+        .map_or(((None, None, None), None), |(m, tree)| (m, Some(tree)));
+    let previous = match project.id {
+        Some(id) => db.artifact_sizes(id)?,
+        None => Vec::new(),
+    };
+    let artifacts = timings.time("artifacts", || {
+        measure_artifacts_with(p, cfg, ptype, &previous, artifact_scan)
+    });
+    let commit_times = if git && !opts.dry_run {
+        timings.time("activity", || commit_times_since(p, calendar_start()))
+    } else {
+        None
+    };
+
+    // If available, use git last commit to improve recency
+    #[cfg(feature = "git")]
+    let git_info = {
+        let info = timings.time("git", || read_git_info(p));
+        if let Some(ts) = info.last_commit_at {
+            if let Some(le) = last_edited_at {
+                if ts > le {
+                    last_edited_at = Some(ts);
+                }
             } else {
-                let id = db.upsert_project(&name, &path_str, Some(ptype.as_str()), git)?;
-                db.upsert_metrics(id, size_bytes, files_count, last_edited_at, loc)?;
-                #[cfg(feature = "git")]
-                if let Some(info) = git_info {
-                    db.upsert_git_info(
-                        id,
-                        info.last_commit_at,
-                        info.branch.as_deref(),
-                        info.remote_url.as_deref(),
-                    )?;
+                last_edited_at = Some(ts);
+            }
+        }
+        Some(info)
+    };
+    #[cfg(not(feature = "git"))]
+    let _git_info: Option<()> = None;
+
+    if git {
+        match cfg.recency {
+            RecencyMode::Mtime => {}
+            RecencyMode::TrackedFiles => {
+                if let Some(files) = timings.time("recency", || tracked_files(p)) {
+                    last_edited_at = newest_mtime(&files);
                 }
-                #[cfg(feature = "analyzers")]
-                if let Some((_total, breakdown)) = compute_loc_breakdown(p) {
-                    db.replace_loc_breakdown(id, &breakdown)?;
+            }
+            RecencyMode::LastCommit => {
+                #[cfg(feature = "git")]
+                let commit_at = git_info.as_ref().and_then(|i| i.last_commit_at);
+                #[cfg(not(feature = "git"))]
+                let commit_at = timings.time("recency", || last_commit_at(p));
+                if commit_at.is_some() {
+                    last_edited_at = commit_at;
                 }
             }
+        }
+    }
 
-            processed_roots.push(p.to_path_buf());
-            count += 1;
+    let oversized = cfg.analyzer_limits.exceeded(size_bytes, files_count);
+    if let Some(reason) = &oversized {
+        tracing::info!(path = %p.display(), %reason, "skipping expensive analyzers");
+    }
+
+    let Some(id) = project.id.filter(|_| !opts.dry_run) else {
+        tracing::info!(
+            path=%p.display(),
+            project_type=%ptype.as_str(),
+            git=git,
+            size=?size_bytes,
+            files=?files_count,
+            last_edited=?last_edited_at,
+            "found project"
+        );
+        #[cfg(feature = "git")]
+        let (branch, remote_url, git_dirty) = git_info
+            .map(|i| (i.branch, i.remote_url, i.dirty))
+            .unwrap_or_default();
+        #[cfg(not(feature = "git"))]
+        let (branch, remote_url, git_dirty) = (None, None, None);
+        previews.push(ProjectPreview {
+            name: p
+                .file_name()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            path: p.to_string_lossy().to_string(),
+            project_type: ptype.as_str().to_string(),
+            is_git_repo: git,
+            size_bytes,
+            files_count,
+            last_edited_at,
+            artifact_bytes: artifacts
+                .filter(|a| !a.is_empty())
+                .map(|a| a.iter().map(|a| a.bytes).sum()),
+            branch,
+            remote_url,
+            git_dirty,
+        });
+        return Ok(None);
+    };
+    timings.time("db", || -> Result<()> {
+        db.record_edit(id, last_edited_at)?;
+        db.upsert_metrics(id, size_bytes, files_count, last_edited_at)?;
+        if let Some(tree) = &tree {
+            db.set_shape_metrics(id, &tree.shape)?;
+            db.replace_assets(id, &tree.assets)?;
         }
+        if let Some(times) = &commit_times {
+            db.set_commit_activity(id, times)?;
+        }
+        db.store_artifacts(id, artifacts.as_deref())?;
+        db.set_size_estimated(
+            id,
+            matches!(cfg.size_mode, SizeMode::Estimate) && size_bytes.is_some(),
+        )?;
+        #[cfg(feature = "git")]
+        if let Some(info) = &git_info {
+            db.upsert_git_info(
+                id,
+                info.last_commit_at,
+                info.branch.as_deref(),
+                info.remote_url.as_deref(),
+                info.dirty,
+            )?;
+            db.set_git_ahead(id, info.ahead)?;
+            db.replace_git_branches(id, info.default_branch.as_deref(), &info.local_branches)?;
+        }
+        db.set_analysis_skipped(id, oversized.as_deref())?;
+        if oversized.is_none() || !cfg.analyzer_limits.skips("readme") {
+            let readme = crate::detail::read_readme(p);
+            db.set_search_readme(id, readme.as_ref().map(|(_, text, _)| text.as_str()))?;
+        }
+        Ok(())
+    })?;
+    let ctx = AnalyzerContext {
+        cfg,
+        project_type: ptype.as_str(),
+        is_git_repo: git,
+        oversized: oversized.as_deref(),
+    };
+    for (analyzer, elapsed) in analyzers.run(db, id, p, &ctx)? {
+        timings.add(&format!("analyzer:{analyzer}"), elapsed);
     }
-    Ok(count)
+    db.set_enrich_pending(id, false)?;
+    Ok(Some(id))
 }
 
-fn compute_metrics(
+/// Newest modification time among `files`, skipping any that no longer exist.
+fn newest_mtime(files: &[PathBuf]) -> Option<i64> {
+    files
+        .iter()
+        .filter_map(|f| fs::metadata(f).and_then(|md| md.modified()).ok())
+        .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .max()
+}
+
+/// Size, file count and newest mtime for one project directory (gitignore-aware).
+pub fn compute_metrics(
     root: &Path,
     cfg: &AppConfig,
     _git: bool,
 ) -> Result<(Option<i64>, Option<i64>, Option<i64>)> {
+    walk_metrics(root, cfg).map(|(metrics, _)| metrics)
+}
+
+type Metrics = (Option<i64>, Option<i64>, Option<i64>);
+
+/// What the metrics walk learns besides size, file count and recency.
+struct TreeStats {
+    shape: ShapeMetrics,
+    assets: Vec<AssetTotal>,
+}
+
+/// `compute_metrics` plus the project's shape and assets, from the same walk.
+fn walk_metrics(root: &Path, cfg: &AppConfig) -> Result<(Metrics, TreeStats)> {
+    if let SizeMode::Estimate = cfg.size_mode {
+        return estimate_metrics(root, cfg);
+    }
+    let mut usage = DiskUsage::new(cfg);
+    let mut shape = ShapeCounter::new(root);
+    let mut assets = AssetCounter::default();
     let mut total_size: i64 = 0;
     let mut files_count: i64 = 0;
     let mut latest_mtime: i64 = 0;
@@ -168,6 +816,7 @@ fn compute_metrics(
         .build();
 
     for res in walk {
+        crate::nice::pace();
         let entry = match res {
             Ok(e) => e,
             Err(err) => {
@@ -184,13 +833,30 @@ fn compute_metrics(
                     continue;
                 }
             }
+            total_size += usage.dir_bytes(&entry) as i64;
             continue;
         }
 
-        if entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
-            files_count += 1;
-            if let Ok(md) = fs::metadata(p) {
-                total_size += md.len() as i64;
+        // Symlinks count as files of their own (link) size, like git sees them, so a link
+        // into another project is not measured twice and a broken one is no error
+        if entry
+            .file_type()
+            .is_some_and(|ft| ft.is_file() || ft.is_symlink())
+        {
+            let md = entry.metadata();
+            // Excluded files still take up space
+            match &md {
+                Ok(md) if cfg.content_exclude.excludes(p, md.len()) => {}
+                _ => {
+                    files_count += 1;
+                    shape.add(p.strip_prefix(root).unwrap_or(p));
+                }
+            }
+            if let Ok(md) = md {
+                if let Some(category) = asset_category(p).filter(|_| md.is_file()) {
+                    assets.add(category, md.len());
+                }
+                total_size += usage.file_bytes(&md) as i64;
                 if let Ok(mtime) = md.modified() {
                     if let Ok(secs) = mtime.duration_since(std::time::UNIX_EPOCH) {
                         latest_mtime = latest_mtime.max(secs.as_secs() as i64);
@@ -201,7 +867,7 @@ fn compute_metrics(
     }
 
     let size_opt = match cfg.size_mode {
-        SizeMode::ExactCached => Some(total_size),
+        SizeMode::ExactCached | SizeMode::Estimate => Some(total_size),
         SizeMode::None => None,
     };
 
@@ -212,5 +878,313 @@ fn compute_metrics(
         None
     };
 
-    Ok((size_opt, files_opt, last_edit_opt))
+    let tree = TreeStats {
+        shape: shape.finish(),
+        assets: assets.finish(),
+    };
+    Ok(((size_opt, files_opt, last_edit_opt), tree))
+}
+
+/// `(directory, bytes)` for each of the project's top-level `ARTIFACT_DIRS`, ignore files
+/// notwithstanding (they are usually what ignores them), plus its type's
+/// `TYPE_ARTIFACT_DIRS`, Android module output and Xcode DerivedData (by absolute path) for
+/// mobile projects. `None` when sizes are off.
+pub fn measure_artifacts(
+    root: &Path,
+    cfg: &AppConfig,
+    ptype: ProjectType,
+) -> Option<Vec<(String, i64)>> {
+    let measured = measure_artifacts_with(root, cfg, ptype, &[], &ArtifactScan::default())?;
+    Some(measured.into_iter().map(|a| (a.dir, a.bytes)).collect())
+}
+
+/// [`measure_artifacts`], reusing the size in `previous` of each directory whose
+/// fingerprint has not changed since. Others are walked in full, or sampled under
+/// `SizeMode::Estimate`.
+fn measure_artifacts_with(
+    root: &Path,
+    cfg: &AppConfig,
+    ptype: ProjectType,
+    previous: &[ArtifactSize],
+    scan: &ArtifactScan,
+) -> Option<Vec<ArtifactSize>> {
+    if let SizeMode::None = cfg.size_mode {
+        return None;
+    }
+    let mut dirs: Vec<String> = ARTIFACT_DIRS.iter().map(|d| d.to_string()).collect();
+    dirs.extend(
+        TYPE_ARTIFACT_DIRS
+            .iter()
+            .filter(|(t, _)| *t == ptype)
+            .flat_map(|(_, names)| names.iter().map(|n| n.to_string())),
+    );
+    match ptype {
+        ProjectType::Android => dirs.extend(crate::mobile::android_build_dirs(root)),
+        ProjectType::Ios => dirs.extend(scan.derived_data(root)),
+        _ => {}
+    }
+    let mut usage = DiskUsage::new(cfg);
+    let mut out = Vec::new();
+    for name in dirs {
+        let dir = root.join(&name);
+        if !fs::symlink_metadata(&dir).is_ok_and(|md| md.is_dir()) {
+            continue;
+        }
+        let fingerprint = tree_fingerprint(&dir);
+        let unchanged = previous
+            .iter()
+            .find(|p| p.dir == name && p.fingerprint.is_some() && p.fingerprint == fingerprint);
+        let bytes = match (unchanged, &cfg.size_mode) {
+            (Some(p), _) => p.bytes,
+            (None, SizeMode::Estimate) => estimate_tree_bytes(&dir, &mut usage),
+            (None, _) => tree_bytes(&dir, &mut usage),
+        };
+        out.push(ArtifactSize {
+            dir: name,
+            bytes,
+            fingerprint,
+        });
+    }
+    Some(out)
+}
+
+/// Newest modification time (in ns) of `dir` and the two levels below it. Installing or
+/// removing packages and rebuilding add or replace entries there, so while it holds still
+/// the directory's size very likely does too.
+fn tree_fingerprint(dir: &Path) -> Option<i64> {
+    WalkBuilder::new(dir)
+        .standard_filters(false)
+        .max_depth(Some(2))
+        .build()
+        .flatten()
+        .filter_map(|e| e.metadata().ok()?.modified().ok())
+        .filter_map(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i64)
+        .max()
+}
+
+/// Everything under `dir`, ignore files notwithstanding.
+fn tree_bytes(dir: &Path, usage: &mut DiskUsage) -> i64 {
+    let mut bytes = 0i64;
+    for entry in WalkBuilder::new(dir)
+        .standard_filters(false)
+        .build()
+        .flatten()
+    {
+        crate::nice::pace();
+        if entry.file_type().is_some_and(|t| t.is_dir()) {
+            bytes += usage.dir_bytes(&entry) as i64;
+        } else if entry.file_type().is_some_and(|t| t.is_file()) {
+            if let Ok(md) = entry.metadata() {
+                bytes += usage.file_bytes(&md) as i64;
+            }
+        }
+    }
+    bytes
+}
+
+/// [`tree_bytes`] sampled like `estimate_metrics`: every listing is read, but only some of
+/// each directory's files are stat'ed and the rest extrapolated.
+fn estimate_tree_bytes(dir: &Path, usage: &mut DiskUsage) -> i64 {
+    let mut dir_bytes = 0u64;
+    let mut samples: HashMap<PathBuf, DirSample> = HashMap::new();
+    for entry in WalkBuilder::new(dir)
+        .standard_filters(false)
+        .build()
+        .flatten()
+    {
+        crate::nice::pace();
+        let Some(ft) = entry.file_type() else {
+            continue;
+        };
+        if ft.is_dir() {
+            dir_bytes += usage.dir_bytes(&entry);
+            continue;
+        }
+        if !ft.is_file() {
+            continue;
+        }
+        let parent = entry.path().parent().unwrap_or(dir).to_path_buf();
+        let sample = samples.entry(parent).or_default();
+        sample.files += 1;
+        if sample.files <= SAMPLE_FIRST || sample.files.is_multiple_of(SAMPLE_STRIDE) {
+            if let Ok(md) = entry.metadata() {
+                sample.sampled += 1;
+                sample.sampled_bytes += usage.file_bytes(&md);
+            }
+        }
+    }
+    let files: f64 = samples
+        .values()
+        .filter(|d| d.sampled > 0)
+        .map(|d| d.sampled_bytes as f64 * d.files as f64 / d.sampled as f64)
+        .sum();
+    (dir_bytes as f64 + files).round() as i64
+}
+
+/// Every file in a directory is stat'ed up to this many...
+const SAMPLE_FIRST: u64 = 16;
+/// ...then only every this-many-th one
+const SAMPLE_STRIDE: u64 = 16;
+
+#[derive(Default)]
+struct DirSample {
+    files: u64,
+    /// Files left out of `files_count` by `content_exclude`
+    excluded: u64,
+    sampled: u64,
+    sampled_bytes: u64,
+}
+
+/// `compute_metrics` for `SizeMode::Estimate`, du-style: file counts come from directory
+/// listings alone, and sizes are only read for a sample of each directory's files and
+/// scaled up by its file count. Small directories are measured exactly. Recency uses the
+/// sampled files plus directory mtimes, which move whenever a file is created, deleted or
+/// saved by rename.
+fn estimate_metrics(root: &Path, cfg: &AppConfig) -> Result<(Metrics, TreeStats)> {
+    let mut usage = DiskUsage::new(cfg);
+    let mut shape = ShapeCounter::new(root);
+    let mut assets = AssetCounter::default();
+    let mut dir_bytes = 0u64;
+    let mut dirs: HashMap<PathBuf, DirSample> = HashMap::new();
+    let mut latest_mtime: i64 = 0;
+    let mut note_mtime = |md: &fs::Metadata| {
+        if let Ok(secs) = md
+            .modified()
+            .map(|t| t.duration_since(std::time::UNIX_EPOCH))
+        {
+            latest_mtime = latest_mtime.max(secs.map(|d| d.as_secs() as i64).unwrap_or(0));
+        }
+    };
+
+    let walk = WalkBuilder::new(root)
+        .git_ignore(true)
+        .hidden(true)
+        .ignore(true)
+        .build();
+    for res in walk {
+        crate::nice::pace();
+        let entry = match res {
+            Ok(e) => e,
+            Err(err) => {
+                tracing::warn!(%err, "walk error (metrics)");
+                continue;
+            }
+        };
+        // The walker's file type comes from the directory listing, so this costs no stat
+        let Some(ft) = entry.file_type() else {
+            continue;
+        };
+        if ft.is_dir() {
+            if let Ok(md) = entry.metadata() {
+                note_mtime(&md);
+                dir_bytes += usage.bytes(&md);
+            }
+            continue;
+        }
+        if !ft.is_file() && !ft.is_symlink() {
+            continue;
+        }
+        let parent = entry.path().parent().unwrap_or(root).to_path_buf();
+        let dir = dirs.entry(parent).or_default();
+        dir.files += 1;
+        // Sizes are unknown without a stat, so only the extension list applies here
+        if cfg.content_exclude.excludes_by_name(entry.path()) {
+            dir.excluded += 1;
+        } else {
+            let p = entry.path();
+            shape.add(p.strip_prefix(root).unwrap_or(p));
+        }
+        // Assets are few and large, so they are always stat'ed rather than extrapolated
+        if let Some(category) = asset_category(entry.path()).filter(|_| ft.is_file()) {
+            if let Ok(md) = entry.metadata() {
+                assets.add(category, md.len());
+            }
+        }
+        if dir.files <= SAMPLE_FIRST || dir.files.is_multiple_of(SAMPLE_STRIDE) {
+            if let Ok(md) = entry.metadata() {
+                dir.sampled += 1;
+                dir.sampled_bytes += usage.file_bytes(&md);
+                note_mtime(&md);
+            }
+        }
+    }
+
+    let files_count: u64 = dirs.values().map(|d| d.files - d.excluded).sum();
+    let size: f64 = dir_bytes as f64
+        + dirs
+            .values()
+            .filter(|d| d.sampled > 0)
+            .map(|d| d.sampled_bytes as f64 * d.files as f64 / d.sampled as f64)
+            .sum::<f64>();
+    let last_edit_opt = if latest_mtime > 0 {
+        Some(latest_mtime)
+    } else {
+        None
+    };
+    Ok((
+        (
+            Some(size.round() as i64),
+            Some(files_count as i64),
+            last_edit_opt,
+        ),
+        TreeStats {
+            shape: shape.finish(),
+            assets: assets.finish(),
+        },
+    ))
+}
+
+/// How much a file or directory adds to a project's size. By default that is its apparent
+/// length (directories add nothing); with `size_on_disk` it is the allocated blocks, and a
+/// hard-linked inode only counts the first time it is seen.
+struct DiskUsage {
+    on_disk: bool,
+    #[cfg_attr(not(unix), allow(dead_code))]
+    seen: HashSet<(u64, u64)>,
+}
+
+impl DiskUsage {
+    fn new(cfg: &AppConfig) -> Self {
+        Self {
+            on_disk: cfg.size_on_disk,
+            seen: HashSet::new(),
+        }
+    }
+
+    fn dir_bytes(&mut self, entry: &ignore::DirEntry) -> u64 {
+        if !self.on_disk {
+            return 0;
+        }
+        entry.metadata().map(|md| self.bytes(&md)).unwrap_or(0)
+    }
+
+    fn file_bytes(&mut self, md: &fs::Metadata) -> u64 {
+        if self.on_disk {
+            self.bytes(md)
+        } else {
+            md.len()
+        }
+    }
+
+    #[cfg(unix)]
+    fn bytes(&mut self, md: &fs::Metadata) -> u64 {
+        use std::os::unix::fs::MetadataExt;
+        if !self.on_disk {
+            return 0;
+        }
+        if md.nlink() > 1 && !self.seen.insert((md.dev(), md.ino())) {
+            return 0;
+        }
+        // st_blocks is always in 512-byte units
+        md.blocks() * 512
+    }
+
+    #[cfg(not(unix))]
+    fn bytes(&mut self, md: &fs::Metadata) -> u64 {
+        if !self.on_disk || md.is_dir() {
+            return 0;
+        }
+        md.len()
+    }
 }
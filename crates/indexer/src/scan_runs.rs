@@ -0,0 +1,155 @@
+//! A record of every scan that wrote to the index: when it ran, which roots it covered, what
+//! it found, where the time went and whether it failed. Dry runs are not recorded. Only the
+//! last `KEEP` runs are kept.
+
+use anyhow::Result;
+use rusqlite::{params, OptionalExtension};
+use serde::Serialize;
+use time::OffsetDateTime;
+
+use crate::config::FreshnessConfig;
+use crate::db::Db;
+use crate::scan::PhaseTiming;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS scan_runs (
+      id INTEGER PRIMARY KEY,
+      started_at INTEGER NOT NULL,
+      finished_at INTEGER NOT NULL,
+      -- JSON array of root paths
+      roots TEXT NOT NULL,
+      projects INTEGER NOT NULL,
+      moves INTEGER NOT NULL,
+      discover_only INTEGER NOT NULL,
+      total_ms REAL NOT NULL,
+      -- JSON array of phase timings
+      timings TEXT NOT NULL,
+      error TEXT
+    );
+"#;
+
+/// How many runs are kept
+const KEEP: i64 = 100;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanRun {
+    pub started_at: i64,
+    pub finished_at: i64,
+    /// Roots queued when the scan started
+    pub roots: Vec<String>,
+    pub projects: i64,
+    pub moves: i64,
+    pub discover_only: bool,
+    pub total_ms: f64,
+    /// Slowest phase first
+    pub timings: Vec<PhaseTiming>,
+    /// Why the scan failed; counts and timings are then empty
+    pub error: Option<String>,
+}
+
+/// How current the index is, for "updated 3 days ago" and stale warnings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct LastScanInfo {
+    /// When the last completed scan finished; `None` for an index never scanned
+    pub finished_at: Option<i64>,
+    /// Projects that scan found
+    pub projects: Option<i64>,
+    /// Why the latest scan failed, when it did (`finished_at` is then from an earlier one)
+    pub last_error: Option<String>,
+    /// `finished_at` is older than `freshness.stale_after_days`
+    pub stale: bool,
+}
+
+impl Db {
+    pub fn record_scan_run(&self, run: &ScanRun) -> Result<()> {
+        self.conn.execute(
+            "INSERT INTO scan_runs (started_at, finished_at, roots, projects, moves, \
+             discover_only, total_ms, timings, error) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            params![
+                run.started_at,
+                run.finished_at,
+                serde_json::to_string(&run.roots)?,
+                run.projects,
+                run.moves,
+                run.discover_only,
+                run.total_ms,
+                serde_json::to_string(&run.timings)?,
+                run.error,
+            ],
+        )?;
+        self.conn.execute(
+            "DELETE FROM scan_runs WHERE id NOT IN \
+             (SELECT id FROM scan_runs ORDER BY id DESC LIMIT ?1)",
+            params![KEEP],
+        )?;
+        Ok(())
+    }
+
+    /// The last completed scan and whether it is older than `freshness` allows. Discovery-only
+    /// scans do not count, as they measure nothing. Indexes from before scans were recorded
+    /// fall back to the last time a project was stored.
+    pub fn last_scan_info(&self, freshness: &FreshnessConfig) -> Result<LastScanInfo> {
+        let completed: Option<(i64, i64)> = self
+            .conn
+            .query_row(
+                "SELECT finished_at, projects FROM scan_runs \
+                 WHERE error IS NULL AND discover_only = 0 ORDER BY id DESC LIMIT 1",
+                [],
+                |r| Ok((r.get(0)?, r.get(1)?)),
+            )
+            .optional()?;
+        let (finished_at, projects) = match completed {
+            Some((at, n)) => (Some(at), Some(n)),
+            None => (
+                self.conn
+                    .query_row("SELECT MAX(updated_at) FROM projects", [], |r| r.get(0))?,
+                None,
+            ),
+        };
+        let last_error = self.last_scan_run()?.and_then(|run| run.error);
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        let stale = freshness.stale_after_days > 0
+            && finished_at
+                .is_some_and(|at| now - at > i64::from(freshness.stale_after_days) * 86_400);
+        Ok(LastScanInfo {
+            finished_at,
+            projects,
+            last_error,
+            stale,
+        })
+    }
+
+    /// The most recently finished scan, failed or not.
+    pub fn last_scan_run(&self) -> Result<Option<ScanRun>> {
+        let run = self
+            .conn
+            .query_row(
+                &format!("SELECT {RUN_COLUMNS} FROM scan_runs ORDER BY id DESC LIMIT 1"),
+                [],
+                run_from_row,
+            )
+            .optional()?;
+        Ok(run)
+    }
+}
+
+const RUN_COLUMNS: &str = "started_at, finished_at, roots, projects, moves, discover_only, \
+     total_ms, timings, error";
+
+fn run_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<ScanRun> {
+    let roots: String = row.get(2)?;
+    let timings: String = row.get(7)?;
+    Ok(ScanRun {
+        started_at: row.get(0)?,
+        finished_at: row.get(1)?,
+        roots: serde_json::from_str(&roots).unwrap_or_default(),
+        projects: row.get(3)?,
+        moves: row.get(4)?,
+        discover_only: row.get(5)?,
+        total_ms: row.get(6)?,
+        timings: serde_json::from_str(&timings).unwrap_or_default(),
+        error: row.get(8)?,
+    })
+}
@@ -0,0 +1,185 @@
+//! Full-text search over project names, paths and READMEs (SQLite FTS5). Each hit says where
+//! it matched: highlighted name and path, plus a README snippet when the README matched, so
+//! the UI can show why a project came up.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Db, ProjectRecord};
+
+/// Marks the start/end of a match in FTS5 `highlight()`/`snippet()` output
+pub(crate) const OPEN: char = '\u{1}';
+pub(crate) const CLOSE: char = '\u{2}';
+/// Relevance with name matches weighted over path, and path over README (lower is better)
+pub(crate) const BM25: &str = "bm25(project_search, 10.0, 2.0, 1.0)";
+/// Tokens around a README match in its snippet
+const SNIPPET_TOKENS: u32 = 12;
+
+/// Kept in step with `projects` by triggers; `readme` is filled in by scans.
+pub(crate) const SCHEMA: &str = r#"
+    CREATE VIRTUAL TABLE IF NOT EXISTS project_search USING fts5(
+      name, path, readme,
+      tokenize = 'unicode61 remove_diacritics 2',
+      -- Prefix indexes keep short type-ahead queries fast
+      prefix = '2 3'
+    );
+    CREATE TRIGGER IF NOT EXISTS project_search_insert AFTER INSERT ON projects BEGIN
+      INSERT INTO project_search (rowid, name, path, readme) VALUES (new.id, new.name, new.path, '');
+    END;
+    CREATE TRIGGER IF NOT EXISTS project_search_update AFTER UPDATE OF name, path ON projects BEGIN
+      UPDATE project_search SET name = new.name, path = new.path WHERE rowid = new.id;
+    END;
+    CREATE TRIGGER IF NOT EXISTS project_search_delete AFTER DELETE ON projects BEGIN
+      DELETE FROM project_search WHERE rowid = old.id;
+    END;
+    INSERT INTO project_search (rowid, name, path, readme)
+      SELECT id, name, path, '' FROM projects
+      WHERE id NOT IN (SELECT rowid FROM project_search);
+"#;
+
+/// Text with the character ranges that matched the query.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Highlighted {
+    pub text: String,
+    /// `(start, end)` offsets in characters (Unicode scalar values), end exclusive
+    pub spans: Vec<(u32, u32)>,
+}
+
+impl Highlighted {
+    pub(crate) fn parse(marked: &str) -> Self {
+        let mut text = String::with_capacity(marked.len());
+        let mut spans = Vec::new();
+        let (mut pos, mut start) = (0u32, None);
+        for c in marked.chars() {
+            match c {
+                OPEN => start = Some(pos),
+                CLOSE => {
+                    if let Some(s) = start.take() {
+                        spans.push((s, pos));
+                    }
+                }
+                _ => {
+                    text.push(c);
+                    pos += 1;
+                }
+            }
+        }
+        Self { text, spans }
+    }
+
+    pub fn is_match(&self) -> bool {
+        !self.spans.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct SearchHit {
+    pub project: ProjectRecord,
+    pub name: Highlighted,
+    pub path: Highlighted,
+    /// Excerpt around the match when the README matched
+    pub readme: Option<Highlighted>,
+}
+
+/// Just enough to list a type-ahead match.
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct Suggestion {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+}
+
+/// Every whitespace-separated word must match, each as a prefix.
+pub(crate) fn fts_query(q: &str) -> Option<String> {
+    let terms: Vec<String> = q
+        .split_whitespace()
+        .map(|t| t.replace('"', ""))
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{t}\"*"))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+impl Db {
+    /// Replace the README text searched for `project_id`.
+    pub fn set_search_readme(&self, project_id: i64, readme: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE project_search SET readme = ?2 WHERE rowid = ?1",
+            params![project_id, readme.unwrap_or("")],
+        )?;
+        Ok(())
+    }
+
+    /// Type-ahead matches for `prefix` against names and paths, names first. Only the
+    /// search index is read, so this stays cheap enough to run on every keystroke.
+    pub fn suggest(&self, prefix: &str, limit: u32) -> Result<Vec<Suggestion>> {
+        let Some(query) = fts_query(prefix) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT rowid, name, path FROM project_search \
+             WHERE project_search MATCH ?1 ORDER BY {BM25}, length(name) LIMIT ?2"
+        ))?;
+        let rows = stmt
+            .query_map(params![format!("{{name path}} : ({query})"), limit], |r| {
+                Ok(Suggestion {
+                    id: r.get(0)?,
+                    name: r.get(1)?,
+                    path: r.get(2)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+
+    /// Best matches for `q` first.
+    pub fn search(&self, q: &str, limit: u32) -> Result<Vec<SearchHit>> {
+        let Some(query) = fts_query(q) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT rowid, highlight(project_search, 0, ?2, ?3), \
+                    highlight(project_search, 1, ?2, ?3), \
+                    snippet(project_search, 2, ?2, ?3, '…', ?4) \
+             FROM project_search WHERE project_search MATCH ?1 \
+             ORDER BY {BM25} LIMIT ?5"
+        ))?;
+        let rows = stmt
+            .query_map(
+                params![
+                    query,
+                    OPEN.to_string(),
+                    CLOSE.to_string(),
+                    SNIPPET_TOKENS,
+                    limit
+                ],
+                |r| {
+                    Ok((
+                        r.get::<_, i64>(0)?,
+                        r.get::<_, String>(1)?,
+                        r.get::<_, String>(2)?,
+                        r.get::<_, String>(3)?,
+                    ))
+                },
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut hits = Vec::with_capacity(rows.len());
+        for (id, name, path, readme) in rows {
+            let Some(project) = self.project_by_id(id)? else {
+                continue;
+            };
+            let readme = Some(Highlighted::parse(&readme)).filter(Highlighted::is_match);
+            hits.push(SearchHit {
+                project,
+                name: Highlighted::parse(&name),
+                path: Highlighted::parse(&path),
+                readme,
+            });
+        }
+        Ok(hits)
+    }
+}
@@ -0,0 +1,221 @@
+use ignore::WalkBuilder;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+use crate::config::AppConfig;
+
+/// Why a file was flagged as a likely secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretKind {
+    /// `.env`-style file assigning a value to a key-like variable (API_KEY=..., TOKEN=...)
+    EnvFile,
+    /// SSH private key (id_rsa, id_ed25519, ...)
+    SshPrivateKey,
+    /// PEM/key file containing a private key block
+    PrivateKey,
+    /// AWS access key id (AKIA...) or aws_secret_access_key assignment
+    AwsCredentials,
+}
+
+impl SecretKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SecretKind::EnvFile => "env_file",
+            SecretKind::SshPrivateKey => "ssh_private_key",
+            SecretKind::PrivateKey => "private_key",
+            SecretKind::AwsCredentials => "aws_credentials",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "env_file" => Some(SecretKind::EnvFile),
+            "ssh_private_key" => Some(SecretKind::SshPrivateKey),
+            "private_key" => Some(SecretKind::PrivateKey),
+            "aws_credentials" => Some(SecretKind::AwsCredentials),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SecretFinding {
+    /// Path relative to the project root
+    pub path: String,
+    pub kind: SecretKind,
+}
+
+const SSH_KEY_NAMES: &[&str] = &["id_rsa", "id_dsa", "id_ecdsa", "id_ed25519"];
+const KEY_EXTENSIONS: &[&str] = &["pem", "key", "p12", "pfx"];
+const ENV_KEY_HINTS: &[&str] = &[
+    "SECRET",
+    "TOKEN",
+    "PASSWORD",
+    "PASSWD",
+    "API_KEY",
+    "APIKEY",
+    "PRIVATE_KEY",
+    "ACCESS_KEY",
+    "CREDENTIAL",
+];
+/// Text files small enough and config-like enough to be worth grepping for AWS keys.
+const CONTENT_EXTENSIONS: &[&str] = &[
+    "json",
+    "yml",
+    "yaml",
+    "toml",
+    "ini",
+    "cfg",
+    "conf",
+    "properties",
+    "sh",
+    "py",
+    "js",
+    "ts",
+    "tf",
+    "tfvars",
+    "txt",
+];
+
+/// Walk a project and report files that likely contain credentials.
+///
+/// Unlike the metrics walk this deliberately does not honor .gitignore: secrets that are
+/// ignored by git are exactly the ones that only live on this disk.
+pub fn scan_secrets(root: &Path, cfg: &AppConfig) -> Vec<SecretFinding> {
+    let mut findings = Vec::new();
+    let ignores = cfg.global_ignores.clone();
+    let walk = WalkBuilder::new(root)
+        .git_ignore(false)
+        .git_exclude(false)
+        .hidden(false)
+        .ignore(false)
+        .filter_entry(move |e| {
+            let is_dir = e.file_type().map(|ft| ft.is_dir()).unwrap_or(false);
+            !(is_dir
+                && e.file_name()
+                    .to_str()
+                    .map(|n| ignores.iter().any(|ign| ign == n))
+                    .unwrap_or(false))
+        })
+        .build();
+
+    for res in walk {
+        crate::nice::pace();
+        let entry = match res {
+            Ok(e) => e,
+            Err(err) => {
+                tracing::debug!(%err, "walk error (secrets)");
+                continue;
+            }
+        };
+        if !entry.file_type().map(|ft| ft.is_file()).unwrap_or(false) {
+            continue;
+        }
+        let p = entry.path();
+        if let Some(kind) = classify_file(p, cfg.secrets.max_file_bytes) {
+            let rel = p.strip_prefix(root).unwrap_or(p);
+            findings.push(SecretFinding {
+                path: rel.to_string_lossy().to_string(),
+                kind,
+            });
+        }
+    }
+    findings
+}
+
+fn classify_file(p: &Path, max_bytes: u64) -> Option<SecretKind> {
+    let name = p.file_name()?.to_str()?;
+    let ext = p.extension().and_then(|e| e.to_str()).unwrap_or("");
+
+    if SSH_KEY_NAMES.contains(&name) {
+        return Some(SecretKind::SshPrivateKey);
+    }
+
+    let is_env = name == ".env" || (name.starts_with(".env.") && !is_env_template(name));
+    let is_key = KEY_EXTENSIONS.contains(&ext);
+    let is_aws_file = name == "credentials" || CONTENT_EXTENSIONS.contains(&ext);
+    if !(is_env || is_key || is_aws_file) {
+        return None;
+    }
+
+    let md = fs::metadata(p).ok()?;
+    if md.len() > max_bytes {
+        return None;
+    }
+    let content = fs::read(p).ok()?;
+    let text = String::from_utf8_lossy(&content);
+
+    if is_key && text.contains("PRIVATE KEY-----") {
+        return Some(SecretKind::PrivateKey);
+    }
+    if contains_aws_credentials(&text) {
+        return Some(SecretKind::AwsCredentials);
+    }
+    if is_env && env_assigns_secret(&text) {
+        return Some(SecretKind::EnvFile);
+    }
+    None
+}
+
+fn is_env_template(name: &str) -> bool {
+    name.ends_with(".example") || name.ends_with(".sample") || name.ends_with(".template")
+}
+
+fn env_assigns_secret(text: &str) -> bool {
+    text.lines().any(|line| {
+        let line = line.trim().trim_start_matches("export ");
+        if line.starts_with('#') {
+            return false;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            return false;
+        };
+        let key = key.trim().to_ascii_uppercase();
+        let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+        !value.is_empty() && ENV_KEY_HINTS.iter().any(|h| key.contains(h))
+    })
+}
+
+fn contains_aws_credentials(text: &str) -> bool {
+    let assigns_secret_key = text.lines().any(|line| {
+        let lower = line.to_ascii_lowercase();
+        let Some(pos) = lower.find("aws_secret_access_key") else {
+            return false;
+        };
+        let rest = line[pos + "aws_secret_access_key".len()..]
+            .trim_start_matches(|c: char| c == '"' || c == '\'' || c.is_whitespace());
+        let Some(value) = rest.strip_prefix('=').or_else(|| rest.strip_prefix(':')) else {
+            return false;
+        };
+        value
+            .trim()
+            .trim_matches(|c| c == '"' || c == '\'' || c == ',')
+            .len()
+            >= 20
+    });
+    if assigns_secret_key {
+        return true;
+    }
+    // AWS access key ids: AKIA followed by 16 uppercase alphanumerics
+    let bytes = text.as_bytes();
+    let mut i = 0;
+    while let Some(pos) = text[i..].find("AKIA") {
+        let start = i + pos;
+        let tail = &bytes[start + 4..];
+        if tail.len() >= 16
+            && tail[..16]
+                .iter()
+                .all(|b| b.is_ascii_uppercase() || b.is_ascii_digit())
+            && tail
+                .get(16)
+                .map(|b| !b.is_ascii_alphanumeric())
+                .unwrap_or(true)
+        {
+            return true;
+        }
+        i = start + 4;
+    }
+    false
+}
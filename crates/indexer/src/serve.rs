@@ -0,0 +1,114 @@
+//! Local HTTP endpoints for launchers (Raycast, Alfred, shell scripts) that open projects
+//! without going through the app, served by `watch` and the app when `serve.enabled` is set.
+//!
+//! - `POST /opened` with `{"path": "..."}`: count an open of the project at or containing
+//!   `path` towards frecency. Answers the project as JSON, 404 when the path is not indexed.
+//!
+//! Requests must be `Content-Type: application/json`, which a web page can only send to
+//! another origin after a CORS preflight this server never approves.
+
+use std::path::Path;
+
+#[cfg(feature = "serve")]
+use crate::db::{Db, ProjectRecord};
+
+#[cfg(feature = "serve")]
+#[derive(serde::Deserialize)]
+struct Opened {
+    path: String,
+}
+
+/// Serve the endpoints on `addr` from a background thread, recording into the database at
+/// `db_path`. Returns the bound address (useful with port 0).
+#[cfg(feature = "serve")]
+pub fn listen(
+    addr: &str,
+    db_path: &Path,
+) -> anyhow::Result<(std::net::SocketAddr, std::thread::JoinHandle<()>)> {
+    let server = tiny_http::Server::http(addr)
+        .map_err(|e| anyhow::anyhow!("serve endpoint on {addr}: {e}"))?;
+    let bound = server
+        .server_addr()
+        .to_ip()
+        .ok_or_else(|| anyhow::anyhow!("serve endpoint on {addr}: not an IP address"))?;
+    let db = Db::open(db_path)?;
+    tracing::info!(%bound, "serving POST /opened");
+    let handle = std::thread::spawn(move || {
+        for mut req in server.incoming_requests() {
+            let (status, body) = respond(&db, &mut req);
+            let header =
+                tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..])
+                    .expect("static header");
+            let resp = tiny_http::Response::from_string(body)
+                .with_status_code(status)
+                .with_header(header);
+            if let Err(err) = req.respond(resp) {
+                tracing::debug!(%err, "serve response failed");
+            }
+        }
+    });
+    Ok((bound, handle))
+}
+
+#[cfg(feature = "serve")]
+fn respond(db: &Db, req: &mut tiny_http::Request) -> (u16, String) {
+    let error =
+        |status: u16, message: &str| (status, serde_json::json!({ "error": message }).to_string());
+    if req.url() != "/opened" {
+        return error(404, "not found");
+    }
+    if *req.method() != tiny_http::Method::Post {
+        return error(405, "use POST");
+    }
+    let json = req
+        .headers()
+        .iter()
+        .any(|h| h.field.equiv("Content-Type") && h.value.as_str().starts_with("application/json"));
+    if !json {
+        return error(415, "expected Content-Type: application/json");
+    }
+    let mut body = String::new();
+    if std::io::Read::read_to_string(req.as_reader(), &mut body).is_err() {
+        return error(400, "unreadable body");
+    }
+    let opened: Opened = match serde_json::from_str(&body) {
+        Ok(o) => o,
+        Err(e) => return error(400, &e.to_string()),
+    };
+    match record_opened(db, &opened.path) {
+        Ok(Some(project)) => (200, serde_json::to_string(&project).unwrap_or_default()),
+        Ok(None) => error(404, "not an indexed project"),
+        Err(err) => {
+            tracing::warn!(%err, path = %opened.path, "recording an open failed");
+            error(500, &format!("{err:#}"))
+        }
+    }
+}
+
+/// Record an open of the project at or containing `path`.
+#[cfg(feature = "serve")]
+fn record_opened(db: &Db, path: &str) -> anyhow::Result<Option<ProjectRecord>> {
+    let path = shellexpand::tilde(path).to_string();
+    let Some(project) = db.project_containing(&crate::paths::canonical_string(Path::new(&path)))?
+    else {
+        return Ok(None);
+    };
+    db.record_open(project.id)?;
+    Ok(Some(project))
+}
+
+/// Start the endpoints if the feature is compiled in and `serve.enabled` is set.
+pub fn start_from_config(cfg: &crate::config::ServeConfig, db_path: &Path) {
+    if !cfg.enabled {
+        return;
+    }
+    #[cfg(feature = "serve")]
+    if let Err(err) = listen(&cfg.listen, db_path) {
+        tracing::warn!(%err, "failed to start serve endpoints");
+    }
+    #[cfg(not(feature = "serve"))]
+    {
+        let _ = db_path;
+        tracing::warn!("serve.enabled is set but this build lacks the `serve` feature");
+    }
+}
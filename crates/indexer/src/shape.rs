@@ -0,0 +1,106 @@
+//! Cheap complexity metrics gathered during the metrics walk, for spotting bloated repos at a
+//! glance: source files among all files, average file size, deepest directory nesting and
+//! the number of top-level modules. No file is opened; only names and paths count.
+
+use anyhow::Result;
+use rusqlite::params;
+use std::collections::HashSet;
+use std::ffi::OsString;
+use std::path::Path;
+
+use crate::db::Db;
+
+/// Extensions counted as source code
+const SOURCE_EXTENSIONS: &[&str] = &[
+    "rs", "go", "py", "pyi", "js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts", "rb", "java",
+    "kt", "kts", "scala", "cs", "fs", "php", "swift", "m", "mm", "c", "h", "cc", "cpp", "cxx",
+    "hpp", "hh", "zig", "nim", "dart", "lua", "ex", "exs", "erl", "hs", "ml", "mli", "clj", "cljs",
+    "jl", "r", "sh", "bash", "zsh", "ps1", "sql", "vue", "svelte", "elm", "sol", "v", "sv", "vhd",
+];
+
+/// Directories whose children are a project's modules when it has one
+const SOURCE_ROOTS: &[&str] = &["src", "lib"];
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ShapeMetrics {
+    pub source_files: i64,
+    /// Deepest file's directory nesting below the root (0 when every file is at the root)
+    pub max_depth: i64,
+    /// Entries of `src/` or `lib/` (or else the root) that are or contain source files
+    pub top_level_modules: i64,
+}
+
+pub fn is_source_file(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| SOURCE_EXTENSIONS.iter().any(|s| s.eq_ignore_ascii_case(e)))
+}
+
+/// Accumulates `ShapeMetrics` from the files of one walk.
+pub(crate) struct ShapeCounter {
+    source_root: Option<OsString>,
+    shape: ShapeMetrics,
+    modules: HashSet<OsString>,
+}
+
+impl ShapeCounter {
+    pub(crate) fn new(root: &Path) -> Self {
+        let source_root = SOURCE_ROOTS
+            .iter()
+            .find(|d| root.join(d).is_dir())
+            .map(OsString::from);
+        Self {
+            source_root,
+            shape: ShapeMetrics::default(),
+            modules: HashSet::new(),
+        }
+    }
+
+    /// Count a file at `rel`, its path relative to the project root.
+    pub(crate) fn add(&mut self, rel: &Path) {
+        let mut parts = rel.components().map(|c| c.as_os_str());
+        self.shape.max_depth = self
+            .shape
+            .max_depth
+            .max(rel.components().count().saturating_sub(1) as i64);
+        if !is_source_file(rel) {
+            return;
+        }
+        self.shape.source_files += 1;
+        let module = match &self.source_root {
+            Some(src) => match (parts.next(), parts.next()) {
+                (Some(first), Some(module)) if first == src.as_os_str() => module,
+                _ => return,
+            },
+            None => match parts.next() {
+                Some(first) => first,
+                None => return,
+            },
+        };
+        self.modules.insert(module.to_os_string());
+    }
+
+    pub(crate) fn finish(mut self) -> ShapeMetrics {
+        self.shape.top_level_modules = self.modules.len() as i64;
+        self.shape
+    }
+}
+
+impl Db {
+    /// Store `shape`; the average file size comes from the stored size and file count.
+    pub fn set_shape_metrics(&self, project_id: i64, shape: &ShapeMetrics) -> Result<()> {
+        self.conn.execute(
+            "UPDATE metrics SET source_files_count = ?2, max_depth = ?3, \
+               top_level_modules = ?4, \
+               avg_file_bytes = CASE WHEN files_count > 0 THEN size_bytes / files_count END \
+             WHERE project_id = ?1",
+            params![
+                project_id,
+                shape.source_files,
+                shape.max_depth,
+                shape.top_level_modules
+            ],
+        )?;
+        Ok(())
+    }
+}
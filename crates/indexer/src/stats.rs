@@ -0,0 +1,101 @@
+//! Where the disk space goes across the whole index: bytes by project type and by each
+//! project's dominant language, the largest projects, and source vs artifact bytes.
+//!
+//! Only top-level projects are summed: a parent's sizes already include the directories of
+//! its nested children (workspace members, sub-projects), so counting both would double up.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+
+use crate::assets::AssetTotal;
+use crate::db::{Db, ProjectRecord, SortKey};
+
+/// One group's share of the index.
+#[derive(Debug, Clone, Serialize)]
+pub struct UsageRow {
+    /// Project type or language; `None` when unknown
+    pub name: Option<String>,
+    pub projects: i64,
+    /// Project sizes (what scans measure as `size_bytes`)
+    pub source_bytes: i64,
+    /// Build/dependency output (`artifact_bytes`)
+    pub artifact_bytes: i64,
+}
+
+impl UsageRow {
+    pub fn total_bytes(&self) -> i64 {
+        self.source_bytes + self.artifact_bytes
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsageStats {
+    /// Top-level projects (those without a `parent_id`)
+    pub projects: i64,
+    pub source_bytes: i64,
+    pub artifact_bytes: i64,
+    /// Largest total first
+    pub by_type: Vec<UsageRow>,
+    /// By the language with the most code lines in each project; largest total first
+    pub by_language: Vec<UsageRow>,
+    /// Media and binary assets by category (part of `source_bytes`); largest first
+    pub by_asset: Vec<AssetTotal>,
+    /// Checkpoints, datasets, runs and environments (see `ml`), wherever they were counted
+    pub ml_bytes: i64,
+    /// Largest `size_bytes` first
+    pub largest: Vec<ProjectRecord>,
+}
+
+/// Per-project bytes joined with the grouping column `key`
+const USAGE_SQL: &str = "SELECT {key}, COUNT(*), COALESCE(SUM(m.size_bytes), 0), \
+       COALESCE(SUM(m.artifact_bytes), 0) \
+     FROM projects p LEFT JOIN metrics m ON m.project_id = p.id \
+     WHERE p.parent_id IS NULL \
+     GROUP BY 1 \
+     ORDER BY COALESCE(SUM(m.size_bytes), 0) + COALESCE(SUM(m.artifact_bytes), 0) DESC, 1";
+
+/// The language with the most code in `p`; ties go to the alphabetically first
+const DOMINANT_LANGUAGE: &str = "(SELECT l.language FROM loc_lang l WHERE l.project_id = p.id \
+     ORDER BY l.code DESC, l.language LIMIT 1)";
+
+impl Db {
+    /// Disk usage across every indexed project, with the `top` largest projects.
+    pub fn disk_usage_stats(&self, top: usize) -> Result<DiskUsageStats> {
+        let by_type = self.usage_by("p.type")?;
+        let by_language = self.usage_by(DOMINANT_LANGUAGE)?;
+        let (projects, source_bytes, artifact_bytes, ml_bytes) = self.conn.query_row(
+            "SELECT COUNT(*), COALESCE(SUM(m.size_bytes), 0), COALESCE(SUM(m.artifact_bytes), 0), \
+               COALESCE(SUM(m.ml_bytes), 0) \
+             FROM projects p LEFT JOIN metrics m ON m.project_id = p.id \
+             WHERE p.parent_id IS NULL",
+            params![],
+            |r| Ok((r.get(0)?, r.get(1)?, r.get(2)?, r.get(3)?)),
+        )?;
+        Ok(DiskUsageStats {
+            projects,
+            source_bytes,
+            artifact_bytes,
+            by_type,
+            by_language,
+            by_asset: self.asset_totals()?,
+            ml_bytes,
+            largest: self.list_projects(SortKey::Size, top)?,
+        })
+    }
+
+    fn usage_by(&self, key: &str) -> Result<Vec<UsageRow>> {
+        let mut stmt = self.conn.prepare(&USAGE_SQL.replace("{key}", key))?;
+        let rows = stmt
+            .query_map([], |r| {
+                Ok(UsageRow {
+                    name: r.get(0)?,
+                    projects: r.get(1)?,
+                    source_bytes: r.get(2)?,
+                    artifact_bytes: r.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
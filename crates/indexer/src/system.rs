@@ -0,0 +1,163 @@
+//! System-wide scans for admins: index the code in every user's home directory into one
+//! shared database, attributing each project to the user whose home it was found in, and
+//! summarise per owner how much is stale and how much is regenerable build output.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use time::OffsetDateTime;
+
+use crate::config::AppConfig;
+use crate::db::Db;
+use crate::scan::{scan_roots, ScanOptions};
+
+/// Entries of the home base that are not user homes
+const NOT_HOMES: &[&str] = &["Shared", "Guest", "lost+found", "Deleted Users"];
+
+/// A user's home directory.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserHome {
+    pub user: String,
+    pub path: PathBuf,
+}
+
+/// One owner's share of a system index.
+#[derive(Debug, Clone, Serialize)]
+pub struct OwnerSummary {
+    pub owner: String,
+    pub projects: i64,
+    pub size_bytes: i64,
+    /// `node_modules`, `target` and other regenerable output (see `scan::ARTIFACT_DIRS`)
+    pub artifact_bytes: i64,
+    /// Projects not edited for `stale_after_days`
+    pub stale_projects: i64,
+    pub stale_bytes: i64,
+}
+
+/// Where home directories live on this platform.
+pub fn home_base() -> PathBuf {
+    if cfg!(target_os = "macos") {
+        PathBuf::from("/Users")
+    } else if cfg!(windows) {
+        std::env::var_os("SystemDrive")
+            .map(|d| PathBuf::from(format!("{}\\Users", d.to_string_lossy())))
+            .unwrap_or_else(|| PathBuf::from("C:\\Users"))
+    } else {
+        PathBuf::from("/home")
+    }
+}
+
+/// The shared database of system scans, outside any user's home.
+pub fn system_db_path() -> PathBuf {
+    let dir = if cfg!(target_os = "macos") {
+        PathBuf::from("/Library/Application Support/ProjectBrowser")
+    } else if cfg!(windows) {
+        std::env::var_os("ProgramData")
+            .map(PathBuf::from)
+            .unwrap_or_else(|| PathBuf::from("C:\\ProgramData"))
+            .join("ProjectBrowser")
+    } else {
+        PathBuf::from("/var/lib/project-browser")
+    };
+    dir.join("projects.sqlite")
+}
+
+/// Home directories under `base`, one per user, named after the directory.
+pub fn user_homes(base: &Path) -> Vec<UserHome> {
+    let Ok(rd) = fs::read_dir(base) else {
+        return Vec::new();
+    };
+    let mut homes: Vec<UserHome> = rd
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .filter_map(|e| {
+            let user = e.file_name().to_str()?.to_string();
+            (!user.starts_with('.') && !NOT_HOMES.contains(&user.as_str())).then(|| UserHome {
+                user,
+                path: e.path(),
+            })
+        })
+        .collect();
+    homes.sort_by(|a, b| a.user.cmp(&b.user));
+    homes
+}
+
+/// `path` as a home, owned by the user it is named after (true of homes under the home
+/// base).
+pub fn home_of(path: &Path) -> Option<UserHome> {
+    let user = path.file_name()?.to_str()?.to_string();
+    Some(UserHome {
+        user,
+        path: path.to_path_buf(),
+    })
+}
+
+/// Scan each home as its own root, attributing what is found to its user. Returns the
+/// number of projects per home, in order.
+pub fn scan_homes(
+    db: &Db,
+    cfg: &AppConfig,
+    opts: &ScanOptions,
+    homes: &[UserHome],
+) -> Result<Vec<usize>> {
+    let mut counts = Vec::with_capacity(homes.len());
+    for home in homes {
+        let cfg = AppConfig {
+            roots: vec![home.path.clone()],
+            root_priority: Default::default(),
+            ..cfg.clone()
+        };
+        let opts = ScanOptions {
+            owner: Some(home.user.clone()),
+            ..opts.clone()
+        };
+        let n = scan_roots(db, &cfg, &opts)?;
+        tracing::info!(user = %home.user, projects = n, "scanned home");
+        counts.push(n);
+    }
+    Ok(counts)
+}
+
+impl Db {
+    pub fn set_owner(&self, project_id: i64, owner: Option<&str>) -> Result<()> {
+        self.conn.execute(
+            "UPDATE projects SET owner = ?2 WHERE id = ?1 AND owner IS NOT ?2",
+            params![project_id, owner],
+        )?;
+        Ok(())
+    }
+
+    /// Totals per owner, largest first; projects without an owner are left out, and nested
+    /// projects are counted inside their parent's sizes rather than again on their own.
+    pub fn owner_summary(&self, stale_after_days: u32) -> Result<Vec<OwnerSummary>> {
+        let cutoff =
+            OffsetDateTime::now_utc().unix_timestamp() - i64::from(stale_after_days) * 86400;
+        let mut stmt = self.conn.prepare(
+            "SELECT p.owner, COUNT(*), COALESCE(SUM(m.size_bytes), 0), \
+                    COALESCE(SUM(m.artifact_bytes), 0), \
+                    COALESCE(SUM(m.last_edited_at < ?1), 0), \
+                    COALESCE(SUM(CASE WHEN m.last_edited_at < ?1 \
+                        THEN COALESCE(m.size_bytes, 0) + COALESCE(m.artifact_bytes, 0) END), 0) \
+             FROM projects p LEFT JOIN metrics m ON m.project_id = p.id \
+             WHERE p.owner IS NOT NULL AND p.parent_id IS NULL \
+             GROUP BY p.owner \
+             ORDER BY COALESCE(SUM(m.size_bytes), 0) + COALESCE(SUM(m.artifact_bytes), 0) DESC, \
+                      p.owner",
+        )?;
+        let rows = stmt
+            .query_map(params![cutoff], |r| {
+                Ok(OwnerSummary {
+                    owner: r.get(0)?,
+                    projects: r.get(1)?,
+                    size_bytes: r.get(2)?,
+                    artifact_bytes: r.get(3)?,
+                    stale_projects: r.get(4)?,
+                    stale_bytes: r.get(5)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
@@ -0,0 +1,418 @@
+//! Toolchain versions projects pin or require (`rust-toolchain.toml`, Cargo `rust-version`
+//! and `edition`, `.nvmrc`/`engines.node`, `.python-version`/`requires-python`, the go.mod
+//! `go` directive, asdf's `.tool-versions`), so queries like `node<16 OR python:3.8` find
+//! the projects still targeting old runtimes.
+//!
+//! Each pin keeps the constraint as written and the lowest `major.minor` it allows, which is
+//! what queries compare. `audit toolchains` counts projects per release line and flags lines
+//! past their end of life.
+
+use anyhow::Result;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use time::OffsetDateTime;
+
+use crate::db::Db;
+
+pub(crate) const SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS toolchains (
+      project_id INTEGER NOT NULL,
+      language TEXT NOT NULL,
+      source TEXT NOT NULL,
+      constraint_text TEXT NOT NULL,
+      version TEXT,
+      -- major * 1000 + minor, for comparisons
+      version_key INTEGER,
+      FOREIGN KEY(project_id) REFERENCES projects(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_toolchains_project ON toolchains(project_id);
+    CREATE INDEX IF NOT EXISTS idx_toolchains_language ON toolchains(language, version_key);
+"#;
+
+/// Languages pins are recorded for; also the query keys that match them
+pub const LANGUAGES: &[&str] = &["rust", "rust-edition", "node", "python", "go"];
+
+/// Upstream end-of-life dates per release line. Unlisted lines older than an end-of-life one
+/// are past theirs too; Rust and its editions are never flagged.
+const BUILTIN_EOL: &[(&str, &str, &str)] = &[
+    ("node", "10", "2021-04-30"),
+    ("node", "12", "2022-04-30"),
+    ("node", "14", "2023-04-30"),
+    ("node", "16", "2023-09-11"),
+    ("node", "17", "2022-06-01"),
+    ("node", "18", "2025-04-30"),
+    ("node", "19", "2023-06-01"),
+    ("node", "20", "2026-04-30"),
+    ("node", "21", "2024-06-01"),
+    ("node", "22", "2027-04-30"),
+    ("node", "23", "2025-06-01"),
+    ("node", "24", "2028-04-30"),
+    ("python", "2.7", "2020-01-01"),
+    ("python", "3.6", "2021-12-23"),
+    ("python", "3.7", "2023-06-27"),
+    ("python", "3.8", "2024-10-07"),
+    ("python", "3.9", "2025-10-31"),
+    ("python", "3.10", "2026-10-31"),
+    ("python", "3.11", "2027-10-31"),
+    ("python", "3.12", "2028-10-31"),
+    ("python", "3.13", "2029-10-31"),
+    ("go", "1.19", "2023-08-08"),
+    ("go", "1.20", "2024-02-06"),
+    ("go", "1.21", "2024-08-13"),
+    ("go", "1.22", "2025-02-11"),
+    ("go", "1.23", "2025-08-12"),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct ToolchainPin {
+    /// One of [`LANGUAGES`]
+    pub language: String,
+    /// Where it was read, e.g. `.nvmrc` or `package.json engines.node`
+    pub source: String,
+    /// As written, e.g. `>=14 <19`, `3.8.10` or `stable`
+    pub constraint: String,
+    /// Lowest `major.minor` the constraint allows; None for channels such as `stable` or
+    /// `lts/*`
+    pub version: Option<String>,
+}
+
+impl ToolchainPin {
+    fn new(language: &str, source: &str, constraint: &str) -> Self {
+        let constraint = constraint.trim().to_string();
+        Self {
+            language: language.to_string(),
+            source: source.to_string(),
+            version: min_version(&constraint).map(|(major, minor)| match minor {
+                Some(minor) => format!("{major}.{minor}"),
+                None => major.to_string(),
+            }),
+            constraint,
+        }
+    }
+}
+
+/// One release line of a language in `audit toolchains`, with the projects pinning it.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolchainUsage {
+    pub language: String,
+    /// Release line: the major version for Node and Rust editions, otherwise `major.minor`.
+    /// None gathers pins without a version, such as `stable` or `lts/*`.
+    pub version: Option<String>,
+    /// End-of-life date, when known
+    pub eol_date: Option<String>,
+    /// The line is past its end of life
+    pub end_of_life: bool,
+    pub projects: Vec<ToolchainProject>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ToolchainProject {
+    pub id: i64,
+    pub name: String,
+    pub path: String,
+    /// The pins that put it on this line, e.g. `.nvmrc: 14`
+    pub pins: Vec<String>,
+}
+
+/// The release line `version` belongs to.
+fn release_line(language: &str, version: &str) -> String {
+    match (language, min_version(version)) {
+        ("node" | "rust-edition", Some((major, _))) | (_, Some((major, None))) => major.to_string(),
+        (_, Some((major, Some(minor)))) => format!("{major}.{minor}"),
+        (_, None) => version.to_string(),
+    }
+}
+
+/// End-of-life date and status of a release line under the built-in table and `overrides`
+/// (`toolchain_eol` config), as of `today` (`YYYY-MM-DD`).
+pub fn eol_status(
+    language: &str,
+    line: &str,
+    overrides: &BTreeMap<String, BTreeMap<String, String>>,
+    today: &str,
+) -> (Option<String>, bool) {
+    let mut dates: BTreeMap<&str, &str> = BUILTIN_EOL
+        .iter()
+        .filter(|(lang, _, _)| *lang == language)
+        .map(|(_, line, date)| (*line, *date))
+        .collect();
+    if let Some(own) = overrides.get(language) {
+        dates.extend(own.iter().map(|(l, d)| (l.as_str(), d.as_str())));
+    }
+    if let Some(date) = dates.get(line) {
+        return (Some(date.to_string()), *date <= today);
+    }
+    // Unlisted lines are past their end of life when a newer line already is
+    let Some(key) = version_key(line) else {
+        return (None, false);
+    };
+    let older = dates
+        .iter()
+        .any(|(l, date)| *date <= today && version_key(l).is_some_and(|k| k > key));
+    (None, older)
+}
+
+/// `major * 1000 + minor` of a `major[.minor]` version, as stored in `version_key`.
+pub fn version_key(version: &str) -> Option<i64> {
+    let (major, minor) = min_version(version)?;
+    Some(major * 1000 + minor.unwrap_or(0).min(999))
+}
+
+/// The `version_key` range a query value covers: `14` is all of 14.x, `3.8` just 3.8.
+pub fn version_range(value: &str) -> Option<(i64, i64)> {
+    let (major, minor) = min_version(value)?;
+    Some(match minor {
+        Some(minor) => (major * 1000 + minor, major * 1000 + minor),
+        None => (major * 1000, major * 1000 + 999),
+    })
+}
+
+/// The first version in a constraint, when it leads (after operators and a `v` prefix):
+/// `>=3.8,<4` is 3.8, `^14.17.0` is 14.17, `stable` and `lts/fermium` have none.
+fn min_version(constraint: &str) -> Option<(i64, Option<i64>)> {
+    let rest = constraint.trim_start_matches(|c: char| "<>=^~!v ".contains(c));
+    let digits = |s: &str| s.len() - s.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let major_len = digits(rest);
+    let major = rest[..major_len].parse().ok()?;
+    let after = &rest[major_len..];
+    let minor = match after.strip_prefix('.') {
+        Some(tail) => tail[..digits(tail)].parse().ok(),
+        // Not the `2024` of `nightly-2024-01-01` or a `3rd` of anything
+        None if after.is_empty() || after.starts_with([' ', ',', '<', '|']) => None,
+        None => return None,
+    };
+    Some((major, minor))
+}
+
+/// Every pin found in `dir`'s manifests and version files.
+pub fn detect_toolchains(dir: &Path) -> Vec<ToolchainPin> {
+    let mut out = Vec::new();
+    let read = |name: &str| fs::read_to_string(dir.join(name)).ok();
+    let toml = |name: &str| read(name).and_then(|s| toml::from_str::<toml::Value>(&s).ok());
+    let first_line = |s: String| {
+        s.lines()
+            .map(str::trim)
+            .find(|l| !l.is_empty() && !l.starts_with('#'))
+            .map(String::from)
+    };
+
+    // Rust: toolchain file (TOML, or the legacy single-line form), then Cargo.toml
+    if let Some(channel) = toml("rust-toolchain.toml")
+        .and_then(|v| Some(v.get("toolchain")?.get("channel")?.as_str()?.to_string()))
+    {
+        out.push(ToolchainPin::new("rust", "rust-toolchain.toml", &channel));
+    } else if let Some(channel) =
+        read("rust-toolchain").and_then(|s| match toml::from_str::<toml::Value>(&s) {
+            Ok(v) => Some(v.get("toolchain")?.get("channel")?.as_str()?.to_string()),
+            Err(_) => first_line(s),
+        })
+    {
+        out.push(ToolchainPin::new("rust", "rust-toolchain", &channel));
+    }
+    if let Some(cargo) = toml("Cargo.toml") {
+        let package = |key: &str| {
+            cargo
+                .get("package")
+                .and_then(|p| p.get(key))
+                .and_then(|v| v.as_str())
+                .map(|v| (format!("Cargo.toml package.{key}"), v))
+                .or_else(|| {
+                    let v = cargo.get("workspace")?.get("package")?.get(key)?.as_str()?;
+                    Some((format!("Cargo.toml workspace.package.{key}"), v))
+                })
+        };
+        if let Some((source, v)) = package("rust-version") {
+            out.push(ToolchainPin::new("rust", &source, v));
+        }
+        if let Some((source, v)) = package("edition") {
+            out.push(ToolchainPin::new("rust-edition", &source, v));
+        }
+    }
+
+    // Node
+    for file in [".nvmrc", ".node-version"] {
+        if let Some(v) = read(file).and_then(first_line) {
+            out.push(ToolchainPin::new("node", file, &v));
+        }
+    }
+    if let Some(engine) = read("package.json")
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        .and_then(|v| v["engines"]["node"].as_str().map(String::from))
+    {
+        out.push(ToolchainPin::new(
+            "node",
+            "package.json engines.node",
+            &engine,
+        ));
+    }
+
+    // Python
+    if let Some(v) = read(".python-version").and_then(first_line) {
+        out.push(ToolchainPin::new("python", ".python-version", &v));
+    }
+    if let Some(pyproject) = toml("pyproject.toml") {
+        if let Some(v) = pyproject
+            .get("project")
+            .and_then(|p| p.get("requires-python"))
+            .and_then(|v| v.as_str())
+        {
+            out.push(ToolchainPin::new(
+                "python",
+                "pyproject.toml requires-python",
+                v,
+            ));
+        }
+        if let Some(v) = pyproject
+            .get("tool")
+            .and_then(|t| t.get("poetry"))
+            .and_then(|p| p.get("dependencies"))
+            .and_then(|d| d.get("python"))
+            .and_then(|v| v.as_str())
+        {
+            out.push(ToolchainPin::new(
+                "python",
+                "pyproject.toml tool.poetry.dependencies.python",
+                v,
+            ));
+        }
+    }
+
+    // Go
+    if let Some(v) = read("go.mod").and_then(|s| {
+        s.lines()
+            .find_map(|l| l.trim().strip_prefix("go ").map(|v| v.trim().to_string()))
+    }) {
+        out.push(ToolchainPin::new("go", "go.mod", &v));
+    }
+
+    // asdf / mise
+    if let Some(versions) = read(".tool-versions") {
+        for line in versions.lines() {
+            let mut words = line.split_whitespace();
+            let (Some(tool), Some(version)) = (words.next(), words.next()) else {
+                continue;
+            };
+            let language = match tool {
+                "rust" => "rust",
+                "nodejs" | "node" => "node",
+                "python" => "python",
+                "golang" | "go" => "go",
+                _ => continue,
+            };
+            out.push(ToolchainPin::new(language, ".tool-versions", version));
+        }
+    }
+    out
+}
+
+impl Db {
+    pub fn replace_toolchains(&self, project_id: i64, pins: &[ToolchainPin]) -> Result<()> {
+        let tx = self.conn.unchecked_transaction()?;
+        tx.execute(
+            "DELETE FROM toolchains WHERE project_id = ?1",
+            params![project_id],
+        )?;
+        for pin in pins {
+            tx.execute(
+                "INSERT INTO toolchains \
+                 (project_id, language, source, constraint_text, version, version_key) \
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    project_id,
+                    pin.language,
+                    pin.source,
+                    pin.constraint,
+                    pin.version,
+                    pin.version.as_deref().and_then(version_key),
+                ],
+            )?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// Projects per language and release line, oldest line first, with end-of-life status
+    /// from the built-in dates and `eol_overrides`.
+    pub fn toolchain_audit(
+        &self,
+        eol_overrides: &BTreeMap<String, BTreeMap<String, String>>,
+    ) -> Result<Vec<ToolchainUsage>> {
+        let today = OffsetDateTime::now_utc().date().to_string();
+        self.toolchain_audit_on(eol_overrides, &today)
+    }
+
+    /// [`Db::toolchain_audit`] with end-of-life status as of `today` (`YYYY-MM-DD`).
+    pub fn toolchain_audit_on(
+        &self,
+        eol_overrides: &BTreeMap<String, BTreeMap<String, String>>,
+        today: &str,
+    ) -> Result<Vec<ToolchainUsage>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.language, t.version, t.source, t.constraint_text, p.id, p.name, p.path \
+             FROM toolchains t JOIN projects p ON p.id = t.project_id \
+             ORDER BY t.language, t.version_key IS NULL, t.version_key, p.name, t.source",
+        )?;
+        let mut rows = stmt.query([])?;
+        let mut out: Vec<ToolchainUsage> = Vec::new();
+        while let Some(row) = rows.next()? {
+            let language: String = row.get(0)?;
+            let version: Option<String> = row.get(1)?;
+            let pin = format!("{}: {}", row.get::<_, String>(2)?, row.get::<_, String>(3)?);
+            let line = version.map(|v| release_line(&language, &v));
+            let usage = match out.last_mut() {
+                Some(u) if u.language == language && u.version == line => u,
+                _ => {
+                    let (eol_date, end_of_life) = match &line {
+                        Some(line) => eol_status(&language, line, eol_overrides, today),
+                        None => (None, false),
+                    };
+                    out.push(ToolchainUsage {
+                        language,
+                        version: line,
+                        eol_date,
+                        end_of_life,
+                        projects: Vec::new(),
+                    });
+                    out.last_mut().expect("just pushed")
+                }
+            };
+            // A project can pin one line twice, e.g. `.nvmrc: 14.17` and `engines: >=14`
+            let id: i64 = row.get(4)?;
+            match usage.projects.iter_mut().find(|p| p.id == id) {
+                Some(p) => p.pins.push(pin),
+                None => usage.projects.push(ToolchainProject {
+                    id,
+                    name: row.get(5)?,
+                    path: row.get(6)?,
+                    pins: vec![pin],
+                }),
+            }
+        }
+        for usage in &mut out {
+            usage.projects.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Ok(out)
+    }
+
+    pub fn toolchains(&self, project_id: i64) -> Result<Vec<ToolchainPin>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT language, source, constraint_text, version FROM toolchains \
+             WHERE project_id = ?1 ORDER BY language, source",
+        )?;
+        let rows = stmt
+            .query_map(params![project_id], |row| {
+                Ok(ToolchainPin {
+                    language: row.get(0)?,
+                    source: row.get(1)?,
+                    constraint: row.get(2)?,
+                    version: row.get(3)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(rows)
+    }
+}
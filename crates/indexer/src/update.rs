@@ -0,0 +1,163 @@
+//! Release feed lookups for `cli self update` and the app's "new version available" notice.
+//! The feed is GitHub's latest-release JSON; `PB_UPDATE_FEED` points it elsewhere (a mirror
+//! or a file served for testing). Binaries are verified against a `<asset>.sha256` file
+//! published next to them before anything on disk is touched.
+
+use anyhow::{bail, Context, Result};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+pub const DEFAULT_FEED: &str =
+    "https://api.github.com/repos/NeuralEmpowerment/local-code-browser/releases/latest";
+
+/// Refuse to buffer anything larger than this from the feed
+const MAX_DOWNLOAD_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    #[serde(default)]
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<Asset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Asset {
+    pub name: String,
+    pub browser_download_url: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[cfg_attr(feature = "specta", derive(specta::Type))]
+pub struct UpdateStatus {
+    pub current: String,
+    pub latest: String,
+    pub update_available: bool,
+    pub url: String,
+}
+
+fn feed_url() -> String {
+    std::env::var("PB_UPDATE_FEED").unwrap_or_else(|_| DEFAULT_FEED.to_string())
+}
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout(std::time::Duration::from_secs(30))
+        .user_agent(concat!("project-browser/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+pub fn latest_release() -> Result<Release> {
+    let url = feed_url();
+    agent()
+        .get(&url)
+        .set("Accept", "application/vnd.github+json")
+        .call()
+        .with_context(|| format!("fetching release feed {url}"))?
+        .into_json()
+        .context("parsing release feed")
+}
+
+fn parse_version(s: &str) -> Result<Version> {
+    Version::parse(s.trim_start_matches('v')).with_context(|| format!("invalid version {s}"))
+}
+
+/// Compare `current` (usually `CARGO_PKG_VERSION`) with the latest published release.
+pub fn check(current: &str, release: &Release) -> Result<UpdateStatus> {
+    let latest = parse_version(&release.tag_name)?;
+    Ok(UpdateStatus {
+        current: current.to_string(),
+        latest: latest.to_string(),
+        update_available: latest > parse_version(current)?,
+        url: release.html_url.clone(),
+    })
+}
+
+/// Release asset name for this platform, e.g. `project-browser-aarch64-macos`.
+pub fn platform_asset(bin: &str) -> String {
+    format!(
+        "{bin}-{}-{}{}",
+        std::env::consts::ARCH,
+        std::env::consts::OS,
+        std::env::consts::EXE_SUFFIX
+    )
+}
+
+fn download(url: &str) -> Result<Vec<u8>> {
+    let resp = agent()
+        .get(url)
+        .call()
+        .with_context(|| format!("downloading {url}"))?;
+    let mut buf = Vec::new();
+    resp.into_reader()
+        .take(MAX_DOWNLOAD_BYTES + 1)
+        .read_to_end(&mut buf)?;
+    if buf.len() as u64 > MAX_DOWNLOAD_BYTES {
+        bail!("{url} is larger than {MAX_DOWNLOAD_BYTES} bytes");
+    }
+    Ok(buf)
+}
+
+/// The asset called `name` and its `<name>.sha256` checksum file.
+pub fn find_asset<'a>(release: &'a Release, name: &str) -> Result<(&'a Asset, &'a Asset)> {
+    let find = |n: &str| {
+        release
+            .assets
+            .iter()
+            .find(|a| a.name == n)
+            .with_context(|| format!("release {} has no asset {n}", release.tag_name))
+    };
+    Ok((find(name)?, find(&format!("{name}.sha256"))?))
+}
+
+/// Download `name` from the release and check it against `<name>.sha256`.
+pub fn download_verified(release: &Release, name: &str) -> Result<Vec<u8>> {
+    let (binary, checksum) = find_asset(release, name)?;
+
+    let expected = String::from_utf8(download(&checksum.browser_download_url)?)?;
+    // `shasum`/`sha256sum` format: "<hex>  <file>"
+    let expected = expected
+        .split_whitespace()
+        .next()
+        .context("empty checksum file")?
+        .to_ascii_lowercase();
+    let bytes = download(&binary.browser_download_url)?;
+    let actual = format!("{:x}", Sha256::digest(&bytes));
+    if actual != expected {
+        bail!("checksum mismatch for {name}: expected {expected}, got {actual}");
+    }
+    Ok(bytes)
+}
+
+/// Write `bytes` next to the running executable and swap it in. The old binary is kept as
+/// `<exe>.old` until the next update, since Windows cannot delete a running executable.
+pub fn replace_current_exe(bytes: &[u8]) -> Result<PathBuf> {
+    let exe = std::env::current_exe()?.canonicalize()?;
+    let staged = with_suffix(&exe, "new");
+    let backup = with_suffix(&exe, "old");
+    fs::write(&staged, bytes).with_context(|| format!("writing {}", staged.display()))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))?;
+    }
+    let _ = fs::remove_file(&backup);
+    fs::rename(&exe, &backup).with_context(|| format!("moving {} aside", exe.display()))?;
+    if let Err(err) = fs::rename(&staged, &exe) {
+        // Put the original back so the install is never left without a binary
+        let _ = fs::rename(&backup, &exe);
+        return Err(err).with_context(|| format!("installing {}", exe.display()));
+    }
+    Ok(exe)
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s = path.as_os_str().to_owned();
+    s.push(format!(".{suffix}"));
+    PathBuf::from(s)
+}
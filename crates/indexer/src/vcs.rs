@@ -1,12 +1,28 @@
 #[cfg(feature = "git")]
-use git2::{BranchType, Repository};
-use std::path::Path;
+use git2::{Repository, StatusOptions};
+use std::path::{Path, PathBuf};
+#[cfg(not(feature = "git"))]
+use std::process::Command;
 
 #[derive(Debug, Clone)]
 pub struct GitInfo {
     pub last_commit_at: Option<i64>,
     pub branch: Option<String>,
     pub remote_url: Option<String>,
+    /// Uncommitted changes or untracked files in the working tree
+    pub dirty: Option<bool>,
+    /// Commits on the current branch not on its upstream; None without an upstream
+    pub ahead: Option<i64>,
+    /// What `origin/HEAD` points at, else a local `main` or `master`
+    pub default_branch: Option<String>,
+    pub local_branches: Vec<BranchTip>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BranchTip {
+    pub name: String,
+    /// Commit time of the branch's tip
+    pub last_commit_at: i64,
 }
 
 #[cfg(feature = "git")]
@@ -18,6 +34,10 @@ pub fn read_git_info(dir: &Path) -> GitInfo {
                 last_commit_at: None,
                 branch: None,
                 remote_url: None,
+                dirty: None,
+                ahead: None,
+                default_branch: None,
+                local_branches: Vec::new(),
             }
         }
     };
@@ -35,16 +55,79 @@ pub fn read_git_info(dir: &Path) -> GitInfo {
         .ok()
         .and_then(|h| h.shorthand().map(|s| s.to_string()));
 
-    // Remote URL from 'origin'
+    // Remote URL from 'origin', else the first configured remote
     let remote_url = repo
         .find_remote("origin")
         .ok()
-        .and_then(|r| r.url().map(|s| s.to_string()));
+        .and_then(|r| r.url().map(|s| s.to_string()))
+        .or_else(|| {
+            let names = repo.remotes().ok()?;
+            let first = names.iter().flatten().next()?.to_string();
+            repo.find_remote(&first)
+                .ok()
+                .and_then(|r| r.url().map(|s| s.to_string()))
+        });
+
+    // Bare repos have no working tree to be dirty
+    let dirty = if repo.is_bare() {
+        None
+    } else {
+        let mut opts = StatusOptions::new();
+        opts.include_untracked(true)
+            .include_ignored(false)
+            .exclude_submodules(true);
+        repo.statuses(Some(&mut opts)).ok().map(|st| !st.is_empty())
+    };
+
+    let ahead = (|| {
+        let head = repo.head().ok()?;
+        let local = repo
+            .find_branch(head.shorthand()?, git2::BranchType::Local)
+            .ok()?;
+        let upstream = local.upstream().ok()?;
+        let (ahead, _) = repo
+            .graph_ahead_behind(head.target()?, upstream.get().target()?)
+            .ok()?;
+        Some(ahead as i64)
+    })();
+
+    let local_branches: Vec<BranchTip> = repo
+        .branches(Some(git2::BranchType::Local))
+        .map(|branches| {
+            branches
+                .flatten()
+                .filter_map(|(b, _)| {
+                    Some(BranchTip {
+                        name: b.name().ok()??.to_string(),
+                        last_commit_at: b.get().peel_to_commit().ok()?.time().seconds(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let default_branch = repo
+        .find_reference("refs/remotes/origin/HEAD")
+        .ok()
+        .and_then(|r| {
+            r.symbolic_target()?
+                .strip_prefix("refs/remotes/origin/")
+                .map(str::to_string)
+        })
+        .or_else(|| {
+            ["main", "master"]
+                .into_iter()
+                .find(|name| local_branches.iter().any(|b| b.name == *name))
+                .map(str::to_string)
+        });
 
     GitInfo {
         last_commit_at,
         branch,
         remote_url,
+        dirty,
+        ahead,
+        default_branch,
+        local_branches,
     }
 }
 
@@ -54,5 +137,136 @@ pub fn read_git_info(_dir: &Path) -> GitInfo {
         last_commit_at: None,
         branch: None,
         remote_url: None,
+        dirty: None,
+        ahead: None,
+        default_branch: None,
+        local_branches: Vec::new(),
+    }
+}
+
+/// Files git tracks under `dir` (taken from the index, so freshly built artifacts never
+/// appear), or `None` when `dir` is not inside a work tree.
+#[cfg(feature = "git")]
+pub fn tracked_files(dir: &Path) -> Option<Vec<PathBuf>> {
+    let repo = Repository::discover(dir).ok()?;
+    let workdir = repo.workdir()?.canonicalize().ok()?;
+    let dir = dir.canonicalize().ok()?;
+    let index = repo.index().ok()?;
+    Some(
+        index
+            .iter()
+            .filter_map(|entry| {
+                let rel = std::str::from_utf8(&entry.path).ok()?;
+                let path = workdir.join(rel);
+                path.starts_with(&dir).then_some(path)
+            })
+            .collect(),
+    )
+}
+
+/// Without the `git` feature the `git` CLI lists the index instead.
+#[cfg(not(feature = "git"))]
+pub fn tracked_files(dir: &Path) -> Option<Vec<PathBuf>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["ls-files", "-z"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(
+        out.stdout
+            .split(|b| *b == 0)
+            .filter_map(|rel| std::str::from_utf8(rel).ok())
+            .filter(|rel| !rel.is_empty())
+            .map(|rel| dir.join(rel))
+            .collect(),
+    )
+}
+
+/// Commit time of HEAD for the repo containing `dir`.
+#[cfg(feature = "git")]
+pub fn last_commit_at(dir: &Path) -> Option<i64> {
+    read_git_info(dir).last_commit_at
+}
+
+#[cfg(not(feature = "git"))]
+pub fn last_commit_at(dir: &Path) -> Option<i64> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["log", "-1", "--format=%ct"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    String::from_utf8_lossy(&out.stdout).trim().parse().ok()
+}
+
+/// Commit times (Unix seconds) reachable from HEAD back to `since`, newest first.
+#[cfg(feature = "git")]
+pub fn commit_times_since(dir: &Path, since: i64) -> Option<Vec<i64>> {
+    let repo = Repository::discover(dir).ok()?;
+    let mut walk = repo.revwalk().ok()?;
+    walk.push_head().ok()?;
+    walk.set_sorting(git2::Sort::TIME).ok()?;
+    let mut times = Vec::new();
+    for oid in walk.flatten() {
+        let Ok(commit) = repo.find_commit(oid) else {
+            continue;
+        };
+        let t = commit.time().seconds();
+        // Time-sorted, so everything after this is older
+        if t < since {
+            break;
+        }
+        times.push(t);
     }
+    Some(times)
+}
+
+#[cfg(not(feature = "git"))]
+pub fn commit_times_since(dir: &Path, since: i64) -> Option<Vec<i64>> {
+    let out = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["log", &format!("--since=@{since}"), "--format=%ct"])
+        .output()
+        .ok()?;
+    if !out.status.success() {
+        return None;
+    }
+    Some(
+        String::from_utf8_lossy(&out.stdout)
+            .lines()
+            .filter_map(|l| l.trim().parse().ok())
+            .collect(),
+    )
+}
+
+/// URL of `origin` (else the first remote) for the repo at `dir`.
+#[cfg(feature = "git")]
+pub fn remote_url(dir: &Path) -> Option<String> {
+    read_git_info(dir).remote_url
+}
+
+#[cfg(not(feature = "git"))]
+pub fn remote_url(dir: &Path) -> Option<String> {
+    let git = |args: &[&str]| {
+        let out = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .ok()?;
+        let text = String::from_utf8_lossy(&out.stdout).trim().to_string();
+        (out.status.success() && !text.is_empty()).then_some(text)
+    };
+    git(&["config", "--get", "remote.origin.url"]).or_else(|| {
+        let first = git(&["remote"])?.lines().next()?.to_string();
+        git(&["config", "--get", &format!("remote.{first}.url")])
+    })
 }
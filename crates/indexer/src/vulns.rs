@@ -0,0 +1,149 @@
+use anyhow::Result;
+use serde::Serialize;
+use serde_json::Value;
+use std::path::Path;
+use std::process::Command;
+
+/// Highest advisory severity reported for a project. `Unknown` is used when the tool
+/// reports a vulnerability without a severity (cargo-audit, pip-audit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Unknown,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 5] = [
+        Severity::Unknown,
+        Severity::Low,
+        Severity::Moderate,
+        Severity::High,
+        Severity::Critical,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Unknown => "unknown",
+            Severity::Low => "low",
+            Severity::Moderate => "moderate",
+            Severity::High => "high",
+            Severity::Critical => "critical",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VulnSummary {
+    /// External tool that produced the report (cargo-audit, npm-audit, pip-audit)
+    pub tool: &'static str,
+    pub vuln_count: i64,
+    pub severity_max: Option<Severity>,
+}
+
+/// Run the ecosystem's audit tool for a project, if one applies and is installed.
+///
+/// Returns Ok(None) when there is no lockfile to audit or the tool is not available.
+pub fn audit_project(dir: &Path, project_type: Option<&str>) -> Result<Option<VulnSummary>> {
+    match project_type {
+        Some("rust") if dir.join("Cargo.lock").exists() => {
+            run_json(dir, "cargo", &["audit", "--json"]).map(|v| v.map(|v| parse_cargo_audit(&v)))
+        }
+        Some("node" | "deno" | "bun") if dir.join("package-lock.json").exists() => {
+            run_json(dir, "npm", &["audit", "--json"]).map(|v| v.map(|v| parse_npm_audit(&v)))
+        }
+        Some("python") if dir.join("requirements.txt").exists() => run_json(
+            dir,
+            "pip-audit",
+            &[
+                "-f",
+                "json",
+                "--progress-spinner",
+                "off",
+                "-r",
+                "requirements.txt",
+            ],
+        )
+        .map(|v| v.map(|v| parse_pip_audit(&v))),
+        _ => Ok(None),
+    }
+}
+
+/// Audit tools exit non-zero when vulnerabilities are found, so only stdout is inspected.
+fn run_json(dir: &Path, program: &str, args: &[&str]) -> Result<Option<Value>> {
+    let output = match Command::new(program).args(args).current_dir(dir).output() {
+        Ok(o) => o,
+        Err(err) => {
+            tracing::debug!(%err, program, "audit tool not available");
+            return Ok(None);
+        }
+    };
+    match serde_json::from_slice(&output.stdout) {
+        Ok(v) => Ok(Some(v)),
+        Err(err) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("{program} {args:?} produced no JSON report: {err}\n{stderr}")
+        }
+    }
+}
+
+pub fn parse_cargo_audit(v: &Value) -> VulnSummary {
+    let count = v["vulnerabilities"]["count"].as_i64().unwrap_or(0);
+    VulnSummary {
+        tool: "cargo-audit",
+        vuln_count: count,
+        severity_max: (count > 0).then_some(Severity::Unknown),
+    }
+}
+
+pub fn parse_npm_audit(v: &Value) -> VulnSummary {
+    let counts = &v["metadata"]["vulnerabilities"];
+    let mut total = 0;
+    let mut max = None;
+    for sev in [
+        Severity::Low,
+        Severity::Moderate,
+        Severity::High,
+        Severity::Critical,
+    ] {
+        let n = counts[sev.as_str()].as_i64().unwrap_or(0);
+        total += n;
+        if n > 0 {
+            max = Some(sev);
+        }
+    }
+    // npm reports "info" advisories too; count them without raising severity
+    total += counts["info"].as_i64().unwrap_or(0);
+    if total > 0 && max.is_none() {
+        max = Some(Severity::Unknown);
+    }
+    VulnSummary {
+        tool: "npm-audit",
+        vuln_count: total,
+        severity_max: max,
+    }
+}
+
+pub fn parse_pip_audit(v: &Value) -> VulnSummary {
+    // pip-audit >= 2.5 wraps the list in {"dependencies": [...]}
+    let deps = v["dependencies"].as_array().or_else(|| v.as_array());
+    let count = deps
+        .map(|deps| {
+            deps.iter()
+                .map(|d| d["vulns"].as_array().map(|a| a.len() as i64).unwrap_or(0))
+                .sum()
+        })
+        .unwrap_or(0);
+    VulnSummary {
+        tool: "pip-audit",
+        vuln_count: count,
+        severity_max: (count > 0).then_some(Severity::Unknown),
+    }
+}
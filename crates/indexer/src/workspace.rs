@@ -0,0 +1,211 @@
+//! Monorepo/workspace members. A project whose manifest declares workspace members (Cargo
+//! `[workspace]`, npm/yarn `workspaces`, `pnpm-workspace.yaml`, `go.work`, `lerna.json`)
+//! has those directories indexed as child projects linked to it through `parent_id`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// `**` patterns look this many directories deep at most
+const MAX_GLOB_DEPTH: usize = 4;
+
+/// Member directories declared by the workspace manifests in `dir`, existing ones only,
+/// sorted and without `dir` itself.
+pub fn workspace_members(dir: &Path) -> Vec<PathBuf> {
+    let mut patterns = Vec::new();
+    let mut excluded = Vec::new();
+    cargo_members(dir, &mut patterns, &mut excluded);
+    npm_members(dir, &mut patterns);
+    pnpm_members(dir, &mut patterns);
+    go_work_members(dir, &mut patterns);
+    lerna_members(dir, &mut patterns);
+
+    let mut out: Vec<PathBuf> = Vec::new();
+    for pattern in &patterns {
+        // npm/pnpm negations ("!packages/legacy") remove matches
+        if let Some(neg) = pattern.strip_prefix('!') {
+            excluded.push(neg.to_string());
+            continue;
+        }
+        out.extend(expand(dir, pattern));
+    }
+    let excluded: Vec<PathBuf> = excluded.iter().flat_map(|p| expand(dir, p)).collect();
+    out.retain(|p| p != dir && p.starts_with(dir) && !excluded.contains(p));
+    out.sort();
+    out.dedup();
+    out
+}
+
+fn cargo_members(dir: &Path, patterns: &mut Vec<String>, excluded: &mut Vec<String>) {
+    let Ok(text) = fs::read_to_string(dir.join("Cargo.toml")) else {
+        return;
+    };
+    let Ok(doc) = text.parse::<toml::Table>() else {
+        return;
+    };
+    let Some(ws) = doc.get("workspace").and_then(|w| w.as_table()) else {
+        return;
+    };
+    let strings = |key: &str| -> Vec<String> {
+        ws.get(key)
+            .and_then(|v| v.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    patterns.extend(strings("members"));
+    excluded.extend(strings("exclude"));
+}
+
+fn json_strings(v: Option<&serde_json::Value>) -> Vec<String> {
+    v.and_then(|v| v.as_array())
+        .map(|a| {
+            a.iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn read_json(path: &Path) -> Option<serde_json::Value> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// `"workspaces": [...]` or yarn's `"workspaces": { "packages": [...] }`
+fn npm_members(dir: &Path, patterns: &mut Vec<String>) {
+    let Some(pkg) = read_json(&dir.join("package.json")) else {
+        return;
+    };
+    let ws = pkg.get("workspaces");
+    patterns.extend(json_strings(ws));
+    patterns.extend(json_strings(ws.and_then(|w| w.get("packages"))));
+}
+
+fn lerna_members(dir: &Path, patterns: &mut Vec<String>) {
+    if let Some(lerna) = read_json(&dir.join("lerna.json")) {
+        patterns.extend(json_strings(lerna.get("packages")));
+    }
+}
+
+/// The `packages:` list of `pnpm-workspace.yaml`; only the block-list form is understood.
+fn pnpm_members(dir: &Path, patterns: &mut Vec<String>) {
+    let Ok(text) = fs::read_to_string(dir.join("pnpm-workspace.yaml")) else {
+        return;
+    };
+    let mut in_packages = false;
+    for line in text.lines() {
+        let trimmed = line.trim();
+        if !line.starts_with([' ', '\t', '-']) && !trimmed.is_empty() {
+            in_packages = trimmed.starts_with("packages:");
+            continue;
+        }
+        if in_packages {
+            if let Some(item) = trimmed.strip_prefix('-') {
+                let item = item.split(" #").next().unwrap_or("").trim();
+                patterns.push(item.trim_matches(['\'', '"']).to_string());
+            }
+        }
+    }
+}
+
+/// `use ./a` and `use ( ./a ./b )` directives of `go.work`
+fn go_work_members(dir: &Path, patterns: &mut Vec<String>) {
+    let Ok(text) = fs::read_to_string(dir.join("go.work")) else {
+        return;
+    };
+    let mut in_block = false;
+    for line in text.lines() {
+        let line = line.split("//").next().unwrap_or("").trim();
+        if in_block {
+            if line == ")" {
+                in_block = false;
+            } else if !line.is_empty() {
+                patterns.push(line.to_string());
+            }
+        } else if let Some(rest) = line.strip_prefix("use") {
+            let rest = rest.trim();
+            if rest == "(" {
+                in_block = true;
+            } else if !rest.is_empty() {
+                patterns.push(rest.to_string());
+            }
+        }
+    }
+}
+
+/// Directories under `base` matching `pattern` (`*` within a component, `**` for any depth).
+fn expand(base: &Path, pattern: &str) -> Vec<PathBuf> {
+    let parts: Vec<&str> = pattern
+        .trim_start_matches("./")
+        .trim_end_matches('/')
+        .split('/')
+        .filter(|p| !p.is_empty() && *p != ".")
+        .collect();
+    let mut out = Vec::new();
+    expand_parts(base, &parts, 0, &mut out);
+    out
+}
+
+fn expand_parts(dir: &Path, parts: &[&str], depth: usize, out: &mut Vec<PathBuf>) {
+    let Some((first, rest)) = parts.split_first() else {
+        if dir.is_dir() {
+            out.push(dir.to_path_buf());
+        }
+        return;
+    };
+    if *first == "**" {
+        expand_parts(dir, rest, depth, out);
+        if depth < MAX_GLOB_DEPTH {
+            for sub in subdirs(dir) {
+                expand_parts(&sub, parts, depth + 1, out);
+            }
+        }
+    } else if first.contains('*') {
+        for sub in subdirs(dir) {
+            let name = sub.file_name().map(|n| n.to_string_lossy().into_owned());
+            if name.is_some_and(|n| !n.starts_with('.') && wildcard(first, &n)) {
+                expand_parts(&sub, rest, depth + 1, out);
+            }
+        }
+    } else if *first == ".." {
+        // Members outside the workspace root are not children of it
+    } else {
+        expand_parts(&dir.join(first), rest, depth + 1, out);
+    }
+}
+
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+    let Ok(rd) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut dirs: Vec<PathBuf> = rd
+        .flatten()
+        .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+        .map(|e| e.path())
+        .filter(|p| p.file_name().is_some_and(|n| n != "node_modules"))
+        .collect();
+    dirs.sort();
+    dirs
+}
+
+/// `*` matches any run of characters.
+fn wildcard(pattern: &str, name: &str) -> bool {
+    let mut pieces = pattern.split('*');
+    let first = pieces.next().unwrap_or("");
+    let Some(mut rest) = name.strip_prefix(first) else {
+        return false;
+    };
+    let pieces: Vec<&str> = pieces.collect();
+    for (i, piece) in pieces.iter().enumerate() {
+        if i == pieces.len() - 1 {
+            return rest.ends_with(piece);
+        }
+        match rest.find(piece) {
+            Some(at) => rest = &rest[at + piece.len()..],
+            None => return false,
+        }
+    }
+    rest.is_empty()
+}
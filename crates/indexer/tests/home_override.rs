@@ -0,0 +1,24 @@
+//! `PROJECT_BROWSER_HOME` is read from the process environment, so this test gets a binary
+//! of its own: setting it in `basic` would leak into tests running alongside there.
+
+use indexer::config::{ConfigStore, HOME_ENV};
+use indexer::{AppConfig, Db};
+
+#[test]
+fn home_override_relocates_config_and_data() {
+    let home = tempfile::tempdir().unwrap();
+    std::env::set_var(HOME_ENV, home.path());
+    assert_eq!(ConfigStore::config_dir().unwrap(), home.path());
+    assert_eq!(ConfigStore::data_dir().unwrap(), home.path());
+    assert!(ConfigStore::user_ignore_path_legacy().is_none());
+
+    let cfg = AppConfig {
+        roots: vec![home.path().join("code")],
+        ..AppConfig::default()
+    };
+    ConfigStore::save(&cfg).unwrap();
+    assert!(home.path().join("config.json").is_file());
+    assert_eq!(ConfigStore::load().unwrap().roots, cfg.roots);
+    let db = Db::open_default().unwrap();
+    assert_eq!(db.path, home.path().join("projects.sqlite"));
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+use crate::FileType;
+
+/// Optional `.versionrc.toml` at the repo root. Patterns are globs relative to the root,
+/// e.g. `exclude = ["crates/version-manager/Cargo.toml", "examples/**"]`.
+#[derive(Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+pub struct VersionRc {
+    /// Extra files to manage even if discovery would skip them
+    #[serde(default)]
+    pub include: Vec<String>,
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// Lockstep groups; files outside every group are versioned independently.
+    /// Without any groups, all files form one lockstep group.
+    #[serde(default)]
+    pub groups: Vec<GroupConfig>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct GroupConfig {
+    pub name: String,
+    /// Globs matched against discovered paths
+    pub members: Vec<String>,
+    /// Git tag prefix for this group. Default: `v`
+    #[serde(default)]
+    pub tag_prefix: Option<String>,
+}
+
+pub const VERSIONRC: &str = ".versionrc.toml";
+
+/// Directories never descended into
+const SKIP_DIRS: &[&str] = &["node_modules", "target", "dist", "build"];
+
+pub fn load_versionrc(root: &Path) -> Result<VersionRc> {
+    let path = root.join(VERSIONRC);
+    if !path.exists() {
+        return Ok(VersionRc::default());
+    }
+    let content = fs::read_to_string(&path)?;
+    toml::from_str(&content).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
+pub fn glob_set(patterns: &[String]) -> Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+    for p in patterns {
+        builder.add(Glob::new(p).with_context(|| format!("Invalid pattern in {VERSIONRC}: {p}"))?);
+    }
+    Ok(builder.build()?)
+}
+
+pub fn file_type(path: &Path) -> Option<FileType> {
+    match path.file_name()?.to_str()? {
+        "Cargo.toml" => Some(FileType::CargoToml),
+        "package.json" => Some(FileType::PackageJson),
+        "tauri.conf.json" => Some(FileType::TauriConfig),
+        _ => None,
+    }
+}
+
+/// A discovered file and whether `.versionrc.toml` asked for it explicitly. Explicit files
+/// are reported even when they carry no version; discovered ones are dropped in that case.
+pub struct Candidate {
+    pub path: PathBuf,
+    pub file_type: FileType,
+    pub explicit: bool,
+}
+
+/// Walk `root` for version-bearing files, skipping hidden and build/dependency directories,
+/// then apply `.versionrc.toml`. Paths are relative to `root` and sorted.
+pub fn discover(root: &Path, rc: &VersionRc) -> Result<Vec<Candidate>> {
+    let include = glob_set(&rc.include)?;
+    let exclude = glob_set(&rc.exclude)?;
+
+    let walker = WalkDir::new(root).sort_by_file_name().into_iter();
+    let mut out = Vec::new();
+    for entry in walker.filter_entry(|e| {
+        e.depth() == 0
+            || !e.file_type().is_dir()
+            || e.file_name()
+                .to_str()
+                .is_some_and(|n| !n.starts_with('.') && !SKIP_DIRS.contains(&n))
+    }) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(root)?.to_path_buf();
+        let Some(file_type) = file_type(&rel) else {
+            continue;
+        };
+        let explicit = include.is_match(&rel);
+        if !explicit && exclude.is_match(&rel) {
+            continue;
+        }
+        out.push(Candidate {
+            path: rel,
+            file_type,
+            explicit,
+        });
+    }
+
+    // Included paths inside skipped directories are not reached by the walk
+    for pattern in &rc.include {
+        let rel = PathBuf::from(pattern);
+        if out.iter().any(|c| c.path == rel) || !root.join(&rel).is_file() {
+            continue;
+        }
+        let file_type = file_type(&rel)
+            .with_context(|| format!("{VERSIONRC}: unsupported file type {pattern}"))?;
+        out.push(Candidate {
+            path: rel,
+            file_type,
+            explicit: true,
+        });
+    }
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(out)
+}
@@ -0,0 +1,250 @@
+//! Format-preserving version edits. Only the manifest's own version is touched:
+//! `package.version` / `workspace.package.version` in Cargo.toml and the top-level
+//! `"version"` key in JSON files. Everything else, comments and key order included,
+//! is written back byte for byte.
+
+use anyhow::{bail, Context, Result};
+use semver::Version;
+use toml_edit::{DocumentMut, Formatted, Item, Value};
+
+/// Returns the edited manifest. Packages with `version.workspace = true` are left as is.
+pub fn set_cargo_version(content: &str, new_version: &Version) -> Result<String> {
+    let mut doc: DocumentMut = content.parse().context("Invalid TOML")?;
+    let package = doc.get_mut("package").and_then(|p| p.get_mut("version"));
+    let mut updated = replace_string(package, new_version);
+    let workspace = doc
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("package"))
+        .and_then(|p| p.get_mut("version"));
+    updated |= replace_string(workspace, new_version);
+    if !updated {
+        bail!("no package.version or workspace.package.version string found");
+    }
+    Ok(doc.to_string())
+}
+
+/// Swap a string value in place, keeping its surrounding whitespace and comments.
+fn replace_string(item: Option<&mut Item>, new_version: &Version) -> bool {
+    let Some(Value::String(s)) = item.and_then(Item::as_value_mut) else {
+        return false;
+    };
+    let decor = s.decor().clone();
+    *s = Formatted::new(new_version.to_string());
+    *s.decor_mut() = decor;
+    true
+}
+
+/// Replace the value of the top-level `"version"` string, leaving nested `version` keys
+/// (dependencies, engines, ...) and the file's formatting alone.
+pub fn set_json_version(content: &str, new_version: &Version) -> Result<String> {
+    set_json_string(content, &["version"], new_version)
+}
+
+/// Like `set_json_version` for the string at `path`, a chain of object keys from the root.
+pub fn set_json_string(content: &str, path: &[&str], new_version: &Version) -> Result<String> {
+    let (start, end) = string_span(content, path)?;
+    let mut out = String::with_capacity(content.len() + 8);
+    out.push_str(&content[..start]);
+    out.push_str(&serde_json::to_string(&new_version.to_string())?);
+    out.push_str(&content[end..]);
+    serde_json::from_str::<serde_json::Value>(&out).context("edit produced invalid JSON")?;
+    Ok(out)
+}
+
+/// Byte range of the quoted string at `path`.
+fn string_span(content: &str, path: &[&str]) -> Result<(usize, usize)> {
+    // Validate up front so the scanner below can assume well-formed input
+    serde_json::from_str::<serde_json::Value>(content).context("Invalid JSON")?;
+    let b = content.as_bytes();
+    let mut span = (skip_ws(b, 0), content.len());
+    for (depth, key) in path.iter().enumerate() {
+        if b.get(span.0) != Some(&b'{') {
+            match depth {
+                0 => bail!("top-level JSON value is not an object"),
+                _ => bail!("\"{}\" is not an object", path[depth - 1]),
+            }
+        }
+        span = find_key(content, span.0, key)?
+            .with_context(|| format!("no \"{}\" key", path[..=depth].join(".")))?;
+    }
+    if b[span.0] != b'"' {
+        bail!("\"{}\" is not a string", path.join("."));
+    }
+    Ok(span)
+}
+
+/// Value span of `key` among the direct members of the object starting at `i`.
+fn find_key(content: &str, mut i: usize, key: &str) -> Result<Option<(usize, usize)>> {
+    let b = content.as_bytes();
+    i += 1;
+    loop {
+        i = skip_ws(b, i);
+        match b.get(i) {
+            Some(b'}') | None => return Ok(None),
+            Some(b',') => {
+                i += 1;
+                continue;
+            }
+            _ => {}
+        }
+        let key_end = skip_string(b, i);
+        let name: String = serde_json::from_str(&content[i..key_end])?;
+        i = skip_ws(b, key_end) + 1; // ':'
+        i = skip_ws(b, i);
+        let value_end = skip_value(b, i);
+        if name == key {
+            return Ok(Some((i, value_end)));
+        }
+        i = value_end;
+    }
+}
+
+fn skip_ws(b: &[u8], mut i: usize) -> usize {
+    while i < b.len() && b[i].is_ascii_whitespace() {
+        i += 1;
+    }
+    i
+}
+
+/// `i` is at the opening quote; returns the index after the closing quote.
+fn skip_string(b: &[u8], mut i: usize) -> usize {
+    i += 1;
+    while i < b.len() {
+        match b[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+fn skip_value(b: &[u8], mut i: usize) -> usize {
+    match b[i] {
+        b'"' => skip_string(b, i),
+        b'{' | b'[' => {
+            let mut depth = 0usize;
+            while i < b.len() {
+                match b[i] {
+                    b'"' => {
+                        i = skip_string(b, i);
+                        continue;
+                    }
+                    b'{' | b'[' => depth += 1,
+                    b'}' | b']' => {
+                        depth -= 1;
+                        if depth == 0 {
+                            return i + 1;
+                        }
+                    }
+                    _ => {}
+                }
+                i += 1;
+            }
+            i
+        }
+        _ => {
+            while i < b.len() && !matches!(b[i], b',' | b'}' | b']') && !b[i].is_ascii_whitespace()
+            {
+                i += 1;
+            }
+            i
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn v(s: &str) -> Version {
+        Version::parse(s).unwrap()
+    }
+
+    #[test]
+    fn cargo_leaves_dependency_versions_alone() {
+        let src = r#"# leading comment
+[dependencies]
+serde = { version = "1.0.0", features = ["derive"] }
+
+[package]
+name = "x"
+version   =   "0.1.0" # keep me
+edition = "2021"
+
+[dependencies.anyhow]
+version = "1"
+"#;
+        let out = set_cargo_version(src, &v("0.2.0-rc.1")).unwrap();
+        assert_eq!(
+            out,
+            src.replace(r#""0.1.0" # keep me"#, r#""0.2.0-rc.1" # keep me"#)
+        );
+    }
+
+    #[test]
+    fn cargo_workspace_package_and_inherited_versions() {
+        let root = "[workspace]\nmembers = [\"a\"]\n\n[workspace.package]\nversion = \"1.2.3\"\n\n[workspace.dependencies]\nserde = { version = \"1\" }\n";
+        let out = set_cargo_version(root, &v("1.3.0")).unwrap();
+        assert!(out.contains("[workspace.package]\nversion = \"1.3.0\""));
+        assert!(out.contains("serde = { version = \"1\" }"));
+
+        let member = "[package]\nname = \"a\"\nversion.workspace = true\n";
+        assert!(set_cargo_version(member, &v("1.3.0")).is_err());
+        let virtual_manifest = "[workspace]\nmembers = []\n";
+        assert!(set_cargo_version(virtual_manifest, &v("1.3.0")).is_err());
+    }
+
+    #[test]
+    fn cargo_inline_package_table() {
+        let src = "package = { name = \"x\", version = \"0.1.0\" }\n";
+        let out = set_cargo_version(src, &v("0.1.1+build.5")).unwrap();
+        assert_eq!(
+            out,
+            "package = { name = \"x\", version = \"0.1.1+build.5\" }\n"
+        );
+    }
+
+    #[test]
+    fn json_only_touches_top_level_version() {
+        let src = r#"{
+  "name": "web",
+  "engines": { "node": ">=18", "version": "9.9.9" },
+  "scripts": {"build": "echo \"version\": \"0.0.0\""},
+  "tags": ["version", {"version": "1"}],
+  "private": true,
+  "version":"0.1.11",
+  "dependencies": { "react": "^18.0.0" }
+}
+"#;
+        let out = set_json_version(src, &v("0.2.0")).unwrap();
+        assert_eq!(
+            out,
+            src.replace(r#""version":"0.1.11""#, r#""version":"0.2.0""#)
+        );
+    }
+
+    #[test]
+    fn json_errors_without_string_version() {
+        assert!(set_json_version(r#"{"nested": {"version": "1.0.0"}}"#, &v("1.0.1")).is_err());
+        assert!(set_json_version(r#"{"version": 1}"#, &v("1.0.1")).is_err());
+        assert!(set_json_version(r#"["version"]"#, &v("1.0.1")).is_err());
+        assert!(set_json_version("{ not json", &v("1.0.1")).is_err());
+    }
+
+    #[test]
+    fn json_nested_path_for_lockfiles() {
+        let src = r#"{"name":"web","version":"0.1.0","lockfileVersion":3,"packages":{"":{"name":"web","version":"0.1.0"},"node_modules/a":{"version":"0.1.0"}}}"#;
+        let err = set_json_string(src, &["packages", ""], &v("0.2.0")).unwrap_err();
+        assert!(err.to_string().contains("not a string"));
+        let out = set_json_string(src, &["packages", "", "version"], &v("0.2.0")).unwrap();
+        assert_eq!(
+            out,
+            src.replacen(
+                r#""name":"web","version":"0.1.0"}"#,
+                r#""name":"web","version":"0.2.0"}"#,
+                1
+            )
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use anyhow::{bail, Context, Result};
+
+use crate::discover::{glob_set, VersionRc};
+use crate::VersionFile;
+
+/// Files that must always share one version. Indices point into the discovered file list.
+pub struct Group {
+    pub name: String,
+    pub tag_prefix: String,
+    pub members: Vec<usize>,
+    /// The implicit group used when `.versionrc.toml` configures none
+    pub implicit: bool,
+}
+
+/// Lockstep groups plus the files outside all of them, which are versioned independently.
+/// A file belongs to the first group whose globs match it.
+pub fn resolve(files: &[VersionFile], rc: &VersionRc) -> Result<(Vec<Group>, Vec<usize>)> {
+    if rc.groups.is_empty() {
+        let group = Group {
+            name: "default".to_string(),
+            tag_prefix: "v".to_string(),
+            members: (0..files.len()).collect(),
+            implicit: true,
+        };
+        return Ok((vec![group], Vec::new()));
+    }
+    let mut groups = Vec::new();
+    let mut claimed = vec![false; files.len()];
+    for config in &rc.groups {
+        let set = glob_set(&config.members)?;
+        let members: Vec<usize> = (0..files.len())
+            .filter(|&i| !claimed[i] && set.is_match(&files[i].path))
+            .collect();
+        for &i in &members {
+            claimed[i] = true;
+        }
+        groups.push(Group {
+            name: config.name.clone(),
+            tag_prefix: config.tag_prefix.clone().unwrap_or_else(|| "v".to_string()),
+            members,
+            implicit: false,
+        });
+    }
+    let independent = (0..files.len()).filter(|&i| !claimed[i]).collect();
+    Ok((groups, independent))
+}
+
+/// The files one command operates on.
+pub struct Selection {
+    /// Group or package name; `None` for the implicit all-files group
+    pub label: Option<String>,
+    pub tag_prefix: String,
+    pub members: Vec<usize>,
+}
+
+impl Selection {
+    fn from_group(group: &Group) -> Self {
+        Self {
+            label: (!group.implicit).then(|| group.name.clone()),
+            tag_prefix: group.tag_prefix.clone(),
+            members: group.members.clone(),
+        }
+    }
+}
+
+/// Resolve `--package` (a package name or manifest path) or `--group`. A package inside a
+/// lockstep group selects its whole group. With neither, there must be exactly one group.
+pub fn select(
+    files: &[VersionFile],
+    rc: &VersionRc,
+    package: Option<&str>,
+    group: Option<&str>,
+) -> Result<Selection> {
+    let (groups, _) = resolve(files, rc)?;
+    if let Some(package) = package {
+        let idx = files
+            .iter()
+            .position(|f| f.name.as_deref() == Some(package) || f.path == package)
+            .with_context(|| format!("No version file for package {package}"))?;
+        if let Some(g) = groups.iter().find(|g| g.members.contains(&idx)) {
+            return Ok(Selection::from_group(g));
+        }
+        let name = files[idx]
+            .name
+            .clone()
+            .unwrap_or_else(|| package.to_string());
+        return Ok(Selection {
+            tag_prefix: format!("{name}-v"),
+            label: Some(name),
+            members: vec![idx],
+        });
+    }
+    if let Some(group) = group {
+        let g = groups
+            .iter()
+            .find(|g| g.name == group)
+            .with_context(|| format!("No group named {group}"))?;
+        return Ok(Selection::from_group(g));
+    }
+    match groups.as_slice() {
+        [only] => Ok(Selection::from_group(only)),
+        _ => {
+            let names: Vec<&str> = groups.iter().map(|g| g.name.as_str()).collect();
+            bail!(
+                "Several version groups are configured ({}); pass --group or --package",
+                names.join(", ")
+            )
+        }
+    }
+}
@@ -1,11 +1,18 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use colored::*;
-use semver::Version;
-use serde::Deserialize;
-use std::collections::HashMap;
+use discover::VersionRc;
+use groups::Selection;
+use semver::{BuildMetadata, Prerelease, Version};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
+
+mod discover;
+mod edit;
+mod groups;
+mod release;
 
 #[derive(Parser)]
 #[command(name = "version-manager")]
@@ -19,59 +26,156 @@ struct Cli {
 enum Commands {
     /// Bump version by type (major, minor, patch)
     Bump {
-        /// Version bump type: major, minor, or patch
+        /// Version bump type: major, minor, patch, premajor, preminor, prepatch, or prerelease
         #[arg(value_enum)]
         bump_type: BumpType,
 
-        /// Commit changes after bumping
-        #[arg(short, long)]
-        commit: bool,
+        /// Pre-release identifier for pre* bumps, e.g. `rc` gives 1.3.0-rc.0
+        #[arg(long)]
+        preid: Option<String>,
+
+        #[command(flatten)]
+        target: TargetArgs,
+
+        #[command(flatten)]
+        apply: ApplyArgs,
+    },
+    /// Set an explicit version, e.g. 2.0.0-rc.1 or 1.4.0+build.7
+    Set {
+        version: String,
+
+        #[command(flatten)]
+        target: TargetArgs,
 
-        /// Create git tag after bumping
-        #[arg(short, long)]
-        tag: bool,
+        #[command(flatten)]
+        apply: ApplyArgs,
+    },
+    /// Create a GitHub release with a changelog from the commits since the previous tag
+    Release {
+        /// Tag to release (default: the selected group's tag for its current version)
+        #[arg(long)]
+        tag: Option<String>,
+
+        #[command(flatten)]
+        target: TargetArgs,
+
+        /// Create the release as a draft
+        #[arg(long)]
+        draft: bool,
+    },
+    /// Check that every lockstep group has a single version
+    Check {
+        /// Print a JSON report instead of text
+        #[arg(long)]
+        json: bool,
+
+        /// Exit with status 1 if any group has drifted (for CI)
+        #[arg(long)]
+        strict: bool,
     },
-    /// Check if versions are synchronized across all files
-    Check,
     /// Show current versions from all files
-    Show,
+    Show {
+        /// Print JSON instead of text
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// Which files a command applies to; see `groups` in `.versionrc.toml`
+#[derive(clap::Args)]
+struct TargetArgs {
+    /// Package name or manifest path; a package in a lockstep group selects the whole group
+    #[arg(long, conflicts_with = "group")]
+    package: Option<String>,
+
+    /// Lockstep group name from .versionrc.toml
+    #[arg(long)]
+    group: Option<String>,
 }
 
-#[derive(clap::ValueEnum, Clone)]
+/// What to do after the version files are written
+#[derive(clap::Args)]
+struct ApplyArgs {
+    /// Commit changes after updating
+    #[arg(short, long)]
+    commit: bool,
+
+    /// Create git tag after updating
+    #[arg(short, long)]
+    tag: bool,
+
+    /// Refresh Cargo.lock and package-lock.json so they match the new version
+    #[arg(long)]
+    update_locks: bool,
+
+    /// Push the current branch (and the tag, with --tag) to origin
+    #[arg(long, requires = "commit")]
+    push: bool,
+
+    /// Create a GitHub release for the new tag
+    #[arg(long, requires_all = ["tag", "push"])]
+    release: bool,
+}
+
+#[derive(clap::ValueEnum, Clone, Copy)]
 enum BumpType {
     Major,
     Minor,
     Patch,
+    Premajor,
+    Preminor,
+    Prepatch,
+    /// Increment the pre-release number, or start one on the next patch
+    Prerelease,
 }
 
 #[derive(Deserialize)]
 struct CargoToml {
     package: Option<CargoPackage>,
+    workspace: Option<CargoWorkspace>,
 }
 
 #[derive(Deserialize)]
 struct CargoPackage {
+    name: Option<String>,
+    /// A string, or a table for `version.workspace = true`
+    version: Option<toml::Value>,
+}
+
+#[derive(Deserialize)]
+struct CargoWorkspace {
+    package: Option<CargoWorkspacePackage>,
+}
+
+#[derive(Deserialize)]
+struct CargoWorkspacePackage {
     version: Option<String>,
 }
 
 #[derive(Deserialize)]
 struct PackageJson {
+    name: Option<String>,
     version: Option<String>,
 }
 
 #[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
 struct TauriConfig {
+    product_name: Option<String>,
     version: Option<String>,
 }
 
 #[derive(Debug)]
 struct VersionFile {
     path: String,
+    /// Package name, used by `--package`
+    name: Option<String>,
     version: Option<Version>,
     file_type: FileType,
 }
 
-#[derive(Debug, PartialEq)]
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
 enum FileType {
     CargoToml,
     PackageJson,
@@ -84,130 +188,275 @@ fn main() -> Result<()> {
     match cli.command {
         Commands::Bump {
             bump_type,
-            commit,
-            tag,
+            preid,
+            target,
+            apply,
         } => {
-            bump_version(bump_type, commit, tag)?;
+            bump_version(bump_type, preid.as_deref(), &target, &apply)?;
+        }
+        Commands::Set {
+            version,
+            target,
+            apply,
+        } => {
+            set_version(&version, &target, &apply)?;
+        }
+        Commands::Release { tag, target, draft } => {
+            let tag = match tag {
+                Some(tag) => tag,
+                None => {
+                    let (selection, files) = selected_files(&target)?;
+                    tag_name(&selection.tag_prefix, &current_version(&files)?)
+                }
+            };
+            release::create_release(&tag, draft)?;
         }
-        Commands::Check => {
-            check_version_sync()?;
+        Commands::Check { json, strict } => {
+            check_version_sync(json, strict)?;
         }
-        Commands::Show => {
-            show_versions()?;
+        Commands::Show { json } => {
+            show_versions(json)?;
         }
     }
 
     Ok(())
 }
 
-fn get_version_files() -> Result<Vec<VersionFile>> {
+/// Version-bearing files under the current directory; see `discover` for the rules.
+fn get_version_files(rc: &VersionRc) -> Result<Vec<VersionFile>> {
     let mut files = Vec::new();
 
-    // Cargo.toml files
-    let cargo_files = vec![
-        "src-tauri/Cargo.toml",
-        "crates/cli/Cargo.toml",
-        "crates/indexer/Cargo.toml",
-    ];
-
-    for cargo_file in cargo_files {
-        let path = Path::new(cargo_file);
-        if path.exists() {
-            let content = fs::read_to_string(path)?;
-            let cargo_toml: CargoToml = toml::from_str(&content)
-                .with_context(|| format!("Failed to parse {cargo_file}"))?;
-
-            let version = cargo_toml
-                .package
-                .and_then(|p| p.version)
-                .and_then(|v| Version::parse(&v).ok());
-
-            files.push(VersionFile {
-                path: cargo_file.to_string(),
-                version,
-                file_type: FileType::CargoToml,
-            });
+    for candidate in discover::discover(Path::new("."), rc)? {
+        let path = candidate.path.to_string_lossy().replace('\\', "/");
+        let content = fs::read_to_string(&candidate.path)?;
+        let (name, raw) = match candidate.file_type {
+            FileType::CargoToml => {
+                let cargo_toml: CargoToml =
+                    toml::from_str(&content).with_context(|| format!("Failed to parse {path}"))?;
+                let (name, package) = match cargo_toml.package {
+                    // Packages inheriting `version.workspace = true` follow the root manifest
+                    Some(p) => (
+                        p.name,
+                        p.version.and_then(|v| v.as_str().map(str::to_string)),
+                    ),
+                    None => (None, None),
+                };
+                let workspace = cargo_toml
+                    .workspace
+                    .and_then(|w| w.package)
+                    .and_then(|p| p.version);
+                (name, package.or(workspace))
+            }
+            FileType::PackageJson => {
+                let package_json: PackageJson = serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse {path}"))?;
+                (package_json.name, package_json.version)
+            }
+            FileType::TauriConfig => {
+                let tauri_config: TauriConfig = serde_json::from_str(&content)
+                    .with_context(|| format!("Failed to parse {path}"))?;
+                (tauri_config.product_name, tauri_config.version)
+            }
+        };
+        if raw.is_none() && !candidate.explicit {
+            continue;
         }
-    }
-
-    // package.json
-    let package_json_path = "web/package.json";
-    if Path::new(package_json_path).exists() {
-        let content = fs::read_to_string(package_json_path)?;
-        let package_json: PackageJson = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse {package_json_path}"))?;
-
-        let version = package_json.version.and_then(|v| Version::parse(&v).ok());
 
         files.push(VersionFile {
-            path: package_json_path.to_string(),
-            version,
-            file_type: FileType::PackageJson,
+            path,
+            name,
+            version: raw.and_then(|v| Version::parse(&v).ok()),
+            file_type: candidate.file_type,
         });
     }
 
-    // tauri.conf.json
-    let tauri_config_path = "src-tauri/tauri.conf.json";
-    if Path::new(tauri_config_path).exists() {
-        let content = fs::read_to_string(tauri_config_path)?;
-        let tauri_config: TauriConfig = serde_json::from_str(&content)
-            .with_context(|| format!("Failed to parse {tauri_config_path}"))?;
+    Ok(files)
+}
 
-        let version = tauri_config.version.and_then(|v| Version::parse(&v).ok());
+/// The files chosen by `--package`/`--group`, in discovery order.
+fn selected_files(target: &TargetArgs) -> Result<(Selection, Vec<VersionFile>)> {
+    let rc = discover::load_versionrc(Path::new("."))?;
+    let files = get_version_files(&rc)?;
+    let selection = groups::select(
+        &files,
+        &rc,
+        target.package.as_deref(),
+        target.group.as_deref(),
+    )?;
+    let files = files
+        .into_iter()
+        .enumerate()
+        .filter(|(i, _)| selection.members.contains(i))
+        .map(|(_, f)| f)
+        .collect();
+    Ok((selection, files))
+}
 
-        files.push(VersionFile {
-            path: tauri_config_path.to_string(),
-            version,
-            file_type: FileType::TauriConfig,
-        });
-    }
+/// One row of `show --json`
+#[derive(Serialize)]
+struct FileReport<'a> {
+    path: &'a str,
+    name: Option<&'a str>,
+    version: Option<String>,
+    file_type: &'a FileType,
+    /// `None` for independently versioned files
+    group: Option<&'a str>,
+}
 
-    Ok(files)
+fn file_report<'a>(file: &'a VersionFile, group: Option<&'a str>) -> FileReport<'a> {
+    FileReport {
+        path: &file.path,
+        name: file.name.as_deref(),
+        version: file.version.as_ref().map(Version::to_string),
+        file_type: &file.file_type,
+        group,
+    }
 }
 
-fn show_versions() -> Result<()> {
-    let files = get_version_files()?;
+fn show_versions(json: bool) -> Result<()> {
+    let rc = discover::load_versionrc(Path::new("."))?;
+    let files = get_version_files(&rc)?;
+    let (groups, independent) = groups::resolve(&files, &rc)?;
+
+    if json {
+        let mut rows = Vec::new();
+        for group in &groups {
+            rows.extend(
+                group
+                    .members
+                    .iter()
+                    .map(|&i| file_report(&files[i], Some(&group.name))),
+            );
+        }
+        rows.extend(independent.iter().map(|&i| file_report(&files[i], None)));
+        println!("{}", serde_json::to_string_pretty(&rows)?);
+        return Ok(());
+    }
 
     println!("{}", "Current versions:".green().bold());
     println!("{}", "==================".green().bold());
 
-    for file in files {
-        match file.version {
-            Some(version) => {
-                println!("{}: {}", file.path.cyan(), version.to_string().yellow());
-            }
-            None => {
-                println!("{}: {}", file.path.cyan(), "No version found".red());
-            }
+    let print_file = |file: &VersionFile| match &file.version {
+        Some(version) => {
+            println!("{}: {}", file.path.cyan(), version.to_string().yellow());
+        }
+        None => {
+            println!("{}: {}", file.path.cyan(), "No version found".red());
         }
+    };
+    for group in &groups {
+        if !group.implicit {
+            println!("{}", format!("[{}]", group.name).bold());
+        }
+        group.members.iter().for_each(|&i| print_file(&files[i]));
+    }
+    if !independent.is_empty() {
+        println!("{}", "[independent]".bold());
+        independent.iter().for_each(|&i| print_file(&files[i]));
     }
 
     Ok(())
 }
 
-fn check_version_sync() -> Result<()> {
-    let files = get_version_files()?;
+/// Sync state of one lockstep group, as reported by `check --json`
+#[derive(Serialize)]
+struct GroupStatus {
+    name: String,
+    /// One version across all members, and every member has one
+    ok: bool,
+    /// The shared version when `ok`
+    version: Option<String>,
+    /// Version -> files carrying it
+    versions: BTreeMap<String, Vec<String>>,
+    /// Members without a readable version
+    missing: Vec<String>,
+}
+
+fn group_status(name: &str, files: &[&VersionFile]) -> GroupStatus {
+    let mut versions: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut missing = Vec::new();
+    for file in files {
+        match &file.version {
+            Some(v) => versions
+                .entry(v.to_string())
+                .or_default()
+                .push(file.path.clone()),
+            None => missing.push(file.path.clone()),
+        }
+    }
+    let ok = versions.len() == 1 && missing.is_empty();
+    GroupStatus {
+        name: name.to_string(),
+        ok,
+        version: ok.then(|| versions.keys().next().cloned()).flatten(),
+        versions,
+        missing,
+    }
+}
 
-    // Extract versions that exist
-    let versions: Vec<(&Version, &String)> = files
+/// Exit status contract: always 0 unless `strict`, in which case 1 means some group drifted
+/// or has members without a version.
+fn check_version_sync(json: bool, strict: bool) -> Result<()> {
+    let rc = discover::load_versionrc(Path::new("."))?;
+    let files = get_version_files(&rc)?;
+    let (groups, independent) = groups::resolve(&files, &rc)?;
+
+    let statuses: Vec<GroupStatus> = groups
         .iter()
-        .filter_map(|f| f.version.as_ref().map(|v| (v, &f.path)))
+        .map(|g| {
+            let members: Vec<&VersionFile> = g.members.iter().map(|&i| &files[i]).collect();
+            group_status(&g.name, &members)
+        })
         .collect();
+    let ok = statuses.iter().all(|s| s.ok);
+
+    if json {
+        let report = serde_json::json!({
+            "ok": ok,
+            "groups": statuses,
+            "independent": independent
+                .iter()
+                .map(|&i| file_report(&files[i], None))
+                .collect::<Vec<_>>(),
+        });
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for (group, status) in groups.iter().zip(&statuses) {
+            if !group.implicit {
+                println!("{}", format!("[{}]", group.name).bold());
+            }
+            print_group_status(status);
+        }
+        if !independent.is_empty() {
+            println!("{}", "Independently versioned:".bold());
+            for &i in &independent {
+                let version = files[i]
+                    .version
+                    .as_ref()
+                    .map_or("-".to_string(), Version::to_string);
+                println!("  {} {}", files[i].path.cyan(), version.yellow());
+            }
+        }
+    }
 
-    if versions.is_empty() {
-        println!("{}", "No versions found in any files!".red().bold());
-        return Ok(());
+    if strict && !ok {
+        std::process::exit(1);
     }
+    Ok(())
+}
 
-    // Check if all versions are the same
-    let first_version = versions[0].0;
-    let all_same = versions.iter().all(|(v, _)| v == &first_version);
+fn print_group_status(status: &GroupStatus) {
+    if status.versions.is_empty() {
+        println!("{}", "No versions found in any files!".red().bold());
+        return;
+    }
 
-    if all_same {
+    if status.versions.len() == 1 {
+        let (version, paths) = status.versions.iter().next().unwrap();
         println!("{}", "✅ All versions are synchronized!".green().bold());
-        println!("Version: {}", first_version.to_string().yellow());
+        println!("Version: {}", version.yellow());
 
-        for (_, path) in versions {
+        for path in paths {
             println!("  {}", path.cyan());
         }
     } else {
@@ -217,16 +466,7 @@ fn check_version_sync() -> Result<()> {
         );
         println!("{}", "=====================================".red().bold());
 
-        // Group by version
-        let mut version_groups: HashMap<String, Vec<String>> = HashMap::new();
-        for (version, path) in versions {
-            version_groups
-                .entry(version.to_string())
-                .or_default()
-                .push(path.clone());
-        }
-
-        for (version, paths) in version_groups {
+        for (version, paths) in &status.versions {
             if paths.len() == 1 {
                 println!("{} ({} file):", version.yellow(), paths.len());
             } else {
@@ -238,31 +478,112 @@ fn check_version_sync() -> Result<()> {
             println!();
         }
     }
-
-    Ok(())
+    for path in &status.missing {
+        println!("⚠️  {} has no version", path.cyan());
+    }
 }
 
-fn bump_version(bump_type: BumpType, commit: bool, tag: bool) -> Result<()> {
-    let mut files = get_version_files()?;
-
-    // Find the current version (use the first one we find)
-    let current_version = files
+fn current_version(files: &[VersionFile]) -> Result<Version> {
+    // Use the first version we find
+    Ok(files
         .iter()
         .find_map(|f| f.version.as_ref())
         .context("No version found in any file")?
-        .clone();
-
-    let new_version = match bump_type {
-        BumpType::Major => Version::new(current_version.major + 1, 0, 0),
-        BumpType::Minor => Version::new(current_version.major, current_version.minor + 1, 0),
-        BumpType::Patch => Version::new(
-            current_version.major,
-            current_version.minor,
-            current_version.patch + 1,
-        ),
+        .clone())
+}
+
+/// `-<preid>.0`, or `-0` without an identifier
+fn first_prerelease(preid: Option<&str>) -> Result<Prerelease> {
+    let pre = match preid {
+        Some(id) => format!("{id}.0"),
+        None => "0".to_string(),
     };
+    Prerelease::new(&pre).with_context(|| format!("Invalid pre-release identifier: {pre}"))
+}
+
+/// Next version following npm semantics: releasing a pre-release drops the suffix when it
+/// was already on the target version (1.3.0-rc.2 -> minor -> 1.3.0), and build metadata is
+/// never carried over.
+fn next_version(current: &Version, bump_type: BumpType, preid: Option<&str>) -> Result<Version> {
+    let (major, minor, patch) = (current.major, current.minor, current.patch);
+    let is_pre = !current.pre.is_empty();
+    let mut next = match bump_type {
+        BumpType::Major if is_pre && minor == 0 && patch == 0 => Version::new(major, 0, 0),
+        BumpType::Major | BumpType::Premajor => Version::new(major + 1, 0, 0),
+        BumpType::Minor if is_pre && patch == 0 => Version::new(major, minor, 0),
+        BumpType::Minor | BumpType::Preminor => Version::new(major, minor + 1, 0),
+        BumpType::Patch if is_pre => Version::new(major, minor, patch),
+        BumpType::Patch | BumpType::Prepatch => Version::new(major, minor, patch + 1),
+        BumpType::Prerelease if !is_pre => Version::new(major, minor, patch + 1),
+        BumpType::Prerelease => {
+            let mut next = Version::new(major, minor, patch);
+            let current_pre = current.pre.as_str();
+            let same_id = preid
+                .map(|id| current_pre == id || current_pre.starts_with(&format!("{id}.")))
+                .unwrap_or(true);
+            next.pre = if !same_id {
+                first_prerelease(preid)?
+            } else {
+                let mut parts: Vec<String> = current_pre.split('.').map(String::from).collect();
+                match parts.last().and_then(|p| p.parse::<u64>().ok()) {
+                    Some(n) => *parts.last_mut().unwrap() = (n + 1).to_string(),
+                    None => parts.push("0".to_string()),
+                }
+                Prerelease::new(&parts.join("."))?
+            };
+            return Ok(next);
+        }
+    };
+    if matches!(
+        bump_type,
+        BumpType::Premajor | BumpType::Preminor | BumpType::Prepatch | BumpType::Prerelease
+    ) {
+        next.pre = first_prerelease(preid)?;
+    } else if preid.is_some() {
+        anyhow::bail!("--preid only applies to premajor, preminor, prepatch and prerelease");
+    }
+    next.build = BuildMetadata::EMPTY;
+    Ok(next)
+}
+
+fn bump_version(
+    bump_type: BumpType,
+    preid: Option<&str>,
+    target: &TargetArgs,
+    apply: &ApplyArgs,
+) -> Result<()> {
+    let (selection, files) = selected_files(target)?;
+    let current_version = current_version(&files)?;
+    let new_version = next_version(&current_version, bump_type, preid)?;
+    apply_version(files, &selection, &current_version, &new_version, apply)
+}
+
+fn set_version(version: &str, target: &TargetArgs, apply: &ApplyArgs) -> Result<()> {
+    let new_version = Version::parse(version.trim_start_matches('v'))
+        .with_context(|| format!("Invalid semver version: {version}"))?;
+    let (selection, files) = selected_files(target)?;
+    let current_version = current_version(&files)?;
+    if new_version < current_version {
+        println!(
+            "⚠️  {} is lower than the current version {}",
+            new_version.to_string().yellow(),
+            current_version.to_string().yellow()
+        );
+    }
+    apply_version(files, &selection, &current_version, &new_version, apply)
+}
 
+fn apply_version(
+    mut files: Vec<VersionFile>,
+    selection: &Selection,
+    current_version: &Version,
+    new_version: &Version,
+    apply: &ApplyArgs,
+) -> Result<()> {
     println!("{}", "Version Bump Summary:".green().bold());
+    if let Some(label) = &selection.label {
+        println!("Packages: {}", label.cyan());
+    }
     println!("Current version: {}", current_version.to_string().red());
     println!("New version: {}", new_version.to_string().green());
     println!();
@@ -278,13 +599,10 @@ fn bump_version(bump_type: BumpType, commit: bool, tag: bool) -> Result<()> {
 
         match file.file_type {
             FileType::CargoToml => {
-                update_cargo_toml(&file.path, &new_version)?;
+                update_cargo_toml(&file.path, new_version)?;
             }
-            FileType::PackageJson => {
-                update_package_json(&file.path, &new_version)?;
-            }
-            FileType::TauriConfig => {
-                update_tauri_config(&file.path, &new_version)?;
+            FileType::PackageJson | FileType::TauriConfig => {
+                update_json_version(&file.path, new_version)?;
             }
         }
 
@@ -293,24 +611,54 @@ fn bump_version(bump_type: BumpType, commit: bool, tag: bool) -> Result<()> {
 
     println!();
 
+    if apply.update_locks {
+        update_locks(&files, new_version)?;
+        println!();
+    }
+
     // Commit changes if requested
-    if commit {
+    if apply.commit {
         println!("Committing changes...");
         run_command("git", &["add", "."])?;
-        let commit_msg = format!("chore: bump version from {current_version} to {new_version}");
+        let commit_msg = match &selection.label {
+            Some(label) => {
+                format!("chore: bump {label} version from {current_version} to {new_version}")
+            }
+            None => format!("chore: bump version from {current_version} to {new_version}"),
+        };
         run_command("git", &["commit", "-m", &commit_msg])?;
         println!("  ✅ Changes committed");
     }
 
     // Create tag if requested
-    if tag {
+    if apply.tag {
         println!("Creating git tag...");
-        let tag_name = format!("v{new_version}");
+        let tag_name = tag_name(&selection.tag_prefix, new_version);
         let tag_msg = format!("Version {new_version}: Version bump");
         run_command("git", &["tag", "-a", &tag_name, "-m", &tag_msg])?;
         println!("  ✅ Tag {} created", tag_name.green());
     }
 
+    if apply.push {
+        println!("Pushing to origin...");
+        run_command("git", &["push", "origin", "HEAD"])?;
+        if apply.tag {
+            run_command(
+                "git",
+                &[
+                    "push",
+                    "origin",
+                    &tag_name(&selection.tag_prefix, new_version),
+                ],
+            )?;
+        }
+        println!("  ✅ Pushed");
+    }
+
+    if apply.release {
+        release::create_release(&tag_name(&selection.tag_prefix, new_version), false)?;
+    }
+
     println!();
     println!(
         "{}",
@@ -322,29 +670,100 @@ fn bump_version(bump_type: BumpType, commit: bool, tag: bool) -> Result<()> {
 
 fn update_cargo_toml(path: &str, new_version: &Version) -> Result<()> {
     let content = fs::read_to_string(path)?;
-    let re = regex::Regex::new(r#"version\s*=\s*"([^"]+)""#)?;
-    let new_content = re.replace(&content, format!("version = \"{new_version}\""));
-    fs::write(path, new_content.as_bytes())?;
+    let new_content = edit::set_cargo_version(&content, new_version)
+        .with_context(|| format!("Failed to update {path}"))?;
+    fs::write(path, new_content)?;
     Ok(())
 }
 
-fn update_package_json(path: &str, new_version: &Version) -> Result<()> {
+/// package.json and tauri.conf.json both keep the version at the top level
+fn update_json_version(path: &str, new_version: &Version) -> Result<()> {
     let content = fs::read_to_string(path)?;
-    let re = regex::Regex::new(r#""version"\s*:\s*"([^"]+)""#)?;
-    let new_content = re.replace(&content, format!("\"version\": \"{new_version}\""));
-    fs::write(path, new_content.as_bytes())?;
+    let new_content = edit::set_json_version(&content, new_version)
+        .with_context(|| format!("Failed to update {path}"))?;
+    fs::write(path, new_content)?;
     Ok(())
 }
 
-fn update_tauri_config(path: &str, new_version: &Version) -> Result<()> {
-    let content = fs::read_to_string(path)?;
-    let re = regex::Regex::new(r#""version"\s*:\s*"([^"]+)""#)?;
-    let new_content = re.replace(&content, format!("\"version\": \"{new_version}\""));
-    fs::write(path, new_content.as_bytes())?;
+/// Refresh lockfiles next to the updated manifests: `cargo update --workspace --offline`
+/// for each Cargo workspace, and the root package version inside package-lock.json.
+fn update_locks(files: &[VersionFile], new_version: &Version) -> Result<()> {
+    let mut cargo_dirs: Vec<PathBuf> = Vec::new();
+    for file in files.iter().filter(|f| f.version.is_some()) {
+        let dir = Path::new(&file.path)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        match file.file_type {
+            FileType::CargoToml => {
+                // The nearest Cargo.lock above the manifest belongs to its workspace
+                let lock_dir = dir
+                    .ancestors()
+                    .find(|d| d.join("Cargo.lock").exists())
+                    .map(Path::to_path_buf);
+                if let Some(d) = lock_dir {
+                    if !cargo_dirs.contains(&d) {
+                        cargo_dirs.push(d);
+                    }
+                }
+            }
+            FileType::PackageJson => {
+                let lock = dir.join("package-lock.json");
+                if lock.exists() {
+                    update_package_lock(&lock, new_version)?;
+                }
+            }
+            FileType::TauriConfig => {}
+        }
+    }
+    for dir in cargo_dirs {
+        let manifest = dir.join("Cargo.toml");
+        let display = dir.join("Cargo.lock").display().to_string();
+        println!("Refreshing {}...", display.cyan());
+        run_command(
+            "cargo",
+            &[
+                "update",
+                "--workspace",
+                "--offline",
+                "--manifest-path",
+                &manifest.to_string_lossy(),
+            ],
+        )?;
+        println!("  ✅ Updated");
+    }
     Ok(())
 }
 
+fn update_package_lock(path: &Path, new_version: &Version) -> Result<()> {
+    println!("Refreshing {}...", path.display().to_string().cyan());
+    let mut content = fs::read_to_string(path)?;
+    content = edit::set_json_version(&content, new_version)
+        .with_context(|| format!("Failed to update {}", path.display()))?;
+    // lockfileVersion 2+ repeats the root package under packages[""]
+    if let Ok(updated) = edit::set_json_string(&content, &["packages", "", "version"], new_version)
+    {
+        content = updated;
+    }
+    fs::write(path, content)?;
+    println!("  ✅ Updated to {}", new_version.to_string().green());
+    Ok(())
+}
+
+/// Build metadata is not part of the release identity, and `+` is awkward in refs
+fn tag_name(prefix: &str, version: &Version) -> String {
+    let version = Version {
+        build: BuildMetadata::EMPTY,
+        ..version.clone()
+    };
+    format!("{prefix}{version}")
+}
+
 fn run_command(program: &str, args: &[&str]) -> Result<()> {
+    command_output(program, args).map(|_| ())
+}
+
+fn command_output(program: &str, args: &[&str]) -> Result<String> {
     use std::process::Command;
 
     let output = Command::new(program)
@@ -363,5 +782,5 @@ fn run_command(program: &str, args: &[&str]) -> Result<()> {
         );
     }
 
-    Ok(())
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
 }
@@ -0,0 +1,211 @@
+use anyhow::{bail, Context, Result};
+use colored::*;
+use semver::Version;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::command_output;
+
+/// Commit subjects between the previous tag and `tag` in the repository at `repo`, one
+/// `- subject (sha)` per line.
+pub fn changelog(repo: &Path, tag: &str) -> Result<String> {
+    let repo = repo.to_string_lossy();
+    let previous = command_output(
+        "git",
+        &[
+            "-C",
+            &repo,
+            "describe",
+            "--tags",
+            "--abbrev=0",
+            &format!("{tag}^"),
+        ],
+    )
+    .ok();
+    let range = match &previous {
+        Some(prev) => format!("{}..{tag}", prev.trim()),
+        None => tag.to_string(),
+    };
+    let log = command_output(
+        "git",
+        &[
+            "-C",
+            &repo,
+            "log",
+            "--no-merges",
+            "--pretty=format:- %s (%h)",
+            &range,
+        ],
+    )?;
+    let mut body = String::from("## Changes\n\n");
+    if log.trim().is_empty() {
+        body.push_str("- No changes since the previous release\n");
+    } else {
+        body.push_str(log.trim_end());
+        body.push('\n');
+    }
+    if let Some(prev) = previous {
+        body.push_str(&format!("\nFull diff: `{}...{tag}`\n", prev.trim()));
+    }
+    Ok(body)
+}
+
+/// Create a GitHub release for `tag`. Uses the `gh` CLI when installed, otherwise the REST
+/// API with `GITHUB_TOKEN` (or `GH_TOKEN`) against the `origin` remote.
+pub fn create_release(tag: &str, draft: bool) -> Result<()> {
+    let version = Version::parse(tag.trim_start_matches('v')).ok();
+    let prerelease = version.is_some_and(|v| !v.pre.is_empty());
+    let body = changelog(Path::new("."), tag)?;
+
+    println!("Creating GitHub release {}...", tag.green());
+    if Command::new("gh").arg("--version").output().is_ok() {
+        let mut args = vec![
+            "release",
+            "create",
+            tag,
+            "--title",
+            tag,
+            "--notes-file",
+            "-",
+        ];
+        if prerelease {
+            args.push("--prerelease");
+        }
+        if draft {
+            args.push("--draft");
+        }
+        let mut child = Command::new("gh")
+            .args(&args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .context("Failed to run gh")?;
+        child
+            .stdin
+            .take()
+            .context("gh stdin unavailable")?
+            .write_all(body.as_bytes())?;
+        if !child.wait()?.success() {
+            bail!("gh release create failed");
+        }
+    } else {
+        let token = std::env::var("GITHUB_TOKEN")
+            .or_else(|_| std::env::var("GH_TOKEN"))
+            .context("Neither the gh CLI nor GITHUB_TOKEN/GH_TOKEN is available")?;
+        let remote = command_output("git", &["remote", "get-url", "origin"])?;
+        let repo = github_repo(remote.trim())
+            .with_context(|| format!("origin is not a GitHub remote: {}", remote.trim()))?;
+        ureq::post(&format!("https://api.github.com/repos/{repo}/releases"))
+            .set("Authorization", &format!("Bearer {token}"))
+            .set("Accept", "application/vnd.github+json")
+            .set("User-Agent", "version-manager")
+            .send_json(serde_json::json!({
+                "tag_name": tag,
+                "name": tag,
+                "body": body,
+                "draft": draft,
+                "prerelease": prerelease,
+            }))
+            .context("GitHub API request failed")?;
+    }
+    println!("  ✅ Release {} created", tag.green());
+    Ok(())
+}
+
+/// `owner/repo` from an https or ssh GitHub remote URL.
+fn github_repo(url: &str) -> Option<String> {
+    let rest = url
+        .strip_prefix("git@github.com:")
+        .or_else(|| url.strip_prefix("ssh://git@github.com/"))
+        .or_else(|| url.strip_prefix("https://github.com/"))?;
+    let repo = rest.trim_end_matches('/').trim_end_matches(".git");
+    (repo.split('/').count() == 2).then(|| repo.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn git(dir: &Path, args: &[&str]) -> String {
+        let out = Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .env("GIT_AUTHOR_NAME", "Jane")
+            .env("GIT_AUTHOR_EMAIL", "jane@example.com")
+            .env("GIT_COMMITTER_NAME", "Jane")
+            .env("GIT_COMMITTER_EMAIL", "jane@example.com")
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "git {args:?} failed");
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    }
+
+    #[test]
+    fn github_repo_accepts_https_and_ssh_remotes() {
+        for url in [
+            "https://github.com/owner/repo",
+            "https://github.com/owner/repo.git",
+            "https://github.com/owner/repo/",
+            "git@github.com:owner/repo.git",
+            "ssh://git@github.com/owner/repo.git",
+        ] {
+            assert_eq!(github_repo(url).as_deref(), Some("owner/repo"), "{url}");
+        }
+        for url in [
+            "https://gitlab.com/owner/repo.git",
+            "git@bitbucket.org:owner/repo.git",
+            "https://github.com/owner",
+            "https://github.com/owner/repo/tree/main",
+        ] {
+            assert_eq!(github_repo(url), None, "{url}");
+        }
+    }
+
+    #[test]
+    fn changelog_lists_commits_since_the_previous_tag() {
+        let dir = tempfile::tempdir().unwrap();
+        let repo = dir.path();
+        git(repo, &["init", "-q"]);
+        let commit = |subject: &str| {
+            git(repo, &["commit", "-q", "--allow-empty", "-m", subject]);
+        };
+        commit("first");
+        commit("second");
+        git(repo, &["tag", "v1.0.0"]);
+
+        // The first tag takes everything before it
+        let body = changelog(repo, "v1.0.0").unwrap();
+        assert!(body.starts_with("## Changes\n\n- second ("), "{body}");
+        assert!(body.contains("- first ("));
+        assert!(!body.contains("Full diff"));
+
+        commit("third");
+        git(repo, &["tag", "v1.1.0"]);
+        let body = changelog(repo, "v1.1.0").unwrap();
+        assert!(body.contains("- third ("));
+        assert!(!body.contains("second"));
+        assert!(body.ends_with("\nFull diff: `v1.0.0...v1.1.0`\n"), "{body}");
+
+        // Only a merge since the previous tag: merges are left out
+        let merge = git(
+            repo,
+            &[
+                "commit-tree",
+                "HEAD^{tree}",
+                "-p",
+                "HEAD",
+                "-p",
+                "HEAD~1",
+                "-m",
+                "merge",
+            ],
+        );
+        git(repo, &["tag", "v1.1.1", &merge]);
+        let body = changelog(repo, "v1.1.1").unwrap();
+        assert!(
+            body.contains("- No changes since the previous release\n"),
+            "{body}"
+        );
+        assert!(body.contains("`v1.1.0...v1.1.1`"));
+    }
+}
@@ -0,0 +1,258 @@
+//! End-to-end runs of the `version-manager` binary in throwaway workspaces.
+
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Output};
+
+fn write(root: &Path, rel: &str, body: &str) {
+    let p = root.join(rel);
+    fs::create_dir_all(p.parent().unwrap()).unwrap();
+    fs::write(p, body).unwrap();
+}
+
+fn cargo_toml(name: &str, version: &str) -> String {
+    format!("[package]\nname = \"{name}\"\nversion = \"{version}\"\nedition = \"2021\"\n")
+}
+
+fn version_manager(root: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_version-manager"))
+        .args(args)
+        .current_dir(root)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+/// Run and expect success, returning stdout.
+fn run(root: &Path, args: &[&str]) -> String {
+    let out = version_manager(root, args);
+    assert!(
+        out.status.success(),
+        "{args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).unwrap()
+}
+
+fn git(dir: &Path, args: &[&str]) -> String {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    String::from_utf8(out.stdout).unwrap().trim().to_string()
+}
+
+#[test]
+fn bumps_an_independent_package_alone() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(
+        root,
+        ".versionrc.toml",
+        "[[groups]]\nname = \"app\"\nmembers = [\"app/*\", \"web/package.json\"]\n",
+    );
+    write(root, "app/Cargo.toml", &cargo_toml("app", "1.2.0"));
+    write(
+        root,
+        "web/package.json",
+        r#"{"name":"web","version":"1.2.0"}"#,
+    );
+    write(root, "tool/Cargo.toml", &cargo_toml("tool", "0.3.1"));
+
+    run(root, &["bump", "patch", "--package", "tool"]);
+    let read = |rel: &str| fs::read_to_string(root.join(rel)).unwrap();
+    assert_eq!(read("tool/Cargo.toml"), cargo_toml("tool", "0.3.2"));
+    assert_eq!(read("app/Cargo.toml"), cargo_toml("app", "1.2.0"));
+
+    // A package in a group moves the whole group
+    run(root, &["bump", "minor", "--package", "web"]);
+    assert_eq!(read("app/Cargo.toml"), cargo_toml("app", "1.3.0"));
+    assert!(read("web/package.json").contains(r#""version":"1.3.0""#));
+    assert_eq!(read("tool/Cargo.toml"), cargo_toml("tool", "0.3.2"));
+
+    // Only lockstep groups have to agree
+    assert!(version_manager(root, &["check", "--strict"])
+        .status
+        .success());
+    // The only group is the default target; independent packages are named
+    run(root, &["bump", "patch"]);
+    assert_eq!(read("app/Cargo.toml"), cargo_toml("app", "1.3.1"));
+    assert!(
+        !version_manager(root, &["bump", "patch", "--package", "nope"])
+            .status
+            .success()
+    );
+}
+
+#[test]
+fn sets_explicit_versions_and_bumps_pre_releases() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root, "Cargo.toml", &cargo_toml("app", "1.2.0"));
+    write(
+        root,
+        "web/package.json",
+        r#"{"name":"web","version":"1.2.0"}"#,
+    );
+    write(
+        root,
+        "src-tauri/tauri.conf.json",
+        r#"{"productName":"App","version":"1.2.0"}"#,
+    );
+    let versions = || -> Vec<String> {
+        let rows: serde_json::Value =
+            serde_json::from_str(&run(root, &["show", "--json"])).unwrap();
+        rows.as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["version"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    run(root, &["set", "v2.0.0-rc.1"]);
+    assert_eq!(versions(), ["2.0.0-rc.1"; 3]);
+    run(root, &["bump", "prerelease"]);
+    assert_eq!(versions(), ["2.0.0-rc.2"; 3]);
+    // Releasing a pre-release of the same version drops the suffix
+    run(root, &["bump", "major"]);
+    assert_eq!(versions(), ["2.0.0"; 3]);
+
+    // Build metadata is kept by `set` and dropped by the next bump
+    run(root, &["set", "2.0.0+build.7"]);
+    assert_eq!(versions(), ["2.0.0+build.7"; 3]);
+    run(root, &["bump", "preminor", "--preid", "beta"]);
+    assert_eq!(versions(), ["2.1.0-beta.0"; 3]);
+    run(root, &["bump", "prerelease", "--preid", "rc"]);
+    assert_eq!(versions(), ["2.1.0-rc.0"; 3]);
+
+    assert!(!version_manager(root, &["set", "2.1"]).status.success());
+    assert!(!version_manager(root, &["bump", "patch", "--preid", "rc"])
+        .status
+        .success());
+}
+
+#[test]
+fn check_reports_drift_as_json_and_fails_strict_runs() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root, "Cargo.toml", &cargo_toml("app", "1.2.0"));
+    write(
+        root,
+        "web/package.json",
+        r#"{"name":"web","version":"1.2.0"}"#,
+    );
+
+    let check =
+        |args: &[&str]| -> serde_json::Value { serde_json::from_str(&run(root, args)).unwrap() };
+    let report = check(&["check", "--json"]);
+    assert_eq!(report["ok"], true);
+    assert_eq!(report["groups"][0]["version"], "1.2.0");
+    assert!(version_manager(root, &["check", "--strict"])
+        .status
+        .success());
+
+    write(
+        root,
+        "web/package.json",
+        r#"{"name":"web","version":"1.1.0"}"#,
+    );
+    // Without --strict drift is reported but the exit status stays 0
+    let report = check(&["check", "--json"]);
+    assert_eq!(report["ok"], false);
+    let group = &report["groups"][0];
+    assert_eq!(group["version"], serde_json::Value::Null);
+    assert_eq!(group["versions"]["1.1.0"][0], "web/package.json");
+    assert_eq!(group["versions"]["1.2.0"][0], "Cargo.toml");
+
+    let strict = version_manager(root, &["check", "--strict", "--json"]);
+    assert_eq!(strict.status.code(), Some(1));
+    let report: serde_json::Value = serde_json::from_slice(&strict.stdout).unwrap();
+    assert_eq!(report["ok"], false);
+
+    let rows = check(&["show", "--json"]);
+    assert_eq!(rows[1]["path"], "web/package.json");
+    assert_eq!(rows[1]["file_type"], "package_json");
+    assert_eq!(rows[1]["group"], "default");
+}
+
+#[test]
+fn discovery_skips_dependency_and_hidden_dirs_and_applies_versionrc() {
+    let dir = tempfile::tempdir().unwrap();
+    let root = dir.path();
+    write(root, "Cargo.toml", &cargo_toml("app", "1.0.0"));
+    write(
+        root,
+        "node_modules/dep/package.json",
+        r#"{"name":"dep","version":"9.9.9"}"#,
+    );
+    write(
+        root,
+        ".hidden/package.json",
+        r#"{"name":"hidden","version":"9.9.9"}"#,
+    );
+    write(
+        root,
+        "examples/demo/Cargo.toml",
+        &cargo_toml("demo", "0.1.0"),
+    );
+    // No version and not asked for: dropped
+    write(root, "tools/package.json", r#"{"name":"tools"}"#);
+    // Asked for: reported inside a skipped dir, and without a version
+    write(
+        root,
+        "build/app/package.json",
+        r#"{"name":"built","version":"1.0.0"}"#,
+    );
+    write(root, "meta/package.json", r#"{"name":"meta"}"#);
+    write(
+        root,
+        ".versionrc.toml",
+        "include = [\"build/app/package.json\", \"meta/package.json\"]\n\
+         exclude = [\"examples/**\"]\n",
+    );
+
+    let rows: serde_json::Value = serde_json::from_str(&run(root, &["show", "--json"])).unwrap();
+    let rows = rows.as_array().unwrap();
+    let paths: Vec<_> = rows.iter().map(|r| r["path"].as_str().unwrap()).collect();
+    assert_eq!(
+        paths,
+        ["Cargo.toml", "build/app/package.json", "meta/package.json"]
+    );
+    assert_eq!(rows[1]["version"], "1.0.0");
+    assert_eq!(rows[2]["version"], serde_json::Value::Null);
+
+    write(root, ".versionrc.toml", "exlcude = [\"examples/**\"]\n");
+    let out = version_manager(root, &["show", "--json"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains(".versionrc.toml"));
+}
+
+#[test]
+fn bump_pushes_the_commit_and_tag_to_origin() {
+    let dir = tempfile::tempdir().unwrap();
+    git(dir.path(), &["init", "-q", "--bare", "origin.git"]);
+    git(dir.path(), &["clone", "-q", "origin.git", "work"]);
+    let (origin, root) = (dir.path().join("origin.git"), dir.path().join("work"));
+    git(&root, &["config", "user.name", "Jane"]);
+    git(&root, &["config", "user.email", "jane@example.com"]);
+    write(&root, "Cargo.toml", &cargo_toml("app", "0.1.0"));
+    git(&root, &["add", "."]);
+    git(&root, &["commit", "-q", "-m", "init"]);
+    git(&root, &["push", "-q", "origin", "HEAD"]);
+
+    run(&root, &["bump", "patch", "--commit", "--tag", "--push"]);
+    let branch = git(&root, &["symbolic-ref", "--short", "HEAD"]);
+    let head = git(&root, &["rev-parse", "HEAD"]);
+    assert_eq!(git(&origin, &["rev-parse", &branch]), head);
+    assert_eq!(git(&origin, &["rev-parse", "v0.1.1^{commit}"]), head);
+    assert_eq!(
+        git(&origin, &["log", "-1", "--format=%s", &branch]),
+        "chore: bump version from 0.1.0 to 0.1.1"
+    );
+}
@@ -0,0 +1,71 @@
+//! Running a project's quick actions (`actions` config) from the detail view. Each run gets
+//! a thread; its lines arrive as `action-output` events and the stored run as
+//! `action-finished` (`action-failed` if the run could not be recorded).
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+
+use indexer::actions::{actions_for, run_action, OutputStream};
+use indexer::{ConfigStore, Db};
+
+#[derive(Clone, Serialize)]
+struct ActionLine<'a> {
+    project_id: i64,
+    run_id: i64,
+    stream: OutputStream,
+    line: &'a str,
+}
+
+#[derive(Clone, Serialize)]
+struct ActionFailed {
+    project_id: i64,
+    name: String,
+    error: String,
+}
+
+/// Start action `name` for project `project_id` in the background.
+pub fn start(app: &AppHandle, project_id: i64, name: String) -> anyhow::Result<()> {
+    let cfg = ConfigStore::load()?;
+    let db = Db::open_default()?;
+    let project = db
+        .project_by_id(project_id)?
+        .ok_or_else(|| anyhow::anyhow!("project {project_id} is no longer indexed"))?;
+    // Refuse up front so the detail view gets the error instead of an event that never comes
+    anyhow::ensure!(
+        actions_for(&cfg, &project).iter().any(|a| a.name == name),
+        "action {name:?} does not apply to {}",
+        project.name
+    );
+    let app = app.clone();
+    std::thread::spawn(move || {
+        let emit_line = |run_id, stream, line: &str| {
+            let _ = app.emit(
+                "action-output",
+                ActionLine {
+                    project_id,
+                    run_id,
+                    stream,
+                    line,
+                },
+            );
+        };
+        match run_action(&db, &cfg, &project, &name, emit_line) {
+            Ok(run) => {
+                tracing::info!(project_id, action = %name, status = ?run.status, "action finished");
+                let _ = app.emit("action-finished", run);
+            }
+            Err(err) => {
+                tracing::error!(project_id, action = %name, %err, "action failed");
+                let _ = app.emit(
+                    "action-failed",
+                    ActionFailed {
+                        project_id,
+                        name,
+                        error: format!("{err:#}"),
+                    },
+                );
+            }
+        }
+    });
+    Ok(())
+}
@@ -0,0 +1,71 @@
+//! `projectbrowser://` URL handling; parsing and the index allowlist live in
+//! `indexer::links`.
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter};
+use tauri_plugin_deep_link::DeepLinkExt;
+
+use indexer::links::{self, LinkAction};
+use indexer::{ConfigStore, Db};
+
+#[derive(Clone, Serialize)]
+struct SearchLink {
+    q: String,
+    advanced: bool,
+}
+
+pub fn init(app: &AppHandle) -> anyhow::Result<()> {
+    // Installers register the scheme; dev builds on Linux/Windows have to do it themselves
+    #[cfg(all(debug_assertions, any(windows, target_os = "linux")))]
+    app.deep_link().register_all()?;
+
+    let handle = app.clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            handle_url(&handle, url.as_str());
+        }
+    });
+    // The link that launched the app, if any
+    if let Some(urls) = app.deep_link().get_current()? {
+        for url in urls {
+            handle_url(app, url.as_str());
+        }
+    }
+    Ok(())
+}
+
+fn handle_url(app: &AppHandle, url: &str) {
+    tracing::info!(%url, "deep link");
+    if let Err(err) = dispatch(app, url) {
+        tracing::warn!(%url, %err, "deep link rejected");
+    }
+}
+
+fn dispatch(app: &AppHandle, url: &str) -> anyhow::Result<()> {
+    match links::parse(url)? {
+        LinkAction::Open { path, editor } => {
+            let cfg = ConfigStore::load()?;
+            let db = Db::open_default()?;
+            let project = links::resolve_path(&db, &path)?;
+            let editor = editor.unwrap_or_else(|| cfg.launcher.editor.clone());
+            if !links::editor_allowed(&editor, &cfg.launcher.editor) {
+                anyhow::bail!("editor {editor:?} is not allowed from links");
+            }
+            // `path` may point at a file inside the project; open exactly that
+            crate::launch::launch_editor(&editor, std::path::Path::new(&path))
+                .map_err(anyhow::Error::msg)?;
+            db.record_open(project.id)?;
+            crate::tray::refresh(app);
+        }
+        LinkAction::Reveal { path } => {
+            let db = Db::open_default()?;
+            links::resolve_path(&db, &path)?;
+            crate::launch::reveal(std::path::Path::new(&path))?;
+        }
+        LinkAction::Search { q, advanced } => {
+            crate::tray::show_main_window(app);
+            app.emit("link-search", SearchLink { q, advanced })?;
+        }
+    }
+    Ok(())
+}
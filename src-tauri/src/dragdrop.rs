@@ -0,0 +1,98 @@
+//! Folders dropped onto the main window are indexed on the spot, without a full rescan.
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use tauri::{AppHandle, DragDropEvent, Emitter, Manager, Window, WindowEvent};
+
+use indexer::{ConfigStore, Db, ProjectRecord};
+
+/// Payload of `project-indexed`.
+#[derive(Clone, Serialize)]
+struct Indexed {
+    project: ProjectRecord,
+    /// The dropped folder's parent unless it is already under a root; the UI offers to add it
+    suggested_root: Option<String>,
+}
+
+/// Payload of `drop-ignored`.
+#[derive(Clone, Serialize)]
+struct Ignored {
+    path: String,
+    reason: String,
+}
+
+/// `on_window_event` hook: index every directory dropped onto the main window.
+pub fn on_window_event(window: &Window, event: &WindowEvent) {
+    if window.label() != "main" {
+        return;
+    }
+    if let WindowEvent::DragDrop(DragDropEvent::Drop { paths, .. }) = event {
+        let app = window.app_handle().clone();
+        let paths = paths.clone();
+        // Metrics and analyzers walk the whole project; keep that off the event loop
+        std::thread::spawn(move || {
+            for path in paths {
+                index_dropped(&app, &path);
+            }
+            crate::tray::refresh(&app);
+        });
+    }
+}
+
+fn index_dropped(app: &AppHandle, path: &Path) {
+    tracing::info!(path = %path.display(), "folder dropped");
+    let result = match index(path) {
+        Ok(Some(indexed)) => app.emit("project-indexed", indexed),
+        Ok(None) => app.emit(
+            "drop-ignored",
+            Ignored {
+                path: path.display().to_string(),
+                reason: "no project detected".into(),
+            },
+        ),
+        Err(err) => {
+            tracing::warn!(path = %path.display(), %err, "indexing dropped folder failed");
+            app.emit(
+                "drop-ignored",
+                Ignored {
+                    path: path.display().to_string(),
+                    reason: err.to_string(),
+                },
+            )
+        }
+    };
+    if let Err(err) = result {
+        tracing::warn!(%err, "drop event failed");
+    }
+}
+
+fn index(path: &Path) -> anyhow::Result<Option<Indexed>> {
+    let cfg = ConfigStore::load()?;
+    let db = Db::open_default()?;
+    let Some(project) = indexer::scan_project(&db, &cfg, path)? else {
+        return Ok(None);
+    };
+    let suggested_root = path
+        .parent()
+        .filter(|parent| !cfg.roots.iter().any(|r| parent.starts_with(r)))
+        .map(|parent| parent.display().to_string());
+    Ok(Some(Indexed {
+        project,
+        suggested_root,
+    }))
+}
+
+/// Add `path` to the configured roots; `false` when it was already covered.
+pub fn add_root(path: &str) -> anyhow::Result<bool> {
+    let mut cfg = ConfigStore::load()?;
+    let root = PathBuf::from(shellexpand::tilde(path).to_string());
+    if !root.is_dir() {
+        anyhow::bail!("{} is not a directory", root.display());
+    }
+    if !cfg.add_root(root) {
+        return Ok(false);
+    }
+    ConfigStore::save(&cfg)?;
+    Ok(true)
+}
@@ -0,0 +1,137 @@
+//! Opening a project from the launcher surfaces (tray, palette): in an editor, in the file
+//! manager, or in the main window; and running its launch command in a terminal.
+
+use std::path::Path;
+use std::process::Command;
+
+use tauri::{AppHandle, Emitter};
+
+use indexer::config::LaunchAction;
+use indexer::{ConfigStore, Db, ProjectRecord};
+
+/// Run `action` (or `launcher.default_action`) for project `project_id` and record the open.
+pub fn open_project(
+    app: &AppHandle,
+    project_id: i64,
+    action: Option<LaunchAction>,
+) -> anyhow::Result<ProjectRecord> {
+    let cfg = ConfigStore::load()?;
+    let db = Db::open_default()?;
+    let project = db
+        .project_by_id(project_id)?
+        .ok_or_else(|| anyhow::anyhow!("project {project_id} is no longer indexed"))?;
+    match action.unwrap_or(cfg.launcher.default_action) {
+        LaunchAction::Editor => {
+            let editor = db
+                .project_launcher(project_id)?
+                .editor
+                .unwrap_or(cfg.launcher.editor);
+            launch_editor(&editor, &project.dir()).map_err(anyhow::Error::msg)?;
+        }
+        LaunchAction::Reveal => reveal(&project.dir())?,
+        LaunchAction::Show => show_in_app(app, &project.path),
+    }
+    db.record_open(project_id)?;
+    crate::tray::refresh(app);
+    Ok(project)
+}
+
+/// Run project `project_id`'s launch command in a terminal (`launcher.terminal`) and record
+/// the open. Returns the command that was started.
+pub fn run_project_command(app: &AppHandle, project_id: i64) -> anyhow::Result<String> {
+    let cfg = ConfigStore::load()?;
+    let db = Db::open_default()?;
+    let project = db
+        .project_by_id(project_id)?
+        .ok_or_else(|| anyhow::anyhow!("project {project_id} is no longer indexed"))?;
+    let command = db
+        .project_launcher(project_id)?
+        .command
+        .ok_or_else(|| anyhow::anyhow!("{} has no launch command set", project.name))?;
+    indexer::launcher::terminal_command(&cfg.launcher.terminal, &project.dir(), &command)
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("could not open a terminal for `{command}`: {e}"))?;
+    tracing::info!(project_id, %command, "started launch command");
+    db.record_open(project_id)?;
+    crate::tray::refresh(app);
+    Ok(command)
+}
+
+/// Spawn `editor` on `path`, trying the usual install locations for known editors.
+pub fn launch_editor(editor: &str, path: &Path) -> Result<String, String> {
+    // Try common paths for editors
+    let editor_paths = match editor {
+        "windsurf" => vec![
+            "windsurf",
+            "/usr/local/bin/windsurf",
+            "/opt/homebrew/bin/windsurf",
+            "/Applications/Windsurf.app/Contents/Resources/app/bin/windsurf",
+            "/Applications/Windsurf.app/Contents/MacOS/Windsurf",
+        ],
+        "cursor" => vec![
+            "cursor",
+            "/usr/local/bin/cursor",
+            "/opt/homebrew/bin/cursor",
+            "/Applications/Cursor.app/Contents/Resources/app/bin/cursor",
+        ],
+        _ => vec![editor],
+    };
+
+    for editor_path in editor_paths {
+        let result = Command::new(editor_path).arg(path).spawn();
+
+        match result {
+            Ok(_) => {
+                tracing::info!(
+                    "Successfully launched {} with path {}",
+                    editor_path,
+                    path.display()
+                );
+                return Ok(format!("Opened {} in {editor}", path.display()));
+            }
+            Err(e) => {
+                tracing::debug!(
+                    "Failed to launch {} with path {}: {}",
+                    editor_path,
+                    path.display(),
+                    e
+                );
+                continue;
+            }
+        }
+    }
+
+    tracing::error!("Failed to launch {} with any known path", editor);
+    Err(format!(
+        "Failed to open {editor}: command not found in common locations"
+    ))
+}
+
+/// Show `path` in Finder / Explorer, or open it in the default file manager elsewhere.
+pub fn reveal(path: &Path) -> anyhow::Result<()> {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut c = Command::new("open");
+        c.arg("-R").arg(path);
+        c
+    } else if cfg!(windows) {
+        let mut c = Command::new("explorer");
+        let mut arg = std::ffi::OsString::from("/select,");
+        arg.push(path);
+        c.arg(arg);
+        c
+    } else {
+        let mut c = Command::new("xdg-open");
+        c.arg(path);
+        c
+    };
+    cmd.spawn()?;
+    Ok(())
+}
+
+/// Bring up the main window with its search narrowed to `path`.
+pub fn show_in_app(app: &AppHandle, path: &str) {
+    crate::tray::show_main_window(app);
+    if let Err(err) = app.emit("show-project", path) {
+        tracing::warn!(%err, "show-project event failed");
+    }
+}
@@ -1,76 +1,65 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 use anyhow::Result;
-use serde::Serialize;
+use tauri::Manager;
+use tracing_subscriber::prelude::*;
 use tracing_subscriber::EnvFilter;
 
-use indexer::{scan_roots, ConfigStore, Db, ScanOptions, SortKey};
+mod actions;
+mod deeplink;
+mod dragdrop;
+mod launch;
+mod notifications;
+mod onboarding;
+mod palette;
+mod scanner;
+mod tray;
+mod windows;
 
-#[derive(Serialize)]
-struct ProjectsPage {
-    items: Vec<indexer::ProjectRecord>,
-    page: u32,
-    page_size: u32,
-    total_count: u32,
-}
+use indexer::config::LaunchAction;
+use indexer::fetch::FetchMode;
+use indexer::fields::{FieldValue, FAVORITE_FIELD};
+use indexer::launcher::ProjectLauncher;
+use indexer::logging::RotatingFile;
+use indexer::queue::QueuedRoot;
+use indexer::rpc::{Backend, ProjectsPage, ProjectsRequest, ScanOutcome};
+use indexer::{scan_roots, AnalyzerRegistry, ConfigStore, Db, ScanOptions, ScanPriority, SortKey};
 
 #[tauri::command]
+#[specta::specta]
 fn test_command() -> Result<String, String> {
     tracing::info!("test_command called");
     Ok("Hello from Rust!".to_string())
 }
 
 #[tauri::command]
-fn open_in_editor(editor: String, path: String) -> Result<String, String> {
+#[specta::specta]
+fn open_in_editor(app: tauri::AppHandle, editor: String, path: String) -> Result<String, String> {
     tracing::info!(
         "open_in_editor called with editor={}, path={}",
         editor,
         path
     );
-
-    use std::process::Command;
-
-    // Try common paths for editors
-    let editor_paths = match editor.as_str() {
-        "windsurf" => vec![
-            "windsurf",
-            "/usr/local/bin/windsurf",
-            "/opt/homebrew/bin/windsurf",
-            "/Applications/Windsurf.app/Contents/Resources/app/bin/windsurf",
-            "/Applications/Windsurf.app/Contents/MacOS/Windsurf",
-        ],
-        "cursor" => vec![
-            "cursor",
-            "/usr/local/bin/cursor",
-            "/opt/homebrew/bin/cursor",
-            "/Applications/Cursor.app/Contents/Resources/app/bin/cursor",
-        ],
-        _ => vec![editor.as_str()],
-    };
-
-    for editor_path in editor_paths {
-        let result = Command::new(editor_path).arg(&path).spawn();
-
-        match result {
-            Ok(_) => {
-                tracing::info!("Successfully launched {} with path {}", editor_path, path);
-                return Ok(format!("Opened {path} in {editor}"));
-            }
-            Err(e) => {
-                tracing::debug!("Failed to launch {} with path {}: {}", editor_path, path, e);
-                continue;
-            }
-        }
+    let opened = launch::launch_editor(&editor, std::path::Path::new(&path))?;
+    // Launching already succeeded; a failed frecency update is only worth a log line
+    let recorded = Db::open_default().and_then(|db| match db.find_project(&path)? {
+        Some(p) => db.record_open(p.id),
+        None => Ok(()),
+    });
+    if let Err(err) = recorded {
+        tracing::warn!(%err, "could not record project open");
     }
-
-    tracing::error!("Failed to launch {} with any known path", editor);
-    Err(format!(
-        "Failed to open {editor}: command not found in common locations"
-    ))
+    tray::refresh(&app);
+    Ok(opened)
 }
 
 #[tauri::command]
-fn scan_start(roots: Option<Vec<String>>, dry_run: Option<bool>) -> Result<usize, String> {
+#[specta::specta]
+fn scan_start(
+    app: tauri::AppHandle,
+    roots: Option<Vec<String>>,
+    dry_run: Option<bool>,
+) -> Result<usize, String> {
     tracing::info!(?roots, "scan_start");
     let mut cfg = ConfigStore::load().map_err(|e| e.to_string())?;
     if let Some(rs) = roots {
@@ -79,27 +68,75 @@ fn scan_start(roots: Option<Vec<String>>, dry_run: Option<bool>) -> Result<usize
             .map(|r| shellexpand::tilde(&r).to_string().into())
             .collect();
     }
+    let opts = ScanOptions {
+        dry_run: dry_run.unwrap_or(false),
+        ..Default::default()
+    };
+    if !opts.dry_run {
+        if let Some(daemon) = Backend::daemon().map_err(|e| e.to_string())? {
+            let roots: Vec<_> = cfg
+                .roots
+                .iter()
+                .map(|r| (r.clone(), cfg.priority_of(r)))
+                .collect();
+            daemon
+                .scan(&cfg, &roots, &opts, &AnalyzerRegistry::with_builtins())
+                .map_err(|e| e.to_string())?;
+            // The daemon scans in its own time; nothing was scanned by this call
+            tracing::info!(
+                roots = roots.len(),
+                "scan_start queued with the watch daemon"
+            );
+            return Ok(0);
+        }
+    }
     let db = Db::open_default().map_err(|e| e.to_string())?;
     tracing::info!(db = %db.path.display(), "scan_start db path");
-    let count = scan_roots(
-        &db,
-        &cfg,
-        &ScanOptions {
-            dry_run: dry_run.unwrap_or(false),
-        },
-    )
+    let count = if opts.dry_run {
+        scan_roots(&db, &cfg, &opts)
+    } else {
+        notifications::scan_and_notify(&app, &db, &cfg, || scan_roots(&db, &cfg, &opts))
+    }
     .map_err(|e| e.to_string())?;
     tracing::info!(count, "scan_complete");
     Ok(count)
 }
 
+/// Queue `root` (or every configured root) for a background scan at `priority`; roots
+/// default to their `root_priority`. Returns the queue in scan order. `scan-finished` fires
+/// once the queue is empty.
 #[tauri::command]
+#[specta::specta]
+fn scan_enqueue(
+    app: tauri::AppHandle,
+    root: Option<String>,
+    priority: Option<ScanPriority>,
+) -> Result<Vec<QueuedRoot>, String> {
+    tracing::info!(?root, ?priority, "scan_enqueue");
+    let root = root.map(|r| shellexpand::tilde(&r).to_string().into());
+    scanner::enqueue(&app, root, priority).map_err(|e| e.to_string())
+}
+
+/// Roots still waiting in the background scan queue, in scan order.
+#[tauri::command]
+#[specta::specta]
+fn scan_queue(app: tauri::AppHandle) -> Vec<QueuedRoot> {
+    scanner::pending(&app)
+}
+
+#[tauri::command]
+#[specta::specta]
 fn projects_query(
     q: Option<String>,
     sort: Option<String>,
     sort_direction: Option<String>,
     page: u32,
     page_size: u32,
+    advanced: Option<bool>,
+    branch: Option<String>,
+    has_remote: Option<bool>,
+    dirty: Option<bool>,
+    aggregates: Option<bool>,
 ) -> Result<ProjectsPage, String> {
     tracing::info!(
         "projects_query called with q={:?}, sort={:?}, page={}, page_size={}",
@@ -108,7 +145,7 @@ fn projects_query(
         page,
         page_size
     );
-    let db = Db::open_default().map_err(|e| {
+    let backend = Backend::connect().map_err(|e| {
         tracing::error!("Failed to open database: {}", e);
         e.to_string()
     })?;
@@ -117,56 +154,578 @@ fn projects_query(
         Some("name") => SortKey::Name,
         Some("type") => SortKey::Type,
         Some("loc") => SortKey::Loc,
+        Some("vulns") => SortKey::Vulns,
+        Some("last_commit") => SortKey::LastCommit,
+        Some("source_files") => SortKey::SourceFiles,
+        Some("avg_file_size") => SortKey::AvgFileSize,
+        Some("depth") => SortKey::Depth,
+        Some("modules") => SortKey::Modules,
+        Some("assets") => SortKey::Assets,
         _ => SortKey::Recent,
     };
-    let qnorm = q.as_ref().and_then(|s| {
-        if s.trim().is_empty() {
-            None
-        } else {
-            Some(s.as_str())
-        }
-    });
+    let qnorm = q.filter(|s| !s.trim().is_empty());
     let ascending = sort_direction.as_deref() == Some("asc");
-    tracing::info!(q = ?qnorm, sort = ?sort_key as i32, ascending, page, page_size, db = %db.path.display(), "projects_query");
+    tracing::info!(q = ?qnorm, sort = ?sort_key as i32, ascending, page, page_size, daemon = backend.is_daemon(), "projects_query");
 
-    let total_count = db.count_projects(qnorm).map_err(|e| {
-        tracing::error!("Database count failed: {}", e);
+    // Advanced mode treats the search box as a filter expression (see indexer::query)
+    let (search, query) = if advanced.unwrap_or(false) {
+        (None, qnorm)
+    } else {
+        (qnorm, None)
+    };
+    let req = ProjectsRequest {
+        search,
+        query,
+        branch: branch.filter(|b| !b.is_empty()),
+        has_remote,
+        dirty,
+        sort: sort_key,
+        ascending,
+        page,
+        page_size,
+        // The aggregate pass already counts matches, so it replaces the separate COUNT(*)
+        aggregates: aggregates.unwrap_or(false),
+    };
+    let result = backend.projects(&req).map_err(|e| {
+        tracing::error!("Database query failed: {}", e);
         e.to_string()
     })?;
-
-    let rows = db
-        .query_projects(qnorm, sort_key, ascending, page, page_size)
-        .map_err(|e| {
-            tracing::error!("Database query failed: {}", e);
-            e.to_string()
-        })?;
     tracing::info!(
-        rows = rows.len(),
-        total_count,
+        rows = result.items.len(),
+        total_count = result.total_count,
         "projects_query_result - returning {} items of {} total",
-        rows.len(),
-        total_count
+        result.items.len(),
+        result.total_count
     );
-    Ok(ProjectsPage {
-        items: rows,
-        page,
-        page_size,
-        total_count,
+    Ok(result)
+}
+
+#[tauri::command]
+#[specta::specta]
+fn project_fields_get(
+    project_id: i64,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.all_fields(project_id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+fn project_field_set(
+    app: tauri::AppHandle,
+    project_id: i64,
+    key: String,
+    value: serde_json::Value,
+) -> Result<(), String> {
+    tracing::info!(project_id, %key, %value, "project_field_set");
+    let value = FieldValue::from_json(&value).map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.set_project_field(project_id, &key, &value)
+        .map_err(|e| e.to_string())?;
+    if key == FAVORITE_FIELD {
+        tray::refresh(&app);
+    }
+    Ok(())
+}
+
+/// Fold `remove_id` into `keep_id` (fields, notes, open history) and delete it.
+#[tauri::command]
+#[specta::specta]
+fn projects_merge(
+    app: tauri::AppHandle,
+    keep_id: i64,
+    remove_id: i64,
+) -> Result<indexer::db::MergeReport, String> {
+    tracing::info!(keep_id, remove_id, "projects_merge");
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    let report = db
+        .merge_projects(keep_id, remove_id)
+        .map_err(|e| e.to_string())?;
+    tray::refresh(&app);
+    Ok(report)
+}
+
+/// When the index was last brought up to date and whether that is too long ago; backs the
+/// freshness note in the footer.
+#[tauri::command]
+#[specta::specta]
+fn last_scan_info() -> Result<indexer::scan_runs::LastScanInfo, String> {
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.last_scan_info(&cfg.freshness).map_err(|e| e.to_string())
+}
+
+/// Undo the last merge or delete; `None` when there is nothing to undo.
+#[tauri::command]
+#[specta::specta]
+fn journal_undo(app: tauri::AppHandle) -> Result<Option<indexer::journal::JournalEntry>, String> {
+    tracing::info!("journal_undo");
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    let entry = db
+        .undo_last(cfg.journal.retention_days)
+        .map_err(|e| e.to_string())?;
+    tray::refresh(&app);
+    Ok(entry)
+}
+
+#[tauri::command]
+#[specta::specta]
+fn project_field_remove(
+    app: tauri::AppHandle,
+    project_id: i64,
+    key: String,
+) -> Result<bool, String> {
+    tracing::info!(project_id, %key, "project_field_remove");
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    let removed = db
+        .remove_project_field(project_id, &key)
+        .map_err(|e| e.to_string())?;
+    if key == FAVORITE_FIELD {
+        tray::refresh(&app);
+    }
+    Ok(removed)
+}
+
+/// Saved UI state (sort, filters, column widths, last search); all keys when `keys` is omitted.
+#[tauri::command]
+#[specta::specta]
+fn ui_state_get(
+    keys: Option<Vec<String>>,
+) -> Result<serde_json::Map<String, serde_json::Value>, String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.ui_state(&keys.unwrap_or_default())
+        .map_err(|e| e.to_string())
+}
+
+/// Set project `project_id`'s own editor and launch command; None or blank clears either.
+#[tauri::command]
+#[specta::specta]
+fn set_project_launcher(
+    project_id: i64,
+    editor: Option<String>,
+    command: Option<String>,
+) -> Result<(), String> {
+    tracing::info!(project_id, ?editor, ?command, "set_project_launcher");
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.set_project_launcher(project_id, &ProjectLauncher { editor, command })
+        .map_err(|e| e.to_string())
+}
+
+/// Run project `project_id`'s launch command in a terminal; returns the command.
+#[tauri::command]
+#[specta::specta]
+fn run_project_command(app: tauri::AppHandle, project_id: i64) -> Result<String, String> {
+    tracing::info!(project_id, "run_project_command");
+    launch::run_project_command(&app, project_id).map_err(|e| e.to_string())
+}
+
+/// The quick actions configured for project `project_id`'s type.
+#[tauri::command]
+#[specta::specta]
+fn actions_list(project_id: i64) -> Result<Vec<indexer::actions::ActionInfo>, String> {
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    let project = db
+        .project_by_id(project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("project {project_id} is no longer indexed"))?;
+    Ok(indexer::actions::actions_for(&cfg, &project))
+}
+
+/// Start quick action `name` in project `project_id`'s directory. Output arrives as
+/// `action-output` events and the finished run as `action-finished` (`action-failed` if
+/// the run could not be recorded).
+#[tauri::command]
+#[specta::specta]
+fn action_run(app: tauri::AppHandle, project_id: i64, name: String) -> Result<(), String> {
+    tracing::info!(project_id, %name, "action_run");
+    actions::start(&app, project_id, name).map_err(|e| e.to_string())
+}
+
+/// Project `project_id`'s recent action runs with their output, newest first.
+#[tauri::command]
+#[specta::specta]
+fn action_runs(project_id: i64) -> Result<Vec<indexer::actions::ActionRun>, String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.action_runs(project_id).map_err(|e| e.to_string())
+}
+
+/// Fetch or pull projects `project_ids` in the background, `concurrency` at a time. Each
+/// repo's result arrives as a `git-fetch-progress` event and all of them as
+/// `git-fetch-finished` (`git-fetch-failed` if the index could not be updated).
+#[tauri::command]
+#[specta::specta]
+fn git_fetch(app: tauri::AppHandle, project_ids: Vec<i64>, mode: FetchMode) -> Result<(), String> {
+    use tauri::Emitter;
+    tracing::info!(count = project_ids.len(), ?mode, "git_fetch");
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    let mut projects = Vec::new();
+    for id in project_ids {
+        projects.extend(db.project_by_id(id).map_err(|e| e.to_string())?);
+    }
+    std::thread::spawn(move || {
+        let fetched = indexer::fetch::fetch_projects(&db, &projects, mode, cfg.concurrency, |r| {
+            let _ = app.emit("git-fetch-progress", r);
+        });
+        match fetched {
+            Ok(results) => {
+                let _ = app.emit("git-fetch-finished", results);
+            }
+            Err(err) => {
+                tracing::error!(%err, "git_fetch failed");
+                let _ = app.emit("git-fetch-failed", err.to_string());
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Save one UI state value; `null` forgets it.
+#[tauri::command]
+#[specta::specta]
+fn ui_state_set(key: String, value: serde_json::Value) -> Result<(), String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.set_ui_state(&key, &value).map_err(|e| e.to_string())
+}
+
+/// Quick-open palette results for `q`, ranked by match quality, frecency, favorites and
+/// staleness (weights in `ranking.*`).
+#[tauri::command]
+#[specta::specta]
+fn palette_search(q: String, limit: Option<u32>) -> Result<Vec<indexer::ProjectRecord>, String> {
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.quick_open(&q, limit.unwrap_or(20), &cfg.ranking)
+        .map_err(|e| e.to_string())
+}
+
+/// Full-text matches for `q` over names, paths and READMEs, with what matched highlighted.
+#[tauri::command]
+#[specta::specta]
+fn projects_search(
+    q: String,
+    limit: Option<u32>,
+) -> Result<Vec<indexer::search::SearchHit>, String> {
+    let backend = Backend::connect().map_err(|e| e.to_string())?;
+    backend
+        .search(&q, limit.unwrap_or(500))
+        .map_err(|e| e.to_string())
+}
+
+/// Type-ahead names for the palette, cheap enough for every keystroke; `palette_search`
+/// refines the list once typing pauses.
+#[tauri::command]
+#[specta::specta]
+fn palette_suggest(
+    prefix: String,
+    limit: Option<u32>,
+) -> Result<Vec<indexer::search::Suggestion>, String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.suggest(&prefix, limit.unwrap_or(20))
+        .map_err(|e| e.to_string())
+}
+
+/// Open a palette pick with `action`, or `launcher.default_action` when omitted.
+#[tauri::command]
+#[specta::specta]
+fn palette_open(
+    app: tauri::AppHandle,
+    project_id: i64,
+    action: Option<LaunchAction>,
+) -> Result<(), String> {
+    tracing::info!(project_id, ?action, "palette_open");
+    palette::hide(&app);
+    launch::open_project(&app, project_id, action)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+fn palette_hide(app: tauri::AppHandle) {
+    palette::hide(&app);
+}
+
+/// Open the detail view for `project_id` in its own window, or focus it if already open.
+#[tauri::command]
+#[specta::specta]
+fn open_detail_window(app: tauri::AppHandle, project_id: i64) -> Result<(), String> {
+    tracing::info!(project_id, "open_detail_window");
+    windows::open_detail(&app, project_id).map_err(|e| e.to_string())
+}
+
+/// README, per-language LOC and fields for the detail view.
+#[tauri::command]
+#[specta::specta]
+fn project_detail(project_id: i64) -> Result<indexer::detail::ProjectDetail, String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    indexer::detail::project_detail(&db, project_id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("project {project_id} is no longer indexed"))
+}
+
+/// Ranked suggestions of what to delete to free space, for the cleanup wizard.
+#[tauri::command]
+#[specta::specta]
+fn cleanup_recommendations() -> Result<Vec<indexer::cleanup::Recommendation>, String> {
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.recommend_cleanup(&cfg.cleanup)
+        .map_err(|e| e.to_string())
+}
+
+/// Commits and edits per day over the last year, for the contribution heatmap. Covers the
+/// whole index when `project_id` is omitted.
+#[tauri::command]
+#[specta::specta]
+fn activity_calendar(
+    project_id: Option<i64>,
+) -> Result<Vec<indexer::activity::ActivityDay>, String> {
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    db.activity_calendar(project_id).map_err(|e| e.to_string())
+}
+
+/// Candidate roots in the usual places (~/Code, ~/Projects, ~/src, ...) with estimated
+/// project counts, for first-run setup.
+#[tauri::command]
+#[specta::specta]
+async fn onboarding_suggest_roots() -> Result<Vec<indexer::roots::RootSuggestion>, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let cfg = ConfigStore::load()?;
+        indexer::roots::suggest_home_roots(&cfg)
     })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
 }
 
-fn main() {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
+/// Whether first-run setup is done, plus what the setup screen starts from.
+#[tauri::command]
+#[specta::specta]
+fn onboarding_status() -> Result<onboarding::OnboardingStatus, String> {
+    onboarding::status().map_err(|e| e.to_string())
+}
+
+/// Detection-only preview of `root`: up to 50 projects within a few levels, returned in
+/// seconds. Nothing is written to the index.
+#[tauri::command]
+#[specta::specta]
+async fn onboarding_quick_scan(root: String) -> Result<indexer::roots::QuickScan, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let cfg = ConfigStore::load()?;
+        let root = std::path::PathBuf::from(shellexpand::tilde(&root).to_string());
+        if !root.is_dir() {
+            anyhow::bail!("{} is not a directory", root.display());
+        }
+        Ok(indexer::roots::quick_scan(&root, &cfg, 50))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e: anyhow::Error| e.to_string())
+}
+
+/// Save the setup choices and queue a full scan of the chosen roots.
+#[tauri::command]
+#[specta::specta]
+fn onboarding_complete(
+    app: tauri::AppHandle,
+    config: onboarding::OnboardingConfig,
+) -> Result<Vec<QueuedRoot>, String> {
+    onboarding::complete(&app, config).map_err(|e| e.to_string())
+}
+
+/// Add `path` to the scan roots (e.g. the parent of a dropped folder). Returns whether
+/// the config changed.
+#[tauri::command]
+#[specta::specta]
+fn roots_add(path: String) -> Result<bool, String> {
+    tracing::info!(%path, "roots_add");
+    dragdrop::add_root(&path).map_err(|e| e.to_string())
+}
+
+/// Whether a newer release than this build is published; backs the About dialog notice.
+#[tauri::command]
+#[specta::specta]
+async fn update_check() -> Result<indexer::update::UpdateStatus, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let release = indexer::update::latest_release()?;
+        indexer::update::check(env!("CARGO_PKG_VERSION"), &release)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// Recent lines of the app log file for the debug panel, oldest first. `redact` scrubs paths,
+/// remotes and emails for pasting into a bug report.
+#[tauri::command]
+#[specta::specta]
+fn show_logs(lines: Option<usize>, redact: Option<bool>) -> Result<Vec<String>, String> {
+    let cfg = ConfigStore::load().map_err(|e| e.to_string())?;
+    let path = indexer::logging::log_path(&cfg.logging).map_err(|e| e.to_string())?;
+    let lines =
+        indexer::logging::tail_lines(&path, lines.unwrap_or(200)).map_err(|e| e.to_string())?;
+    if !redact.unwrap_or(false) {
+        return Ok(lines);
+    }
+    let db = Db::open_default().map_err(|e| e.to_string())?;
+    let redactor = indexer::diagnose::log_redactor(&db, &cfg).map_err(|e| e.to_string())?;
+    Ok(lines.iter().map(|l| redactor.text(l)).collect())
+}
+
+/// Write a redacted diagnostics bundle (see `diagnose --bundle`) to the downloads folder and
+/// return its path.
+#[tauri::command]
+#[specta::specta]
+async fn export_diagnostics() -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(|| {
+        let cfg = ConfigStore::load()?;
+        let db = Db::open_default()?;
+        let out = indexer::diagnose::default_bundle_path();
+        indexer::diagnose::write_bundle(&db, &cfg, &out)?;
+        Ok::<_, anyhow::Error>(out.to_string_lossy().into_owned())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+    .map_err(|e| e.to_string())
+}
+
+/// stderr plus, unless disabled, the rotating log file. `RUST_LOG` overrides `logging.level`.
+fn init_logging(cfg: &indexer::config::LoggingConfig) {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(&cfg.level));
+    let (file, file_err) = if cfg.file_enabled {
+        match RotatingFile::from_config(cfg) {
+            Ok(f) => (Some(f), None),
+            Err(err) => (None, Some(err)),
+        }
+    } else {
+        (None, None)
+    };
+    let file_layer = file.map(|f| {
+        tracing_subscriber::fmt::layer()
+            .with_ansi(false)
+            .with_writer(move || f.clone())
+    });
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
+        .with(file_layer)
         .init();
+    if let Some(err) = file_err {
+        tracing::warn!(%err, "file logging disabled");
+    }
+}
 
-    tauri::Builder::default()
-        .invoke_handler(tauri::generate_handler![
+/// Every command the web UI may call. Debug builds regenerate `web/src/bindings.ts` from
+/// these signatures, so the frontend types follow the Rust ones.
+fn specta_builder() -> tauri_specta::Builder {
+    tauri_specta::Builder::<tauri::Wry>::new()
+        .commands(tauri_specta::collect_commands![
             test_command,
             open_in_editor,
             scan_start,
-            projects_query
+            scan_enqueue,
+            scan_queue,
+            projects_query,
+            project_fields_get,
+            project_field_set,
+            project_field_remove,
+            projects_merge,
+            last_scan_info,
+            journal_undo,
+            show_logs,
+            export_diagnostics,
+            update_check,
+            palette_search,
+            palette_suggest,
+            projects_search,
+            palette_open,
+            palette_hide,
+            roots_add,
+            onboarding_suggest_roots,
+            onboarding_status,
+            onboarding_quick_scan,
+            onboarding_complete,
+            open_detail_window,
+            project_detail,
+            cleanup_recommendations,
+            activity_calendar,
+            ui_state_get,
+            ui_state_set,
+            set_project_launcher,
+            run_project_command,
+            actions_list,
+            action_run,
+            action_runs,
+            git_fetch
         ])
+        // Commands reject with the error string, as plain `invoke` does
+        .error_handling(tauri_specta::ErrorHandlingMode::Throw)
+}
+
+fn main() {
+    if std::env::args().skip(1).any(|a| a == "--portable") {
+        ConfigStore::set_portable(true);
+    }
+    let cfg = ConfigStore::load();
+    init_logging(&cfg.as_ref().map(|c| c.logging.clone()).unwrap_or_default());
+
+    let cfg = cfg.unwrap_or_else(|err| {
+        tracing::warn!(%err, "config load failed; using defaults");
+        indexer::AppConfig::default()
+    });
+    indexer::metrics::start_from_config(&cfg.metrics);
+    if cfg.serve.enabled {
+        match Db::open_default() {
+            Ok(db) => indexer::serve::start_from_config(&cfg.serve, &db.path),
+            Err(err) => tracing::warn!(%err, "serve endpoints need the database"),
+        }
+    }
+
+    let builder = specta_builder();
+    #[cfg(debug_assertions)]
+    if let Err(err) = builder.export(
+        // i64 ids and sizes stay well inside JS's safe integer range
+        specta_typescript::Typescript::default()
+            .bigint(specta_typescript::BigIntExportBehavior::Number),
+        concat!(env!("CARGO_MANIFEST_DIR"), "/../web/src/bindings.ts"),
+    ) {
+        tracing::warn!(%err, "failed to export TypeScript bindings");
+    }
+
+    tauri::Builder::default()
+        .manage(scanner::Scanner::default())
+        .invoke_handler(builder.invoke_handler())
+        // Must come first: on Windows/Linux a link starts a second instance, whose URL
+        // this forwards to the running app's deep-link handler
+        .plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+            tray::show_main_window(app);
+        }))
+        .plugin(tauri_plugin_deep_link::init())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
+        .plugin(tauri_plugin_notification::init())
+        .on_window_event(dragdrop::on_window_event)
+        .setup(move |app| {
+            if let Some(main) = app.get_webview_window("main") {
+                windows::restore(&main);
+                windows::track(&main);
+            }
+            if cfg.launcher.tray {
+                tray::init(app.handle())?;
+            }
+            if !cfg.launcher.hotkey.is_empty() {
+                // A taken or malformed shortcut should not keep the app from starting
+                if let Err(err) = palette::init(app.handle(), &cfg.launcher.hotkey) {
+                    tracing::warn!(%err, "quick-open palette disabled");
+                }
+            }
+            if let Err(err) = deeplink::init(app.handle()) {
+                tracing::warn!(%err, "{}:// links disabled", indexer::links::SCHEME);
+            }
+            Ok(())
+        })
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
 }
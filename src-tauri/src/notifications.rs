@@ -0,0 +1,37 @@
+//! OS notifications for app-initiated scans; which events fire is set in `notifications.*`.
+
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+use indexer::notify::{notable_changes, IndexSnapshot};
+use indexer::{AppConfig, Db};
+
+/// Run `scan` (which returns the number of projects found), then notify about completion
+/// and notable changes to the index.
+pub fn scan_and_notify(
+    app: &AppHandle,
+    db: &Db,
+    cfg: &AppConfig,
+    scan: impl FnOnce() -> anyhow::Result<usize>,
+) -> anyhow::Result<usize> {
+    let before = IndexSnapshot::take(db)?;
+    let count = scan()?;
+    // The scan itself succeeded; notifications are best effort
+    match notable_changes(db, &before, count, &cfg.notifications) {
+        Ok(events) => {
+            for event in events {
+                let shown = app
+                    .notification()
+                    .builder()
+                    .title(event.title())
+                    .body(event.body())
+                    .show();
+                if let Err(err) = shown {
+                    tracing::warn!(%err, "could not show notification");
+                }
+            }
+        }
+        Err(err) => tracing::warn!(%err, "could not diff the index after scanning"),
+    }
+    Ok(count)
+}
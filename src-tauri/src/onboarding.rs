@@ -0,0 +1,65 @@
+//! First-run setup: the app shows its guided setup until a config file exists, previews
+//! roots with a quick detection-only scan, then saves the choices and queues a full scan.
+
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use indexer::queue::QueuedRoot;
+use indexer::{ConfigStore, Db, ProjectFilter};
+
+#[derive(Serialize, specta::Type)]
+pub struct OnboardingStatus {
+    /// Setup has been finished (or a config was written some other way)
+    pub completed: bool,
+    pub roots: Vec<String>,
+    pub indexed_projects: u32,
+    pub editor: String,
+}
+
+/// What the setup flow lets the user pick; everything else keeps its current value.
+#[derive(Deserialize, specta::Type)]
+pub struct OnboardingConfig {
+    pub roots: Vec<String>,
+    pub editor: Option<String>,
+    /// Empty disables the quick-open palette
+    pub hotkey: Option<String>,
+}
+
+pub fn status() -> anyhow::Result<OnboardingStatus> {
+    let cfg = ConfigStore::load()?;
+    let db = Db::open_default()?;
+    Ok(OnboardingStatus {
+        completed: ConfigStore::config_path()?.exists(),
+        roots: cfg.roots.iter().map(|r| r.display().to_string()).collect(),
+        indexed_projects: db.count_projects(&ProjectFilter::default())?,
+        editor: cfg.launcher.editor,
+    })
+}
+
+/// Save `choices` into the config and queue a full scan of the chosen roots.
+pub fn complete(app: &AppHandle, choices: OnboardingConfig) -> anyhow::Result<Vec<QueuedRoot>> {
+    let mut cfg = ConfigStore::load()?;
+    let roots: Vec<_> = choices
+        .roots
+        .iter()
+        .filter(|r| !r.trim().is_empty())
+        .map(|r| std::path::PathBuf::from(shellexpand::tilde(r).to_string()))
+        .collect();
+    if roots.is_empty() {
+        anyhow::bail!("pick at least one folder to scan");
+    }
+    if let Some(missing) = roots.iter().find(|r| !r.is_dir()) {
+        anyhow::bail!("{} is not a directory", missing.display());
+    }
+    cfg.roots = roots;
+    if let Some(editor) = choices.editor.filter(|e| !e.trim().is_empty()) {
+        cfg.launcher.editor = editor;
+    }
+    if let Some(hotkey) = choices.hotkey {
+        // Takes effect on the next launch; the shortcut is registered at startup
+        cfg.launcher.hotkey = hotkey;
+    }
+    ConfigStore::save(&cfg)?;
+    tracing::info!(roots = ?cfg.roots, "onboarding complete");
+    crate::scanner::enqueue(app, None, None)
+}
@@ -0,0 +1,61 @@
+//! Spotlight-style quick-open palette: a small undecorated window toggled by the global
+//! `launcher.hotkey`, hidden again as soon as it loses focus.
+
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, ShortcutState};
+
+pub const LABEL: &str = "palette";
+
+/// Create the (hidden) palette window and bind `hotkey` to toggle it.
+pub fn init(app: &AppHandle, hotkey: &str) -> anyhow::Result<()> {
+    let window = WebviewWindowBuilder::new(
+        app,
+        LABEL,
+        WebviewUrl::App("index.html?view=palette".into()),
+    )
+    .title("Quick Open")
+    .inner_size(640.0, 420.0)
+    .center()
+    .decorations(false)
+    .always_on_top(true)
+    .skip_taskbar(true)
+    .resizable(false)
+    .visible(false)
+    .build()?;
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Focused(false) = event {
+            let _ = handle.hide();
+        }
+    });
+
+    app.global_shortcut()
+        .on_shortcut(hotkey, |app, _shortcut, event| {
+            if event.state == ShortcutState::Pressed {
+                toggle(app);
+            }
+        })
+        .map_err(|err| anyhow::anyhow!("cannot register hotkey {hotkey:?}: {err}"))?;
+    Ok(())
+}
+
+pub fn toggle(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(LABEL) else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) {
+        let _ = window.hide();
+        return;
+    }
+    let _ = window.center();
+    let _ = window.show();
+    let _ = window.set_focus();
+    // The palette clears its query and reloads results each time it appears
+    let _ = window.emit("palette-shown", ());
+}
+
+pub fn hide(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        let _ = window.hide();
+    }
+}
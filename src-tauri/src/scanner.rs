@@ -0,0 +1,130 @@
+//! The app's background scan queue: the tray and the UI enqueue roots with a priority and a
+//! single worker thread drains the queue, so requests made mid-scan are picked up in
+//! priority order instead of starting overlapping scans. While a `watch` daemon is running
+//! the roots are queued with it instead and the app scans nothing itself.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use indexer::queue::QueuedRoot;
+use indexer::rpc::{Backend, ScanOutcome};
+use indexer::{
+    AnalyzerRegistry, ConfigStore, Db, ScanOptions, ScanPriority, ScanProgress, ScanQueue,
+};
+
+/// Enrichment progress is emitted every this many projects (and on the last one)
+const PROGRESS_EVERY: usize = 25;
+
+#[derive(Default)]
+pub struct Scanner {
+    queue: ScanQueue,
+    running: AtomicBool,
+}
+
+/// Queue `root` at `priority`, or every configured root at its `root_priority` when `root`
+/// is `None`, and make sure the worker is running. Returns the queue as it now stands.
+pub fn enqueue(
+    app: &AppHandle,
+    root: Option<PathBuf>,
+    priority: Option<ScanPriority>,
+) -> anyhow::Result<Vec<QueuedRoot>> {
+    let scanner = app.state::<Scanner>();
+    let cfg = ConfigStore::load()?;
+    let roots: Vec<(PathBuf, ScanPriority)> = match root {
+        Some(root) => {
+            let priority = priority.unwrap_or_else(|| cfg.priority_of(&root));
+            vec![(root, priority)]
+        }
+        None => cfg
+            .roots
+            .iter()
+            .map(|r| {
+                let configured = cfg.priority_of(r);
+                (
+                    r.clone(),
+                    priority.map_or(configured, |p| p.max(configured)),
+                )
+            })
+            .collect(),
+    };
+    if let Some(daemon) = Backend::daemon()? {
+        if let ScanOutcome::Queued(pending) = daemon.scan(
+            &cfg,
+            &roots,
+            &ScanOptions::default(),
+            &AnalyzerRegistry::default(),
+        )? {
+            return Ok(pending);
+        }
+    }
+    for (root, priority) in roots {
+        scanner.queue.enqueue(root, priority);
+    }
+    let pending = scanner.queue.pending();
+    if !scanner.running.swap(true, Ordering::SeqCst) {
+        let app = app.clone();
+        std::thread::spawn(move || work(app));
+    }
+    Ok(pending)
+}
+
+/// The daemon's queue while one is running, else the app's own.
+pub fn pending(app: &AppHandle) -> Vec<QueuedRoot> {
+    let daemon = Backend::daemon()
+        .ok()
+        .flatten()
+        .and_then(|b| b.status().ok().flatten());
+    match daemon {
+        Some(status) => status.queued,
+        None => app.state::<Scanner>().queue.pending(),
+    }
+}
+
+fn work(app: AppHandle) {
+    let scanner = app.state::<Scanner>();
+    loop {
+        match drain(&app, &scanner.queue) {
+            Ok(count) => {
+                tracing::info!(count, "queued scan complete");
+                let _ = app.emit("scan-finished", count);
+            }
+            Err(err) => tracing::error!(%err, "queued scan failed"),
+        }
+        crate::tray::refresh(&app);
+        scanner.running.store(false, Ordering::SeqCst);
+        // A root queued between the last pop and clearing `running` would otherwise wait
+        // for the next request
+        if scanner.queue.is_empty() || scanner.running.swap(true, Ordering::SeqCst) {
+            break;
+        }
+    }
+}
+
+/// Run the queue, emitting `scan-progress` once discovery has stored the project rows and
+/// then as metrics fill in, so the list is usable long before the scan finishes.
+fn drain(app: &AppHandle, queue: &ScanQueue) -> anyhow::Result<usize> {
+    let cfg = ConfigStore::load()?;
+    let db = Db::open_default()?;
+    crate::notifications::scan_and_notify(app, &db, &cfg, || {
+        let report = queue.run_with_progress(
+            &db,
+            &cfg,
+            &ScanOptions {
+                nice: cfg.nice.background,
+                ..Default::default()
+            },
+            &AnalyzerRegistry::with_builtins(),
+            &mut |progress| {
+                if let ScanProgress::Enriched { done, total } = progress {
+                    if !done.is_multiple_of(PROGRESS_EVERY) && done != total {
+                        return;
+                    }
+                }
+                let _ = app.emit("scan-progress", progress);
+            },
+        )?;
+        Ok(report.projects)
+    })
+}
@@ -0,0 +1,171 @@
+//! Tray / menu bar quick-launcher: favorites and frecent projects open with
+//! `launcher.default_action` (the configured editor by default) without bringing up the
+//! main window.
+
+use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::TrayIconBuilder;
+use tauri::{AppHandle, Emitter, Manager, Wry};
+
+use indexer::rpc::Backend;
+use indexer::{ConfigStore, Db, ProjectFilter, ProjectRecord};
+
+/// Menu ids for project entries are `open:<project id>`
+const OPEN_PREFIX: &str = "open:";
+
+/// The tray's menu. Linux cannot swap a tray menu once set, so refreshes rebuild its
+/// contents in place instead.
+struct TrayMenu(Menu<Wry>);
+
+pub fn init(app: &AppHandle) -> tauri::Result<()> {
+    let menu = Menu::new(app)?;
+    fill_menu(app, &menu)?;
+    app.manage(TrayMenu(menu.clone()));
+    let mut tray = TrayIconBuilder::new()
+        .tooltip("Project Browser")
+        .menu(&menu)
+        .on_menu_event(|app, event| on_menu_event(app, event.id().as_ref()));
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+/// Rebuild the menu after opens, favorite changes and scans. A no-op without a tray.
+pub fn refresh(app: &AppHandle) {
+    let Some(menu) = app.try_state::<TrayMenu>() else {
+        return;
+    };
+    if let Err(err) = fill_menu(app, &menu.0) {
+        tracing::warn!(%err, "tray menu refresh failed");
+    }
+}
+
+fn fill_menu(app: &AppHandle, menu: &Menu<Wry>) -> tauri::Result<()> {
+    let limit = ConfigStore::load()
+        .map(|c| c.launcher.tray_items)
+        .unwrap_or(10);
+    let (favorites, recent) = match Db::open_default().and_then(|db| {
+        Ok((
+            db.favorite_projects(limit)?,
+            db.frecent_projects(&ProjectFilter::default(), limit)?,
+        ))
+    }) {
+        Ok(lists) => lists,
+        Err(err) => {
+            tracing::warn!(%err, "tray menu could not read the index");
+            (Vec::new(), Vec::new())
+        }
+    };
+
+    let favorites = project_items(app, &favorites)?;
+    let favorites_menu =
+        Submenu::with_items(app, "Favorites", !favorites.is_empty(), &as_dyn(&favorites))?;
+    let recent_header = MenuItem::new(app, "Recent", false, None::<&str>)?;
+    let recent = project_items(app, &recent)?;
+    let search = MenuItem::with_id(app, "search", "Search Projects…", true, None::<&str>)?;
+    // The watch daemon's periodic rescans wait on battery (see `power`); a manual one runs
+    let deferred = Backend::daemon()
+        .ok()
+        .flatten()
+        .and_then(|b| b.status().ok().flatten())
+        .and_then(|status| status.deferred);
+    let deferred_note = deferred
+        .as_ref()
+        .map(|reason| {
+            MenuItem::new(
+                app,
+                format!("Scheduled scans paused: {reason}"),
+                false,
+                None::<&str>,
+            )
+        })
+        .transpose()?;
+    let rescan_label = if deferred.is_some() {
+        "Rescan Now Anyway"
+    } else {
+        "Rescan Now"
+    };
+    let rescan = MenuItem::with_id(app, "rescan", rescan_label, true, None::<&str>)?;
+    let show = MenuItem::with_id(app, "show", "Show Window", true, None::<&str>)?;
+    let quit = PredefinedMenuItem::quit(app, Some("Quit Project Browser"))?;
+    let separator = || PredefinedMenuItem::separator(app);
+
+    let mut items: Vec<&dyn IsMenuItem<Wry>> = vec![&favorites_menu];
+    let sep1 = separator()?;
+    items.push(&sep1);
+    if !recent.is_empty() {
+        items.push(&recent_header);
+        items.extend(as_dyn(&recent));
+    }
+    let sep2 = separator()?;
+    items.push(&search);
+    if let Some(note) = &deferred_note {
+        items.push(note);
+    }
+    items.extend([&rescan as &dyn IsMenuItem<Wry>, &sep2, &show, &quit]);
+    while menu.remove_at(0)?.is_some() {}
+    menu.append_items(&items)
+}
+
+fn project_items(app: &AppHandle, projects: &[ProjectRecord]) -> tauri::Result<Vec<MenuItem<Wry>>> {
+    projects
+        .iter()
+        .map(|p| {
+            MenuItem::with_id(
+                app,
+                format!("{OPEN_PREFIX}{}", p.id),
+                &p.name,
+                true,
+                None::<&str>,
+            )
+        })
+        .collect()
+}
+
+fn as_dyn(items: &[MenuItem<Wry>]) -> Vec<&dyn IsMenuItem<Wry>> {
+    items.iter().map(|i| i as &dyn IsMenuItem<Wry>).collect()
+}
+
+fn on_menu_event(app: &AppHandle, id: &str) {
+    if let Some(project_id) = id.strip_prefix(OPEN_PREFIX) {
+        match project_id.parse() {
+            Ok(project_id) => open_project(app, project_id),
+            Err(_) => tracing::warn!(id, "unknown tray menu item"),
+        }
+        return;
+    }
+    match id {
+        "search" => {
+            show_main_window(app);
+            if let Err(err) = app.emit("focus-search", ()) {
+                tracing::warn!(%err, "focus-search event failed");
+            }
+        }
+        "rescan" => rescan(app.clone()),
+        "show" => show_main_window(app),
+        _ => {}
+    }
+}
+
+fn open_project(app: &AppHandle, project_id: i64) {
+    match crate::launch::open_project(app, project_id, None) {
+        Ok(project) => tracing::info!(project_id, path = %project.path, "opened from tray"),
+        Err(err) => tracing::error!(project_id, %err, "tray open failed"),
+    }
+}
+
+/// Queue every configured root; the scan worker refreshes the menu when it is done.
+fn rescan(app: AppHandle) {
+    if let Err(err) = crate::scanner::enqueue(&app, None, None) {
+        tracing::error!(%err, "tray rescan failed");
+    }
+}
+
+pub fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+}
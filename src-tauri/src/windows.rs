@@ -0,0 +1,107 @@
+//! Secondary windows (project detail) and per-label window geometry, remembered in the
+//! config store's `window-state.json`.
+
+use tauri::{
+    AppHandle, LogicalPosition, LogicalSize, Manager, WebviewUrl, WebviewWindow,
+    WebviewWindowBuilder, WindowEvent,
+};
+
+use indexer::config::WindowState;
+use indexer::ConfigStore;
+
+/// Open (or focus) the detail window for `project_id`. Each project gets its own window,
+/// so its geometry is remembered per project.
+pub fn open_detail(app: &AppHandle, project_id: i64) -> anyhow::Result<()> {
+    let label = format!("detail-{project_id}");
+    if let Some(window) = app.get_webview_window(&label) {
+        window.unminimize()?;
+        window.show()?;
+        window.set_focus()?;
+        return Ok(());
+    }
+    let mut builder = WebviewWindowBuilder::new(
+        app,
+        &label,
+        WebviewUrl::App(format!("index.html?view=detail&id={project_id}").into()),
+    )
+    .title("Project Details")
+    .inner_size(720.0, 640.0)
+    .min_inner_size(420.0, 320.0);
+    match saved_state(app, &label) {
+        Some(state) => {
+            builder = builder
+                .inner_size(state.width, state.height)
+                .position(state.x, state.y)
+                .maximized(state.maximized);
+        }
+        None => builder = builder.center(),
+    }
+    let window = builder.build()?;
+    track(&window);
+    Ok(())
+}
+
+/// Apply the remembered geometry to an already created window (the main window).
+pub fn restore(window: &WebviewWindow) {
+    let Some(state) = saved_state(window.app_handle(), window.label()) else {
+        return;
+    };
+    let _ = window.set_size(LogicalSize::new(state.width, state.height));
+    let _ = window.set_position(LogicalPosition::new(state.x, state.y));
+    if state.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Remember `window`'s geometry when it is closed.
+pub fn track(window: &WebviewWindow) {
+    let handle = window.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::CloseRequested { .. } = event {
+            if let Err(err) = save(&handle) {
+                tracing::warn!(label = handle.label(), %err, "could not save window state");
+            }
+        }
+    });
+}
+
+fn save(window: &WebviewWindow) -> anyhow::Result<()> {
+    let scale = window.scale_factor()?;
+    let maximized = window.is_maximized()?;
+    if maximized {
+        // Keep the restored geometry from before maximizing, so un-maximizing still works
+        if let Some(mut state) = ConfigStore::window_state(window.label())? {
+            state.maximized = true;
+            return ConfigStore::save_window_state(window.label(), state);
+        }
+    }
+    let pos = window.outer_position()?.to_logical::<f64>(scale);
+    let size = window.inner_size()?.to_logical::<f64>(scale);
+    ConfigStore::save_window_state(
+        window.label(),
+        WindowState {
+            x: pos.x,
+            y: pos.y,
+            width: size.width,
+            height: size.height,
+            maximized,
+        },
+    )
+}
+
+/// The saved state for `label`, unless its top-left corner is no longer on any monitor
+/// (e.g. an external display was unplugged).
+fn saved_state(app: &AppHandle, label: &str) -> Option<WindowState> {
+    let state = ConfigStore::window_state(label).ok()??;
+    let monitors = app.available_monitors().ok()?;
+    let visible = monitors.iter().any(|m| {
+        let scale = m.scale_factor();
+        let pos = m.position().to_logical::<f64>(scale);
+        let size = m.size().to_logical::<f64>(scale);
+        state.x >= pos.x
+            && state.y >= pos.y
+            && state.x < pos.x + size.width
+            && state.y < pos.y + size.height
+    });
+    visible.then_some(state)
+}
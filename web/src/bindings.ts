@@ -0,0 +1,716 @@
+
+// This file was generated by [tauri-specta](https://github.com/oscartbeaumont/tauri-specta). Do not edit this file manually.
+
+/** user-defined commands **/
+
+
+export const commands = {
+async testCommand() : Promise<string> {
+    return await TAURI_INVOKE("test_command");
+},
+async openInEditor(editor: string, path: string) : Promise<string> {
+    return await TAURI_INVOKE("open_in_editor", { editor, path });
+},
+async scanStart(roots: string[] | null, dryRun: boolean | null) : Promise<number> {
+    return await TAURI_INVOKE("scan_start", { roots, dryRun });
+},
+/**
+ * Queue `root` (or every configured root) for a background scan at `priority`; roots
+ * default to their `root_priority`. Returns the queue in scan order. `scan-finished` fires
+ * once the queue is empty.
+ */
+async scanEnqueue(root: string | null, priority: ScanPriority | null) : Promise<QueuedRoot[]> {
+    return await TAURI_INVOKE("scan_enqueue", { root, priority });
+},
+/**
+ * Roots still waiting in the background scan queue, in scan order.
+ */
+async scanQueue() : Promise<QueuedRoot[]> {
+    return await TAURI_INVOKE("scan_queue");
+},
+async projectsQuery(q: string | null, sort: string | null, sortDirection: string | null, page: number, pageSize: number, advanced: boolean | null, branch: string | null, hasRemote: boolean | null, dirty: boolean | null, aggregates: boolean | null) : Promise<ProjectsPage> {
+    return await TAURI_INVOKE("projects_query", { q, sort, sortDirection, page, pageSize, advanced, branch, hasRemote, dirty, aggregates });
+},
+async projectFieldsGet(projectId: number) : Promise<Partial<{ [key in string]: JsonValue }>> {
+    return await TAURI_INVOKE("project_fields_get", { projectId });
+},
+async projectFieldSet(projectId: number, key: string, value: JsonValue) : Promise<null> {
+    return await TAURI_INVOKE("project_field_set", { projectId, key, value });
+},
+async projectFieldRemove(projectId: number, key: string) : Promise<boolean> {
+    return await TAURI_INVOKE("project_field_remove", { projectId, key });
+},
+/**
+ * Fold `remove_id` into `keep_id` (fields, notes, open history) and delete it.
+ */
+async projectsMerge(keepId: number, removeId: number) : Promise<MergeReport> {
+    return await TAURI_INVOKE("projects_merge", { keepId, removeId });
+},
+/**
+ * When the index was last brought up to date and whether that is too long ago; backs the
+ * freshness note in the footer.
+ */
+async lastScanInfo() : Promise<LastScanInfo> {
+    return await TAURI_INVOKE("last_scan_info");
+},
+/**
+ * Undo the last merge or delete; `None` when there is nothing to undo.
+ */
+async journalUndo() : Promise<JournalEntry | null> {
+    return await TAURI_INVOKE("journal_undo");
+},
+/**
+ * Recent lines of the app log file for the debug panel, oldest first. `redact` scrubs paths,
+ * remotes and emails for pasting into a bug report.
+ */
+async showLogs(lines: number | null, redact: boolean | null) : Promise<string[]> {
+    return await TAURI_INVOKE("show_logs", { lines, redact });
+},
+/**
+ * Write a redacted diagnostics bundle (see `diagnose --bundle`) to the downloads folder and
+ * return its path.
+ */
+async exportDiagnostics() : Promise<string> {
+    return await TAURI_INVOKE("export_diagnostics");
+},
+/**
+ * Whether a newer release than this build is published; backs the About dialog notice.
+ */
+async updateCheck() : Promise<UpdateStatus> {
+    return await TAURI_INVOKE("update_check");
+},
+/**
+ * Quick-open palette results for `q`, ranked by match quality, frecency, favorites and
+ * staleness (weights in `ranking.*`).
+ */
+async paletteSearch(q: string, limit: number | null) : Promise<ProjectRecord[]> {
+    return await TAURI_INVOKE("palette_search", { q, limit });
+},
+/**
+ * Type-ahead names for the palette, cheap enough for every keystroke; `palette_search`
+ * refines the list once typing pauses.
+ */
+async paletteSuggest(prefix: string, limit: number | null) : Promise<Suggestion[]> {
+    return await TAURI_INVOKE("palette_suggest", { prefix, limit });
+},
+/**
+ * Full-text matches for `q` over names, paths and READMEs, with what matched highlighted.
+ */
+async projectsSearch(q: string, limit: number | null) : Promise<SearchHit[]> {
+    return await TAURI_INVOKE("projects_search", { q, limit });
+},
+/**
+ * Open a palette pick with `action`, or `launcher.default_action` when omitted.
+ */
+async paletteOpen(projectId: number, action: LaunchAction | null) : Promise<null> {
+    return await TAURI_INVOKE("palette_open", { projectId, action });
+},
+async paletteHide() : Promise<void> {
+    await TAURI_INVOKE("palette_hide");
+},
+/**
+ * Add `path` to the scan roots (e.g. the parent of a dropped folder). Returns whether
+ * the config changed.
+ */
+async rootsAdd(path: string) : Promise<boolean> {
+    return await TAURI_INVOKE("roots_add", { path });
+},
+/**
+ * Candidate roots in the usual places (~/Code, ~/Projects, ~/src, ...) with estimated
+ * project counts, for first-run setup.
+ */
+async onboardingSuggestRoots() : Promise<RootSuggestion[]> {
+    return await TAURI_INVOKE("onboarding_suggest_roots");
+},
+/**
+ * Whether first-run setup is done, plus what the setup screen starts from.
+ */
+async onboardingStatus() : Promise<OnboardingStatus> {
+    return await TAURI_INVOKE("onboarding_status");
+},
+/**
+ * Detection-only preview of `root`: up to 50 projects within a few levels, returned in
+ * seconds. Nothing is written to the index.
+ */
+async onboardingQuickScan(root: string) : Promise<QuickScan> {
+    return await TAURI_INVOKE("onboarding_quick_scan", { root });
+},
+/**
+ * Save the setup choices and queue a full scan of the chosen roots.
+ */
+async onboardingComplete(config: OnboardingConfig) : Promise<QueuedRoot[]> {
+    return await TAURI_INVOKE("onboarding_complete", { config });
+},
+/**
+ * Open the detail view for `project_id` in its own window, or focus it if already open.
+ */
+async openDetailWindow(projectId: number) : Promise<null> {
+    return await TAURI_INVOKE("open_detail_window", { projectId });
+},
+/**
+ * README, per-language LOC and fields for the detail view.
+ */
+async projectDetail(projectId: number) : Promise<ProjectDetail> {
+    return await TAURI_INVOKE("project_detail", { projectId });
+},
+/**
+ * Ranked suggestions of what to delete to free space, for the cleanup wizard.
+ */
+async cleanupRecommendations() : Promise<Recommendation[]> {
+    return await TAURI_INVOKE("cleanup_recommendations");
+},
+/**
+ * Commits and edits per day over the last year, for the contribution heatmap. Covers the
+ * whole index when `project_id` is omitted.
+ */
+async activityCalendar(projectId: number | null) : Promise<ActivityDay[]> {
+    return await TAURI_INVOKE("activity_calendar", { projectId });
+},
+/**
+ * Saved UI state (sort, filters, column widths, last search); all keys when `keys` is omitted.
+ */
+async uiStateGet(keys: string[] | null) : Promise<Partial<{ [key in string]: JsonValue }>> {
+    return await TAURI_INVOKE("ui_state_get", { keys });
+},
+/**
+ * Save one UI state value; `null` forgets it.
+ */
+async uiStateSet(key: string, value: JsonValue) : Promise<null> {
+    return await TAURI_INVOKE("ui_state_set", { key, value });
+},
+/**
+ * Set project `project_id`'s own editor and launch command; None or blank clears either.
+ */
+async setProjectLauncher(projectId: number, editor: string | null, command: string | null) : Promise<null> {
+    return await TAURI_INVOKE("set_project_launcher", { projectId, editor, command });
+},
+/**
+ * Run project `project_id`'s launch command in a terminal; returns the command.
+ */
+async runProjectCommand(projectId: number) : Promise<string> {
+    return await TAURI_INVOKE("run_project_command", { projectId });
+},
+/**
+ * The quick actions configured for project `project_id`'s type.
+ */
+async actionsList(projectId: number) : Promise<ActionInfo[]> {
+    return await TAURI_INVOKE("actions_list", { projectId });
+},
+/**
+ * Start quick action `name` in project `project_id`'s directory. Output arrives as
+ * `action-output` events and the finished run as `action-finished` (`action-failed` if
+ * the run could not be recorded).
+ */
+async actionRun(projectId: number, name: string) : Promise<null> {
+    return await TAURI_INVOKE("action_run", { projectId, name });
+},
+/**
+ * Project `project_id`'s recent action runs with their output, newest first.
+ */
+async actionRuns(projectId: number) : Promise<ActionRun[]> {
+    return await TAURI_INVOKE("action_runs", { projectId });
+},
+/**
+ * Fetch or pull projects `project_ids` in the background, `concurrency` at a time. Each
+ * repo's result arrives as a `git-fetch-progress` event and all of them as
+ * `git-fetch-finished` (`git-fetch-failed` if the index could not be updated).
+ */
+async gitFetch(projectIds: number[], mode: FetchMode) : Promise<null> {
+    return await TAURI_INVOKE("git_fetch", { projectIds, mode });
+}
+}
+
+/** user-defined events **/
+
+
+
+/** user-defined constants **/
+
+
+
+/** user-defined types **/
+
+/**
+ * One configured action offered for a project.
+ */
+export type ActionInfo = { name: string; command: string[] }
+export type ActionRun = { id: number; project_id: number; action: string; command: string[]; started_at: number; finished_at: number | null; status: RunStatus; exit_code: number | null; 
+/**
+ * Captured output, cut off after 256 KiB per stream
+ */
+stdout: string; stderr: string }
+export type ActivityDay = { 
+/**
+ * `YYYY-MM-DD`, UTC
+ */
+date: string; commits: number; edits: number }
+export type AssetTotal = { category: string; files: number; bytes: number }
+export type ChildTotals = { size_bytes: number; files_count: number; loc: number }
+export type CleanupAction = 
+/**
+ * Delete build/dependency output; it is regenerated by the next build
+ */
+"delete_artifacts" | 
+/**
+ * Delete a stale copy of a repo that is also checked out elsewhere
+ */
+"delete_duplicate" | 
+/**
+ * Delete a long-untouched project; it can be cloned again from its remote
+ */
+"delete_clone" | 
+/**
+ * Delete ML checkpoints, experiment runs and environments (see `ml`)
+ */
+"delete_ml_artifacts"
+export type FetchMode = 
+/**
+ * `git fetch`: update remote-tracking branches, leave the working tree alone
+ */
+"fetch" | 
+/**
+ * `git pull --ff-only`: also fast-forward the current branch
+ */
+"pull"
+export type FetchOutcome = 
+/**
+ * New commits arrived (on the upstream for a fetch, on the branch for a pull)
+ */
+"updated" | "up_to_date" | 
+/**
+ * A pull could not fast-forward: the branch diverged or local changes are in the way
+ */
+"conflict" | "failed" | 
+/**
+ * Not a git repository or no remote to fetch from
+ */
+"skipped"
+export type FetchResult = { project_id: number; name: string; path: string; outcome: FetchOutcome; 
+/**
+ * Commits that arrived; None when the branch has no upstream to compare
+ */
+new_commits: number | null; 
+/**
+ * git's error for conflicts and failures
+ */
+message: string | null }
+/**
+ * A project found by `quick_scan`; nothing is written to the index.
+ */
+export type FoundProject = { name: string; path: string; project_type: string }
+/**
+ * Text with the character ranges that matched the query.
+ */
+export type Highlighted = { text: string; 
+/**
+ * `(start, end)` offsets in characters (Unicode scalar values), end exclusive
+ */
+spans: ([number, number])[] }
+export type JournalEntry = { id: number; 
+/**
+ * `delete` or `merge`
+ */
+kind: string; summary: string; created_at: number; undone_at: number | null }
+export type JsonValue = null | boolean | number | string | JsonValue[] | Partial<{ [key in string]: JsonValue }>
+/**
+ * How current the index is, for "updated 3 days ago" and stale warnings.
+ */
+export type LastScanInfo = { 
+/**
+ * When the last completed scan finished; `None` for an index never scanned
+ */
+finished_at: number | null; 
+/**
+ * Projects that scan found
+ */
+projects: number | null; 
+/**
+ * Why the latest scan failed, when it did (`finished_at` is then from an earlier one)
+ */
+last_error: string | null; 
+/**
+ * `finished_at` is older than `freshness.stale_after_days`
+ */
+stale: boolean }
+export type LaunchAction = 
+/**
+ * Open in `launcher.editor`
+ */
+"editor" | 
+/**
+ * Show in the system file manager
+ */
+"reveal" | 
+/**
+ * Bring up the main window filtered to the project
+ */
+"show"
+/**
+ * What `merge_projects` carried over from the removed record.
+ */
+export type MergeReport = { 
+/**
+ * User fields (tags, notes, ...) the kept project did not already have
+ */
+fields: number; 
+/**
+ * Hook-provided custom fields the kept project did not already have
+ */
+custom_fields: number; 
+/**
+ * Open-history entries
+ */
+opens: number }
+export type MlArtifact = { kind: MlArtifactKind; 
+/**
+ * Relative to the project
+ */
+path: string; bytes: number }
+export type MlArtifactKind = 
+/**
+ * A weights or checkpoint file of at least `ml.min_file_mb`
+ */
+"checkpoint" | 
+/**
+ * A `datasets/` directory; often not re-downloadable
+ */
+"dataset" | 
+/**
+ * Experiment-tracker output such as `wandb/` or `mlruns/`
+ */
+"runs" | 
+/**
+ * A virtualenv or conda environment of at least `ml.min_env_mb`
+ */
+"environment"
+/**
+ * What the setup flow lets the user pick; everything else keeps its current value.
+ */
+export type OnboardingConfig = { roots: string[]; editor: string | null; 
+/**
+ * Empty disables the quick-open palette
+ */
+hotkey: string | null }
+export type OnboardingStatus = { 
+/**
+ * Setup has been finished (or a config was written some other way)
+ */
+completed: boolean; roots: string[]; indexed_projects: number; editor: string }
+/**
+ * Summary over every project matching a filter, for the chips above the results.
+ */
+export type ProjectAggregates = { count: number; total_size_bytes: number; total_loc: number; 
+/**
+ * Most common type first
+ */
+by_type: TypeCount[] }
+export type ProjectDetail = { project: ProjectRecord; 
+/**
+ * File name of the README shown, relative to the project
+ */
+readme_name: string | null; readme: string | null; 
+/**
+ * True when the README was longer than the detail view shows
+ */
+readme_truncated: boolean; 
+/**
+ * `(language, code lines)`, largest first; empty until LOC has been computed
+ */
+loc_by_language: ([string, number])[]; 
+/**
+ * Pinned or required toolchain versions, by language
+ */
+toolchains: ToolchainPin[]; 
+/**
+ * Media and binary assets by category, largest first
+ */
+assets: AssetTotal[]; 
+/**
+ * Checkpoints, datasets, experiment runs and environments, largest first
+ */
+ml_artifacts: MlArtifact[]; fields: Partial<{ [key in string]: JsonValue }>; 
+/**
+ * Own editor and launch command (also among `fields`)
+ */
+launcher: ProjectLauncher; 
+/**
+ * Other records with the same name or git remote, candidates for merging
+ */
+duplicates: ProjectRecord[]; 
+/**
+ * Companion projects: shared dependencies, same owner, similar languages, nearby
+ */
+related: RelatedProject[]; 
+/**
+ * Enclosing workspaces, outermost first
+ */
+ancestors: ProjectRecord[]; 
+/**
+ * Workspace members, by name
+ */
+children: ProjectRecord[]; 
+/**
+ * Sums over `children`
+ */
+children_totals: ChildTotals }
+export type ProjectLauncher = { 
+/**
+ * Editor command for this project; None uses `launcher.editor`
+ */
+editor: string | null; 
+/**
+ * Shell command run in a terminal from the project directory
+ */
+command: string | null }
+export type ProjectRecord = { id: number; name: string; path: string; project_type: string | null; is_git_repo: boolean; size_bytes: number | null; 
+/**
+ * `size_bytes` was extrapolated from a sample (`size_mode: estimate`), not summed
+ */
+size_estimated: boolean; files_count: number | null; last_edited_at: number | null; loc: number | null; 
+/**
+ * `loc` was extrapolated after reading only part of the project (`loc_limits`)
+ */
+loc_truncated: boolean; 
+/**
+ * True when the secrets heuristic flagged at least one file
+ */
+has_secrets: boolean; 
+/**
+ * Number of flagged files; None when the secrets scan has not run for this project
+ */
+secrets_count: number | null; 
+/**
+ * Vulnerabilities found by the last `audit deps` run; None when never audited
+ */
+vuln_count: number | null; 
+/**
+ * Highest severity from the last dependency audit (see `vulns::Severity`)
+ */
+vuln_severity: string | null; has_tests: boolean | null; test_files_count: number | null; 
+/**
+ * Jupyter notebooks; only counted for data-science, Python and Julia projects
+ */
+notebooks_count: number | null; has_ci: boolean | null; 
+/**
+ * Comma-separated CI systems, e.g. "github-actions,gitlab-ci"
+ */
+ci_providers: string | null; 
+/**
+ * Dev-environment configs at the root (see `devenv::DevEnvInfo`); None until analyzed
+ */
+has_devcontainer: boolean | null; has_dockerfile: boolean | null; has_compose: boolean | null; has_nix_shell: boolean | null; has_tool_versions: boolean | null; 
+/**
+ * Shape of the tree (see `shape::ShapeMetrics`); None until scanned
+ */
+source_files_count: number | null; 
+/**
+ * `size_bytes / files_count`
+ */
+avg_file_bytes: number | null; max_depth: number | null; top_level_modules: number | null; 
+/**
+ * Bytes in images, video, audio, archives and model weights (see `assets`)
+ */
+asset_bytes: number | null; 
+/**
+ * Bytes in checkpoints, datasets, experiment runs and environments (see `ml`)
+ */
+ml_bytes: number | null; 
+/**
+ * Git enrichment; populated only when scanning with the `git` feature
+ */
+last_commit_at: number | null; branch: string | null; remote_url: string | null; git_dirty: boolean | null; 
+/**
+ * Workspace/monorepo root this project is a member of
+ */
+parent_id: number | null; 
+/**
+ * Workspace members indexed under this project
+ */
+child_count: number; 
+/**
+ * Bytes in build/dependency output directories (`node_modules`, `target`, ...); None
+ * when there are none or sizes are off
+ */
+artifact_bytes: number | null; 
+/**
+ * User the project was attributed to by a system-wide scan
+ */
+owner: string | null; 
+/**
+ * Host name of the machine that last scanned the project (see `location`)
+ */
+machine: string | null; 
+/**
+ * Filesystem UUID, mount point or drive of the volume it is on
+ */
+volume_id: string | null; 
+/**
+ * Why the last scan skipped the expensive analyzers (`analyzer_limits`); LOC and
+ * dependencies are then from an earlier scan, if any
+ */
+analysis_skipped: string | null }
+/**
+ * One page of `ProjectsRequest` results.
+ */
+export type ProjectsPage = { items: ProjectRecord[]; page: number; page_size: number; total_count: number; 
+/**
+ * Present when requested with `aggregates: true`
+ */
+aggregates: ProjectAggregates | null }
+export type QueuedRoot = { root: string; priority: ScanPriority }
+export type QuickScan = { projects: FoundProject[]; 
+/**
+ * Stopped at the project limit or the directory budget; a full scan will find more
+ */
+truncated: boolean }
+export type Recommendation = { action: CleanupAction; project: ProjectRecord; 
+/**
+ * What to delete
+ */
+paths: string[]; reclaim_bytes: number; 
+/**
+ * Days since the project was last edited or opened
+ */
+idle_days: number; 
+/**
+ * Clean working tree and every commit on the upstream
+ */
+fully_pushed: boolean; 
+/**
+ * The more recently used checkout, for `delete_duplicate`
+ */
+duplicate_of: string | null; 
+/**
+ * Ranking score: bytes freed (GB) weighted by staleness
+ */
+score: number; 
+/**
+ * One line for people, e.g. "delete node_modules in web: 2.1 GB, last touched 14
+ * months ago, fully pushed"
+ */
+summary: string }
+export type RelatedProject = { project: ProjectRecord; 
+/**
+ * 0..1
+ */
+score: number; 
+/**
+ * Why it is related, e.g. "same owner github.com/acme"
+ */
+reasons: string[] }
+export type RootSuggestion = { path: string; 
+/**
+ * Projects found within `SAMPLE_DEPTH` levels
+ */
+estimated_projects: number; 
+/**
+ * The sample hit its budget, so there are probably more
+ */
+truncated: boolean; 
+/**
+ * Already one of (or inside one of) the configured roots
+ */
+configured: boolean }
+export type RunStatus = "running" | 
+/**
+ * Exited with status 0
+ */
+"succeeded" | "failed" | 
+/**
+ * Killed after `timeout_secs`
+ */
+"timed_out"
+/**
+ * Where a root goes in the scan queue: higher priorities are scanned first, equal
+ * priorities in the order they were queued.
+ */
+export type ScanPriority = "low" | "normal" | "high"
+export type SearchHit = { project: ProjectRecord; name: Highlighted; path: Highlighted; 
+/**
+ * Excerpt around the match when the README matched
+ */
+readme: Highlighted | null }
+/**
+ * Just enough to list a type-ahead match.
+ */
+export type Suggestion = { id: number; name: string; path: string }
+export type ToolchainPin = { 
+/**
+ * One of [`LANGUAGES`]
+ */
+language: string; 
+/**
+ * Where it was read, e.g. `.nvmrc` or `package.json engines.node`
+ */
+source: string; 
+/**
+ * As written, e.g. `>=14 <19`, `3.8.10` or `stable`
+ */
+constraint: string; 
+/**
+ * Lowest `major.minor` the constraint allows; None for channels such as `stable` or
+ * `lts/*`
+ */
+version: string | null }
+export type TypeCount = { project_type: string | null; count: number }
+export type UpdateStatus = { current: string; latest: string; update_available: boolean; url: string }
+
+/** tauri-specta globals **/
+
+import {
+	invoke as TAURI_INVOKE,
+	Channel as TAURI_CHANNEL,
+} from "@tauri-apps/api/core";
+import * as TAURI_API_EVENT from "@tauri-apps/api/event";
+import { type WebviewWindow as __WebviewWindow__ } from "@tauri-apps/api/webviewWindow";
+
+type __EventObj__<T> = {
+	listen: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.listen<T>>;
+	once: (
+		cb: TAURI_API_EVENT.EventCallback<T>,
+	) => ReturnType<typeof TAURI_API_EVENT.once<T>>;
+	emit: null extends T
+		? (payload?: T) => ReturnType<typeof TAURI_API_EVENT.emit>
+		: (payload: T) => ReturnType<typeof TAURI_API_EVENT.emit>;
+};
+
+export type Result<T, E> =
+	| { status: "ok"; data: T }
+	| { status: "error"; error: E };
+
+function __makeEvents__<T extends Record<string, any>>(
+	mappings: Record<keyof T, string>,
+) {
+	return new Proxy(
+		{} as unknown as {
+			[K in keyof T]: __EventObj__<T[K]> & {
+				(handle: __WebviewWindow__): __EventObj__<T[K]>;
+			};
+		},
+		{
+			get: (_, event) => {
+				const name = mappings[event as keyof T];
+
+				return new Proxy((() => {}) as any, {
+					apply: (_, __, [window]: [__WebviewWindow__]) => ({
+						listen: (arg: any) => window.listen(name, arg),
+						once: (arg: any) => window.once(name, arg),
+						emit: (arg: any) => window.emit(name, arg),
+					}),
+					get: (_, command: keyof __EventObj__<any>) => {
+						switch (command) {
+							case "listen":
+								return (arg: any) => TAURI_API_EVENT.listen(name, arg);
+							case "once":
+								return (arg: any) => TAURI_API_EVENT.once(name, arg);
+							case "emit":
+								return (arg: any) => TAURI_API_EVENT.emit(name, arg);
+						}
+					},
+				});
+			},
+		},
+	);
+}
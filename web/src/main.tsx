@@ -2,11 +2,18 @@ import React from 'react'
 import { createRoot } from 'react-dom/client'
 import './index.css'
 import App from './ui/App'
+import Palette from './ui/Palette'
+import Detail from './ui/Detail'
+
+// The quick-open palette and project detail windows load the same bundle with ?view=…
+const params = new URLSearchParams(window.location.search)
+const view = params.get('view')
 
 const root = document.getElementById('root')!
 createRoot(root).render(
   <React.StrictMode>
-    <App />
+    {view === 'palette' ? <Palette />
+      : view === 'detail' ? <Detail projectId={Number(params.get('id'))} />
+      : <App />}
   </React.StrictMode>
 )
-
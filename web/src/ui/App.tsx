@@ -1,83 +1,194 @@
-import { useEffect, useMemo, useState } from 'react'
-import { invoke } from '@tauri-apps/api/core'
+import { useEffect, useMemo, useRef, useState } from 'react'
+import { listen } from '@tauri-apps/api/event'
+import { commands, type FetchMode, type FetchResult, type LastScanInfo, type OnboardingStatus, type ProjectAggregates, type ProjectRecord, type SearchHit, type UpdateStatus } from '../bindings'
 import { FixedSizeList as List } from 'react-window'
+import { formatBytes, formatDate } from './format'
+import Highlight from './Highlight'
+import Onboarding from './Onboarding'
 
-type Project = {
-  id: number
-  name: string
-  path: string
-  project_type?: string
-  is_git_repo: boolean
-  size_bytes?: number
-  files_count?: number
-  last_edited_at?: number
-  loc?: number
-}
+type Project = ProjectRecord
+type Aggregates = ProjectAggregates
 
-type Page = {
-  items: Project[]
-  page: number
-  page_size: number
-  total_count: number
-}
+type GitFilter = 'any' | 'no_remote' | 'dirty'
+
+type ScanProgress =
+  | { phase: 'discovered', projects: number }
+  | { phase: 'enriched', done: number, total: number }
 
 const DEFAULT_PAGE_SIZE = 500
+const DEFAULT_NAME_WIDTH = 224 // px, 14rem
 
-function formatBytes(bytes: number): string {
-  if (bytes === 0) return '0 B'
-  
-  const k = 1024
-  const sizes = ['B', 'KB', 'MB', 'GB', 'TB']
-  const i = Math.floor(Math.log(bytes) / Math.log(k))
-  
-  return parseFloat((bytes / Math.pow(k, i)).toFixed(1)) + ' ' + sizes[i]
-}
+type SortKey = 'recent'|'size'|'name'|'type'|'loc'|'last_commit'|'source_files'|'avg_file_size'|'depth'|'modules'|'assets'
 
-function formatDate(timestamp: number | null): string {
-  if (!timestamp) return '-'
-  
-  const date = new Date(timestamp * 1000) // Convert from Unix timestamp
-  const now = new Date()
-  const diffMs = now.getTime() - date.getTime()
-  const diffDays = Math.floor(diffMs / (1000 * 60 * 60 * 24))
-  
-  if (diffDays === 0) return 'Today'
-  if (diffDays === 1) return 'Yesterday'
-  if (diffDays < 7) return `${diffDays}d ago`
-  if (diffDays < 30) return `${Math.floor(diffDays / 7)}w ago`
-  if (diffDays < 365) return `${Math.floor(diffDays / 30)}mo ago`
-  return `${Math.floor(diffDays / 365)}y ago`
+// Persisted under the `browser` ui_state key so the view survives restarts
+type BrowserState = {
+  q: string
+  advanced: boolean
+  gitFilter: GitFilter
+  sort: SortKey
+  sortDirection: 'asc'|'desc'
+  pageSize: number
+  nameWidth: number
 }
 
 export default function App() {
   const [q, setQ] = useState('')
-  const [sort, setSort] = useState<'recent'|'size'|'name'|'type'|'loc'>('recent')
+  const [advanced, setAdvanced] = useState(false)
+  const [gitFilter, setGitFilter] = useState<GitFilter>('any')
+  const [sort, setSort] = useState<SortKey>('recent')
   const [sortDirection, setSortDirection] = useState<'asc'|'desc'>('desc')
   const [page, setPage] = useState(0)
   const [pageSize, setPageSize] = useState(DEFAULT_PAGE_SIZE)
   const [rows, setRows] = useState<Project[]>([])
+  // Why each row matched a plain-text search, keyed by project id
+  const [hits, setHits] = useState<Map<number, SearchHit>>(new Map())
   const [totalCount, setTotalCount] = useState(0)
+  const [aggregates, setAggregates] = useState<Aggregates | null>(null)
   const [loading, setLoading] = useState(false)
   const [scanning, setScanning] = useState(false)
   const [message, setMessage] = useState<string | null>(null)
   const [selectedProject, setSelectedProject] = useState<Project | null>(null)
   const [showOpenMenu, setShowOpenMenu] = useState(false)
+  const [logLines, setLogLines] = useState<string[] | null>(null)
+  const [showAbout, setShowAbout] = useState(false)
+  const [update, setUpdate] = useState<UpdateStatus | null>(null)
+  const [favorite, setFavorite] = useState(false)
+  const [suggestedRoot, setSuggestedRoot] = useState<string | null>(null)
+  const [nameWidth, setNameWidth] = useState(DEFAULT_NAME_WIDTH)
+  // The first fetch waits until the saved view has been applied
+  const [restored, setRestored] = useState(false)
+  const [onboarding, setOnboarding] = useState<OnboardingStatus | null>(null)
+  // Repos done out of those sent to `git_fetch`, while a batch runs
+  const [fetching, setFetching] = useState<{ done: number, total: number } | null>(null)
+  const [freshness, setFreshness] = useState<LastScanInfo | null>(null)
+  const searchRef = useRef<HTMLInputElement>(null)
+  const columns = `${nameWidth}px 5rem 7rem 5rem 7rem 1fr`
+
+  useEffect(() => {
+    commands.uiStateGet(['browser'])
+      .then(state => {
+        const saved = state.browser as unknown as Partial<BrowserState> | undefined
+        if (!saved) return
+        if (saved.q !== undefined) setQ(saved.q)
+        if (saved.advanced !== undefined) setAdvanced(saved.advanced)
+        if (saved.gitFilter) setGitFilter(saved.gitFilter)
+        if (saved.sort) setSort(saved.sort)
+        if (saved.sortDirection) setSortDirection(saved.sortDirection)
+        if (saved.pageSize) setPageSize(saved.pageSize)
+        if (saved.nameWidth) setNameWidth(saved.nameWidth)
+      })
+      .catch(e => setMessage(`Could not restore view: ${String(e)}`))
+      .finally(() => setRestored(true))
+  }, [])
+
+  // First run: guided setup instead of an empty list
+  useEffect(() => {
+    commands.onboardingStatus()
+      .then(status => { if (!status.completed && status.indexed_projects === 0) setOnboarding(status) })
+      .catch(() => {})
+  }, [])
+
+  useEffect(() => { refreshFreshness() }, [])
 
   useEffect(() => { 
-    fetchPage(0) 
-  }, [sort, sortDirection])
-  
-  useEffect(() => { 
-    fetchPage(0) 
-  }, []) // Load projects on initial mount
+    if (restored) fetchPage(0) 
+  }, [restored, sort, sortDirection, gitFilter])
+
+  // Debounced so typing in the search box does not write on every keystroke
+  useEffect(() => {
+    if (!restored) return
+    const state: BrowserState = { q, advanced, gitFilter, sort, sortDirection, pageSize, nameWidth }
+    const timer = setTimeout(() => {
+      commands.uiStateSet('browser', state).catch(() => {})
+    }, 500)
+    return () => clearTimeout(timer)
+  }, [restored, q, advanced, gitFilter, sort, sortDirection, pageSize, nameWidth])
+
+  // Tray menu: "Search Projects…" focuses the search box, "Rescan Now" refreshes the list.
+  // Launchers with the `show` action narrow the list to one project; search links set the query.
+  useEffect(() => {
+    const unlisten = [
+      listen('focus-search', () => searchRef.current?.focus()),
+      listen<number>('scan-finished', e => {
+        setScanning(false)
+        setMessage(`Scanned ${e.payload} project(s)`)
+        fetchPage(0)
+        refreshFreshness()
+      }),
+      // Background scans store every project before measuring them; refresh as rows fill in
+      listen<ScanProgress>('scan-progress', e => {
+        const p = e.payload
+        setMessage(p.phase === 'discovered'
+          ? `Found ${p.projects} project(s), measuring…`
+          : `Measured ${p.done} of ${p.total} project(s)…`)
+        fetchPage(0)
+      }),
+      listen<string>('show-project', e => {
+        setAdvanced(false)
+        setQ(e.payload)
+        fetchPage(0, e.payload, false)
+      }),
+      // projectbrowser://search?q=…&advanced=1
+      listen<{ q: string, advanced: boolean }>('link-search', e => {
+        setAdvanced(e.payload.advanced)
+        setQ(e.payload.q)
+        fetchPage(0, e.payload.q, e.payload.advanced)
+      }),
+      // Folders dropped onto the window are indexed by the backend one by one
+      listen<{ project: Project, suggested_root: string | null }>('project-indexed', e => {
+        setMessage(`Indexed ${e.payload.project.name}`)
+        setSuggestedRoot(e.payload.suggested_root)
+        fetchPage(0)
+      }),
+      listen<{ path: string, reason: string }>('drop-ignored', e => {
+        setMessage(`Skipped ${e.payload.path}: ${e.payload.reason}`)
+      }),
+      listen<FetchResult>('git-fetch-progress', e => {
+        setFetching(f => f && { ...f, done: f.done + 1 })
+        setMessage(`${e.payload.name}: ${e.payload.outcome.replace(/_/g, ' ')}`)
+      }),
+      listen<FetchResult[]>('git-fetch-finished', e => {
+        const count = (o: string) => e.payload.filter(r => r.outcome === o).length
+        const conflicts = e.payload.filter(r => r.outcome === 'conflict').map(r => r.name)
+        setFetching(null)
+        setMessage(`${count('updated')} updated, ${count('up_to_date')} up to date, ${count('failed')} failed`
+          + (conflicts.length ? `; conflicts in ${conflicts.join(', ')}` : ''))
+        fetchPage(0)
+      }),
+      listen<string>('git-fetch-failed', e => {
+        setFetching(null)
+        setMessage(`Fetch failed: ${e.payload}`)
+      }),
+    ]
+    return () => { unlisten.forEach(p => p.then(f => f())) }
+  }, [])
+
+  async function addSuggestedRoot() {
+    if (!suggestedRoot) return
+    try {
+      const added = await commands.rootsAdd(suggestedRoot)
+      setMessage(added ? `Added root ${suggestedRoot}` : `${suggestedRoot} is already scanned`)
+    } catch (e) {
+      setMessage(`Could not add root: ${String(e)}`)
+    }
+    setSuggestedRoot(null)
+  }
 
-  async function fetchPage(p: number) {
+  async function fetchPage(p: number, query: string = q, adv: boolean = advanced) {
     setLoading(true)
     try {
-      const res = await invoke<Page>('projects_query', { q, sort, sortDirection, page: p, pageSize })
+      const res = await commands.projectsQuery(
+        query, sort, sortDirection, p, pageSize, adv, null,
+        gitFilter === 'no_remote' ? false : null,
+        gitFilter === 'dirty' ? true : null,
+        true,
+      )
       setRows(res.items)
+      const found = query.trim() && !adv ? await commands.projectsSearch(query, pageSize) : []
+      setHits(new Map(found.map(h => [h.project.id, h])))
       setPage(p)
       setTotalCount(res.total_count)
+      setAggregates(res.aggregates ?? null)
       setMessage(`${res.items.length} of ${res.total_count} projects loaded`)
     } catch (e: any) {
       console.error('projects_query failed', e)
@@ -96,29 +207,92 @@ export default function App() {
     }
   }
 
+  function refreshFreshness() {
+    commands.lastScanInfo().then(setFreshness).catch(() => {})
+  }
+
   async function triggerScan() {
     setScanning(true)
     setMessage('Scanning projects...')
     try {
-      const count = await invoke<number>('scan_start', { roots: undefined, dry_run: false })
-      setMessage(`Scanned ${count} project(s)`) 
-      await fetchPage(0)
+      // Runs in the background; scan-progress and scan-finished report back
+      await commands.scanEnqueue(null, null)
     } catch (e: any) {
-      console.error('scan_start failed', e)
+      console.error('scan_enqueue failed', e)
       setMessage(`Scan failed: ${String(e)}`)
-    } finally { setScanning(false) }
+      setScanning(false)
+    }
+  }
+
+  // Bulk fetch/pull of the git repos in the current list
+  async function fetchAll(mode: FetchMode) {
+    const ids = rows.filter(r => r.is_git_repo && r.remote_url).map(r => r.id)
+    if (ids.length === 0) { setMessage('No repos with a remote in this list'); return }
+    if (mode === 'pull' && !confirm(`Pull (fast-forward only) ${ids.length} repo(s)?`)) return
+    setFetching({ done: 0, total: ids.length })
+    try {
+      await commands.gitFetch(ids, mode)
+    } catch (e) {
+      setFetching(null)
+      setMessage(`Fetch failed: ${String(e)}`)
+    }
+  }
+
+  async function openAbout() {
+    setShowAbout(true)
+    // Passive check; offline or rate-limited feeds just leave the notice out
+    try {
+      setUpdate(await commands.updateCheck())
+    } catch (e) {
+      console.warn('update_check failed', e)
+    }
+  }
+
+  // Drag handle on the Name header
+  function startResize(e: React.MouseEvent) {
+    e.preventDefault()
+    const startX = e.clientX
+    const startWidth = nameWidth
+    const onMove = (ev: MouseEvent) => {
+      setNameWidth(Math.min(640, Math.max(120, startWidth + ev.clientX - startX)))
+    }
+    const onUp = () => {
+      window.removeEventListener('mousemove', onMove)
+      window.removeEventListener('mouseup', onUp)
+    }
+    window.addEventListener('mousemove', onMove)
+    window.addEventListener('mouseup', onUp)
+  }
+
+  async function toggleLogs() {
+    if (logLines) { setLogLines(null); return }
+    try {
+      setLogLines(await commands.showLogs(300, null))
+    } catch (e: any) {
+      setMessage(`Could not read logs: ${String(e)}`)
+    }
+  }
+
+  async function copyRedactedLogs() {
+    try {
+      const lines = await commands.showLogs(300, true)
+      await navigator.clipboard.writeText(lines.join('\n'))
+      setMessage('Redacted logs copied to clipboard')
+    } catch (e: any) {
+      setMessage(`Could not copy logs: ${String(e)}`)
+    }
   }
 
   const Row = ({ index, style }: { index: number, style: any }) => {
     const r = rows[index]
     return (
-      <div style={style} className="grid grid-cols-[14rem_5rem_7rem_5rem_7rem_1fr] gap-2 px-2 py-1 border-b border-zinc-800">
-        <div className="truncate" title={r.name}>{r.name}</div>
+      <div style={{ ...style, gridTemplateColumns: columns }} className="grid gap-2 px-2 py-1 border-b border-zinc-800">
+        <div className="truncate" title={r.name}><Highlight h={hits.get(r.id)?.name} fallback={r.name} /></div>
         <div className="text-zinc-400">{r.project_type ?? '-'}</div>
-        <div className="text-zinc-400 text-right">{formatBytes(r.size_bytes ?? 0)}</div>
-        <div className="text-zinc-400 text-right">{r.loc ?? 0}</div>
+        <div className="text-zinc-400 text-right">{r.size_estimated ? '~' : ''}{formatBytes(r.size_bytes ?? 0)}</div>
+        <div className="text-zinc-400 text-right" title={r.loc_truncated ? 'Truncated estimate (loc_limits)' : undefined}>{r.loc_truncated ? '~' : ''}{r.loc ?? 0}</div>
         <div className="text-zinc-400 text-right">{formatDate(r.last_edited_at)}</div>
-        <div className="truncate text-zinc-300" title={r.path}>{r.path}</div>
+        <div className="truncate text-zinc-300" title={r.path}><Highlight h={hits.get(r.id)?.path} fallback={r.path} /></div>
       </div>
     )
   }
@@ -127,18 +301,34 @@ export default function App() {
     <div className="h-full flex flex-col">
       <header className="p-3 flex items-center gap-2 border-b border-zinc-800">
         <input
+          ref={searchRef}
           value={q}
           onChange={e => setQ(e.target.value)}
           onKeyDown={e => { if (e.key === 'Enter') fetchPage(0) }}
-          placeholder="Search name or path..."
-          className="w-80 px-3 py-2 rounded bg-zinc-800 outline-none"
+          placeholder={advanced ? 'type:rust AND loc>5000 AND edited<90d' : 'Search name or path...'}
+          className={`w-80 px-3 py-2 rounded bg-zinc-800 outline-none ${advanced ? 'font-mono text-sm' : ''}`}
         />
+        <label className="flex items-center gap-1 text-sm text-zinc-400" title="Filter expression: key:value, loc>5000, edited<90d, AND/OR/NOT, (…)">
+          <input type="checkbox" checked={advanced} onChange={e => setAdvanced(e.target.checked)} />
+          Advanced
+        </label>
         <select value={sort} onChange={e => setSort(e.target.value as any)} className="px-2 py-2 rounded bg-zinc-800">
           <option value="recent">Recent</option>
           <option value="size">Size</option>
           <option value="name">Name</option>
           <option value="type">Type</option>
           <option value="loc">LOC</option>
+          <option value="last_commit">Last commit</option>
+          <option value="source_files">Source files</option>
+          <option value="avg_file_size">Avg file size</option>
+          <option value="depth">Depth</option>
+          <option value="modules">Modules</option>
+          <option value="assets">Assets</option>
+        </select>
+        <select value={gitFilter} onChange={e => setGitFilter(e.target.value as GitFilter)} className="px-2 py-2 rounded bg-zinc-800" title="Git filter">
+          <option value="any">All repos</option>
+          <option value="no_remote">Git, no remote</option>
+          <option value="dirty">Uncommitted changes</option>
         </select>
         <button onClick={() => fetchPage(0)} className="px-3 py-2 rounded bg-zinc-700">Search</button>
         <button 
@@ -151,6 +341,14 @@ export default function App() {
           )}
           {scanning ? 'Scanning...' : 'Scan'}
         </button>
+        <button onClick={() => fetchAll('fetch')} disabled={fetching !== null} className="px-3 py-2 rounded bg-zinc-700 disabled:opacity-50"
+          title="git fetch every repo in the list">
+          {fetching ? `Fetching ${fetching.done}/${fetching.total}…` : 'Fetch all'}
+        </button>
+        <button onClick={() => fetchAll('pull')} disabled={fetching !== null} className="px-3 py-2 rounded bg-zinc-700 disabled:opacity-50"
+          title="git pull --ff-only every repo in the list">
+          Pull all
+        </button>
         {loading && <span className="ml-2 text-sm text-zinc-400">Loading…</span>}
         
         {/* Page Size Selector */}
@@ -195,13 +393,31 @@ export default function App() {
           </div>
         )}
       </header>
-      <div className="grid grid-cols-[14rem_5rem_7rem_5rem_7rem_1fr] gap-2 px-2 py-2 text-xs text-zinc-400 border-b border-zinc-800">
-        <button 
-          onClick={() => handleHeaderClick('name')} 
-          className="text-left hover:text-zinc-200 flex items-center gap-1"
-        >
-          Name {sort === 'name' && (sortDirection === 'asc' ? '↑' : '↓')}
-        </button>
+      {aggregates && aggregates.count > 0 && (
+        <div className="px-3 py-2 flex flex-wrap items-center gap-2 text-xs border-b border-zinc-800">
+          <span className="px-2 py-1 rounded bg-zinc-800">{formatBytes(aggregates.total_size_bytes)}</span>
+          <span className="px-2 py-1 rounded bg-zinc-800">{aggregates.total_loc.toLocaleString()} LOC</span>
+          {aggregates.by_type.map(t => (
+            <span key={t.project_type ?? '-'} className="px-2 py-1 rounded bg-zinc-800 text-zinc-400">
+              {t.project_type ?? 'unknown'} · {t.count}
+            </span>
+          ))}
+        </div>
+      )}
+      <div style={{ gridTemplateColumns: columns }} className="grid gap-2 px-2 py-2 text-xs text-zinc-400 border-b border-zinc-800">
+        <div className="relative flex">
+          <button 
+            onClick={() => handleHeaderClick('name')} 
+            className="text-left hover:text-zinc-200 flex items-center gap-1"
+          >
+            Name {sort === 'name' && (sortDirection === 'asc' ? '↑' : '↓')}
+          </button>
+          <div
+            onMouseDown={startResize}
+            className="absolute -right-2 top-0 bottom-0 w-2 cursor-col-resize hover:bg-zinc-600"
+            title="Drag to resize"
+          />
+        </div>
         <button 
           onClick={() => handleHeaderClick('type')} 
           className="text-left hover:text-zinc-200 flex items-center gap-1"
@@ -231,21 +447,30 @@ export default function App() {
       <div className="flex-1 min-h-0">
         <div className="h-full overflow-auto">
           {rows.map((r, index) => (
-            <div key={r.id} className="grid grid-cols-[14rem_5rem_7rem_5rem_7rem_1fr] gap-2 px-2 py-1 border-b border-zinc-800 hover:bg-zinc-800/50">
-              <div className="truncate" title={r.name}>{r.name}</div>
+            <div key={r.id} style={{ gridTemplateColumns: columns }} className="grid gap-2 px-2 py-1 border-b border-zinc-800 hover:bg-zinc-800/50">
+              <div className="truncate" title={r.name}><Highlight h={hits.get(r.id)?.name} fallback={r.name} /></div>
               <div className="text-zinc-400">{r.project_type ?? '-'}</div>
-              <div className="text-zinc-400 text-right">{formatBytes(r.size_bytes ?? 0)}</div>
-              <div className="text-zinc-400 text-right">{r.loc ?? 0}</div>
+              <div className="text-zinc-400 text-right">{r.size_estimated ? '~' : ''}{formatBytes(r.size_bytes ?? 0)}</div>
+              <div className="text-zinc-400 text-right" title={r.loc_truncated ? 'Truncated estimate (loc_limits)' : undefined}>{r.loc_truncated ? '~' : ''}{r.loc ?? 0}</div>
               <div className="text-zinc-400 text-right">{formatDate(r.last_edited_at)}</div>
               <button 
                 onClick={() => {
                   setSelectedProject(r)
                   setShowOpenMenu(true)
+                  setFavorite(false)
+                  commands.projectFieldsGet(r.id)
+                    .then(f => setFavorite(f.favorite === true))
+                    .catch(e => console.warn('project_fields_get failed', e))
                 }}
                 className="truncate text-zinc-300 text-left hover:text-white hover:underline" 
                 title={r.path}
               >
-                {r.path}
+                <Highlight h={hits.get(r.id)?.path} fallback={r.path} />
+                {hits.get(r.id)?.readme && (
+                  <span className="ml-2 text-xs text-zinc-500">
+                    README: <Highlight h={hits.get(r.id)?.readme} fallback="" />
+                  </span>
+                )}
               </button>
             </div>
           ))}
@@ -253,14 +478,97 @@ export default function App() {
       </div>
       <footer className="p-2 text-xs text-zinc-500 border-t border-zinc-800 flex items-center gap-3">
         <span>{rows.length} items</span>
+        {freshness && (freshness.finished_at !== null || freshness.last_error) && (
+          <span
+            className={freshness.stale || freshness.last_error ? 'text-amber-400' : undefined}
+            title={freshness.last_error ? `Last scan failed: ${freshness.last_error}` : undefined}
+          >
+            {freshness.finished_at !== null ? `Index updated ${formatDate(freshness.finished_at)}` : 'Never scanned'}
+            {freshness.last_error && ' (last scan failed)'}
+          </span>
+        )}
         {message && <span className="text-zinc-400">— {message}</span>}
+        {suggestedRoot && (
+          <button onClick={addSuggestedRoot} className="hover:text-zinc-300" title="Include this folder in future scans">
+            Add {suggestedRoot} as root
+          </button>
+        )}
+        <button onClick={toggleLogs} className="ml-auto hover:text-zinc-300">{logLines ? 'Hide logs' : 'Logs'}</button>
+        <button onClick={openAbout} className="hover:text-zinc-300">About</button>
       </footer>
+      {logLines && (
+        <div className="relative">
+          <button
+            onClick={copyRedactedLogs}
+            className="absolute top-1 right-3 text-[11px] text-zinc-500 hover:text-zinc-300"
+            title="Copy with paths, remotes and emails hashed, for a bug report"
+          >
+            Copy redacted
+          </button>
+          <pre className="h-48 overflow-auto p-2 text-[11px] leading-4 bg-zinc-950 text-zinc-400 border-t border-zinc-800">
+            {logLines.length ? logLines.join('\n') : 'No log output yet'}
+          </pre>
+        </div>
+      )}
 
+      {showAbout && (
+        <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50" onClick={() => setShowAbout(false)}>
+          <div className="bg-zinc-800 rounded-lg p-6 min-w-80" onClick={e => e.stopPropagation()}>
+            <h3 className="text-lg font-semibold mb-2">Project Browser</h3>
+            <p className="text-sm text-zinc-400">Version {update?.current ?? '…'}</p>
+            {update?.update_available && (
+              <p className="mt-3 text-sm text-blue-300">
+                Version {update.latest} is available — <a href={update.url} target="_blank" rel="noreferrer" className="underline">release notes</a>
+              </p>
+            )}
+            <div className="flex justify-end mt-6">
+              <button
+                onClick={async () => {
+                  try {
+                    setMessage(`Diagnostics written to ${await commands.exportDiagnostics()}`)
+                  } catch (e) {
+                    setMessage(`Could not export diagnostics: ${String(e)}`)
+                  }
+                }}
+                className="px-4 py-2 text-zinc-400 hover:text-white mr-auto"
+                title="A redacted zip of build, database and scan details and recent logs, for a bug report"
+              >
+                Export diagnostics
+              </button>
+              <button onClick={() => setShowAbout(false)} className="px-4 py-2 text-zinc-400 hover:text-white">Close</button>
+            </div>
+          </div>
+        </div>
+      )}
+
+      {onboarding && (
+        <Onboarding
+          status={onboarding}
+          onDone={msg => { setOnboarding(null); setMessage(msg) }}
+        />
+      )}
       {/* Open In... Modal */}
       {showOpenMenu && selectedProject && (
         <div className="fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50" onClick={() => setShowOpenMenu(false)}>
           <div className="bg-zinc-800 rounded-lg p-6 min-w-96 max-w-2xl" onClick={e => e.stopPropagation()}>
-            <h3 className="text-lg font-semibold mb-4">Open Project</h3>
+            <div className="flex items-center justify-between mb-4">
+              <h3 className="text-lg font-semibold">Open Project</h3>
+              <button
+                onClick={async () => {
+                  try {
+                    if (favorite) await commands.projectFieldRemove(selectedProject.id, 'favorite')
+                    else await commands.projectFieldSet(selectedProject.id, 'favorite', true)
+                    setFavorite(!favorite)
+                  } catch (e) {
+                    setMessage(`Could not update favorites: ${String(e)}`)
+                  }
+                }}
+                className={`text-xl ${favorite ? 'text-yellow-400' : 'text-zinc-500 hover:text-zinc-300'}`}
+                title={favorite ? 'Remove from favorites' : 'Add to favorites (shown in the tray menu)'}
+              >
+                {favorite ? '★' : '☆'}
+              </button>
+            </div>
             <div className="mb-4">
               <p className="text-sm text-zinc-400 mb-2">Project:</p>
               <p className="text-white font-mono text-sm bg-zinc-900 p-2 rounded">{selectedProject.name}</p>
@@ -270,10 +578,7 @@ export default function App() {
               <button 
                 onClick={async () => {
                   try {
-                    await invoke('open_in_editor', { 
-                      editor: 'windsurf', 
-                      path: selectedProject.path 
-                    })
+                    await commands.openInEditor('windsurf', selectedProject.path)
                     setMessage(`Opening ${selectedProject.name} in Windsurf...`)
                   } catch (error) {
                     // Fallback: copy command to clipboard
@@ -293,10 +598,7 @@ export default function App() {
               <button 
                 onClick={async () => {
                   try {
-                    await invoke('open_in_editor', { 
-                      editor: 'cursor', 
-                      path: selectedProject.path 
-                    })
+                    await commands.openInEditor('cursor', selectedProject.path)
                     setMessage(`Opening ${selectedProject.name} in Cursor...`)
                   } catch (error) {
                     // Fallback: copy command to clipboard
@@ -314,7 +616,21 @@ export default function App() {
                 </div>
               </button>
             </div>
-            <div className="flex justify-end mt-6">
+            <div className="flex justify-between mt-6">
+              <button
+                onClick={async () => {
+                  try {
+                    await commands.openDetailWindow(selectedProject.id)
+                    setShowOpenMenu(false)
+                  } catch (e) {
+                    setMessage(`Could not open details: ${String(e)}`)
+                  }
+                }}
+                className="px-4 py-2 text-zinc-400 hover:text-white"
+                title="README, languages and git info in a separate window"
+              >
+                Details ↗
+              </button>
               <button 
                 onClick={() => setShowOpenMenu(false)}
                 className="px-4 py-2 text-zinc-400 hover:text-white"
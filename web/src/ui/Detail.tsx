@@ -0,0 +1,405 @@
+import { Fragment, useEffect, useState } from 'react'
+import { listen } from '@tauri-apps/api/event'
+import { commands, type ActionInfo, type ActionRun, type ProjectDetail, type ProjectLauncher, type ProjectRecord } from '../bindings'
+import { formatBytes, formatDate } from './format'
+import Heatmap from './Heatmap'
+
+// Languages beyond this are summed into "Other"
+const MAX_LANGUAGES = 8
+
+function languageRows(loc: [string, number][]): [string, number][] {
+  if (loc.length <= MAX_LANGUAGES) return loc
+  const other = loc.slice(MAX_LANGUAGES - 1).reduce((sum, [, n]) => sum + n, 0)
+  return [...loc.slice(0, MAX_LANGUAGES - 1), ['Other', other]]
+}
+
+function LauncherSection({ projectId, launcher, onSaved }: { projectId: number, launcher: ProjectLauncher, onSaved: () => void }) {
+  const [editor, setEditor] = useState(launcher.editor ?? '')
+  const [command, setCommand] = useState(launcher.command ?? '')
+  const [status, setStatus] = useState<string | null>(null)
+
+  async function save() {
+    try {
+      await commands.setProjectLauncher(projectId, editor || null, command || null)
+      setStatus('Saved')
+      onSaved()
+    } catch (e) {
+      setStatus(String(e))
+    }
+  }
+
+  async function run() {
+    try {
+      setStatus(`Started ${await commands.runProjectCommand(projectId)}`)
+    } catch (e) {
+      setStatus(String(e))
+    }
+  }
+
+  return (
+    <section>
+      <h2 className="text-sm font-semibold text-zinc-400 mb-1">Launch</h2>
+      <div className="grid grid-cols-[8rem_1fr] gap-y-1 items-center text-sm">
+        <label className="text-zinc-500" htmlFor="launch-editor">Open with</label>
+        <input id="launch-editor" value={editor} onChange={e => setEditor(e.target.value)} placeholder="default editor"
+          className="px-2 py-0.5 rounded bg-zinc-800 font-mono text-xs" />
+        <label className="text-zinc-500" htmlFor="launch-command">Command</label>
+        <input id="launch-command" value={command} onChange={e => setCommand(e.target.value)} placeholder="e.g. make dev"
+          className="px-2 py-0.5 rounded bg-zinc-800 font-mono text-xs" />
+      </div>
+      <div className="flex items-center gap-2 mt-2">
+        <button onClick={save} className="px-2 py-0.5 rounded bg-zinc-700 hover:bg-zinc-600 text-xs">Save</button>
+        <button onClick={run} disabled={!launcher.command} className="px-2 py-0.5 rounded bg-zinc-700 hover:bg-zinc-600 disabled:opacity-50 text-xs">
+          Run in terminal
+        </button>
+        {status && <span className="text-xs text-zinc-500">{status}</span>}
+      </div>
+    </section>
+  )
+}
+
+type ActionLine = { project_id: number; run_id: number; stream: 'stdout' | 'stderr'; line: string }
+type ActionFailed = { project_id: number; name: string; error: string }
+
+// Lines kept on screen for the running action; the stored run has the rest
+const MAX_LIVE_LINES = 500
+
+function ActionsSection({ projectId }: { projectId: number }) {
+  const [actions, setActions] = useState<ActionInfo[]>([])
+  const [runs, setRuns] = useState<ActionRun[]>([])
+  const [running, setRunning] = useState<string | null>(null)
+  const [lines, setLines] = useState<ActionLine[]>([])
+  const [shown, setShown] = useState<ActionRun | null>(null)
+  const [error, setError] = useState<string | null>(null)
+
+  function loadRuns() {
+    commands.actionRuns(projectId).then(setRuns).catch(e => setError(String(e)))
+  }
+
+  useEffect(() => {
+    commands.actionsList(projectId).then(setActions).catch(e => setError(String(e)))
+    loadRuns()
+    const unlisten = [
+      listen<ActionLine>('action-output', e => {
+        if (e.payload.project_id !== projectId) return
+        setLines(ls => [...ls, e.payload].slice(-MAX_LIVE_LINES))
+      }),
+      listen<ActionRun>('action-finished', e => {
+        if (e.payload.project_id !== projectId) return
+        setRunning(null)
+        setShown(e.payload)
+        loadRuns()
+      }),
+      listen<ActionFailed>('action-failed', e => {
+        if (e.payload.project_id !== projectId) return
+        setRunning(null)
+        setError(`${e.payload.name} failed: ${e.payload.error}`)
+        loadRuns()
+      }),
+    ]
+    return () => { unlisten.forEach(p => p.then(f => f())) }
+  }, [projectId])
+
+  async function run(name: string) {
+    setError(null)
+    setLines([])
+    setShown(null)
+    try {
+      await commands.actionRun(projectId, name)
+      setRunning(name)
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  if (actions.length === 0 && runs.length === 0) return null
+
+  const output = running || !shown
+    ? lines
+    : [
+        ...shown.stdout.split('\n').filter(Boolean).map(line => ({ stream: 'stdout', line })),
+        ...shown.stderr.split('\n').filter(Boolean).map(line => ({ stream: 'stderr', line })),
+      ]
+
+  return (
+    <section>
+      <h2 className="text-sm font-semibold text-zinc-400 mb-1">Actions</h2>
+      <div className="flex flex-wrap items-center gap-2">
+        {actions.map(a => (
+          <button key={a.name} onClick={() => run(a.name)} disabled={running !== null} title={a.command.join(' ')}
+            className="px-2 py-0.5 rounded bg-zinc-700 hover:bg-zinc-600 disabled:opacity-50 text-xs">
+            {a.name}
+          </button>
+        ))}
+        {running && <span className="text-xs text-zinc-500">Running {running}…</span>}
+        {error && <span className="text-xs text-red-400">{error}</span>}
+      </div>
+      {output.length > 0 && (
+        <pre className="mt-2 max-h-64 overflow-auto rounded bg-zinc-900 p-2 text-xs">
+          {output.map((l, i) => (
+            <div key={i} className={l.stream === 'stderr' ? 'text-amber-300' : undefined}>{l.line}</div>
+          ))}
+        </pre>
+      )}
+      {runs.length > 0 && (
+        <ul className="mt-2 text-xs">
+          {runs.map(r => (
+            <li key={r.id} className="flex items-center gap-2 py-0.5">
+              <button onClick={() => { setLines([]); setShown(r) }} className="underline hover:text-zinc-300">{r.action}</button>
+              <span className={r.status === 'succeeded' ? 'text-green-400' : r.status === 'running' ? 'text-zinc-500' : 'text-red-400'}>
+                {r.status.replace('_', ' ')}{r.exit_code != null && r.status === 'failed' ? ` (${r.exit_code})` : ''}
+              </span>
+              <span className="text-zinc-500">{formatDate(r.started_at)}</span>
+            </li>
+          ))}
+        </ul>
+      )}
+    </section>
+  )
+}
+
+export default function Detail({ projectId }: { projectId: number }) {
+  const [detail, setDetail] = useState<ProjectDetail | null>(null)
+  const [error, setError] = useState<string | null>(null)
+  const [merged, setMerged] = useState<string | null>(null)
+
+  function load() {
+    commands.projectDetail(projectId).then(setDetail).catch(e => setError(String(e)))
+  }
+
+  useEffect(load, [projectId])
+
+  async function mergeIn(dup: ProjectRecord) {
+    if (!confirm(`Merge ${dup.path} into this project? Its fields, notes and open history move here and the record is deleted.`)) return
+    try {
+      const r = await commands.projectsMerge(projectId, dup.id)
+      setMerged(`Merged ${dup.path}: ${r.fields} field(s), ${r.opens} open(s) carried over`)
+      load()
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  function openDetail(id: number) {
+    commands.openDetailWindow(id).catch(e => setError(String(e)))
+  }
+
+  async function undoMerge() {
+    try {
+      const e = await commands.journalUndo()
+      setMerged(e ? `Undid ${e.kind}` : 'Nothing to undo')
+      load()
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  if (error) return <div className="p-4 text-sm text-red-400">{error}</div>
+  if (!detail) return <div className="p-4 text-sm text-zinc-500">Loading…</div>
+
+  const p = detail.project
+  const langs = languageRows(detail.loc_by_language)
+  const maxLoc = Math.max(1, ...langs.map(([, n]) => n))
+
+  return (
+    <div className="h-screen overflow-y-auto bg-zinc-900 text-zinc-100 p-4 flex flex-col gap-4">
+      <header>
+        {detail.ancestors.length > 0 && (
+          <nav className="text-xs text-zinc-500 mb-1">
+            {detail.ancestors.map(a => (
+              <span key={a.id}>
+                <button onClick={() => openDetail(a.id)} className="hover:text-zinc-300 hover:underline">{a.name}</button>
+                {' / '}
+              </span>
+            ))}
+          </nav>
+        )}
+        <h1 className="text-xl font-semibold">{p.name}</h1>
+        <p className="text-xs text-zinc-500 font-mono">{p.path}</p>
+        {(p.machine || p.volume_id) && (
+          <p className="text-xs text-zinc-500" title="Machine that scanned it and the volume it is on">
+            {[p.machine, p.volume_id && `volume ${p.volume_id}`].filter(Boolean).join(' · ')}
+          </p>
+        )}
+        <div className="flex flex-wrap gap-2 mt-2 text-xs">
+          {p.project_type && <span className="px-2 py-1 rounded bg-zinc-800">{p.project_type}</span>}
+          <span className="px-2 py-1 rounded bg-zinc-800">{p.size_estimated ? '~' : ''}{formatBytes(p.size_bytes ?? 0)}</span>
+          <span className="px-2 py-1 rounded bg-zinc-800" title={p.avg_file_bytes != null ? `${formatBytes(p.avg_file_bytes)} per file on average` : undefined}>{p.files_count ?? 0} files{p.source_files_count != null ? ` (${p.source_files_count} source)` : ''}</span>
+          {p.max_depth != null && <span className="px-2 py-1 rounded bg-zinc-800" title="Deepest directory nesting">depth {p.max_depth}</span>}
+          {!!p.top_level_modules && <span className="px-2 py-1 rounded bg-zinc-800" title="Top-level modules under src/, lib/ or the root">{p.top_level_modules} modules</span>}
+          {p.loc != null && <span className="px-2 py-1 rounded bg-zinc-800" title={p.loc_truncated ? 'Truncated estimate: only part of the project was read (loc_limits)' : undefined}>{p.loc_truncated ? '~' : ''}{p.loc.toLocaleString()} LOC</span>}
+          {!!p.notebooks_count && <span className="px-2 py-1 rounded bg-zinc-800">{p.notebooks_count} notebooks</span>}
+          {p.has_devcontainer && <span className="px-2 py-1 rounded bg-sky-900/60 text-sky-200">devcontainer</span>}
+          {p.has_dockerfile && <span className="px-2 py-1 rounded bg-sky-900/60 text-sky-200">Dockerfile</span>}
+          {p.has_compose && <span className="px-2 py-1 rounded bg-sky-900/60 text-sky-200">Compose</span>}
+          {p.has_nix_shell && <span className="px-2 py-1 rounded bg-sky-900/60 text-sky-200">Nix shell</span>}
+          {p.has_tool_versions && <span className="px-2 py-1 rounded bg-sky-900/60 text-sky-200">.tool-versions</span>}
+          <span className="px-2 py-1 rounded bg-zinc-800">edited {formatDate(p.last_edited_at)}</span>
+        </div>
+        {p.analysis_skipped && (
+          <p className="text-xs text-amber-400 mt-2">Analysis skipped: {p.analysis_skipped}</p>
+        )}
+      </header>
+
+      <LauncherSection key={p.id} projectId={p.id} launcher={detail.launcher} onSaved={load} />
+
+      <ActionsSection projectId={p.id} />
+
+      {detail.toolchains.length > 0 && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">Toolchains</h2>
+          <dl className="grid grid-cols-[8rem_1fr] gap-y-1 text-sm">
+            {detail.toolchains.map(t => (
+              <Fragment key={`${t.language} ${t.source}`}>
+                <dt className="text-zinc-500">{t.language}</dt>
+                <dd>
+                  <span className="font-mono">{t.constraint}</span>
+                  <span className="text-xs text-zinc-500 ml-2">{t.source}</span>
+                </dd>
+              </Fragment>
+            ))}
+          </dl>
+        </section>
+      )}
+
+      {detail.assets.length > 0 && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">Assets</h2>
+          <dl className="grid grid-cols-[8rem_1fr] gap-y-1 text-sm">
+            {detail.assets.map(a => (
+              <Fragment key={a.category}>
+                <dt className="text-zinc-500">{a.category}</dt>
+                <dd>
+                  {formatBytes(a.bytes)}
+                  <span className="text-xs text-zinc-500 ml-2">{a.files} files</span>
+                </dd>
+              </Fragment>
+            ))}
+          </dl>
+        </section>
+      )}
+
+      {detail.ml_artifacts.length > 0 && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">ML artifacts</h2>
+          <dl className="grid grid-cols-[8rem_1fr] gap-y-1 text-sm">
+            {detail.ml_artifacts.map(a => (
+              <Fragment key={a.path}>
+                <dt className="text-zinc-500">{a.kind}</dt>
+                <dd>
+                  <span className="font-mono text-xs">{a.path}</span>
+                  <span className="text-xs text-zinc-500 ml-2">{formatBytes(a.bytes)}</span>
+                </dd>
+              </Fragment>
+            ))}
+          </dl>
+        </section>
+      )}
+
+      {p.is_git_repo && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">Git</h2>
+          <dl className="grid grid-cols-[8rem_1fr] gap-y-1 text-sm">
+            <dt className="text-zinc-500">Branch</dt><dd>{p.branch ?? '-'}</dd>
+            <dt className="text-zinc-500">Last commit</dt><dd>{formatDate(p.last_commit_at)}</dd>
+            <dt className="text-zinc-500">Remote</dt><dd className="truncate">{p.remote_url ?? 'none'}</dd>
+            <dt className="text-zinc-500">Working tree</dt>
+            <dd>{p.git_dirty == null ? '-' : p.git_dirty ? 'uncommitted changes' : 'clean'}</dd>
+          </dl>
+        </section>
+      )}
+
+      <section>
+        <h2 className="text-sm font-semibold text-zinc-400 mb-1">Activity</h2>
+        <Heatmap projectId={p.id} />
+      </section>
+
+      {(detail.duplicates.length > 0 || merged) && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">Possible duplicates</h2>
+          {merged && (
+            <p className="text-xs text-green-400 mb-1">
+              {merged}
+              <button onClick={undoMerge} className="ml-2 underline hover:text-green-300">Undo</button>
+            </p>
+          )}
+          {detail.duplicates.map(d => (
+            <div key={d.id} className="flex items-center gap-2 text-sm py-0.5">
+              <span className="flex-1 truncate font-mono text-xs">{d.path}</span>
+              <button onClick={() => mergeIn(d)} className="px-2 py-0.5 rounded bg-zinc-700 hover:bg-zinc-600 text-xs">
+                Merge into this
+              </button>
+            </div>
+          ))}
+        </section>
+      )}
+
+      {detail.children.length > 0 && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">
+            Workspace members ({detail.children.length})
+          </h2>
+          <p className="text-xs text-zinc-500 mb-1">
+            {formatBytes(detail.children_totals.size_bytes)} · {detail.children_totals.files_count} files · {detail.children_totals.loc.toLocaleString()} LOC in total
+          </p>
+          {detail.children.map(c => (
+            <button
+              key={c.id}
+              onClick={() => openDetail(c.id)}
+              className="grid grid-cols-[1fr_6rem_6rem] w-full text-left text-sm py-0.5 hover:bg-zinc-800 rounded px-1"
+            >
+              <span className="truncate">{c.name}</span>
+              <span className="text-right text-xs text-zinc-400">{formatBytes(c.size_bytes ?? 0)}</span>
+              <span className="text-right text-xs text-zinc-400">{(c.loc ?? 0).toLocaleString()} LOC</span>
+            </button>
+          ))}
+        </section>
+      )}
+
+      {detail.related.length > 0 && (
+        <section>
+          <h2 className="text-sm font-semibold text-zinc-400 mb-1">Related projects</h2>
+          {detail.related.map(r => (
+            <button
+              key={r.project.id}
+              onClick={() => openDetail(r.project.id)}
+              className="block w-full text-left text-sm py-0.5 hover:bg-zinc-800 rounded px-1"
+            >
+              <span className="font-medium">{r.project.name}</span>
+              <span className="ml-2 text-xs text-zinc-500">{r.reasons.join(' · ')}</span>
+            </button>
+          ))}
+        </section>
+      )}
+
+      <section>
+        <h2 className="text-sm font-semibold text-zinc-400 mb-1">Languages</h2>
+        {langs.length ? (
+          <div className="flex flex-col gap-1 text-xs">
+            {langs.map(([lang, n]) => (
+              <div key={lang} className="grid grid-cols-[7rem_1fr_5rem] items-center gap-2">
+                <span className="truncate">{lang}</span>
+                <div className="h-2 rounded bg-blue-600" style={{ width: `${(n / maxLoc) * 100}%` }} />
+                <span className="text-right text-zinc-400">{n.toLocaleString()}</span>
+              </div>
+            ))}
+          </div>
+        ) : (
+          <p className="text-xs text-zinc-500">No LOC breakdown yet; rescan with LOC enabled</p>
+        )}
+      </section>
+
+      <section className="flex-1">
+        <h2 className="text-sm font-semibold text-zinc-400 mb-1">{detail.readme_name ?? 'README'}</h2>
+        {detail.readme ? (
+          <pre className="whitespace-pre-wrap text-xs leading-5 bg-zinc-950 rounded p-3">
+            {detail.readme}
+            {detail.readme_truncated && '\n…'}
+          </pre>
+        ) : (
+          <p className="text-xs text-zinc-500">No README</p>
+        )}
+      </section>
+    </div>
+  )
+}
@@ -0,0 +1,52 @@
+import { useEffect, useState } from 'react'
+import { commands, type ActivityDay } from '../bindings'
+
+// Cell shades from no activity to the busiest days
+const SHADES = ['bg-zinc-800', 'bg-green-900', 'bg-green-700', 'bg-green-500', 'bg-green-300']
+
+function shade(n: number, max: number): string {
+  if (n === 0 || max === 0) return SHADES[0]
+  return SHADES[Math.min(SHADES.length - 1, Math.ceil((n / max) * (SHADES.length - 1)))]
+}
+
+// One column per week, Sunday at the top, like GitHub's contribution graph
+export default function Heatmap({ projectId }: { projectId: number | null }) {
+  const [days, setDays] = useState<ActivityDay[]>([])
+
+  useEffect(() => {
+    commands.activityCalendar(projectId).then(setDays).catch(() => setDays([]))
+  }, [projectId])
+
+  if (days.length === 0) return null
+  const total = (d: ActivityDay) => d.commits + d.edits
+  const max = Math.max(...days.map(total))
+  const lead = new Date(`${days[0].date}T00:00:00Z`).getUTCDay()
+  const cells: (ActivityDay | null)[] = [...Array(lead).fill(null), ...days]
+  const weeks: (ActivityDay | null)[][] = []
+  for (let i = 0; i < cells.length; i += 7) weeks.push(cells.slice(i, i + 7))
+  const commits = days.reduce((sum, d) => sum + d.commits, 0)
+  const active = days.filter(d => total(d) > 0).length
+
+  return (
+    <div>
+      <div className="flex gap-[2px] overflow-x-auto">
+        {weeks.map((week, w) => (
+          <div key={w} className="flex flex-col gap-[2px]">
+            {week.map((d, i) => d ? (
+              <div
+                key={d.date}
+                className={`w-2.5 h-2.5 rounded-sm ${shade(total(d), max)}`}
+                title={`${d.date}: ${d.commits} commits, ${d.edits} edits`}
+              />
+            ) : (
+              <div key={`pad${i}`} className="w-2.5 h-2.5" />
+            ))}
+          </div>
+        ))}
+      </div>
+      <p className="text-xs text-zinc-500 mt-1">
+        {commits.toLocaleString()} commits · active on {active} days in the last year
+      </p>
+    </div>
+  )
+}
@@ -0,0 +1,17 @@
+import type { ReactNode } from 'react'
+import type { Highlighted } from '../bindings'
+
+// Spans are in characters (code points), so index an array of them rather than the UTF-16 string
+export default function Highlight({ h, fallback }: { h?: Highlighted | null, fallback: string }) {
+  if (!h || h.spans.length === 0) return <>{fallback}</>
+  const chars = Array.from(h.text)
+  const parts: ReactNode[] = []
+  let pos = 0
+  h.spans.forEach(([start, end], i) => {
+    if (start > pos) parts.push(<span key={`t${i}`}>{chars.slice(pos, start).join('')}</span>)
+    parts.push(<mark key={`m${i}`} className="bg-yellow-500/30 text-inherit rounded-sm">{chars.slice(start, end).join('')}</mark>)
+    pos = end
+  })
+  if (pos < chars.length) parts.push(<span key="rest">{chars.slice(pos).join('')}</span>)
+  return <>{parts}</>
+}
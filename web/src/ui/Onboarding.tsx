@@ -0,0 +1,134 @@
+import { useEffect, useState } from 'react'
+import { commands, type OnboardingStatus, type QuickScan, type RootSuggestion } from '../bindings'
+
+// First-run setup: pick roots from suggestions (or type one), preview what they contain,
+// then save and start the full scan in the background.
+export default function Onboarding({ status, onDone }: { status: OnboardingStatus, onDone: (message: string) => void }) {
+  const [suggestions, setSuggestions] = useState<RootSuggestion[] | null>(null)
+  const [selected, setSelected] = useState<string[]>([])
+  const [custom, setCustom] = useState('')
+  const [previewRoot, setPreviewRoot] = useState<string | null>(null)
+  const [preview, setPreview] = useState<QuickScan | null>(null)
+  const [editor, setEditor] = useState(status.editor)
+  const [error, setError] = useState<string | null>(null)
+  const [saving, setSaving] = useState(false)
+
+  useEffect(() => {
+    commands.onboardingSuggestRoots()
+      .then(found => {
+        setSuggestions(found)
+        setSelected(found.map(s => s.path))
+        if (found.length) showPreview(found[0].path)
+      })
+      .catch(e => setError(String(e)))
+  }, [])
+
+  async function showPreview(root: string) {
+    setPreviewRoot(root)
+    setPreview(null)
+    try {
+      setPreview(await commands.onboardingQuickScan(root))
+      setError(null)
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  function toggle(path: string) {
+    setSelected(sel => sel.includes(path) ? sel.filter(p => p !== path) : [...sel, path])
+  }
+
+  function addCustom() {
+    const path = custom.trim()
+    if (!path) return
+    if (!selected.includes(path)) setSelected([...selected, path])
+    setCustom('')
+    showPreview(path)
+  }
+
+  async function finish() {
+    setSaving(true)
+    try {
+      await commands.onboardingComplete({ roots: selected, editor, hotkey: null })
+      onDone(`Scanning ${selected.length} folder(s) in the background…`)
+    } catch (e) {
+      setError(String(e))
+      setSaving(false)
+    }
+  }
+
+  const customRoots = selected.filter(p => !suggestions?.some(s => s.path === p))
+
+  return (
+    <div className="fixed inset-0 bg-zinc-900 z-50 flex flex-col p-6 gap-4 text-zinc-100">
+      <div>
+        <h2 className="text-xl font-semibold">Welcome to Project Browser</h2>
+        <p className="text-sm text-zinc-400">Pick the folders that hold your projects. You can change them later in the config.</p>
+      </div>
+      <div className="flex-1 grid grid-cols-2 gap-4 min-h-0">
+        <div className="flex flex-col gap-2 min-h-0 overflow-y-auto">
+          {!suggestions && <p className="text-sm text-zinc-500">Looking for projects…</p>}
+          {suggestions?.length === 0 && <p className="text-sm text-zinc-500">No projects in the usual places; add a folder below.</p>}
+          {suggestions?.map(s => (
+            <label
+              key={s.path}
+              className={`flex items-center gap-3 p-2 rounded cursor-pointer ${previewRoot === s.path ? 'bg-zinc-800' : 'hover:bg-zinc-800/50'}`}
+              onMouseEnter={() => previewRoot !== s.path && showPreview(s.path)}
+            >
+              <input type="checkbox" checked={selected.includes(s.path)} onChange={() => toggle(s.path)} />
+              <span className="flex-1 truncate font-mono text-sm">{s.path}</span>
+              <span className="text-xs text-zinc-400">
+                ~{s.estimated_projects}{s.truncated ? '+' : ''} projects{s.configured ? ' · configured' : ''}
+              </span>
+            </label>
+          ))}
+          {customRoots.map(p => (
+            <label key={p} className="flex items-center gap-3 p-2 rounded">
+              <input type="checkbox" checked onChange={() => toggle(p)} />
+              <span className="flex-1 truncate font-mono text-sm">{p}</span>
+            </label>
+          ))}
+          <div className="flex gap-2 mt-2">
+            <input
+              value={custom}
+              onChange={e => setCustom(e.target.value)}
+              onKeyDown={e => e.key === 'Enter' && addCustom()}
+              placeholder="Another folder, e.g. ~/clients"
+              className="flex-1 px-2 py-1 rounded bg-zinc-800 text-sm"
+            />
+            <button onClick={addCustom} className="px-3 py-1 rounded bg-zinc-700 hover:bg-zinc-600 text-sm">Add</button>
+          </div>
+        </div>
+        <div className="flex flex-col min-h-0 bg-zinc-950 rounded p-3">
+          <p className="text-xs text-zinc-400 mb-2 truncate">{previewRoot ? `In ${previewRoot}` : 'Preview'}</p>
+          {previewRoot && !preview && <p className="text-sm text-zinc-500">Scanning…</p>}
+          {preview && (
+            <div className="flex-1 overflow-y-auto text-sm">
+              {preview.projects.map(p => (
+                <div key={p.path} className="flex justify-between py-0.5">
+                  <span className="truncate">{p.name}</span>
+                  <span className="text-xs text-zinc-500">{p.project_type}</span>
+                </div>
+              ))}
+              {!preview.projects.length && <p className="text-zinc-500">No projects found here</p>}
+              {preview.truncated && <p className="text-xs text-zinc-500 mt-1">…and more; the full scan finds the rest</p>}
+            </div>
+          )}
+        </div>
+      </div>
+      <div className="flex items-center gap-3">
+        <label className="text-sm text-zinc-400">Editor</label>
+        <input value={editor} onChange={e => setEditor(e.target.value)} className="w-40 px-2 py-1 rounded bg-zinc-800 text-sm" />
+        {error && <span className="text-sm text-red-400 truncate">{error}</span>}
+        <button onClick={() => onDone('Setup skipped')} className="ml-auto px-4 py-2 text-zinc-400 hover:text-white">Skip</button>
+        <button
+          onClick={finish}
+          disabled={saving || !selected.length}
+          className="px-4 py-2 rounded bg-blue-600 hover:bg-blue-700 disabled:bg-zinc-700"
+        >
+          Scan {selected.length} folder(s)
+        </button>
+      </div>
+    </div>
+  )
+}
@@ -0,0 +1,115 @@
+import { useEffect, useRef, useState } from 'react'
+import { listen } from '@tauri-apps/api/event'
+import { commands, type LaunchAction, type ProjectRecord, type Suggestion } from '../bindings'
+
+// The ranked search runs once typing pauses; type-ahead suggestions fill in until then
+const SEARCH_DEBOUNCE_MS = 120
+
+type Result = Suggestion | ProjectRecord
+
+// Enter runs launcher.default_action; modifiers pick a specific one
+function actionFor(e: React.KeyboardEvent): LaunchAction | null {
+  if (e.metaKey || e.ctrlKey) return 'reveal'
+  if (e.shiftKey) return 'show'
+  return null
+}
+
+export default function Palette() {
+  const [q, setQ] = useState('')
+  const [results, setResults] = useState<Result[]>([])
+  const [selected, setSelected] = useState(0)
+  const [error, setError] = useState<string | null>(null)
+  const inputRef = useRef<HTMLInputElement>(null)
+  // Query the ranked results on screen belong to; late suggestions must not replace them
+  const searched = useRef<string | null>(null)
+
+  async function search(query: string) {
+    try {
+      setResults(await commands.paletteSearch(query, 20))
+      searched.current = query
+      setSelected(0)
+      setError(null)
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  useEffect(() => {
+    if (q.trim()) {
+      commands.paletteSuggest(q, 20)
+        .then(s => {
+          if (searched.current === q || inputRef.current?.value !== q) return
+          setResults(s)
+          setSelected(0)
+        })
+        .catch(e => setError(String(e)))
+    }
+    const t = setTimeout(() => search(q), q.trim() ? SEARCH_DEBOUNCE_MS : 0)
+    return () => clearTimeout(t)
+  }, [q])
+
+  useEffect(() => {
+    const unlisten = listen('palette-shown', () => {
+      setQ('')
+      search('')
+      inputRef.current?.focus()
+    })
+    return () => { unlisten.then(f => f()) }
+  }, [])
+
+  async function open(project: Result, action: LaunchAction | null) {
+    try {
+      await commands.paletteOpen(project.id, action)
+    } catch (e) {
+      setError(String(e))
+    }
+  }
+
+  function onKeyDown(e: React.KeyboardEvent) {
+    if (e.key === 'ArrowDown') {
+      e.preventDefault()
+      setSelected(i => Math.min(i + 1, results.length - 1))
+    } else if (e.key === 'ArrowUp') {
+      e.preventDefault()
+      setSelected(i => Math.max(i - 1, 0))
+    } else if (e.key === 'Enter' && results[selected]) {
+      open(results[selected], actionFor(e))
+    } else if (e.key === 'Escape') {
+      commands.paletteHide()
+    }
+  }
+
+  return (
+    <div className="h-screen flex flex-col bg-zinc-900 text-zinc-100 border border-zinc-700 rounded-lg overflow-hidden">
+      <input
+        ref={inputRef}
+        autoFocus
+        value={q}
+        onChange={e => setQ(e.target.value)}
+        onKeyDown={onKeyDown}
+        placeholder="Open project…"
+        className="px-4 py-3 text-lg bg-zinc-800 outline-none"
+      />
+      <div className="flex-1 overflow-y-auto">
+        {results.map((r, i) => (
+          <div
+            key={r.id}
+            onMouseEnter={() => setSelected(i)}
+            onClick={() => open(r, null)}
+            className={`px-4 py-2 cursor-pointer ${i === selected ? 'bg-blue-600' : ''}`}
+          >
+            <div className="flex justify-between">
+              <span className="font-medium">{r.name}</span>
+              <span className="text-xs text-zinc-400">{'project_type' in r ? r.project_type ?? '' : ''}</span>
+            </div>
+            <div className="text-xs text-zinc-400 truncate">{r.path}</div>
+          </div>
+        ))}
+        {!results.length && <div className="px-4 py-3 text-sm text-zinc-500">No matching projects</div>}
+      </div>
+      <div className="px-4 py-1 text-xs text-zinc-500 border-t border-zinc-800">
+        {error ?? '↵ open · ⇧↵ show in app · ⌘/Ctrl↵ reveal · esc close'}
+      </div>
+    </div>
+  )
+}